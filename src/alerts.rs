@@ -0,0 +1,613 @@
+use std::{
+    fmt,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{sys_info::SystemInfo, utils};
+
+// A firing alert resolves only once its value is this fraction of the
+// threshold back past it, so a value bouncing on the line doesn't flap
+const HYSTERESIS: f64 = 0.05;
+// How often a rule's command may run at most, unless the rule says
+const DEFAULT_COOLDOWN: Duration = Duration::from_secs(300);
+
+/// The part of the screen an alert is about, whose block is highlighted
+/// while it fires.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Area {
+    Cpu,
+    Memory,
+    Disks,
+    Processes,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Metric {
+    Cpu,
+    Memory,
+    Swap,
+    Load,
+    Temperature,
+    // Usage of the filesystem mounted here
+    Disk(String),
+}
+
+impl Metric {
+    fn named(name: &str) -> Option<Self> {
+        match name {
+            "cpu" | "cpu_total" => Some(Metric::Cpu),
+            "memory" | "mem" => Some(Metric::Memory),
+            "swap" => Some(Metric::Swap),
+            "load" | "load_1" => Some(Metric::Load),
+            "temperature" | "temp" => Some(Metric::Temperature),
+            _ => None,
+        }
+    }
+
+    // Load is a run-queue length; everything else is a percentage or °C
+    fn allows_percent(&self) -> bool {
+        !matches!(self, Metric::Load | Metric::Temperature)
+    }
+
+    fn value(&self, metrics: &SystemInfo) -> Option<f64> {
+        match self {
+            Metric::Cpu => Some(metrics.cpu_total_usage as f64),
+            Metric::Memory => Some(utils::safe_percentage(
+                metrics.memory_used,
+                metrics.memory_total,
+            )),
+            Metric::Swap => Some(utils::safe_percentage(
+                metrics.swap_used,
+                metrics.swap_total,
+            )),
+            Metric::Load => Some(metrics.load_average.one),
+            Metric::Temperature => metrics.cpu_temperature.map(f64::from),
+            Metric::Disk(mount) => metrics
+                .disks
+                .iter()
+                .find(|disk| disk.mount_point == *mount)
+                .map(|disk| disk.usage as f64),
+        }
+    }
+
+    fn area(&self) -> Area {
+        match self {
+            Metric::Cpu | Metric::Load | Metric::Temperature => Area::Cpu,
+            Metric::Memory | Metric::Swap => Area::Memory,
+            Metric::Disk(_) => Area::Disks,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparison {
+    Above,
+    AtLeast,
+    Below,
+    AtMost,
+}
+
+impl Comparison {
+    fn holds(self, value: f64, limit: f64) -> bool {
+        match self {
+            Comparison::Above => value > limit,
+            Comparison::AtLeast => value >= limit,
+            Comparison::Below => value < limit,
+            Comparison::AtMost => value <= limit,
+        }
+    }
+
+    // Whether `value` is far enough back from `limit` to resolve
+    fn clears(self, value: f64, limit: f64) -> bool {
+        let margin = limit.abs() * HYSTERESIS;
+        match self {
+            Comparison::Above | Comparison::AtLeast => value < limit - margin,
+            Comparison::Below | Comparison::AtMost => value > limit + margin,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Condition {
+    Threshold {
+        metric: Metric,
+        comparison: Comparison,
+        limit: f64,
+    },
+    // No process of this name in the list
+    NotRunning(String),
+}
+
+/// An entry of the `alerts` config list: just the rule, or a table that
+/// also asks for desktop notifications or a command to run, e.g.
+/// `{ rule = "process foo not running", exec = "systemctl restart foo" }`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AlertSpec {
+    Rule(String),
+    Table {
+        rule: String,
+        #[serde(default)]
+        notify: bool,
+        // Run through the shell when the alert fires, given --allow-exec
+        exec: Option<String>,
+        // The least time between two runs of `exec`, 300 by default
+        cooldown_secs: Option<u64>,
+    },
+}
+
+impl AlertSpec {
+    fn text(&self) -> &str {
+        match self {
+            AlertSpec::Rule(rule) | AlertSpec::Table { rule, .. } => rule,
+        }
+    }
+
+    fn rule(&self) -> Result<Rule, String> {
+        let rule: Rule = self.text().parse()?;
+        Ok(match self {
+            AlertSpec::Rule(_) => rule,
+            AlertSpec::Table {
+                notify,
+                exec,
+                cooldown_secs,
+                ..
+            } => Rule {
+                notify: *notify,
+                exec: exec.clone().filter(|exec| !exec.trim().is_empty()),
+                cooldown: cooldown_secs.map_or(DEFAULT_COOLDOWN, Duration::from_secs),
+                ..rule
+            },
+        })
+    }
+}
+
+/// One rule of the `alerts` config list, e.g. `cpu > 90 for 30s`,
+/// `disk /var > 90%` or `process nginx not running`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    text: String,
+    condition: Condition,
+    // How long the condition must hold before the alert fires
+    duration: Duration,
+    // Whether firing and resolving raise desktop notifications
+    pub notify: bool,
+    pub exec: Option<String>,
+    pub cooldown: Duration,
+}
+
+impl Rule {
+    /// What the rule watches and its value in `metrics`, e.g.
+    /// `Disk /var at 93%` or `nginx is not running`.
+    pub fn reading(&self, metrics: &SystemInfo) -> String {
+        let (metric, limit) = match &self.condition {
+            Condition::NotRunning(name) => {
+                return match self.holds(metrics) {
+                    Some(true) => format!("{} is not running", name),
+                    Some(false) => format!("{} is running", name),
+                    None => format!("{} is unknown among simulated processes", name),
+                };
+            }
+            Condition::Threshold { metric, limit, .. } => (metric, limit),
+        };
+        let label = match metric {
+            Metric::Cpu => "CPU".to_string(),
+            Metric::Memory => "Memory".to_string(),
+            Metric::Swap => "Swap".to_string(),
+            Metric::Load => "Load".to_string(),
+            Metric::Temperature => "CPU temperature".to_string(),
+            Metric::Disk(mount) => format!("Disk {}", mount),
+        };
+        match (metric.value(metrics), metric) {
+            (None, Metric::Disk(_)) => format!("{} is not mounted", label),
+            (None, _) => format!("{} is unavailable", label),
+            (Some(value), Metric::Load) => format!("{} at {:.2}, limit {}", label, value, limit),
+            (Some(value), Metric::Temperature) => {
+                format!("{} at {:.0}°C, limit {}°C", label, value, limit)
+            }
+            (Some(value), _) => format!("{} at {:.1}%, limit {}%", label, value, limit),
+        }
+    }
+
+    pub fn area(&self) -> Area {
+        match &self.condition {
+            Condition::Threshold { metric, .. } => metric.area(),
+            Condition::NotRunning(_) => Area::Processes,
+        }
+    }
+
+    // None when there is nothing to judge by, such as an unmounted disk or
+    // a simulated process list
+    fn holds(&self, metrics: &SystemInfo) -> Option<bool> {
+        match &self.condition {
+            Condition::Threshold {
+                metric,
+                comparison,
+                limit,
+            } => Some(comparison.holds(metric.value(metrics)?, *limit)),
+            Condition::NotRunning(_) if metrics.simulated => None,
+            Condition::NotRunning(name) => Some(
+                !metrics
+                    .processes
+                    .iter()
+                    .any(|process| process.name == *name),
+            ),
+        }
+    }
+
+    fn clears(&self, metrics: &SystemInfo) -> bool {
+        match &self.condition {
+            Condition::Threshold {
+                metric,
+                comparison,
+                limit,
+            } => metric
+                .value(metrics)
+                .is_none_or(|value| comparison.clears(value, *limit)),
+            Condition::NotRunning(_) => self.holds(metrics) == Some(false),
+        }
+    }
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        let (condition, rest) = match words.as_slice() {
+            ["process", rest @ ..] => {
+                let not = rest
+                    .iter()
+                    .position(|word| *word == "not")
+                    .filter(|&not| not > 0 && rest.get(not + 1) == Some(&"running"))
+                    .ok_or("expected 'process <name> not running'")?;
+                (
+                    Condition::NotRunning(rest[..not].join(" ")),
+                    &rest[not + 2..],
+                )
+            }
+            ["disk", mount, comparison, limit, rest @ ..] => (
+                threshold(Metric::Disk(mount.to_string()), comparison, limit)?,
+                rest,
+            ),
+            [name, comparison, limit, rest @ ..] => {
+                let metric = Metric::named(name).ok_or_else(|| {
+                    format!(
+                        "unknown metric '{}'; expected cpu, memory, swap, load, temperature, disk or process",
+                        name
+                    )
+                })?;
+                (threshold(metric, comparison, limit)?, rest)
+            }
+            _ => return Err("expected e.g. 'cpu > 90 for 30s'".to_string()),
+        };
+        let duration = match rest {
+            [] => Duration::ZERO,
+            ["for", duration] => parse_duration(duration)?,
+            _ => return Err(format!("unexpected '{}'", rest.join(" "))),
+        };
+        Ok(Self {
+            text: words.join(" "),
+            condition,
+            duration,
+            notify: false,
+            exec: None,
+            cooldown: DEFAULT_COOLDOWN,
+        })
+    }
+}
+
+fn threshold(metric: Metric, comparison: &str, limit: &str) -> Result<Condition, String> {
+    let comparison = match comparison {
+        ">" => Comparison::Above,
+        ">=" => Comparison::AtLeast,
+        "<" => Comparison::Below,
+        "<=" => Comparison::AtMost,
+        other => return Err(format!("expected >, >=, < or <= but found '{}'", other)),
+    };
+    let number = match limit.strip_suffix('%') {
+        Some(_) if !metric.allows_percent() => {
+            return Err("load and temperature take no %".to_string());
+        }
+        Some(number) => number,
+        None => limit,
+    };
+    let limit = number
+        .parse::<f64>()
+        .ok()
+        .filter(|limit| limit.is_finite())
+        .ok_or_else(|| format!("'{}' is not a number", limit))?;
+    Ok(Condition::Threshold {
+        metric,
+        comparison,
+        limit,
+    })
+}
+
+// e.g. `30s`, `5m` or `1h`
+fn parse_duration(text: &str) -> Result<Duration, String> {
+    let (digits, unit) = match text.char_indices().last() {
+        Some((index, 's')) => (&text[..index], 1),
+        Some((index, 'm')) => (&text[..index], 60),
+        Some((index, 'h')) => (&text[..index], 3600),
+        _ => (text, 1),
+    };
+    digits
+        .parse::<u64>()
+        .ok()
+        .and_then(|count| count.checked_mul(unit))
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("'{}' is not a duration such as 30s or 5m", text))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Clear,
+    // The condition has held since then, but not yet for long enough
+    Pending(Instant),
+    Firing,
+}
+
+/// An alert starting or stopping, by its index in the rules.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transition {
+    Fired(usize),
+    Resolved(usize),
+}
+
+/// The configured rules and whether each is firing.
+#[derive(Debug, Default)]
+pub struct Alerts {
+    rules: Vec<Rule>,
+    states: Vec<State>,
+}
+
+impl Alerts {
+    /// Parses the `alerts` config list, skipping the rules that don't parse
+    /// with a warning for each.
+    pub fn new(specs: &[AlertSpec]) -> (Self, Vec<String>) {
+        let mut rules = Vec::new();
+        let mut warnings = Vec::new();
+        for spec in specs {
+            match spec.rule() {
+                Ok(rule) => rules.push(rule),
+                Err(err) => warnings.push(format!("alert '{}' ignored: {}", spec.text(), err)),
+            }
+        }
+        let states = vec![State::Clear; rules.len()];
+        (Self { rules, states }, warnings)
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    /// Moves every rule on by one sample taken at `now`, returning the
+    /// alerts that started or stopped firing.
+    pub fn evaluate(&mut self, metrics: &SystemInfo, now: Instant) -> Vec<Transition> {
+        let mut transitions = Vec::new();
+        for (index, (rule, state)) in self.rules.iter().zip(&mut self.states).enumerate() {
+            let holds = rule.holds(metrics) == Some(true);
+            *state = match *state {
+                State::Clear | State::Pending(_) if !holds => State::Clear,
+                State::Clear => State::Pending(now),
+                State::Pending(since) => State::Pending(since),
+                State::Firing if rule.clears(metrics) => {
+                    transitions.push(Transition::Resolved(index));
+                    State::Clear
+                }
+                State::Firing => State::Firing,
+            };
+            if let State::Pending(since) = *state
+                && now.saturating_duration_since(since) >= rule.duration
+            {
+                transitions.push(Transition::Fired(index));
+                *state = State::Firing;
+            }
+        }
+        transitions
+    }
+
+    pub fn firing(&self) -> impl Iterator<Item = &Rule> {
+        self.rules
+            .iter()
+            .zip(&self.states)
+            .filter(|(_, state)| **state == State::Firing)
+            .map(|(rule, _)| rule)
+    }
+
+    pub fn is_firing(&self, area: Area) -> bool {
+        self.firing().any(|rule| rule.area() == area)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sys_info::{DiskInfo, ProcessInfo};
+
+    fn alerts(specs: &[&str]) -> Alerts {
+        let specs: Vec<AlertSpec> = specs
+            .iter()
+            .map(|spec| AlertSpec::Rule(spec.to_string()))
+            .collect();
+        let (alerts, warnings) = Alerts::new(&specs);
+        assert_eq!(warnings, Vec::<String>::new());
+        alerts
+    }
+
+    fn with_cpu(usage: u64) -> SystemInfo {
+        SystemInfo {
+            cpu_total_usage: usage,
+            ..SystemInfo::default()
+        }
+    }
+
+    #[test]
+    fn parses_the_documented_forms() {
+        let rule: Rule = "cpu_total  >  90 for 30s".parse().unwrap();
+        assert_eq!(rule.to_string(), "cpu_total > 90 for 30s");
+        assert_eq!(rule.duration, Duration::from_secs(30));
+        assert_eq!(rule.area(), Area::Cpu);
+        let rule: Rule = "memory > 95%".parse().unwrap();
+        assert_eq!(rule.duration, Duration::ZERO);
+        assert_eq!(rule.area(), Area::Memory);
+        let rule: Rule = "disk /var >= 90% for 5m".parse().unwrap();
+        assert_eq!(
+            rule.condition,
+            Condition::Threshold {
+                metric: Metric::Disk("/var".to_string()),
+                comparison: Comparison::AtLeast,
+                limit: 90.0,
+            }
+        );
+        assert_eq!(rule.duration, Duration::from_secs(300));
+        let rule: Rule = "process nginx not running".parse().unwrap();
+        assert_eq!(rule.condition, Condition::NotRunning("nginx".to_string()));
+        assert_eq!(rule.area(), Area::Processes);
+    }
+
+    #[test]
+    fn reads_commands_from_tables() {
+        let spec = AlertSpec::Table {
+            rule: "process foo not running".to_string(),
+            notify: false,
+            exec: Some("systemctl restart foo".to_string()),
+            cooldown_secs: Some(60),
+        };
+        let (alerts, _) = Alerts::new(&[spec, AlertSpec::Rule("cpu > 90".to_string())]);
+        let rules = alerts.rules();
+        assert_eq!(rules[0].exec.as_deref(), Some("systemctl restart foo"));
+        assert_eq!(rules[0].cooldown, Duration::from_secs(60));
+        assert_eq!(rules[1].exec, None);
+        assert_eq!(rules[1].cooldown, DEFAULT_COOLDOWN);
+    }
+
+    #[test]
+    fn reports_rules_that_do_not_parse() {
+        let specs = [
+            "cpu > 90",
+            "gpu > 90",
+            "load > 4%",
+            "memory = 90",
+            "cpu > 90 for a while",
+            "process not running",
+        ]
+        .map(|spec| AlertSpec::Rule(spec.to_string()));
+        let (alerts, warnings) = Alerts::new(&specs);
+        assert_eq!(alerts.rules().len(), 1);
+        assert_eq!(warnings.len(), 5);
+        assert!(warnings[0].starts_with("alert 'gpu > 90' ignored: unknown metric 'gpu'"));
+        assert_eq!(
+            warnings[1],
+            "alert 'load > 4%' ignored: load and temperature take no %"
+        );
+    }
+
+    #[test]
+    fn fires_only_after_holding_for_the_duration() {
+        let mut alerts = alerts(&["cpu > 90 for 30s"]);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        assert_eq!(alerts.evaluate(&with_cpu(95), at(0)), vec![]);
+        assert_eq!(alerts.evaluate(&with_cpu(95), at(20)), vec![]);
+        // A dip starts the wait over
+        assert_eq!(alerts.evaluate(&with_cpu(50), at(25)), vec![]);
+        assert_eq!(alerts.evaluate(&with_cpu(95), at(40)), vec![]);
+        assert_eq!(alerts.evaluate(&with_cpu(95), at(60)), vec![]);
+        assert_eq!(
+            alerts.evaluate(&with_cpu(95), at(70)),
+            vec![Transition::Fired(0)]
+        );
+        assert!(alerts.is_firing(Area::Cpu));
+        assert!(!alerts.is_firing(Area::Memory));
+        assert_eq!(alerts.evaluate(&with_cpu(95), at(80)), vec![]);
+    }
+
+    #[test]
+    fn does_not_flap_around_the_threshold() {
+        let mut alerts = alerts(&["cpu > 90"]);
+        let now = Instant::now();
+        let transitions: Vec<Vec<Transition>> = [91, 89, 92, 88, 85, 91]
+            .into_iter()
+            .map(|usage| alerts.evaluate(&with_cpu(usage), now))
+            .collect();
+        // Only 85 is below 90 less 5%
+        assert_eq!(
+            transitions,
+            vec![
+                vec![Transition::Fired(0)],
+                vec![],
+                vec![],
+                vec![],
+                vec![Transition::Resolved(0)],
+                vec![Transition::Fired(0)],
+            ]
+        );
+    }
+
+    #[test]
+    fn watches_disks_by_mount_and_processes_by_name() {
+        let mut alerts = alerts(&["disk /var > 90%", "process nginx not running"]);
+        let disk = |usage| DiskInfo {
+            mount_point: "/var".to_string(),
+            usage,
+            ..DiskInfo::default()
+        };
+        let mut metrics = SystemInfo {
+            disks: vec![disk(95)],
+            processes: vec![ProcessInfo::fixture(10, 1, "nginx")],
+            simulated: false,
+            ..SystemInfo::default()
+        };
+        let now = Instant::now();
+        assert_eq!(alerts.evaluate(&metrics, now), vec![Transition::Fired(0)]);
+        metrics.processes.clear();
+        metrics.disks[0] = disk(80);
+        assert_eq!(
+            alerts.evaluate(&metrics, now),
+            vec![Transition::Resolved(0), Transition::Fired(1)]
+        );
+        assert_eq!(
+            alerts.firing().map(Rule::to_string).collect::<Vec<_>>(),
+            ["process nginx not running"]
+        );
+        assert_eq!(
+            alerts.rules()[0].reading(&metrics),
+            "Disk /var at 80.0%, limit 90%"
+        );
+        assert_eq!(alerts.rules()[1].reading(&metrics), "nginx is not running");
+        // An unmounted disk has nothing to alert about
+        metrics.disks.clear();
+        assert_eq!(alerts.evaluate(&metrics, now), vec![]);
+        assert!(!alerts.is_firing(Area::Disks));
+        assert_eq!(
+            alerts.rules()[0].reading(&metrics),
+            "Disk /var is not mounted"
+        );
+    }
+
+    #[test]
+    fn ignores_simulated_processes() {
+        let mut alerts = alerts(&["process nginx not running"]);
+        let metrics = SystemInfo {
+            processes: Vec::new(),
+            ..SystemInfo::default()
+        };
+        assert!(metrics.simulated);
+        assert_eq!(alerts.evaluate(&metrics, Instant::now()), vec![]);
+        assert_eq!(
+            alerts.rules()[0].reading(&metrics),
+            "nginx is unknown among simulated processes"
+        );
+    }
+}
@@ -1,15 +1,352 @@
-use std::time::{Duration, Instant};
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    io,
+    path::PathBuf,
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
 
-use crate::sys_info::{ProcessSort, SystemInfo};
+use ratatui::{
+    layout::{Position, Rect},
+    widgets::ScrollbarState,
+};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+use crate::{
+    alerts::{Alerts, Transition},
+    columns::Column,
+    events::EventLog,
+    exec::{CommandRunner, Skipped},
+    history::{self, History},
+    keys::{Action, KeyBindings},
+    metrics_log::MetricsLog,
+    notify::Notifier,
+    process_groups,
+    process_history::ProcessHistory,
+    process_tree,
+    remote::{Client, Connection},
+    snapshot,
+    sys_info::{
+        self, Collector, DiskInfo, InterfaceHistory, ProcessInfo, ProcessSort, Section, Signal,
+        SystemInfo,
+    },
+    theme::{self, ColorDepth, Severity, Theme},
+    utils::{UnitSystem, find_ignore_case},
+};
+
+// How long a status message replaces the footer hints
+const STATUS_DURATION: Duration = Duration::from_secs(3);
+
+// The process table's columns in the order ←/→ steps through them
+const SORT_COLUMNS: [ProcessSort; 8] = [
+    ProcessSort::Pid,
+    ProcessSort::Name,
+    ProcessSort::Cpu,
+    ProcessSort::MemoryPercent,
+    ProcessSort::Memory,
+    ProcessSort::User,
+    ProcessSort::State,
+    ProcessSort::Threads,
+];
+
+pub struct EnvironmentPopup {
+    pub pid: u32,
+    pub name: String,
+    pub entries: Result<Vec<(String, String)>, String>,
+    // Index of the first entry shown; entries wrap, so lines are not uniform
+    pub scroll: usize,
+    pub reveal_secrets: bool,
+}
+
+pub struct OpenFilesPopup {
+    pub pid: u32,
+    pub name: String,
+    pub files: Result<sys_info::OpenFiles, String>,
+    // First line shown, counting the group headings
+    pub scroll: usize,
+}
+
+impl OpenFilesPopup {
+    /// The popup's lines: a heading per kind, a line per descriptor and one
+    /// for those beyond the limit.
+    pub fn line_count(&self) -> usize {
+        let Ok(files) = &self.files else {
+            return 1;
+        };
+        let groups = files.files.chunk_by(|a, b| a.kind == b.kind).count();
+        groups + files.files.len() + usize::from(files.total > files.files.len())
+    }
+}
+
+/// A process an action popup was opened for. Captured when the popup
+/// opens so a refresh re-sorting the list cannot retarget it.
+pub struct ActionTarget {
+    pub pid: u32,
+    pub name: String,
+    pub user: String,
+}
+
+impl ActionTarget {
+    /// Init and xtop itself need an extra confirmation before being signalled.
+    pub fn is_critical(&self) -> bool {
+        self.pid == 1 || self.pid == std::process::id()
+    }
+}
+
+/// A modal popup that takes over key handling until it is confirmed or
+/// cancelled.
+pub enum PendingAction {
+    Kill {
+        // The tagged processes, or the selected one when none are tagged
+        targets: Vec<ActionTarget>,
+        signal: Signal,
+        // Set after the first confirmation of a critical target
+        armed: bool,
+    },
+    SignalPicker {
+        targets: Vec<ActionTarget>,
+        query: String,
+        // Index into the signals matching the query
+        selected: usize,
+        armed: bool,
+    },
+}
+
+impl PendingAction {
+    pub fn targets(&self) -> &[ActionTarget] {
+        match self {
+            PendingAction::Kill { targets, .. } | PendingAction::SignalPicker { targets, .. } => {
+                targets
+            }
+        }
+    }
+
+    pub fn is_armed(&self) -> bool {
+        match self {
+            PendingAction::Kill { armed, .. } | PendingAction::SignalPicker { armed, .. } => *armed,
+        }
+    }
+
+    /// The signal that confirming would send.
+    pub fn signal(&self) -> Option<Signal> {
+        match self {
+            PendingAction::Kill { signal, .. } => Some(*signal),
+            PendingAction::SignalPicker {
+                query, selected, ..
+            } => filter_signals(query).get(*selected).copied(),
+        }
+    }
+}
+
+/// Signals whose name or number matches what was typed in the picker.
+pub fn filter_signals(query: &str) -> Vec<Signal> {
+    let query = query.to_uppercase();
+    Signal::ALL
+        .into_iter()
+        .filter(|signal| {
+            signal.name().contains(&query)
+                || sys_info::signal_number(*signal)
+                    .is_some_and(|number| number.to_string().starts_with(&query))
+        })
+        .collect()
+}
+
+/// Where things were drawn on the last frame, so mouse clicks can be mapped
+/// back to what was under them. Filled in by the renderer through `&App`.
+#[derive(Debug, Default)]
+pub struct LayoutState {
+    // Everything between the header and the footer
+    pub content: Cell<Option<Rect>>,
+    // Process table rows below the header, one screen line each
+    pub process_rows: Cell<Option<Rect>>,
+    pub process_headers: RefCell<Vec<(Rect, ProcessSort)>>,
+    pub view_tabs: RefCell<Vec<(Rect, View)>>,
+    // Lines the current non-process view overflows its panes by, the most
+    // `scroll_offset` can usefully be
+    pub scroll_limit: Cell<usize>,
+    // The same for the help overlay and `help_scroll`
+    pub help_scroll_limit: Cell<usize>,
+}
+
+const WHEEL_LINES: usize = 3;
+
+// Some ACPI sensors now and then read 0 or 255°C; those are left out of the
+// history so one glitch does not squash the chart's scale
+const PLAUSIBLE_CELSIUS: std::ops::RangeInclusive<f32> = 1.0..=125.0;
+
+/// Selection, scroll position and filters of a view while another one is
+/// shown, so switching back returns to where it was left.
+#[derive(Debug, Default)]
+struct ViewState {
+    selected_pid: Option<u32>,
+    selected_process: usize,
+    process_scroll_offset: usize,
+    show_proc_details: bool,
+    scroll_offset: usize,
+    selected_service: usize,
+    selected_interface: usize,
+    search_query: String,
+    search_mode: SearchMode,
+    container_filter: Option<String>,
+}
+
+pub struct StatusMessage {
+    pub text: String,
+    pub is_error: bool,
+    pub shown_at: Instant,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum View {
+    Overview,
     System,
     Process,
     Resources,
     Network,
     Disks,
     Options,
+    Gpu,
+    Sensors,
+    Services,
+}
+
+impl View {
+    /// The sections of a refresh the view shows, kept up to date every
+    /// tick while it is open.
+    pub fn sections(self) -> &'static [Section] {
+        match self {
+            View::Overview => &[Section::ProcessDetails, Section::Disks],
+            View::Process => &[Section::ProcessDetails],
+            View::Network => &[Section::Interfaces],
+            View::Disks => &[Section::Disks],
+            View::Sensors => &[Section::Sensors],
+            View::Services => &[Section::Services],
+            View::System | View::Resources | View::Options | View::Gpu => &[],
+        }
+    }
+}
+
+/// What the per-core bars in the System view show.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CpuDisplay {
+    Usage,
+    Frequency,
+    Governor,
+}
+
+/// How far back the Resources charts reach.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChartWindow {
+    HalfMinute,
+    Minute,
+    FiveMinutes,
+    FifteenMinutes,
+}
+
+impl ChartWindow {
+    pub fn duration(self) -> Duration {
+        Duration::from_secs(match self {
+            ChartWindow::HalfMinute => 30,
+            ChartWindow::Minute => 60,
+            ChartWindow::FiveMinutes => 300,
+            ChartWindow::FifteenMinutes => 900,
+        })
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ChartWindow::HalfMinute => "30s",
+            ChartWindow::Minute => "1m",
+            ChartWindow::FiveMinutes => "5m",
+            ChartWindow::FifteenMinutes => "15m",
+        }
+    }
+
+    fn narrower(self) -> Self {
+        match self {
+            ChartWindow::HalfMinute | ChartWindow::Minute => ChartWindow::HalfMinute,
+            ChartWindow::FiveMinutes => ChartWindow::Minute,
+            ChartWindow::FifteenMinutes => ChartWindow::FiveMinutes,
+        }
+    }
+
+    fn wider(self) -> Self {
+        match self {
+            ChartWindow::HalfMinute => ChartWindow::Minute,
+            ChartWindow::Minute => ChartWindow::FiveMinutes,
+            ChartWindow::FiveMinutes | ChartWindow::FifteenMinutes => ChartWindow::FifteenMinutes,
+        }
+    }
+}
+
+/// A row of the Options view that can be changed in place.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Setting {
+    UpdateInterval,
+    Paused,
+    FullCommand,
+    TreeView,
+    Aggregation,
+    HideKernelThreads,
+    OnlyMine,
+    SortColumn,
+    SortReverse,
+    Theme,
+    Units,
+}
+
+/// The Options view rows, top to bottom.
+pub const SETTINGS: [Setting; 11] = [
+    Setting::UpdateInterval,
+    Setting::Paused,
+    Setting::FullCommand,
+    Setting::TreeView,
+    Setting::Aggregation,
+    Setting::HideKernelThreads,
+    Setting::OnlyMine,
+    Setting::SortColumn,
+    Setting::SortReverse,
+    Setting::Theme,
+    Setting::Units,
+];
+
+const INTERVAL_STEP: Duration = Duration::from_millis(250);
+
+/// Whether keys drive the UI or are typed into an input line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InputMode {
+    Normal,
+    Search,
+}
+
+/// One line of the process table.
+pub struct ProcessRow<'a> {
+    // Borrowed for real processes, owned for the summary line of a group
+    pub process: Cow<'a, ProcessInfo>,
+    // Tree guides drawn before the name
+    pub prefix: String,
+    // Number of processes summed into an aggregated group line
+    pub group_size: Option<usize>,
+}
+
+impl<'a> ProcessRow<'a> {
+    fn new(process: &'a ProcessInfo, prefix: String) -> Self {
+        Self {
+            process: Cow::Borrowed(process),
+            prefix,
+            group_size: None,
+        }
+    }
+}
+
+/// Whether a search hides non-matching processes or only highlights matches.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum SearchMode {
+    #[default]
+    Filter,
+    Highlight,
 }
 
 pub struct App {
@@ -18,108 +355,399 @@ pub struct App {
     pub scroll_offset: usize,
     pub process_scroll_offset: usize,
     pub selected_process: usize,
+    // The PID on the selected row, so re-sorting cannot move the selection
+    // onto a different process
+    pub selected_pid: Option<u32>,
     pub show_help: bool,
+    // First help line shown; the help is taller than small terminals
+    pub help_scroll: usize,
     pub paused: bool,
+    // Collection target while paused; `metrics` keeps the frozen snapshot
+    live_metrics: Option<SystemInfo>,
     pub update_interval: Duration,
     pub last_update: Instant,
+    // How far back the system histories reach
+    pub history_length: Duration,
     pub process_sort: ProcessSort,
+    pub cpu_display: CpuDisplay,
+    // Per-core history charts in place of the System view's core bars
+    pub show_core_graphs: bool,
+    pub chart_window: ChartWindow,
+    // Always already reduced to color_depth
+    pub theme: Theme,
+    pub theme_name: String,
+    pub color_depth: ColorDepth,
+    // KiB/MiB/GiB or KB/MB/GB for every size shown
+    pub units: UnitSystem,
+    pub keys: KeyBindings,
+    // Digits typed before a motion repeat it, as in `5j`, instead of
+    // switching views straight away
+    pub count_prefix: bool,
+    // Process table columns kept however narrow the terminal gets
+    pub pinned_columns: Vec<Column>,
+    // The line of view tabs under the header
+    pub show_tab_bar: bool,
     pub sort_reverse: bool,
     pub show_full_command: bool,
     pub show_tree_view: bool,
     pub show_proc_details: bool,
     pub proc_aggregated: bool,
+    // Names of aggregated groups shown with their member PIDs
+    pub expanded_groups: HashSet<String>,
+    // Processes marked for batch kill, signal and renice; kept across
+    // re-sorts and pruned when they exit
+    pub tagged_pids: HashSet<u32>,
+    // CPU and RSS samples behind the detail pane sparklines
+    pub process_history: ProcessHistory,
     pub max_processes: usize,
+    // Header rates are highlighted above this multiple of their recent average
+    pub spike_factor: f64,
+    pub container_filter: Option<String>,
+    pub hide_kernel_threads: bool,
+    // Lists only processes owned by `username`
+    pub only_mine: bool,
+    pub username: String,
+    pub input_mode: InputMode,
+    // Case-insensitive filter on name and full command; empty shows everything
+    pub search_query: String,
+    pub search_mode: SearchMode,
+    pub selected_service: usize,
+    // Network interface whose history is charted
+    pub selected_interface: usize,
+    // Index into SETTINGS of the Options view's cursor
+    pub selected_setting: usize,
+    pub services_failed_first: bool,
+    pub show_pseudo_filesystems: bool,
+    pub expand_pools: bool,
+    pub environment: Option<EnvironmentPopup>,
+    pub open_files: Option<OpenFilesPopup>,
+    pub pending_action: Option<PendingAction>,
+    pub status: Option<StatusMessage>,
+    pub layout: LayoutState,
+    // Saved state of the views not currently shown
+    view_states: HashMap<View, ViewState>,
+    // A JSON snapshot still being written
+    export: Option<JoinHandle<io::Result<PathBuf>>>,
+    // Set by --log-metrics, and dropped after a write fails
+    pub metrics_log: Option<MetricsLog>,
+    pub alerts: Alerts,
+    pub events: EventLog,
+    pub show_events: bool,
+    pub events_scroll: usize,
+    // Desktop notifications for the rules that ask for them
+    notifier: Notifier,
+    // The commands rules run when they fire
+    pub commands: CommandRunner,
+    source: Source,
+}
+
+/// Where snapshots come from: this machine, or an `xtop --serve` elsewhere.
+pub enum Source {
+    Local(Box<Collector>),
+    Remote(Client),
+}
+
+impl Source {
+    // Brings `metrics` up to date, the `visible` sections included, returning
+    // false when there is nothing new
+    fn refresh(&mut self, metrics: &mut SystemInfo, visible: &[Section]) -> bool {
+        match self {
+            Source::Local(collector) => {
+                collector.refresh_for(metrics, visible);
+                true
+            }
+            Source::Remote(client) => match client.take_snapshot() {
+                Some(fresh) => {
+                    metrics.update_from(fresh);
+                    true
+                }
+                None => false,
+            },
+        }
+    }
+
+    // Refreshes the `visible` sections that are behind, returning whether
+    // any were. A server always sends everything.
+    fn catch_up(&mut self, metrics: &mut SystemInfo, visible: &[Section]) -> bool {
+        match self {
+            Source::Local(collector) => collector.catch_up(metrics, visible),
+            Source::Remote(_) => false,
+        }
+    }
 }
 
 impl Default for App {
     fn default() -> Self {
+        let mut collector = Collector::new();
+        let mut metrics = SystemInfo::default();
+        collector.refresh(&mut metrics);
+        // Start the charts from the first snapshot rather than empty
+        record_history(&mut metrics, history::DEFAULT_CAPACITY);
         Self {
-            current_view: View::System,
-            metrics: SystemInfo::default(),
+            current_view: View::Overview,
+            metrics,
             scroll_offset: 0,
             process_scroll_offset: 0,
             selected_process: 0,
+            selected_pid: None,
             show_help: false,
+            help_scroll: 0,
             paused: false,
+            live_metrics: None,
             update_interval: Duration::from_millis(1000),
             last_update: Instant::now(),
+            history_length: Duration::from_secs(600),
             process_sort: ProcessSort::Cpu,
+            cpu_display: CpuDisplay::Usage,
+            show_core_graphs: false,
+            chart_window: ChartWindow::Minute,
+            theme: Theme::default(),
+            theme_name: "default".to_string(),
+            color_depth: ColorDepth::TrueColor,
+            units: UnitSystem::default(),
+            keys: KeyBindings::default(),
+            count_prefix: false,
+            show_tab_bar: true,
+            pinned_columns: Vec::new(),
             sort_reverse: true,
             show_full_command: false,
             show_tree_view: false,
             show_proc_details: false,
             proc_aggregated: false,
+            expanded_groups: HashSet::new(),
+            tagged_pids: HashSet::new(),
+            process_history: ProcessHistory::default(),
             max_processes: 20,
+            spike_factor: 3.0,
+            container_filter: None,
+            hide_kernel_threads: false,
+            only_mine: false,
+            username: whoami::username(),
+            input_mode: InputMode::Normal,
+            search_query: String::new(),
+            search_mode: SearchMode::Filter,
+            selected_service: 0,
+            selected_interface: 0,
+            selected_setting: 0,
+            services_failed_first: true,
+            show_pseudo_filesystems: false,
+            expand_pools: false,
+            environment: None,
+            open_files: None,
+            pending_action: None,
+            status: None,
+            layout: LayoutState::default(),
+            view_states: HashMap::new(),
+            export: None,
+            metrics_log: None,
+            alerts: Alerts::default(),
+            events: EventLog::default(),
+            show_events: false,
+            events_scroll: 0,
+            notifier: Notifier::default(),
+            commands: CommandRunner::default(),
+            source: Source::Local(Box::new(collector)),
         }
     }
 }
 
 impl App {
     pub fn update_metrics(&mut self) {
-        if self.paused || Instant::now().duration_since(self.last_update) < self.update_interval {
+        if Instant::now().duration_since(self.last_update) < self.update_interval {
             return;
         }
         self.last_update = Instant::now();
-        for usage in &mut self.metrics.cpu_usage_per_core {
-            let change = rand::random::<u64>() % 10;
-            let direction = if rand::random::<bool>() { 1 } else { -1 };
-            *usage = (*usage as i64 + change as i64 * direction).clamp(0, 100) as u64;
-        }
-        self.metrics.cpu_total_usage =
-            self.metrics.cpu_usage_per_core.iter().sum::<u64>() / self.metrics.cpu_count as u64;
-        let mem_change = rand::random::<u64>() % 50;
-        let mem_direction = if rand::random::<bool>() { 1 } else { -1 };
-        self.metrics.memory_used = (self.metrics.memory_used as i64
-            + mem_change as i64 * mem_direction)
-            .clamp(0, self.metrics.memory_total as i64) as u64;
-        self.metrics.total_rx = (self.metrics.total_rx as i64 + rand::random::<i64>() % 200 - 100)
-            .clamp(0, 5000) as u64;
-        self.metrics.total_tx =
-            (self.metrics.total_tx as i64 + rand::random::<i64>() % 100 - 50).clamp(0, 2500) as u64;
-        self.metrics.cpu_history.remove(0);
-        self.metrics.cpu_history.push(self.metrics.cpu_total_usage);
-        self.metrics.memory_history.remove(0);
-        let mem_percent =
-            (self.metrics.memory_used as f64 / self.metrics.memory_total as f64 * 100.0) as u64;
-        self.metrics.memory_history.push(mem_percent);
-        self.metrics.net_rx_history.remove(0);
-        self.metrics.net_rx_history.push(self.metrics.total_rx);
-        self.metrics.net_tx_history.remove(0);
-        self.metrics.net_tx_history.push(self.metrics.total_tx);
-        for process in &mut self.metrics.processes {
-            let cpu_change = rand::random::<f64>() % 5.0;
-            let cpu_direction = if rand::random::<bool>() { 1.0 } else { -1.0 };
-            process.cpu_usage = (process.cpu_usage + cpu_change * cpu_direction).clamp(0.0, 100.0);
-            let mem_change = rand::random::<u64>() % 10;
-            let mem_direction = if rand::random::<bool>() { 1 } else { -1 };
-            process.memory_usage = (process.memory_usage as i64 + mem_change as i64 * mem_direction)
-                .clamp(0, 2000) as u64;
+        let capacity = self.history_capacity();
+        let visible = self.current_view.sections();
+        if let Some(live) = &mut self.live_metrics {
+            // Paused: keep sampling so the histories have no hole on resume,
+            // but leave the displayed snapshot alone
+            if self.source.refresh(live, visible) {
+                record_history(live, capacity);
+                self.log_metrics();
+                self.check_alerts();
+                self.record_events();
+            }
+            return;
+        }
+        self.remember_selection();
+        if !self.source.refresh(&mut self.metrics, visible) {
+            return;
         }
+        record_history(&mut self.metrics, capacity);
+        self.log_metrics();
+        self.check_alerts();
+        self.record_events();
+        let processes = &self.metrics.processes;
+        self.tagged_pids
+            .retain(|pid| processes.iter().any(|process| process.pid == *pid));
         self.sort_processes();
+        self.restore_selection();
+        self.sort_services();
+        self.clamp_selection();
+        if let Some((pid, cpu_usage, rss_kb)) = self
+            .selected_process_info()
+            .map(|process| (process.pid, process.cpu_usage, process.rss_kb))
+        {
+            self.process_history.record(pid, cpu_usage, rss_kb);
+        }
+    }
+
+    /// Runs a bound action. Quitting and suspending are left to the event
+    /// loop, which owns the terminal.
+    pub fn perform(&mut self, action: Action) {
+        if action.is_local_only() && self.is_remote() {
+            self.set_status("Not available for a remote host".to_string(), true);
+            return;
+        }
+        if action.is_local_only() && self.metrics.simulated {
+            self.set_status("Not available for simulated processes".to_string(), true);
+            return;
+        }
+        match action {
+            Action::Quit | Action::Suspend => {}
+            // Esc quits only once there is nothing to clear; see the event loop
+            Action::Back | Action::ClearTags => self.clear_tags(),
+            Action::Tag => self.toggle_tag(),
+            Action::View(view) => self.show_view(view),
+            Action::NextView => self.cycle_view(),
+            Action::Down => self.scroll_down(),
+            Action::Up => self.scroll_up(),
+            Action::PageDown => self.scroll_page_down(),
+            Action::PageUp => self.scroll_page_up(),
+            Action::HalfPageDown => self.scroll_half_page_down(),
+            Action::HalfPageUp => self.scroll_half_page_up(),
+            Action::Top => self.scroll_top(),
+            Action::Bottom => self.scroll_bottom(),
+            Action::SlowerUpdates => self.increase_update_delay(),
+            Action::FasterUpdates => self.decrease_update_delay(),
+            Action::Pause => self.toggle_pause(),
+            Action::ResetSelection => self.reset_selection(),
+            Action::Activate => self.activate_selection(),
+            Action::FullCommand => self.toggle_full_command(),
+            Action::ContainerFilter => self.toggle_container_filter(),
+            Action::HideKernelThreads => self.toggle_kernel_threads(),
+            Action::OnlyMine => self.toggle_only_mine(),
+            Action::Environment => self.toggle_environment(),
+            Action::OpenFiles => self.toggle_open_files(),
+            Action::Events => self.toggle_events(),
+            Action::Export => self.export_snapshot(),
+            Action::FailedFirst => self.toggle_services_failed_first(),
+            Action::PseudoFilesystems => self.toggle_pseudo_filesystems(),
+            Action::CpuDisplay => self.cycle_cpu_display(),
+            Action::CoreGraphs => self.show_core_graphs = !self.show_core_graphs,
+            Action::CycleTheme => self.cycle_theme(true),
+            Action::ZoomIn => self.zoom_charts(true),
+            Action::ZoomOut => self.zoom_charts(false),
+            // While matches are highlighted the name key steps through them
+            Action::Sort(ProcessSort::Name) if self.is_highlighting() => self.jump_to_match(true),
+            // A second press switches between absolute memory and its share
+            Action::Sort(ProcessSort::Memory) if self.process_sort == ProcessSort::Memory => {
+                self.change_sort_column(ProcessSort::MemoryPercent)
+            }
+            Action::Sort(ProcessSort::Memory)
+                if self.process_sort == ProcessSort::MemoryPercent =>
+            {
+                self.change_sort_column(ProcessSort::Memory)
+            }
+            Action::Sort(sort) => self.change_sort_column(sort),
+            Action::PreviousMatch => self.jump_to_match(false),
+            // In the Options view the arrows change the selected setting
+            Action::PreviousSortColumn if self.current_view == View::Options => {
+                self.change_setting(false)
+            }
+            Action::NextSortColumn if self.current_view == View::Options => {
+                self.change_setting(true)
+            }
+            Action::PreviousSortColumn => self.cycle_sort_column(false),
+            Action::NextSortColumn => self.cycle_sort_column(true),
+            Action::ReverseSort => self.toggle_sort_direction(),
+            Action::SignalPicker => self.open_signal_picker(),
+            Action::Search => self.start_search(),
+            Action::Help => self.toggle_help(),
+            Action::TreeView => self.toggle_tree_view(),
+            Action::Aggregate => self.toggle_proc_aggregation(),
+            Action::NiceDown => self.renice_selected(-1),
+            Action::NiceUp => self.renice_selected(1),
+            Action::Kill => self.open_kill_prompt(),
+        }
     }
 
     pub fn cycle_view(&mut self) {
-        self.current_view = match self.current_view {
+        let next = match self.current_view {
+            View::Overview => View::System,
             View::System => View::Process,
             View::Process => View::Resources,
             View::Resources => View::Network,
             View::Network => View::Disks,
             View::Disks => View::Options,
-            View::Options => View::System,
+            View::Options => View::Gpu,
+            View::Gpu => View::Sensors,
+            View::Sensors => View::Services,
+            View::Services => View::Overview,
         };
-        self.reset_selection();
+        self.show_view(next);
+    }
+
+    /// Switches to `view`, putting back the selection, scroll position and
+    /// filters it had when last left. Views not shown before start at the
+    /// top, as after `reset_selection`.
+    pub fn show_view(&mut self, view: View) {
+        if self.current_view == view {
+            return;
+        }
+        self.remember_selection();
+        let leaving = ViewState {
+            selected_pid: self.selected_pid,
+            selected_process: self.selected_process,
+            process_scroll_offset: self.process_scroll_offset,
+            show_proc_details: self.show_proc_details,
+            scroll_offset: self.scroll_offset,
+            selected_service: self.selected_service,
+            selected_interface: self.selected_interface,
+            search_query: std::mem::take(&mut self.search_query),
+            search_mode: self.search_mode,
+            container_filter: self.container_filter.take(),
+        };
+        self.view_states.insert(self.current_view, leaving);
+        self.current_view = view;
+        let state = self.view_states.remove(&view).unwrap_or_default();
+        self.selected_pid = state.selected_pid;
+        self.selected_process = state.selected_process;
+        self.process_scroll_offset = state.process_scroll_offset;
+        self.show_proc_details = state.show_proc_details;
+        // Clamped to the content once the view has been drawn
+        self.scroll_offset = state.scroll_offset;
+        self.selected_service = state.selected_service;
+        self.selected_interface = state.selected_interface;
+        self.search_query = state.search_query;
+        self.search_mode = state.search_mode;
+        self.container_filter = state.container_filter;
+        // Its data may have been refreshed only in the background meanwhile
+        let target = self.live_metrics.as_mut().unwrap_or(&mut self.metrics);
+        if self.source.catch_up(target, view.sections()) && self.live_metrics.is_none() {
+            self.sort_processes();
+            self.sort_services();
+        }
+        // The processes may have changed while the view was hidden
+        self.restore_selection();
+        self.clamp_selection();
     }
 
     pub fn reset_selection(&mut self) {
+        self.scroll_offset = 0;
         self.selected_process = 0;
         self.process_scroll_offset = 0;
         self.show_proc_details = false;
+        self.selected_service = 0;
+        self.selected_interface = 0;
     }
 
     pub fn scroll_down(&mut self) {
+        self.clamp_selection();
         match self.current_view {
             View::Process => {
-                if self.selected_process < self.metrics.processes.len() - 1 {
+                if self.selected_process + 1 < self.process_rows().len() {
                     self.selected_process += 1;
                     let visible_rows = self.max_processes;
                     if self.selected_process >= self.process_scroll_offset + visible_rows {
@@ -127,13 +755,27 @@ impl App {
                     }
                 }
             }
+            View::Services => {
+                if self.selected_service + 1 < self.service_count() {
+                    self.selected_service += 1;
+                }
+            }
+            View::Network => {
+                if self.selected_interface + 1 < self.metrics.network_interfaces.len() {
+                    self.selected_interface += 1;
+                }
+            }
+            View::Options => {
+                self.selected_setting = (self.selected_setting + 1).min(SETTINGS.len() - 1);
+            }
             _ => {
-                self.scroll_offset = self.scroll_offset.saturating_add(1);
+                self.scroll_offset = (self.scroll_offset + 1).min(self.layout.scroll_limit.get());
             }
         }
     }
 
     pub fn scroll_up(&mut self) {
+        self.clamp_selection();
         match self.current_view {
             View::Process => {
                 if self.selected_process > 0 {
@@ -143,6 +785,15 @@ impl App {
                     }
                 }
             }
+            View::Services => {
+                self.selected_service = self.selected_service.saturating_sub(1);
+            }
+            View::Network => {
+                self.selected_interface = self.selected_interface.saturating_sub(1);
+            }
+            View::Options => {
+                self.selected_setting = self.selected_setting.saturating_sub(1);
+            }
             _ => {
                 self.scroll_offset = self.scroll_offset.saturating_sub(1);
             }
@@ -150,39 +801,78 @@ impl App {
     }
 
     pub fn scroll_page_down(&mut self) {
+        self.scroll_rows_down(self.max_processes, 10);
+    }
+
+    pub fn scroll_page_up(&mut self) {
+        self.scroll_rows_up(self.max_processes, 10);
+    }
+
+    pub fn scroll_half_page_down(&mut self) {
+        self.scroll_rows_down((self.max_processes / 2).max(1), 5);
+    }
+
+    pub fn scroll_half_page_up(&mut self) {
+        self.scroll_rows_up((self.max_processes / 2).max(1), 5);
+    }
+
+    // `rows` moves list selections, `lines` the plain scroll offset
+    fn scroll_rows_down(&mut self, rows: usize, lines: usize) {
+        self.clamp_selection();
         match self.current_view {
             View::Process => {
-                let page_size = self.max_processes;
+                let process_count = self.process_rows().len();
                 self.selected_process =
-                    (self.selected_process + page_size).min(self.metrics.processes.len() - 1);
-                self.process_scroll_offset = (self.process_scroll_offset + page_size)
-                    .min(self.metrics.processes.len().saturating_sub(page_size));
+                    (self.selected_process + rows).min(process_count.saturating_sub(1));
+                self.process_scroll_offset = (self.process_scroll_offset + rows)
+                    .min(process_count.saturating_sub(self.max_processes));
+            }
+            View::Services => {
+                self.selected_service =
+                    (self.selected_service + rows).min(self.service_count().saturating_sub(1));
+            }
+            View::Options => {
+                self.selected_setting = (self.selected_setting + rows).min(SETTINGS.len() - 1);
             }
             _ => {
-                self.scroll_offset = self.scroll_offset.saturating_add(10);
+                self.scroll_offset =
+                    (self.scroll_offset + lines).min(self.layout.scroll_limit.get());
             }
         }
     }
 
-    pub fn scroll_page_up(&mut self) {
+    fn scroll_rows_up(&mut self, rows: usize, lines: usize) {
+        self.clamp_selection();
         match self.current_view {
             View::Process => {
-                let page_size = self.max_processes;
-                self.selected_process = self.selected_process.saturating_sub(page_size);
-                self.process_scroll_offset = self.process_scroll_offset.saturating_sub(page_size);
+                self.selected_process = self.selected_process.saturating_sub(rows);
+                self.process_scroll_offset = self.process_scroll_offset.saturating_sub(rows);
+            }
+            View::Services => {
+                self.selected_service = self.selected_service.saturating_sub(rows);
+            }
+            View::Options => {
+                self.selected_setting = self.selected_setting.saturating_sub(rows);
             }
             _ => {
-                self.scroll_offset = self.scroll_offset.saturating_sub(10);
+                self.scroll_offset = self.scroll_offset.saturating_sub(lines);
             }
         }
     }
 
     pub fn scroll_top(&mut self) {
+        self.clamp_selection();
         match self.current_view {
             View::Process => {
                 self.selected_process = 0;
                 self.process_scroll_offset = 0;
             }
+            View::Services => {
+                self.selected_service = 0;
+            }
+            View::Options => {
+                self.selected_setting = 0;
+            }
             _ => {
                 self.scroll_offset = 0;
             }
@@ -190,99 +880,2208 @@ impl App {
     }
 
     pub fn scroll_bottom(&mut self) {
+        self.clamp_selection();
         match self.current_view {
             View::Process => {
-                self.selected_process = self.metrics.processes.len() - 1;
+                let process_count = self.process_rows().len();
+                self.selected_process = process_count.saturating_sub(1);
                 let visible_rows = self.max_processes;
-                self.process_scroll_offset =
-                    self.metrics.processes.len().saturating_sub(visible_rows);
+                self.process_scroll_offset = process_count.saturating_sub(visible_rows);
+            }
+            View::Services => {
+                self.selected_service = self.service_count().saturating_sub(1);
+            }
+            View::Options => {
+                self.selected_setting = SETTINGS.len() - 1;
+            }
+            _ => {
+                self.scroll_offset = self.layout.scroll_limit.get();
             }
-            _ => {}
         }
     }
 
-    pub fn toggle_pause(&mut self) {
-        self.paused = !self.paused;
+    /// Enter opens process details, jumps from a service to its main PID,
+    /// expands btrfs/ZFS pools into their mounts or changes a setting.
+    pub fn activate_selection(&mut self) {
+        match self.current_view {
+            View::Services => self.jump_to_service_process(),
+            View::Options => self.change_setting(true),
+            View::Disks => self.expand_pools = !self.expand_pools,
+            View::Process | View::Overview if self.selected_group().is_some() => {
+                self.toggle_group_expansion()
+            }
+            _ => self.toggle_process_details(),
+        }
     }
 
-    pub fn toggle_help(&mut self) {
-        self.show_help = !self.show_help;
+    /// Steps the setting under the Options cursor: booleans toggle, the sort
+    /// column cycles and the update interval moves by 250ms.
+    pub fn change_setting(&mut self, forward: bool) {
+        match SETTINGS[self.selected_setting] {
+            Setting::UpdateInterval => {
+                let interval = if forward {
+                    self.update_interval + INTERVAL_STEP
+                } else {
+                    self.update_interval.saturating_sub(INTERVAL_STEP)
+                };
+                self.update_interval =
+                    interval.clamp(Duration::from_millis(250), Duration::from_secs(10));
+            }
+            Setting::Paused => self.toggle_pause(),
+            Setting::FullCommand => self.toggle_full_command(),
+            Setting::TreeView => self.toggle_tree_view(),
+            Setting::Aggregation => self.toggle_proc_aggregation(),
+            Setting::HideKernelThreads => self.toggle_kernel_threads(),
+            Setting::OnlyMine => self.toggle_only_mine(),
+            Setting::SortColumn => self.cycle_sort_column(forward),
+            Setting::SortReverse => self.toggle_sort_direction(),
+            Setting::Theme => self.cycle_theme(forward),
+            Setting::Units => self.units = self.units.toggled(),
+        }
     }
 
-    pub fn toggle_process_details(&mut self) {
-        self.show_proc_details = !self.show_proc_details;
+    /// Moves to the next or previous built-in theme. A theme loaded from a
+    /// file is left for the first or last built-in one.
+    pub fn cycle_theme(&mut self, forward: bool) {
+        let count = theme::BUILT_IN.len();
+        let next = match theme::BUILT_IN
+            .iter()
+            .position(|&name| name == self.theme_name)
+        {
+            Some(index) if forward => (index + 1) % count,
+            Some(index) => (index + count - 1) % count,
+            None if forward => 0,
+            None => count - 1,
+        };
+        let name = theme::BUILT_IN[next];
+        if let Some(theme) = Theme::named(name) {
+            self.set_theme(name, theme);
+        }
     }
 
-    pub fn toggle_full_command(&mut self) {
-        self.show_full_command = !self.show_full_command;
+    /// Reduces the current theme, and any chosen later, to `depth` colors.
+    pub fn set_color_depth(&mut self, depth: ColorDepth) {
+        self.color_depth = depth;
+        self.theme = self.theme.clone().with_depth(depth);
     }
 
-    pub fn toggle_tree_view(&mut self) {
-        self.show_tree_view = !self.show_tree_view;
+    /// Switches to `theme`, reduced to the colors the terminal has. The
+    /// configured thresholds carry over.
+    pub fn set_theme(&mut self, name: &str, theme: Theme) {
+        self.theme = Theme {
+            thresholds: self.theme.thresholds,
+            ..theme.with_depth(self.color_depth)
+        };
+        self.theme_name = name.to_string();
     }
 
-    pub fn toggle_proc_aggregation(&mut self) {
-        self.proc_aggregated = !self.proc_aggregated;
+    fn jump_to_service_process(&mut self) {
+        let Some(pid) = self
+            .metrics
+            .services
+            .as_ref()
+            .and_then(|services| services.get(self.selected_service))
+            .and_then(|service| service.main_pid)
+        else {
+            return;
+        };
+        if !self
+            .metrics
+            .processes
+            .iter()
+            .any(|process| process.pid == pid)
+        {
+            return;
+        }
+        self.show_view(View::Process);
+        // Nothing the Processes view was left with may hide the service
+        self.container_filter = None;
+        self.search_query.clear();
+        self.hide_kernel_threads = false;
+        self.only_mine = false;
+        self.proc_aggregated = false;
+        self.selected_pid = Some(pid);
+        self.restore_selection();
     }
 
-    pub fn increase_update_delay(&mut self) {
-        self.update_interval = (self.update_interval * 2).min(Duration::from_secs(10));
+    pub fn toggle_services_failed_first(&mut self) {
+        self.services_failed_first = !self.services_failed_first;
+        self.sort_services();
     }
 
-    pub fn decrease_update_delay(&mut self) {
-        self.update_interval = (self.update_interval / 2).max(Duration::from_millis(250));
+    fn service_count(&self) -> usize {
+        self.metrics.services.as_ref().map_or(0, Vec::len)
     }
 
-    pub fn change_sort_column(&mut self, sort: ProcessSort) {
-        if self.process_sort == sort {
-            self.sort_reverse = !self.sort_reverse;
-        } else {
-            self.process_sort = sort;
-            self.sort_reverse = matches!(sort, ProcessSort::Cpu | ProcessSort::Memory);
+    fn sort_services(&mut self) {
+        if let Some(services) = &mut self.metrics.services {
+            services.sort_by(|a, b| a.name.cmp(&b.name));
+            if self.services_failed_first {
+                services.sort_by_key(|service| !service.is_failed());
+            }
         }
-        self.sort_processes();
-        self.reset_selection();
+        self.selected_service = self
+            .selected_service
+            .min(self.service_count().saturating_sub(1));
     }
 
-    fn sort_processes(&mut self) {
-        match self.process_sort {
-            ProcessSort::Pid => {
-                self.metrics.processes.sort_by(|a, b| a.pid.cmp(&b.pid));
-            }
-            ProcessSort::Name => {
-                self.metrics.processes.sort_by(|a, b| a.name.cmp(&b.name));
-            }
-            ProcessSort::Cpu => {
-                self.metrics
-                    .processes
-                    .sort_by(|a, b| b.cpu_usage.partial_cmp(&a.cpu_usage).unwrap());
-            }
-            ProcessSort::Memory => {
-                self.metrics
-                    .processes
-                    .sort_by(|a, b| b.memory_usage.cmp(&a.memory_usage));
-            }
-            ProcessSort::User => {
-                self.metrics.processes.sort_by(|a, b| a.user.cmp(&b.user));
-            }
-            ProcessSort::Time => {
-                self.metrics
-                    .processes
-                    .sort_by(|a, b| b.uptime.cmp(&a.uptime));
-            }
-            ProcessSort::Threads => {
-                self.metrics
-                    .processes
-                    .sort_by(|a, b| b.threads.cmp(&a.threads));
-            }
-            ProcessSort::State => {
-                self.metrics
-                    .processes
-                    .sort_by(|a, b| a.state.to_string().cmp(&b.state.to_string()));
+    pub fn toggle_pseudo_filesystems(&mut self) {
+        self.show_pseudo_filesystems = !self.show_pseudo_filesystems;
+    }
+
+    /// Disks shown in the Disks view; tmpfs, overlay and loop mounts are
+    /// hidden unless toggled on.
+    pub fn visible_disks(&self) -> Vec<&DiskInfo> {
+        self.metrics
+            .disks
+            .iter()
+            .filter(|disk| self.show_pseudo_filesystems || !disk.is_pseudo())
+            .collect()
+    }
+
+    pub fn toggle_environment(&mut self) {
+        if self.environment.take().is_some() {
+            return;
+        }
+        let Some(process) = self.selected_process_info() else {
+            return;
+        };
+        let entries = sys_info::read_environment(process.pid).map_err(|err| describe_error(&err));
+        self.environment = Some(EnvironmentPopup {
+            pid: process.pid,
+            name: process.name.clone(),
+            entries,
+            scroll: 0,
+            reveal_secrets: false,
+        });
+    }
+
+    pub fn scroll_environment(&mut self, delta: isize) {
+        if let Some(popup) = &mut self.environment {
+            let count = popup.entries.as_ref().map_or(0, Vec::len);
+            popup.scroll = popup
+                .scroll
+                .saturating_add_signed(delta)
+                .min(count.saturating_sub(1));
+        }
+    }
+
+    pub fn toggle_open_files(&mut self) {
+        if self.open_files.take().is_some() {
+            return;
+        }
+        let Some(process) = self.selected_process_info() else {
+            return;
+        };
+        let files = sys_info::read_open_files(process.pid).map_err(|err| describe_error(&err));
+        self.open_files = Some(OpenFilesPopup {
+            pid: process.pid,
+            name: process.name.clone(),
+            files,
+            scroll: 0,
+        });
+    }
+
+    pub fn scroll_open_files(&mut self, delta: isize) {
+        if let Some(popup) = &mut self.open_files {
+            popup.scroll = popup
+                .scroll
+                .saturating_add_signed(delta)
+                .min(popup.line_count().saturating_sub(1));
+        }
+    }
+
+    pub fn toggle_environment_secrets(&mut self) {
+        if let Some(popup) = &mut self.environment {
+            popup.reveal_secrets = !popup.reveal_secrets;
+        }
+    }
+
+    pub fn cycle_cpu_display(&mut self) {
+        self.cpu_display = match self.cpu_display {
+            CpuDisplay::Usage => CpuDisplay::Frequency,
+            CpuDisplay::Frequency => CpuDisplay::Governor,
+            CpuDisplay::Governor => CpuDisplay::Usage,
+        };
+    }
+
+    /// Narrows or widens the Resources charts' time window. The keys are
+    /// left alone elsewhere so they stay free for other views.
+    pub fn zoom_charts(&mut self, zoom_in: bool) {
+        if self.current_view != View::Resources {
+            return;
+        }
+        self.chart_window = if zoom_in {
+            self.chart_window.narrower()
+        } else {
+            self.chart_window.wider()
+        };
+    }
+
+    /// The tagged processes in PID order, or else the selected one.
+    fn selected_targets(&mut self) -> Vec<ActionTarget> {
+        if !matches!(self.current_view, View::Process | View::Overview) {
+            return Vec::new();
+        }
+        let target = |process: &ProcessInfo| ActionTarget {
+            pid: process.pid,
+            name: process.name.clone(),
+            user: process.user.clone(),
+        };
+        if !self.tagged_pids.is_empty() {
+            let mut targets: Vec<ActionTarget> = self
+                .metrics
+                .processes
+                .iter()
+                .filter(|process| self.tagged_pids.contains(&process.pid))
+                .map(target)
+                .collect();
+            targets.sort_by_key(|target| target.pid);
+            return targets;
+        }
+        let Some(process) = self.selected_process_info() else {
+            if self.selected_group().is_some() {
+                self.set_status(
+                    "Press Enter to expand the group and pick a process".to_string(),
+                    true,
+                );
             }
+            return Vec::new();
+        };
+        vec![target(process)]
+    }
+
+    /// Tags or untags the selected process and moves down, so a run of
+    /// processes can be tagged by holding the key.
+    pub fn toggle_tag(&mut self) {
+        if self.current_view != View::Process {
+            return;
         }
-        if !self.sort_reverse {
-            self.metrics.processes.reverse();
+        let Some(pid) = self.selected_process_info().map(|process| process.pid) else {
+            return;
+        };
+        if !self.tagged_pids.remove(&pid) {
+            self.tagged_pids.insert(pid);
+        }
+        self.scroll_down();
+        self.remember_selection();
+    }
+
+    pub fn clear_tags(&mut self) {
+        self.tagged_pids.clear();
+    }
+
+    /// Opens the SIGTERM/SIGKILL confirmation for the tagged or selected
+    /// processes.
+    pub fn open_kill_prompt(&mut self) {
+        let targets = self.selected_targets();
+        if !targets.is_empty() {
+            self.pending_action = Some(PendingAction::Kill {
+                targets,
+                signal: Signal::Term,
+                armed: false,
+            });
+        }
+    }
+
+    pub fn open_signal_picker(&mut self) {
+        if self.current_view != View::Process {
+            return;
         }
+        let targets = self.selected_targets();
+        if !targets.is_empty() {
+            self.pending_action = Some(PendingAction::SignalPicker {
+                targets,
+                query: String::new(),
+                selected: 0,
+                armed: false,
+            });
+        }
+    }
+
+    pub fn select_kill_signal(&mut self, selected: Signal) {
+        if let Some(PendingAction::Kill { signal, armed, .. }) = &mut self.pending_action {
+            *signal = selected;
+            *armed = false;
+        }
+    }
+
+    pub fn toggle_kill_signal(&mut self) {
+        if let Some(PendingAction::Kill { signal, .. }) = &self.pending_action {
+            self.select_kill_signal(match signal {
+                Signal::Term => Signal::Kill,
+                _ => Signal::Term,
+            });
+        }
+    }
+
+    pub fn move_signal_selection(&mut self, delta: isize) {
+        if let Some(PendingAction::SignalPicker {
+            query,
+            selected,
+            armed,
+            ..
+        }) = &mut self.pending_action
+        {
+            let count = filter_signals(query).len();
+            *selected = selected
+                .saturating_add_signed(delta)
+                .min(count.saturating_sub(1));
+            *armed = false;
+        }
+    }
+
+    pub fn edit_signal_query(&mut self, edit: impl FnOnce(&mut String)) {
+        if let Some(PendingAction::SignalPicker {
+            query,
+            selected,
+            armed,
+            ..
+        }) = &mut self.pending_action
+        {
+            edit(query);
+            *selected = 0;
+            *armed = false;
+        }
+    }
+
+    pub fn cancel_pending_action(&mut self) {
+        self.pending_action = None;
+    }
+
+    pub fn confirm_pending_action(&mut self) {
+        let Some(mut action) = self.pending_action.take() else {
+            return;
+        };
+        if self.metrics.simulated {
+            self.set_status("Not available for simulated processes".to_string(), true);
+            return;
+        }
+        let Some(signal) = action.signal() else {
+            // Nothing matches the query; keep the picker open
+            self.pending_action = Some(action);
+            return;
+        };
+        if action.targets().iter().any(ActionTarget::is_critical) && !action.is_armed() {
+            match &mut action {
+                PendingAction::Kill { armed, .. } | PendingAction::SignalPicker { armed, .. } => {
+                    *armed = true
+                }
+            }
+            self.pending_action = Some(action);
+            return;
+        }
+        if let [target] = action.targets() {
+            self.send_signal(target, signal);
+            return;
+        }
+        let targets = action.targets();
+        let failures: Vec<String> = targets
+            .iter()
+            .filter_map(|target| {
+                sys_info::send_signal(target.pid, signal)
+                    .err()
+                    .map(|err| format!("{}: {}", target.pid, describe_error(&err)))
+            })
+            .collect();
+        self.report_batch(
+            format!("Sent {} to", signal.name()),
+            targets.len(),
+            failures,
+        );
+    }
+
+    /// Adjusts the nice value of the tagged or selected processes by
+    /// `delta`; F7 lowers it (higher priority) and F8 raises it, as in htop.
+    pub fn renice_selected(&mut self, delta: i32) {
+        if self.current_view != View::Process {
+            return;
+        }
+        // Neither the renice nor the read back would reach the process shown
+        if self.metrics.simulated {
+            self.set_status("Not available for simulated processes".to_string(), true);
+            return;
+        }
+        let processes: Vec<(u32, String, i32)> = if self.tagged_pids.is_empty() {
+            self.selected_process_info()
+                .map(|process| (process.pid, process.name.clone(), process.nice))
+                .into_iter()
+                .collect()
+        } else {
+            self.metrics
+                .processes
+                .iter()
+                .filter(|process| self.tagged_pids.contains(&process.pid))
+                .map(|process| (process.pid, process.name.clone(), process.nice))
+                .collect()
+        };
+        let mut failures = Vec::new();
+        let mut changed = None;
+        for (pid, _, nice) in &processes {
+            let new_nice = (nice + delta).clamp(-20, 19);
+            if new_nice == *nice {
+                continue;
+            }
+            match sys_info::set_nice(*pid, new_nice) {
+                Ok(()) => {
+                    // Show what the kernel now reports rather than what was asked for
+                    let actual = sys_info::get_nice(*pid).unwrap_or(new_nice);
+                    if let Some(process) = self
+                        .metrics
+                        .processes
+                        .iter_mut()
+                        .find(|process| process.pid == *pid)
+                    {
+                        process.nice = actual;
+                    }
+                    changed = Some(actual);
+                }
+                Err(err) => {
+                    let reason =
+                        if err.kind() == io::ErrorKind::PermissionDenied && new_nice < *nice {
+                            "need root to raise priority".to_string()
+                        } else {
+                            describe_error(&err)
+                        };
+                    failures.push(format!("{}: {}", pid, reason));
+                }
+            }
+        }
+        match (processes.as_slice(), changed, failures.first()) {
+            ([(pid, name, _)], Some(actual), _) => self.set_status(
+                format!("Set nice of {} ({}) to {}", name, pid, actual),
+                false,
+            ),
+            ([_], None, Some(failure)) => {
+                self.set_status(format!("Failed to renice {}", failure), true)
+            }
+            ([], ..) | ([_], ..) => {}
+            (processes, ..) => self.report_batch("Reniced".to_string(), processes.len(), failures),
+        }
+    }
+
+    // Summarises an action applied to several tagged processes
+    fn report_batch(&mut self, done: String, count: usize, failures: Vec<String>) {
+        match failures.first() {
+            None => self.set_status(format!("{} {} processes", done, count), false),
+            Some(first) => self.set_status(
+                format!(
+                    "{} {} of {} processes; {} failed ({})",
+                    done,
+                    count - failures.len(),
+                    count,
+                    failures.len(),
+                    first
+                ),
+                true,
+            ),
+        }
+    }
+
+    fn send_signal(&mut self, target: &ActionTarget, signal: Signal) {
+        match sys_info::send_signal(target.pid, signal) {
+            Ok(()) => self.set_status(
+                format!("Sent {} to {} ({})", signal.name(), target.name, target.pid),
+                false,
+            ),
+            Err(err) => self.set_status(
+                format!(
+                    "Failed to send {} to {}: {}",
+                    signal.name(),
+                    target.pid,
+                    describe_error(&err)
+                ),
+                true,
+            ),
+        }
+    }
+
+    /// Shows `client`'s host from now on, starting from its first snapshot.
+    pub fn connect(&mut self, client: Client, first: SystemInfo) {
+        let capacity = self.history_capacity();
+        self.metrics = first;
+        record_history(&mut self.metrics, capacity);
+        self.sort_processes();
+        self.sort_services();
+        self.source = Source::Remote(client);
+    }
+
+    pub fn is_remote(&self) -> bool {
+        matches!(self.source, Source::Remote(_))
+    }
+
+    /// The link to the remote host, or None when watching this machine.
+    pub fn remote_connection(&self) -> Option<Connection> {
+        match &self.source {
+            Source::Local(_) => None,
+            Source::Remote(client) => Some(client.connection()),
+        }
+    }
+
+    /// Appends the latest sample, paused or not, to the metrics log. A
+    /// failed write, such as on a full disk, stops logging for the session.
+    fn log_metrics(&mut self) {
+        let Some(log) = &mut self.metrics_log else {
+            return;
+        };
+        let metrics = self.live_metrics.as_ref().unwrap_or(&self.metrics);
+        if let Err(err) = log.append(metrics, chrono::Local::now()) {
+            let text = format!("Stopped logging to {}: {}", log.path().display(), err);
+            self.metrics_log = None;
+            self.set_status(text, true);
+        }
+    }
+
+    /// Judges the latest sample, paused or not, against the alert rules,
+    /// and reports the ones that start or stop firing.
+    fn check_alerts(&mut self) {
+        let metrics = self.live_metrics.as_ref().unwrap_or(&self.metrics);
+        let now = Instant::now();
+        let transitions = self.alerts.evaluate(metrics, now);
+        let mut statuses = Vec::new();
+        let mut skipped = Vec::new();
+        for transition in transitions {
+            let (index, verb, severity) = match transition {
+                Transition::Fired(index) => (index, "Alert", Severity::Critical),
+                Transition::Resolved(index) => (index, "Resolved", Severity::Normal),
+            };
+            let rule = &self.alerts.rules()[index];
+            let reading = rule.reading(metrics);
+            if rule.notify {
+                self.notifier.send(
+                    &format!("{} on {}: {}", verb, metrics.hostname, rule),
+                    &reading,
+                    now,
+                );
+            }
+            if let (Transition::Fired(_), Some(command)) = (transition, &rule.exec) {
+                // What fired may be no more than the simulation's random walk
+                if metrics.simulated {
+                    skipped.push(format!(
+                        "Did not run `{}`: the metrics are simulated",
+                        command
+                    ));
+                } else {
+                    match self.commands.run(index, command, rule.cooldown, now) {
+                        Ok(()) => {}
+                        Err(Skipped::NotAllowed) => skipped.push(format!(
+                            "Did not run `{}`: commands need --allow-exec",
+                            command
+                        )),
+                        Err(Skipped::CoolingDown(since)) => skipped.push(format!(
+                            "Did not run `{}`: it ran {}s ago",
+                            command,
+                            since.as_secs()
+                        )),
+                    }
+                }
+            }
+            statuses.push((format!("{}: {}", verb, rule), reading, severity));
+        }
+        for (text, reading, severity) in statuses {
+            self.push_event(severity, format!("{} ({})", text, reading));
+            self.set_status(text, severity == Severity::Critical);
+        }
+        for text in skipped {
+            self.push_event(Severity::Warning, text);
+        }
+        if let Some(reason) = self.notifier.take_failure() {
+            self.set_status(format!("Desktop notifications off: {}", reason), true);
+        }
+    }
+
+    /// Adds what the latest sample shows happening, paused or not, to the
+    /// event log: OOM kills and newcomers to the busiest processes.
+    fn record_events(&mut self) {
+        let metrics = self.live_metrics.as_ref().unwrap_or(&self.metrics);
+        let kills: Vec<String> = metrics
+            .oom_kills
+            .iter()
+            .map(|kill| format!("OOM killer ended {} ({})", kill.name, kill.pid))
+            .collect();
+        self.events
+            .watch_top_processes(&metrics.processes, chrono::Local::now());
+        for kill in kills {
+            self.push_event(Severity::Critical, kill);
+        }
+        if self.show_events {
+            self.events.mark_seen();
+        }
+    }
+
+    fn push_event(&mut self, severity: Severity, text: String) {
+        self.events.push(severity, text, chrono::Local::now());
+    }
+
+    /// Logs how the commands started by alerts ended, with their output.
+    pub fn poll_commands(&mut self) {
+        for finished in self.commands.finished() {
+            let (severity, mut text) = match &finished.status {
+                Ok(status) if finished.success => (
+                    Severity::Normal,
+                    format!("Ran `{}`: {}", finished.command, status),
+                ),
+                Ok(status) => (
+                    Severity::Critical,
+                    format!("`{}` failed: {}", finished.command, status),
+                ),
+                Err(err) => (
+                    Severity::Critical,
+                    format!("Could not run `{}`: {}", finished.command, err),
+                ),
+            };
+            for line in &finished.output {
+                text.push('\n');
+                text.push_str(line);
+            }
+            self.push_event(severity, text);
+        }
+        if self.show_events {
+            self.events.mark_seen();
+        }
+    }
+
+    /// Opens or closes the event log, which counts as having seen it all.
+    pub fn toggle_events(&mut self) {
+        self.show_events = !self.show_events;
+        self.events_scroll = 0;
+        self.events.mark_seen();
+    }
+
+    pub fn scroll_events(&mut self, delta: isize) {
+        self.events_scroll = self
+            .events_scroll
+            .saturating_add_signed(delta)
+            .min(self.events.line_count().saturating_sub(1));
+    }
+
+    /// Starts writing the displayed metrics to a JSON file. Only one export
+    /// runs at a time.
+    pub fn export_snapshot(&mut self) {
+        if self.export.is_some() {
+            return;
+        }
+        self.export = Some(snapshot::export(&self.metrics, snapshot::export_dir()));
+    }
+
+    /// Reports a finished export in the status line.
+    pub fn poll_export(&mut self) {
+        if !self.export.as_ref().is_some_and(JoinHandle::is_finished) {
+            return;
+        }
+        match self.export.take().map(JoinHandle::join) {
+            Some(Ok(Ok(path))) => self.set_status(format!("Saved {}", path.display()), false),
+            Some(Ok(Err(err))) => {
+                self.set_status(format!("Failed to save snapshot: {}", err), true)
+            }
+            Some(Err(_)) => self.set_status("Failed to save snapshot".to_string(), true),
+            None => {}
+        }
+    }
+
+    pub fn set_status(&mut self, text: String, is_error: bool) {
+        self.status = Some(StatusMessage {
+            text,
+            is_error,
+            shown_at: Instant::now(),
+        });
+    }
+
+    /// The status message, while it is still fresh enough to show.
+    pub fn current_status(&self) -> Option<&StatusMessage> {
+        self.status
+            .as_ref()
+            .filter(|status| status.shown_at.elapsed() < STATUS_DURATION)
+    }
+
+    /// Freezes the displayed metrics, or resumes with everything collected
+    /// in the meantime.
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        if self.paused {
+            self.live_metrics = Some(self.metrics.clone());
+        } else if let Some(live) = self.live_metrics.take() {
+            self.remember_selection();
+            self.metrics = live;
+            self.sort_processes();
+            self.restore_selection();
+            self.sort_services();
+            self.clamp_selection();
+        }
+    }
+
+    /// Adopts the process table height from the last draw, so scrolling
+    /// and paging move by the rows that are actually visible. Other views
+    /// get their scroll offset clamped to what they overflow by.
+    pub fn sync_layout(&mut self) {
+        if self.current_view != View::Process {
+            // The content may have shrunk or the terminal grown since the
+            // offset was set
+            self.scroll_offset = self.scroll_offset.min(self.layout.scroll_limit.get());
+            return;
+        }
+        let Some(rows) = self.layout.process_rows.get() else {
+            return;
+        };
+        let height = usize::from(rows.height).max(1);
+        if height != self.max_processes {
+            self.max_processes = height;
+            // Keep the selection on screen in the resized window
+            self.select_row(self.selected_process);
+        }
+    }
+
+    /// How many lines of a `len`-line pane `height` lines tall the current
+    /// scroll skips. Notes the overflow so scrolling stops at the last line.
+    pub fn scroll_skip(&self, len: usize, height: u16) -> usize {
+        let overflow = len.saturating_sub(usize::from(height));
+        let limit = &self.layout.scroll_limit;
+        limit.set(limit.get().max(overflow));
+        self.scroll_offset.min(overflow)
+    }
+
+    /// The scrollbar for a pane scrolled by `scroll_skip`, or `None` when
+    /// everything fits.
+    pub fn scrollbar(&self, len: usize, height: u16) -> Option<ScrollbarState> {
+        scrollbar_state(len, usize::from(height), self.scroll_offset)
+    }
+
+    /// The scrollbar for the process table's `len` rows.
+    pub fn process_scrollbar(&self, len: usize, height: u16) -> Option<ScrollbarState> {
+        scrollbar_state(len, usize::from(height), self.process_scroll_offset)
+    }
+
+    /// Handles a left click: view tabs switch views, process table headers
+    /// sort by their column and process rows are selected.
+    pub fn click(&mut self, column: u16, row: u16) {
+        let position = Position::new(column, row);
+        let tab = self
+            .layout
+            .view_tabs
+            .borrow()
+            .iter()
+            .find(|(area, _)| area.contains(position))
+            .map(|&(_, view)| view);
+        if let Some(view) = tab {
+            self.show_view(view);
+            return;
+        }
+        if self.current_view != View::Process {
+            return;
+        }
+        let header = self
+            .layout
+            .process_headers
+            .borrow()
+            .iter()
+            .find(|(area, _)| area.contains(position))
+            .map(|&(_, sort)| sort);
+        if let Some(sort) = header {
+            self.change_sort_column(sort);
+            return;
+        }
+        if let Some(rows) = self.layout.process_rows.get()
+            && rows.contains(position)
+        {
+            let line = usize::from(row - rows.y);
+            let index = self.process_scroll_offset + line;
+            if line < self.max_processes && index < self.process_rows().len() {
+                self.select_row(index);
+                self.remember_selection();
+            }
+        }
+    }
+
+    /// Scrolls like the arrow keys, three lines per notch, when the wheel
+    /// turns over the process table or another view's content.
+    pub fn wheel(&mut self, column: u16, row: u16, down: bool) {
+        let position = Position::new(column, row);
+        let pane = if self.current_view == View::Process {
+            self.layout.process_rows.get()
+        } else {
+            self.layout.content.get()
+        };
+        if !pane.is_some_and(|area| area.contains(position)) {
+            return;
+        }
+        for _ in 0..WHEEL_LINES {
+            if down {
+                self.scroll_down();
+            } else {
+                self.scroll_up();
+            }
+        }
+        if self.current_view == View::Process {
+            self.remember_selection();
+        }
+    }
+
+    pub fn toggle_help(&mut self) {
+        self.show_help = !self.show_help;
+        self.help_scroll = 0;
+    }
+
+    pub fn scroll_help(&mut self, delta: isize) {
+        self.help_scroll = self
+            .help_scroll
+            .saturating_add_signed(delta)
+            .min(self.layout.help_scroll_limit.get());
+    }
+
+    pub fn toggle_process_details(&mut self) {
+        self.show_proc_details = !self.show_proc_details;
+    }
+
+    pub fn toggle_full_command(&mut self) {
+        self.show_full_command = !self.show_full_command;
+    }
+
+    pub fn toggle_tree_view(&mut self) {
+        self.show_tree_view = !self.show_tree_view;
+        self.clamp_process_selection();
+    }
+
+    /// Switches between one row per PID and one row per executable name,
+    /// keeping the selection on the same process or its group.
+    pub fn toggle_proc_aggregation(&mut self) {
+        let selected = self
+            .process_rows()
+            .get(self.selected_process)
+            .map(|row| (row.process.pid, row.process.name.clone()));
+        self.proc_aggregated = !self.proc_aggregated;
+        let rows = self.process_rows();
+        let index = selected.and_then(|(pid, name)| {
+            rows.iter()
+                .position(|row| row.process.pid == pid)
+                .or_else(|| rows.iter().position(|row| row.process.name == name))
+        });
+        drop(rows);
+        match index {
+            Some(index) => self.select_row(index),
+            None => self.clamp_process_selection(),
+        }
+    }
+
+    /// Expands or collapses the aggregated group on the selected row.
+    fn toggle_group_expansion(&mut self) {
+        let Some(name) = self.selected_group() else {
+            return;
+        };
+        if !self.expanded_groups.remove(&name) {
+            self.expanded_groups.insert(name);
+        }
+    }
+
+    fn selected_group(&self) -> Option<String> {
+        self.process_rows()
+            .get(self.selected_process)
+            .filter(|row| row.group_size.is_some())
+            .map(|row| row.process.name.clone())
+    }
+
+    fn select_row(&mut self, index: usize) {
+        self.selected_process = index;
+        if index < self.process_scroll_offset {
+            self.process_scroll_offset = index;
+        } else if index >= self.process_scroll_offset + self.max_processes {
+            self.process_scroll_offset = index + 1 - self.max_processes;
+        }
+    }
+
+    /// The real process on the selected row; None on an aggregated group line.
+    pub fn selected_process_info(&self) -> Option<&ProcessInfo> {
+        match self
+            .process_rows()
+            .into_iter()
+            .nth(self.selected_process)?
+            .process
+        {
+            Cow::Borrowed(process) => Some(process),
+            Cow::Owned(_) => None,
+        }
+    }
+
+    /// Rows of the process table in display order, after applying active
+    /// filters, the tree view and aggregation.
+    pub fn process_rows(&self) -> Vec<ProcessRow<'_>> {
+        let processes: Vec<&ProcessInfo> = self
+            .metrics
+            .processes
+            .iter()
+            .filter(|process| self.is_listed(process))
+            .filter(|process| match &self.container_filter {
+                Some(container) => process.container.as_ref() == Some(container),
+                None => true,
+            })
+            .filter(|process| {
+                self.search_mode == SearchMode::Highlight || self.matches_search(process)
+            })
+            .collect();
+        if self.proc_aggregated {
+            self.aggregated_rows(&processes)
+        } else if self.show_tree_view {
+            process_tree::flatten(&processes)
+                .into_iter()
+                .map(|(process, prefix)| ProcessRow::new(process, prefix))
+                .collect()
+        } else {
+            processes
+                .into_iter()
+                .map(|process| ProcessRow::new(process, String::new()))
+                .collect()
+        }
+    }
+
+    fn aggregated_rows<'a>(&self, processes: &[&'a ProcessInfo]) -> Vec<ProcessRow<'a>> {
+        let mut groups = process_groups::aggregate(processes);
+        // Groups are ranked by their summed values; members keep list order
+        groups.sort_by(|(a, _), (b, _)| compare_processes(self.process_sort, a, b));
+        if !self.sort_reverse {
+            groups.reverse();
+        }
+        let mut rows = Vec::with_capacity(groups.len());
+        for (total, members) in groups {
+            if let [process] = members[..] {
+                rows.push(ProcessRow::new(process, String::new()));
+                continue;
+            }
+            let expanded = self.expanded_groups.contains(&total.name);
+            rows.push(ProcessRow {
+                process: Cow::Owned(total),
+                prefix: String::new(),
+                group_size: Some(members.len()),
+            });
+            if expanded {
+                let last = members.len() - 1;
+                rows.extend(members.into_iter().enumerate().map(|(index, process)| {
+                    let guide = if index == last { "└─ " } else { "├─ " };
+                    ProcessRow::new(process, guide.to_string())
+                }));
+            }
+        }
+        rows
+    }
+
+    /// Whether a process passes the standing filters that also shape the
+    /// header's process count, unlike the container filter and search.
+    fn is_listed(&self, process: &ProcessInfo) -> bool {
+        !(self.hide_kernel_threads && process.is_kernel_thread())
+            && (!self.only_mine || process.user == self.username)
+    }
+
+    /// How many processes the standing filters leave, when any are active.
+    pub fn listed_process_count(&self) -> Option<usize> {
+        (self.hide_kernel_threads || self.only_mine).then(|| {
+            self.metrics
+                .processes
+                .iter()
+                .filter(|process| self.is_listed(process))
+                .count()
+        })
+    }
+
+    pub fn toggle_kernel_threads(&mut self) {
+        self.keep_selection(|app| app.hide_kernel_threads = !app.hide_kernel_threads);
+    }
+
+    pub fn toggle_only_mine(&mut self) {
+        self.keep_selection(|app| app.only_mine = !app.only_mine);
+    }
+
+    // Applies a change to what is listed, staying on the selected process
+    // if it is still shown and on the same row otherwise
+    fn keep_selection(&mut self, change: impl FnOnce(&mut Self)) {
+        let pid = self
+            .process_rows()
+            .get(self.selected_process)
+            .map(|row| row.process.pid);
+        change(self);
+        match self
+            .process_rows()
+            .iter()
+            .position(|row| Some(row.process.pid) == pid)
+        {
+            Some(index) => self.select_row(index),
+            None => self.clamp_process_selection(),
+        }
+        self.remember_selection();
+    }
+
+    pub fn matches_search(&self, process: &ProcessInfo) -> bool {
+        self.search_query.is_empty()
+            || find_ignore_case(&process.name, &self.search_query).is_some()
+            || find_ignore_case(&process.full_command, &self.search_query).is_some()
+    }
+
+    /// Whether matches are highlighted in place rather than filtered.
+    pub fn is_highlighting(&self) -> bool {
+        self.search_mode == SearchMode::Highlight && !self.search_query.is_empty()
+    }
+
+    pub fn toggle_search_mode(&mut self) {
+        self.search_mode = match self.search_mode {
+            SearchMode::Filter => SearchMode::Highlight,
+            SearchMode::Highlight => SearchMode::Filter,
+        };
+        self.clamp_process_selection();
+    }
+
+    // Indices into the visible (sorted) list, recomputed on every call so
+    // processes that appeared or exited since the last refresh are accounted for
+    fn search_matches(&self) -> Vec<usize> {
+        if !self.is_highlighting() {
+            return Vec::new();
+        }
+        self.process_rows()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, row)| self.matches_search(&row.process))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// "3/17" when the selection is on the third of 17 matches, "-/17" when
+    /// it is on none of them.
+    pub fn search_match_summary(&self) -> Option<String> {
+        if !self.is_highlighting() {
+            return None;
+        }
+        let matches = self.search_matches();
+        Some(
+            match matches
+                .iter()
+                .position(|&index| index == self.selected_process)
+            {
+                Some(position) => format!("{}/{}", position + 1, matches.len()),
+                None => format!("-/{}", matches.len()),
+            },
+        )
+    }
+
+    /// Moves the selection to the next (or previous) match in display order,
+    /// wrapping around at either end.
+    pub fn jump_to_match(&mut self, forward: bool) {
+        let matches = self.search_matches();
+        let next = if forward {
+            matches
+                .iter()
+                .find(|&&index| index > self.selected_process)
+                .or(matches.first())
+        } else {
+            matches
+                .iter()
+                .rev()
+                .find(|&&index| index < self.selected_process)
+                .or(matches.last())
+        };
+        let Some(&index) = next else {
+            return;
+        };
+        self.selected_process = index;
+        if index < self.process_scroll_offset {
+            self.process_scroll_offset = index;
+        } else if index >= self.process_scroll_offset + self.max_processes {
+            self.process_scroll_offset = index + 1 - self.max_processes;
+        }
+    }
+
+    pub fn start_search(&mut self) {
+        if self.current_view == View::Process {
+            self.input_mode = InputMode::Search;
+        }
+    }
+
+    pub fn edit_search(&mut self, edit: impl FnOnce(&mut String)) {
+        edit(&mut self.search_query);
+        self.clamp_process_selection();
+    }
+
+    /// Enter leaves the filter applied; it is shown in the Processes title.
+    pub fn accept_search(&mut self) {
+        self.input_mode = InputMode::Normal;
+    }
+
+    pub fn cancel_search(&mut self) {
+        self.input_mode = InputMode::Normal;
+        self.search_query.clear();
+        self.clamp_process_selection();
+    }
+
+    /// Keeps the process and service selections inside lists that may have
+    /// shrunk since the last refresh.
+    fn clamp_selection(&mut self) {
+        self.clamp_process_selection();
+        self.selected_service = self
+            .selected_service
+            .min(self.service_count().saturating_sub(1));
+        self.selected_interface = self
+            .selected_interface
+            .min(self.metrics.network_interfaces.len().saturating_sub(1));
+    }
+
+    fn clamp_process_selection(&mut self) {
+        let process_count = self.process_rows().len();
+        self.selected_process = self.selected_process.min(process_count.saturating_sub(1));
+        self.process_scroll_offset = self
+            .process_scroll_offset
+            .min(self.selected_process)
+            .min(process_count.saturating_sub(self.max_processes));
+    }
+
+    pub fn toggle_container_filter(&mut self) {
+        self.container_filter = match self.container_filter.take() {
+            Some(_) => None,
+            None => self
+                .selected_process_info()
+                .and_then(|process| process.container.clone()),
+        };
+        self.reset_selection();
+    }
+
+    pub fn increase_update_delay(&mut self) {
+        self.update_interval = (self.update_interval * 2).min(Duration::from_secs(10));
+    }
+
+    pub fn decrease_update_delay(&mut self) {
+        self.update_interval = (self.update_interval / 2).max(Duration::from_millis(250));
+    }
+
+    /// Samples needed to cover `history_length` at the current interval.
+    /// Histories adopt it on their next sample, so samples taken at an
+    /// earlier interval keep their own timestamps.
+    pub fn history_capacity(&self) -> usize {
+        let interval = self.update_interval.as_millis().max(1);
+        (self.history_length.as_millis() / interval) as usize
+    }
+
+    pub fn change_sort_column(&mut self, sort: ProcessSort) {
+        if self.process_sort == sort {
+            self.sort_reverse = !self.sort_reverse;
+        } else {
+            self.process_sort = sort;
+            // Quantities start largest first, user and state names A to Z
+            let descending = !matches!(sort, ProcessSort::User | ProcessSort::State);
+            self.sort_reverse = largest_first(sort) == descending;
+        }
+        self.remember_selection();
+        self.sort_processes();
+        self.restore_selection();
+    }
+
+    /// Moves the sort to the neighbouring column of the process table.
+    pub fn cycle_sort_column(&mut self, forward: bool) {
+        let next = match SORT_COLUMNS
+            .iter()
+            .position(|&column| column == self.process_sort)
+        {
+            Some(index) if forward => SORT_COLUMNS[(index + 1) % SORT_COLUMNS.len()],
+            Some(index) => SORT_COLUMNS[(index + SORT_COLUMNS.len() - 1) % SORT_COLUMNS.len()],
+            // Sorted by a column that is not in the cycle, e.g. FDs
+            None if forward => SORT_COLUMNS[0],
+            None => SORT_COLUMNS[SORT_COLUMNS.len() - 1],
+        };
+        self.change_sort_column(next);
+    }
+
+    pub fn toggle_sort_direction(&mut self) {
+        self.change_sort_column(self.process_sort);
+    }
+
+    /// Whether the list currently shows the largest (or last) values first.
+    pub fn sort_descending(&self) -> bool {
+        largest_first(self.process_sort) == self.sort_reverse
+    }
+
+    fn remember_selection(&mut self) {
+        self.selected_pid = self
+            .process_rows()
+            .get(self.selected_process)
+            .map(|row| row.process.pid);
+    }
+
+    /// Moves the selection back onto the remembered PID after the list was
+    /// re-sorted or refreshed. When that process has exited the selection
+    /// stays at the same position, now on a neighbouring row.
+    fn restore_selection(&mut self) {
+        let Some(pid) = self.selected_pid else {
+            return;
+        };
+        let index = self
+            .process_rows()
+            .iter()
+            .position(|row| row.process.pid == pid);
+        match index {
+            Some(index) => self.select_row(index),
+            None => {
+                self.clamp_process_selection();
+                if matches!(self.current_view, View::Process | View::Overview) {
+                    self.set_status(format!("Process {} exited", pid), false);
+                }
+            }
+        }
+        self.remember_selection();
+    }
+
+    fn sort_processes(&mut self) {
+        let sort = self.process_sort;
+        self.metrics
+            .processes
+            .sort_by(|a, b| compare_processes(sort, a, b));
+        if !self.sort_reverse {
+            self.metrics.processes.reverse();
+        }
+    }
+}
+
+// The thumb spans the visible share of the rows and reaches the end of the
+// track once the last row is in view
+fn scrollbar_state(len: usize, visible: usize, offset: usize) -> Option<ScrollbarState> {
+    let overflow = len.saturating_sub(visible);
+    (overflow > 0).then(|| {
+        ScrollbarState::new(overflow + 1)
+            .position(offset.min(overflow))
+            .viewport_content_length(visible)
+    })
+}
+
+// Appends the latest sample of each rate to its rolling history
+fn record_history(metrics: &mut SystemInfo, capacity: usize) {
+    let now = Instant::now();
+    let mem_percent = (metrics.memory_used as f64 / metrics.memory_total as f64 * 100.0) as u64;
+    for (history, sample) in [
+        (&mut metrics.cpu_history, metrics.cpu_total_usage),
+        (&mut metrics.memory_history, mem_percent),
+        (&mut metrics.net_rx_history, metrics.total_rx),
+        (&mut metrics.net_tx_history, metrics.total_tx),
+    ] {
+        history.set_capacity(capacity);
+        history.push(now, sample);
+    }
+    metrics
+        .cpu_core_history
+        .resize_with(metrics.cpu_usage_per_core.len(), || History::new(capacity));
+    for (history, &usage) in metrics
+        .cpu_core_history
+        .iter_mut()
+        .zip(&metrics.cpu_usage_per_core)
+    {
+        history.set_capacity(capacity);
+        history.push(now, usage);
+    }
+    // Interfaces that came up start from nothing and those gone are dropped
+    let interfaces = &metrics.network_interfaces;
+    metrics
+        .interface_history
+        .retain(|name, _| interfaces.iter().any(|iface| &iface.name == name));
+    for iface in interfaces {
+        let history = metrics
+            .interface_history
+            .entry(iface.name.clone())
+            .or_insert_with(|| InterfaceHistory {
+                rx: History::new(capacity),
+                tx: History::new(capacity),
+            });
+        for (history, sample) in [
+            (&mut history.rx, iface.rx_speed),
+            (&mut history.tx, iface.tx_speed),
+        ] {
+            history.set_capacity(capacity);
+            history.push(now, sample);
+        }
+    }
+    metrics
+        .gpu_history
+        .resize_with(metrics.gpus.len(), || History::new(capacity));
+    for (history, gpu) in metrics.gpu_history.iter_mut().zip(&metrics.gpus) {
+        history.set_capacity(capacity);
+        history.push(now, gpu.utilization.unwrap_or(0));
+    }
+    let cpu_temp = metrics
+        .cpu_temperature
+        .filter(|celsius| PLAUSIBLE_CELSIUS.contains(celsius))
+        .map(|celsius| celsius.round() as u64);
+    // Platforms without per-disk I/O counters leave the charts empty
+    let disk_total = |speed: fn(&DiskInfo) -> Option<u64>| {
+        metrics.disks.iter().filter_map(speed).reduce(|a, b| a + b)
+    };
+    let disk_read = disk_total(|disk| disk.read_speed);
+    let disk_write = disk_total(|disk| disk.write_speed);
+    for (history, sample) in [
+        (
+            &mut metrics.context_switch_history,
+            metrics.context_switches_per_sec,
+        ),
+        (&mut metrics.fork_history, metrics.forks_per_sec),
+        (&mut metrics.disk_read_history, disk_read),
+        (&mut metrics.disk_write_history, disk_write),
+        (&mut metrics.cpu_temp_history, cpu_temp),
+    ] {
+        if let Some(sample) = sample {
+            history.set_capacity(capacity);
+            history.push(now, sample);
+        }
+    }
+    if let Some(pressure) = &metrics.pressure {
+        for (history, sample) in [
+            (&mut metrics.cpu_pressure_history, pressure.cpu.some_avg10),
+            (
+                &mut metrics.memory_pressure_history,
+                pressure.memory.some_avg10,
+            ),
+            (&mut metrics.io_pressure_history, pressure.io.some_avg10),
+        ] {
+            history.set_capacity(capacity);
+            history.push(now, sample);
+        }
+    }
+}
+
+// Whether compare_processes orders the column largest first
+fn largest_first(sort: ProcessSort) -> bool {
+    !matches!(
+        sort,
+        ProcessSort::Pid | ProcessSort::Name | ProcessSort::User | ProcessSort::State
+    )
+}
+
+// Numeric columns order largest first, text columns alphabetically
+fn compare_processes(sort: ProcessSort, a: &ProcessInfo, b: &ProcessInfo) -> Ordering {
+    match sort {
+        ProcessSort::Pid => a.pid.cmp(&b.pid),
+        ProcessSort::Name => a.name.cmp(&b.name),
+        ProcessSort::Cpu => b.cpu_usage.total_cmp(&a.cpu_usage),
+        ProcessSort::Memory => b.memory_usage.cmp(&a.memory_usage),
+        ProcessSort::MemoryPercent => b.memory_percent.total_cmp(&a.memory_percent),
+        ProcessSort::User => a.user.cmp(&b.user),
+        ProcessSort::Time => b.uptime.cmp(&a.uptime),
+        ProcessSort::Threads => b.threads.cmp(&a.threads),
+        ProcessSort::Fds => b.fd_count.cmp(&a.fd_count),
+        ProcessSort::Swap => b.swap_kb.cmp(&a.swap_kb),
+        ProcessSort::State => a.state.to_string().cmp(&b.state.to_string()),
+    }
+}
+
+fn describe_error(err: &io::Error) -> String {
+    match err.kind() {
+        io::ErrorKind::PermissionDenied => "permission denied — run as root".to_string(),
+        io::ErrorKind::NotFound => "process has exited".to_string(),
+        io::ErrorKind::Unsupported => "not supported on this platform".to_string(),
+        _ => err.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{Terminal, backend::TestBackend};
+
+    use super::*;
+    use crate::{alerts::AlertSpec, sys_info::NetworkInterface, ui};
+
+    #[test]
+    fn keeps_selected_pid_across_sorts() {
+        let mut app = App {
+            current_view: View::Process,
+            ..App::default()
+        };
+        app.metrics.processes = vec![
+            ProcessInfo {
+                cpu_usage: 40.0,
+                memory_usage: 10,
+                ..ProcessInfo::fixture(300, 1, "alpha")
+            },
+            ProcessInfo {
+                cpu_usage: 5.0,
+                memory_usage: 900,
+                ..ProcessInfo::fixture(100, 1, "charlie")
+            },
+            ProcessInfo {
+                cpu_usage: 20.0,
+                memory_usage: 500,
+                ..ProcessInfo::fixture(200, 1, "bravo")
+            },
+        ];
+        app.sort_processes();
+        app.selected_process = 2;
+        let selected_pid = |app: &App| app.selected_process_info().map(|process| process.pid);
+        assert_eq!(selected_pid(&app), Some(100));
+        for sort in [
+            ProcessSort::Memory,
+            ProcessSort::Name,
+            ProcessSort::Pid,
+            ProcessSort::Pid,
+            ProcessSort::Cpu,
+        ] {
+            app.change_sort_column(sort);
+            assert_eq!(selected_pid(&app), Some(100), "after sorting by {:?}", sort);
+        }
+    }
+
+    #[test]
+    fn keeps_the_selected_row_on_screen_after_reordering() {
+        let mut app = app_with_processes(500);
+        screen_text(&app, 120, 40);
+        app.sync_layout();
+        let visible = |app: &App| {
+            let pid = app.selected_process_info().unwrap().pid;
+            let offset = app.process_scroll_offset;
+            assert!(
+                (offset..offset + app.max_processes).contains(&app.selected_process),
+                "pid {} at row {} outside {}..{}",
+                pid,
+                app.selected_process,
+                offset,
+                offset + app.max_processes
+            );
+            pid
+        };
+        app.scroll_bottom();
+        for _ in 0..5 {
+            app.scroll_up();
+        }
+        let pid = visible(&app);
+        // Flipping the direction moves the row from the bottom to the top
+        app.toggle_sort_direction();
+        assert_eq!(visible(&app), pid);
+        assert_eq!(app.process_scroll_offset, app.selected_process);
+        // and back again scrolls only as far as needed to bring it in view
+        app.toggle_sort_direction();
+        assert_eq!(visible(&app), pid);
+        assert_eq!(
+            app.process_scroll_offset,
+            app.selected_process + 1 - app.max_processes
+        );
+        // A refresh that reorders the list by CPU, as update_metrics does
+        app.change_sort_column(ProcessSort::Cpu);
+        app.remember_selection();
+        for process in &mut app.metrics.processes {
+            process.cpu_usage = if process.pid == pid { 1.0 } else { 2.0 };
+        }
+        app.sort_processes();
+        app.restore_selection();
+        assert_eq!(visible(&app), pid);
+        assert_eq!(app.selected_process, 499);
+    }
+
+    #[test]
+    fn pause_freezes_the_displayed_snapshot() {
+        let mut app = App::default();
+        app.toggle_pause();
+        let frozen: Vec<u64> = app.metrics.cpu_history.values().collect();
+        app.last_update -= Duration::from_secs(10);
+        app.update_metrics();
+        assert_eq!(app.metrics.cpu_history.values().collect::<Vec<_>>(), frozen);
+        let live = app.live_metrics.as_ref().unwrap();
+        assert_eq!(live.cpu_history.values().len(), frozen.len() + 1);
+        app.toggle_pause();
+        let resumed: Vec<u64> = app.metrics.cpu_history.values().collect();
+        assert_eq!(resumed[..frozen.len()], frozen[..]);
+    }
+
+    fn app_with_processes(count: u32) -> App {
+        let mut app = App {
+            current_view: View::Process,
+            ..App::default()
+        };
+        app.metrics.processes = (1..=count)
+            .map(|pid| ProcessInfo::fixture(pid, 0, "worker"))
+            .collect();
+        app.process_sort = ProcessSort::Pid;
+        app.sort_reverse = true;
+        app.sort_processes();
+        app
+    }
+
+    // Draws the app on a terminal of the given size and returns what is on
+    // it, row after row
+    fn screen_text(app: &App, width: u16, height: u16) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|f| ui::ui(f, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer.content().iter().map(|cell| cell.symbol()).collect()
+    }
+
+    #[test]
+    fn navigates_an_empty_list() {
+        let mut app = app_with_processes(0);
+        app.scroll_down();
+        app.scroll_page_down();
+        app.scroll_bottom();
+        app.scroll_up();
+        app.toggle_tree_view();
+        assert_eq!(app.selected_process, 0);
+        assert_eq!(app.process_scroll_offset, 0);
+        assert!(app.selected_process_info().is_none());
+        screen_text(&app, 120, 40);
+    }
+
+    #[test]
+    fn navigates_a_single_process() {
+        let mut app = app_with_processes(1);
+        app.scroll_down();
+        app.scroll_page_down();
+        app.scroll_bottom();
+        assert_eq!(app.selected_process, 0);
+        assert_eq!(app.process_scroll_offset, 0);
+        app.scroll_up();
+        app.scroll_page_up();
+        assert_eq!(
+            app.selected_process_info().map(|process| process.pid),
+            Some(1)
+        );
+        screen_text(&app, 120, 40);
+    }
+
+    #[test]
+    fn clamps_selection_when_the_list_shrinks() {
+        let mut app = app_with_processes(100);
+        app.selected_process = 80;
+        app.process_scroll_offset = 70;
+        app.remember_selection();
+        app.metrics.processes.truncate(5);
+        app.restore_selection();
+        app.clamp_selection();
+        assert_eq!(app.selected_process, 4);
+        assert!(app.process_scroll_offset <= app.selected_process);
+        assert_eq!(
+            app.selected_process_info().map(|process| process.pid),
+            Some(5)
+        );
+        screen_text(&app, 120, 40);
+        app.scroll_up();
+        assert_eq!(app.selected_process, 3);
+    }
+
+    #[test]
+    fn clicks_map_to_what_was_drawn() {
+        let mut app = app_with_processes(50);
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|f| ui::ui(f, &app)).unwrap();
+        let buffer = terminal.backend().buffer().clone();
+        let text_at = |area: Rect| {
+            (area.x..area.right())
+                .map(|x| buffer[(x, area.y)].symbol().to_string())
+                .collect::<String>()
+        };
+
+        let rows = app.layout.process_rows.get().unwrap();
+        app.click(rows.x + 2, rows.y + 3);
+        assert_eq!(app.selected_process, 3);
+        let pid = app.selected_process_info().unwrap().pid.to_string();
+        let row = Rect {
+            y: rows.y + 3,
+            height: 1,
+            ..rows
+        };
+        assert!(text_at(row).trim_start().starts_with(&pid));
+
+        let headers = app.layout.process_headers.borrow().clone();
+        let (name, _) = headers
+            .iter()
+            .find(|(_, sort)| *sort == ProcessSort::Name)
+            .unwrap();
+        assert!(text_at(*name).starts_with("Name"));
+        app.click(name.x, name.y);
+        assert_eq!(app.process_sort, ProcessSort::Name);
+
+        let tabs = app.layout.view_tabs.borrow().clone();
+        let (system, _) = tabs.iter().find(|(_, view)| *view == View::System).unwrap();
+        assert!(text_at(*system).contains("System"));
+        app.click(system.x + 1, system.y);
+        assert_eq!(app.current_view, View::System);
+    }
+
+    #[test]
+    fn detail_pane_gives_its_rows_to_the_table() {
+        let mut app = app_with_processes(100);
+        screen_text(&app, 120, 40);
+        app.sync_layout();
+        let without_details = app.max_processes;
+        app.select_row(without_details - 1);
+        app.activate_selection();
+        assert!(app.show_proc_details);
+        screen_text(&app, 120, 40);
+        app.sync_layout();
+        assert_eq!(app.max_processes, without_details - 10);
+        // The selection was on the last visible row and must stay visible
+        assert!(app.selected_process < app.process_scroll_offset + app.max_processes);
+        assert!(app.selected_process >= app.process_scroll_offset);
+    }
+
+    #[test]
+    fn hides_kernel_threads_and_keeps_the_selection() {
+        let mut app = app_with_processes(0);
+        app.metrics.processes = vec![
+            ProcessInfo::fixture(1, 0, "init"),
+            ProcessInfo::fixture(2, 0, "kthreadd"),
+            ProcessInfo::fixture(3, 2, "kworker/0:1"),
+            ProcessInfo {
+                full_command: "/usr/bin/helper".to_string(),
+                ..ProcessInfo::fixture(4, 2, "helper")
+            },
+            ProcessInfo::fixture(5, 1, "shell"),
+        ];
+        app.sort_processes();
+        let pids = |app: &App| -> Vec<u32> {
+            app.process_rows()
+                .iter()
+                .map(|row| row.process.pid)
+                .collect()
+        };
+        assert_eq!(pids(&app), vec![1, 2, 3, 4, 5]);
+        app.select_row(4);
+        app.toggle_kernel_threads();
+        assert_eq!(pids(&app), vec![1, 4, 5]);
+        assert_eq!(app.listed_process_count(), Some(3));
+        assert_eq!(app.selected_process_info().unwrap().pid, 5);
+        // A hidden selection falls back to the same row
+        app.toggle_kernel_threads();
+        app.select_row(2);
+        app.toggle_kernel_threads();
+        assert_eq!(app.selected_process, 2);
+        assert_eq!(app.listed_process_count(), Some(3));
+    }
+
+    #[test]
+    fn only_mine_combines_with_other_filters() {
+        let mut app = app_with_processes(0);
+        app.username = "me".to_string();
+        let owned = |pid: u32, ppid: u32, name: &str, user: &str| ProcessInfo {
+            user: user.to_string(),
+            ..ProcessInfo::fixture(pid, ppid, name)
+        };
+        app.metrics.processes = vec![
+            owned(1, 0, "init", "root"),
+            owned(3, 2, "kworker", "me"),
+            owned(10, 1, "vim", "me"),
+            owned(11, 1, "vim", "bob"),
+            owned(12, 1, "bash", "me"),
+        ];
+        app.sort_processes();
+        app.toggle_only_mine();
+        app.toggle_kernel_threads();
+        app.search_query = "vim".to_string();
+        let rows = app.process_rows();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].process.pid, 10);
+        // The header count ignores the search but not the standing filters
+        assert_eq!(app.listed_process_count(), Some(2));
+        app.toggle_kernel_threads();
+        app.toggle_only_mine();
+        assert_eq!(app.listed_process_count(), None);
+    }
+
+    #[test]
+    fn wheel_scrolls_three_rows_over_the_table() {
+        let mut app = app_with_processes(50);
+        screen_text(&app, 120, 40);
+        let rows = app.layout.process_rows.get().unwrap();
+        app.wheel(rows.x, rows.y, true);
+        app.wheel(rows.x, rows.y, true);
+        assert_eq!(app.selected_process, 6);
+        app.wheel(rows.x, rows.y, false);
+        assert_eq!(app.selected_process, 3);
+        // Outside the table, e.g. over the detail pane
+        app.wheel(rows.x, rows.bottom() + 2, true);
+        assert_eq!(app.selected_process, 3);
+    }
+
+    #[test]
+    fn tagged_processes_are_the_action_targets() {
+        let mut app = app_with_processes(10);
+        let first = app.selected_process_info().unwrap().pid;
+        app.toggle_tag();
+        app.toggle_tag();
+        app.scroll_down();
+        app.toggle_tag();
+        let second = app.process_rows()[1].process.pid;
+        let fourth = app.process_rows()[3].process.pid;
+        assert_eq!(app.tagged_pids, HashSet::from([first, second, fourth]));
+        // Untagging removes just that process
+        app.select_row(1);
+        app.toggle_tag();
+        app.change_sort_column(ProcessSort::Name);
+        assert_eq!(app.tagged_pids, HashSet::from([first, fourth]));
+
+        app.open_kill_prompt();
+        let targets: Vec<u32> = app
+            .pending_action
+            .as_ref()
+            .unwrap()
+            .targets()
+            .iter()
+            .map(|target| target.pid)
+            .collect();
+        assert_eq!(targets, vec![first.min(fourth), first.max(fourth)]);
+        screen_text(&app, 120, 40);
+        app.cancel_pending_action();
+        app.perform(Action::Back);
+        assert!(app.tagged_pids.is_empty());
+    }
+
+    #[test]
+    fn zooms_the_resource_charts_only_in_that_view() {
+        let mut app = app_with_processes(3);
+        app.perform(Action::ZoomOut);
+        assert_eq!(app.chart_window, ChartWindow::Minute);
+        app.current_view = View::Resources;
+        app.perform(Action::ZoomIn);
+        app.perform(Action::ZoomIn);
+        assert_eq!(app.chart_window, ChartWindow::HalfMinute);
+        for _ in 0..4 {
+            app.perform(Action::ZoomOut);
+        }
+        assert_eq!(app.chart_window, ChartWindow::FifteenMinutes);
+        // Longer than the stored history, so every sample is drawn
+        screen_text(&app, 120, 40);
+    }
+
+    #[test]
+    fn scrolls_the_disks_table_within_its_rows() {
+        let mut app = App {
+            current_view: View::Disks,
+            ..App::default()
+        };
+        app.metrics.disks = (0..30)
+            .map(|i| DiskInfo {
+                name: format!("sd{}", i),
+                fs_type: "ext4".to_string(),
+                ..DiskInfo::default()
+            })
+            .collect();
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        let mut first_row = |app: &mut App| {
+            terminal.draw(|f| ui::ui(f, app)).unwrap();
+            app.sync_layout();
+            let buffer = terminal.backend().buffer();
+            let area = app.layout.content.get().unwrap();
+            // Below the summary, the block border and the table header
+            let y = area.y + 5;
+            (area.x..area.right())
+                .map(|x| buffer[(x, y)].symbol())
+                .collect::<String>()
+        };
+        assert!(first_row(&mut app).contains("sd0 "));
+        // 23 of the 30 rows fit
+        app.scroll_bottom();
+        app.scroll_down();
+        assert_eq!(app.scroll_offset, 7);
+        assert!(first_row(&mut app).contains("sd7 "));
+        app.metrics.disks.truncate(25);
+        first_row(&mut app);
+        assert_eq!(app.scroll_offset, 2);
+        app.perform(Action::View(View::Network));
+        assert_eq!(app.scroll_offset, 0);
+        first_row(&mut app);
+        app.scroll_down();
+        assert_eq!(app.scroll_offset, 0);
+    }
+
+    #[test]
+    fn process_scrollbar_follows_the_scroll() {
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        let mut scrollbar = |app: &mut App| {
+            terminal.draw(|f| ui::ui(f, app)).unwrap();
+            app.sync_layout();
+            let buffer = terminal.backend().buffer();
+            let rows = app.layout.process_rows.get().unwrap();
+            // Drawn over the table's right border
+            (rows.top()..rows.bottom())
+                .map(|y| buffer[(rows.right(), y)].symbol().to_string())
+                .collect::<Vec<_>>()
+        };
+        let mut app = app_with_processes(5);
+        app.current_view = View::Process;
+        assert!(!scrollbar(&mut app).contains(&"█".to_string()));
+        let mut app = app_with_processes(200);
+        app.current_view = View::Process;
+        let track = scrollbar(&mut app);
+        assert_eq!(track.first().unwrap(), "█");
+        assert_ne!(track.last().unwrap(), "█");
+        app.scroll_bottom();
+        let track = scrollbar(&mut app);
+        assert_ne!(track.first().unwrap(), "█");
+        assert_eq!(track.last().unwrap(), "█");
+    }
+
+    #[test]
+    fn views_keep_their_state_while_hidden() {
+        let mut app = app_with_processes(30);
+        app.select_row(5);
+        app.remember_selection();
+        app.toggle_process_details();
+        app.search_query = "work".to_string();
+        let pid = app.selected_pid;
+        app.perform(Action::NextView);
+        assert_eq!(app.current_view, View::Resources);
+        assert!(!app.show_proc_details);
+        assert!(app.search_query.is_empty());
+        app.perform(Action::View(View::Process));
+        assert_eq!(app.selected_pid, pid);
+        assert!(app.show_proc_details);
+        assert_eq!(app.search_query, "work");
+
+        // The selected process moved while the view was hidden
+        app.perform(Action::View(View::Services));
+        app.metrics.processes.rotate_left(2);
+        app.sort_processes();
+        app.perform(Action::View(View::Process));
+        assert_eq!(app.selected_pid, pid);
+        assert_eq!(app.selected_process_info().map(|p| p.pid), pid);
+
+        app.perform(Action::ResetSelection);
+        assert_eq!(app.selected_process, 0);
+        assert!(!app.show_proc_details);
+    }
+
+    #[test]
+    fn options_view_changes_the_selected_setting() {
+        let mut app = app_with_processes(5);
+        app.perform(Action::View(View::Options));
+        app.perform(Action::NextSortColumn);
+        assert_eq!(app.update_interval, Duration::from_millis(1250));
+        app.perform(Action::PreviousSortColumn);
+        app.perform(Action::PreviousSortColumn);
+        app.perform(Action::PreviousSortColumn);
+        app.perform(Action::PreviousSortColumn);
+        assert_eq!(app.update_interval, Duration::from_millis(250));
+        assert_eq!(app.process_sort, ProcessSort::Pid);
+
+        app.perform(Action::Down);
+        app.perform(Action::Down);
+        app.perform(Action::Activate);
+        assert!(app.show_full_command);
+        app.perform(Action::Bottom);
+        assert_eq!(SETTINGS[app.selected_setting], Setting::Units);
+        app.perform(Action::Activate);
+        assert_eq!(app.units, UnitSystem::Decimal);
+        app.perform(Action::Up);
+        app.perform(Action::Activate);
+        assert_eq!(app.theme_name, "light");
+        app.perform(Action::Up);
+        app.perform(Action::Up);
+        app.perform(Action::NextSortColumn);
+        assert_eq!(app.process_sort, ProcessSort::Name);
+        screen_text(&app, 120, 40);
+        // The cursor stays put while another view is shown
+        app.perform(Action::View(View::Process));
+        app.perform(Action::Down);
+        app.perform(Action::View(View::Options));
+        assert_eq!(SETTINGS[app.selected_setting], Setting::SortColumn);
+    }
+
+    #[test]
+    fn core_graphs_fall_back_to_bars_when_they_do_not_fit() {
+        let mut app = App {
+            current_view: View::System,
+            ..App::default()
+        };
+        app.metrics.cpu_usage_per_core = (0..64).map(|core| core % 100).collect();
+        record_history(&mut app.metrics, 600);
+        assert_eq!(app.metrics.cpu_core_history.len(), 64);
+        app.perform(Action::CoreGraphs);
+        let title = |width, height| {
+            let text = screen_text(&app, width, height);
+            text.contains("CPU History per Core") && text.contains("C63 63%")
+        };
+        assert!(title(120, 40));
+        assert!(!title(80, 30));
+    }
+
+    #[test]
+    fn keeps_a_history_per_network_interface() {
+        let mut app = App {
+            current_view: View::Network,
+            ..App::default()
+        };
+        let eth0 = app.metrics.network_interfaces[0].clone();
+        app.metrics.network_interfaces.push(NetworkInterface {
+            name: "wg0".to_string(),
+            rx_speed: 7,
+            ..eth0.clone()
+        });
+        record_history(&mut app.metrics, 600);
+        assert_eq!(app.metrics.interface_history["eth0"].rx.values().len(), 2);
+        assert_eq!(
+            app.metrics.interface_history["wg0"]
+                .rx
+                .values()
+                .collect::<Vec<_>>(),
+            vec![7]
+        );
+        app.perform(Action::Down);
+        app.perform(Action::Down);
+        assert_eq!(app.selected_interface, 1);
+        let text = screen_text(&app, 120, 40);
+        assert!(text.contains("wg0 History"));
+        // The VPN goes down
+        app.metrics.network_interfaces.truncate(1);
+        record_history(&mut app.metrics, 600);
+        app.clamp_selection();
+        assert!(!app.metrics.interface_history.contains_key("wg0"));
+        assert_eq!(app.selected_interface, 0);
+    }
+
+    #[test]
+    fn charts_the_total_disk_io() {
+        let mut app = App {
+            current_view: View::Disks,
+            ..App::default()
+        };
+        app.metrics.disks = ["sda", "sdb"]
+            .iter()
+            .zip([3, 5])
+            .map(|(name, speed)| DiskInfo {
+                name: name.to_string(),
+                read_speed: Some(speed),
+                write_speed: Some(1),
+                ..DiskInfo::default()
+            })
+            .collect();
+        let before = app.metrics.disk_read_history.values().len();
+        record_history(&mut app.metrics, 600);
+        assert_eq!(app.metrics.disk_read_history.values().last(), Some(8));
+        assert_eq!(app.metrics.disk_read_history.values().len(), before + 1);
+        let text = screen_text(&app, 120, 40);
+        assert!(text.contains("[busiest: sdb]"));
+        // Without counters nothing is recorded
+        for disk in &mut app.metrics.disks {
+            disk.read_speed = None;
+        }
+        record_history(&mut app.metrics, 600);
+        assert_eq!(app.metrics.disk_read_history.values().len(), before + 1);
+    }
+
+    #[test]
+    fn leaves_temperature_glitches_out_of_the_history() {
+        let mut app = App::default();
+        for celsius in [61.4, 0.0, 255.0, 72.6] {
+            app.metrics.cpu_temperature = Some(celsius);
+            record_history(&mut app.metrics, 600);
+        }
+        app.metrics.cpu_temperature = None;
+        record_history(&mut app.metrics, 600);
+        let history: Vec<u64> = app.metrics.cpu_temp_history.values().collect();
+        assert_eq!(history[history.len() - 2..], [61, 73]);
+        app.current_view = View::Resources;
+        let text = screen_text(&app, 120, 40);
+        assert!(text.contains("CPU Temperature 73°C"));
+    }
+
+    #[test]
+    fn marks_the_sort_column_in_both_tables() {
+        let mut app = app_with_processes(3);
+        app.change_sort_column(ProcessSort::Threads);
+        app.current_view = View::Process;
+        let arrow = if app.sort_descending() { "▼" } else { "▲" };
+        assert!(screen_text(&app, 140, 40).contains(&format!("Threads {}", arrow)));
+        app.change_sort_column(ProcessSort::Cpu);
+        app.current_view = View::Overview;
+        let arrow = if app.sort_descending() { "▼" } else { "▲" };
+        assert!(screen_text(&app, 140, 40).contains(&format!("CPU% {}", arrow)));
+    }
+
+    #[test]
+    fn second_memory_sort_switches_to_share_of_ram() {
+        let mut app = app_with_processes(3);
+        for (index, process) in app.metrics.processes.iter_mut().enumerate() {
+            // Larger share but smaller size, so the two orders differ
+            process.memory_percent = index as f64;
+            process.memory_usage = 100 - index as u64;
+        }
+        app.perform(Action::Sort(ProcessSort::Memory));
+        assert_eq!(app.process_sort, ProcessSort::Memory);
+        assert_eq!(app.metrics.processes[0].memory_usage, 100);
+        app.perform(Action::Sort(ProcessSort::Memory));
+        assert_eq!(app.process_sort, ProcessSort::MemoryPercent);
+        assert_eq!(app.metrics.processes[0].memory_percent, 2.0);
+        app.current_view = View::Process;
+        let text = screen_text(&app, 140, 40);
+        assert!(text.contains("MEM% ▼"));
+        app.perform(Action::Sort(ProcessSort::Memory));
+        assert_eq!(app.process_sort, ProcessSort::Memory);
+    }
+
+    #[test]
+    fn shows_firing_alerts_in_the_header() {
+        let mut app = app_with_processes(3);
+        let rules = ["cpu >= 0", "process nowhere not running"]
+            .map(|rule| AlertSpec::Rule(rule.to_string()));
+        app.alerts = Alerts::new(&rules).0;
+        app.metrics.simulated = false;
+        app.check_alerts();
+        assert_eq!(app.alerts.firing().count(), 2);
+        assert!(app.status.as_ref().is_some_and(|status| status.is_error));
+        let mut terminal = Terminal::new(TestBackend::new(140, 40)).unwrap();
+        terminal.draw(|f| ui::ui(f, &app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let text: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("⚠ 2 ALERTS"));
+        // The overview's CPU chart sits at the top left, below the header
+        // and tab bar
+        let corner = buffer
+            .content()
+            .iter()
+            .position(|cell| cell.symbol() == "┌")
+            .unwrap();
+        assert_eq!(buffer.content()[corner].fg, app.theme.danger);
+    }
+
+    #[test]
+    fn logs_alerts_and_oom_kills_as_events() {
+        let mut app = app_with_processes(3);
+        app.alerts = Alerts::new(&[AlertSpec::Rule("cpu >= 0".to_string())]).0;
+        app.metrics.oom_kills = vec![sys_info::OomKill {
+            pid: 4242,
+            name: "stress".to_string(),
+        }];
+        app.check_alerts();
+        app.record_events();
+        assert_eq!(app.events.unseen(), 2);
+        let texts: Vec<&str> = app
+            .events
+            .newest_first()
+            .map(|event| event.text.as_str())
+            .collect();
+        assert_eq!(texts[0], "OOM killer ended stress (4242)");
+        assert!(texts[1].starts_with("Alert: cpu >= 0 (CPU at "));
+        app.status = None;
+        assert!(screen_text(&app, 140, 40).contains("2 new events [L]"));
+        app.perform(Action::Events);
+        assert_eq!(app.events.unseen(), 0);
+        let text = screen_text(&app, 140, 40);
+        assert!(text.contains("Events (2)"));
+        assert!(text.contains("OOM killer ended stress (4242)"));
+        assert!(!text.contains("new event"));
+    }
+
+    #[test]
+    fn runs_alert_commands_only_with_allow_exec() {
+        let mut app = app_with_processes(3);
+        let spec = AlertSpec::Table {
+            rule: "process nowhere not running".to_string(),
+            notify: false,
+            exec: Some("echo restarted; exit 2".to_string()),
+            cooldown_secs: None,
+        };
+        app.alerts = Alerts::new(std::slice::from_ref(&spec)).0;
+        app.metrics.simulated = false;
+        app.check_alerts();
+        let newest = |app: &App| app.events.newest_first().next().unwrap().text.clone();
+        assert_eq!(
+            newest(&app),
+            "Did not run `echo restarted; exit 2`: commands need --allow-exec"
+        );
+        // Nor on simulated metrics, even when allowed
+        let threshold = AlertSpec::Table {
+            rule: "cpu >= 0".to_string(),
+            notify: false,
+            exec: Some("echo restarted; exit 2".to_string()),
+            cooldown_secs: None,
+        };
+        app.alerts = Alerts::new(&[threshold]).0;
+        app.metrics.simulated = true;
+        app.commands.allowed = true;
+        app.check_alerts();
+        assert_eq!(
+            newest(&app),
+            "Did not run `echo restarted; exit 2`: the metrics are simulated"
+        );
+        app.metrics.simulated = false;
+        // Starts over from clear, so the rule fires again
+        app.alerts = Alerts::new(&[spec]).0;
+        app.commands.allowed = true;
+        app.check_alerts();
+        let count = app.events.len();
+        for _ in 0..100 {
+            app.poll_commands();
+            if app.events.len() > count {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        let event = app.events.newest_first().next().unwrap();
+        assert_eq!(event.severity, Severity::Critical);
+        assert_eq!(
+            event.text,
+            "`echo restarted; exit 2` failed: exit status: 2\nrestarted"
+        );
+    }
+
+    #[test]
+    fn jumps_from_a_service_to_its_process_through_filters() {
+        let mut app = app_with_processes(50);
+        app.metrics.services = Some(vec![sys_info::ServiceInfo {
+            name: "worker.service".to_string(),
+            description: String::new(),
+            active_state: "active".to_string(),
+            sub_state: "running".to_string(),
+            main_pid: Some(7),
+            cpu_usage: None,
+            memory_usage: None,
+        }]);
+        app.search_query = "nothing".to_string();
+        app.only_mine = true;
+        app.perform(Action::View(View::Services));
+        app.activate_selection();
+        assert_eq!(app.current_view, View::Process);
+        assert_eq!(app.selected_pid, Some(7));
+        assert_eq!(
+            app.selected_process_info().map(|process| process.pid),
+            Some(7)
+        );
+        assert_eq!(app.selected_process, 6);
+    }
+
+    #[test]
+    fn refuses_process_actions_on_simulated_processes() {
+        let mut app = app_with_processes(0);
+        // Above the largest PID Linux hands out, in case a signal slips through
+        app.metrics.processes = vec![ProcessInfo::fixture(4_194_400, 0, "worker")];
+        app.sort_processes();
+        assert!(app.metrics.simulated);
+        app.perform(Action::Kill);
+        assert!(app.pending_action.is_none());
+        assert!(app.status.as_ref().is_some_and(|status| status.is_error));
+        app.perform(Action::SignalPicker);
+        assert!(app.pending_action.is_none());
+        // A prompt opened before the list turned out to be simulated
+        app.metrics.simulated = false;
+        app.perform(Action::Kill);
+        assert!(app.pending_action.is_some());
+        app.metrics.simulated = true;
+        app.status = None;
+        app.confirm_pending_action();
+        assert!(app.pending_action.is_none());
+        assert!(app.status.as_ref().is_some_and(|status| {
+            status.is_error && status.text == "Not available for simulated processes"
+        }));
+    }
+
+    #[test]
+    fn does_not_renice_simulated_processes() {
+        let mut app = app_with_processes(0);
+        app.metrics.processes = vec![ProcessInfo::fixture(4_194_400, 0, "worker")];
+        app.sort_processes();
+        app.renice_selected(1);
+        assert_eq!(app.metrics.processes[0].nice, 0);
+        assert!(app.status.as_ref().is_some_and(|status| status.is_error));
+    }
+
+    #[test]
+    fn refuses_process_actions_on_a_remote_host() {
+        let mut app = app_with_processes(3);
+        let first = app.metrics.clone();
+        // Nothing listens on port 1, so the link never comes up
+        app.connect(Client::connect("127.0.0.1:1"), first);
+        assert!(app.is_remote());
+        app.perform(Action::Kill);
+        assert!(app.pending_action.is_none());
+        assert!(app.status.as_ref().is_some_and(|status| status.is_error));
+        app.perform(Action::Environment);
+        assert!(app.environment.is_none());
+        app.perform(Action::Tag);
+        assert_eq!(app.tagged_pids.len(), 1);
+        let text = screen_text(&app, 140, 40);
+        assert!(text.contains("RECONNECTING"));
     }
 }
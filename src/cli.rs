@@ -0,0 +1,272 @@
+use std::{path::PathBuf, time::Duration};
+
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::{App, View},
+    sys_info::ProcessSort,
+    theme::ColorDepth,
+};
+
+/// A terminal system monitor.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    /// Refresh interval in milliseconds
+    #[arg(long, value_name = "MS", value_parser = clap::value_parser!(u64).range(250..=10_000))]
+    pub interval: Option<u64>,
+
+    /// View to open on start
+    #[arg(long)]
+    pub view: Option<ViewArg>,
+
+    /// Start with the process tree shown
+    #[arg(long)]
+    pub tree: bool,
+
+    /// Column to sort processes by
+    #[arg(long)]
+    pub sort: Option<SortArg>,
+
+    /// Start paused on the first snapshot
+    #[arg(long)]
+    pub paused: bool,
+
+    /// Do not remember interval, sort and view toggles in state.toml on exit
+    #[arg(long)]
+    pub no_save_state: bool,
+
+    /// Color theme: a built-in one, or a file in the config's themes directory
+    #[arg(long, value_name = "NAME")]
+    pub theme: Option<String>,
+
+    /// Print an example theme file and exit
+    #[arg(long)]
+    pub dump_theme: bool,
+
+    /// Colors the terminal can show; guessed from $COLORTERM and $TERM
+    #[arg(long)]
+    pub color: Option<ColorArg>,
+
+    /// No colors at all, as when $NO_COLOR is set
+    #[arg(long, conflicts_with = "color")]
+    pub no_color: bool,
+
+    /// Append a CSV row of the main metrics to this file on every refresh
+    #[arg(long, value_name = "PATH")]
+    pub log_metrics: Option<PathBuf>,
+
+    /// Print one snapshot of the metrics and exit, leaving the terminal alone
+    #[arg(long)]
+    pub once: bool,
+
+    /// How --once prints the snapshot; text unless given
+    #[arg(long, requires = "once")]
+    pub format: Option<FormatArg>,
+
+    /// Move the log aside to <PATH>.1 once it reaches this size, e.g. 10M
+    #[arg(long, value_name = "SIZE", requires = "log_metrics", value_parser = parse_size)]
+    pub log_max_size: Option<u64>,
+
+    /// Run without a screen, sending metrics to clients that connect here
+    #[arg(long, value_name = "ADDR", conflicts_with = "once")]
+    pub serve: Option<String>,
+
+    /// Let alert rules run their `exec` commands
+    #[arg(long)]
+    pub allow_exec: bool,
+
+    /// Show the metrics of a host running `xtop --serve` instead of this one
+    #[arg(long, value_name = "ADDR", conflicts_with_all = ["once", "serve"])]
+    pub connect: Option<String>,
+}
+
+/// A byte count, optionally followed by K, M or G for powers of 1024.
+fn parse_size(arg: &str) -> Result<u64, String> {
+    let (digits, unit) = match arg.char_indices().last() {
+        Some((index, 'K' | 'k')) => (&arg[..index], crate::utils::KB),
+        Some((index, 'M' | 'm')) => (&arg[..index], crate::utils::MB),
+        Some((index, 'G' | 'g')) => (&arg[..index], crate::utils::GB),
+        _ => (arg, 1),
+    };
+    digits
+        .parse::<u64>()
+        .ok()
+        .and_then(|size| size.checked_mul(unit))
+        .filter(|&size| size > 0)
+        .ok_or_else(|| format!("'{}' is not a size such as 500K or 10M", arg))
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum FormatArg {
+    // The same fields as the JSON snapshot export
+    Json,
+    #[default]
+    Text,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+pub enum ColorArg {
+    #[serde(rename = "truecolor")]
+    Truecolor,
+    #[value(name = "256")]
+    #[serde(rename = "256")]
+    Indexed256,
+    #[value(name = "16")]
+    #[serde(rename = "16")]
+    Ansi16,
+}
+
+impl From<ColorArg> for ColorDepth {
+    fn from(color: ColorArg) -> Self {
+        match color {
+            ColorArg::Truecolor => ColorDepth::TrueColor,
+            ColorArg::Indexed256 => ColorDepth::Indexed256,
+            ColorArg::Ansi16 => ColorDepth::Ansi16,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ViewArg {
+    Overview,
+    System,
+    Process,
+    Resources,
+    Network,
+    Disks,
+    Options,
+    Gpu,
+    Sensors,
+    Services,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortArg {
+    Cpu,
+    Mem,
+    #[serde(rename = "mem-percent")]
+    MemPercent,
+    Pid,
+    Name,
+    User,
+    Threads,
+    Time,
+    State,
+    Fds,
+    Swap,
+}
+
+impl From<ViewArg> for View {
+    fn from(view: ViewArg) -> Self {
+        match view {
+            ViewArg::Overview => View::Overview,
+            ViewArg::System => View::System,
+            ViewArg::Process => View::Process,
+            ViewArg::Resources => View::Resources,
+            ViewArg::Network => View::Network,
+            ViewArg::Disks => View::Disks,
+            ViewArg::Options => View::Options,
+            ViewArg::Gpu => View::Gpu,
+            ViewArg::Sensors => View::Sensors,
+            ViewArg::Services => View::Services,
+        }
+    }
+}
+
+impl From<SortArg> for ProcessSort {
+    fn from(sort: SortArg) -> Self {
+        match sort {
+            SortArg::Cpu => ProcessSort::Cpu,
+            SortArg::Mem => ProcessSort::Memory,
+            SortArg::MemPercent => ProcessSort::MemoryPercent,
+            SortArg::Pid => ProcessSort::Pid,
+            SortArg::Name => ProcessSort::Name,
+            SortArg::User => ProcessSort::User,
+            SortArg::Threads => ProcessSort::Threads,
+            SortArg::Time => ProcessSort::Time,
+            SortArg::State => ProcessSort::State,
+            SortArg::Fds => ProcessSort::Fds,
+            SortArg::Swap => ProcessSort::Swap,
+        }
+    }
+}
+
+impl From<ProcessSort> for SortArg {
+    fn from(sort: ProcessSort) -> Self {
+        match sort {
+            ProcessSort::Cpu => SortArg::Cpu,
+            ProcessSort::Memory => SortArg::Mem,
+            ProcessSort::MemoryPercent => SortArg::MemPercent,
+            ProcessSort::Pid => SortArg::Pid,
+            ProcessSort::Name => SortArg::Name,
+            ProcessSort::User => SortArg::User,
+            ProcessSort::Threads => SortArg::Threads,
+            ProcessSort::Time => SortArg::Time,
+            ProcessSort::State => SortArg::State,
+            ProcessSort::Fds => SortArg::Fds,
+            ProcessSort::Swap => SortArg::Swap,
+        }
+    }
+}
+
+impl Cli {
+    /// Applies the flags that were given over the app's current settings.
+    pub fn apply(&self, app: &mut App) {
+        if let Some(interval) = self.interval {
+            app.update_interval = Duration::from_millis(interval);
+        }
+        if let Some(view) = self.view {
+            app.current_view = view.into();
+        }
+        if self.tree {
+            app.show_tree_view = true;
+        }
+        if let Some(sort) = self.sort {
+            let sort = ProcessSort::from(sort);
+            if app.process_sort != sort {
+                app.change_sort_column(sort);
+            }
+        }
+        if self.paused && !app.paused {
+            app.toggle_pause();
+        }
+        app.commands.allowed = self.allow_exec;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_validates_flags() {
+        assert!(Cli::try_parse_from(["xtop", "--interval", "10"]).is_err());
+        assert!(Cli::try_parse_from(["xtop", "--view", "bogus"]).is_err());
+        let cli =
+            Cli::try_parse_from(["xtop", "--interval", "500", "--sort", "mem", "--tree"]).unwrap();
+        assert_eq!(cli.interval, Some(500));
+        assert!(matches!(cli.sort, Some(SortArg::Mem)));
+        assert!(cli.tree);
+        let cli = Cli::try_parse_from(["xtop", "--color=256"]).unwrap();
+        assert_eq!(cli.color, Some(ColorArg::Indexed256));
+        assert!(Cli::try_parse_from(["xtop", "--color", "8"]).is_err());
+        let cli = Cli::try_parse_from(["xtop", "--log-metrics", "m.csv", "--log-max-size", "10M"])
+            .unwrap();
+        assert_eq!(cli.log_metrics, Some(PathBuf::from("m.csv")));
+        assert_eq!(cli.log_max_size, Some(10 * 1024 * 1024));
+        assert!(Cli::try_parse_from(["xtop", "--log-max-size", "10M"]).is_err());
+        let cli = Cli::try_parse_from(["xtop", "--once", "--format", "json"]).unwrap();
+        assert_eq!(cli.format, Some(FormatArg::Json));
+        assert!(Cli::try_parse_from(["xtop", "--format", "json"]).is_err());
+        let cli = Cli::try_parse_from(["xtop", "--connect", "db1:7878"]).unwrap();
+        assert_eq!(cli.connect.as_deref(), Some("db1:7878"));
+        assert!(Cli::try_parse_from(["xtop", "--connect", "a:1", "--serve", "0.0.0.0:1"]).is_err());
+        assert!(
+            Cli::try_parse_from(["xtop", "--log-metrics", "m.csv", "--log-max-size", "M"]).is_err()
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use ratatui::layout::Constraint;
+
+use crate::sys_info::ProcessSort;
+
+// The gap a table leaves between columns
+const SPACING: u16 = 1;
+// Name takes whatever is left, but columns are dropped to leave it this much
+const NAME_WIDTH: u16 = 24;
+// Room for the " ▼" after the title of the sort column
+const SORT_MARKER_WIDTH: u16 = 2;
+
+/// A column of the process tables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Pid,
+    Name,
+    Cpu,
+    MemPercent,
+    Mem,
+    Virt,
+    Shr,
+    Swap,
+    User,
+    State,
+    Threads,
+}
+
+/// The Process view's columns, left to right, when the terminal is wide
+/// enough for all of them.
+pub const PROCESS_COLUMNS: [Column; 11] = [
+    Column::Pid,
+    Column::Name,
+    Column::Cpu,
+    Column::MemPercent,
+    Column::Mem,
+    Column::Virt,
+    Column::Shr,
+    Column::Swap,
+    Column::User,
+    Column::State,
+    Column::Threads,
+];
+
+/// The compact table shown beside the Overview's charts.
+pub const OVERVIEW_COLUMNS: [Column; 5] = [
+    Column::Pid,
+    Column::Name,
+    Column::Cpu,
+    Column::MemPercent,
+    Column::Mem,
+];
+
+// First to go on a narrow terminal; the rest are never dropped
+const DROP_ORDER: [Column; 7] = [
+    Column::Swap,
+    Column::Shr,
+    Column::Virt,
+    Column::Threads,
+    Column::State,
+    Column::User,
+    Column::MemPercent,
+];
+
+impl Column {
+    /// Looks a column up by the name used for it in the config file.
+    pub fn named(name: &str) -> Option<Self> {
+        PROCESS_COLUMNS
+            .into_iter()
+            .find(|column| column.config_name().eq_ignore_ascii_case(name))
+    }
+
+    pub fn config_name(self) -> &'static str {
+        match self {
+            Column::Pid => "pid",
+            Column::Name => "name",
+            Column::Cpu => "cpu",
+            Column::MemPercent => "mem_percent",
+            Column::Mem => "mem",
+            Column::Virt => "virt",
+            Column::Shr => "shr",
+            Column::Swap => "swap",
+            Column::User => "user",
+            Column::State => "state",
+            Column::Threads => "threads",
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Column::Pid => "PID",
+            Column::Name => "Name",
+            Column::Cpu => "CPU%",
+            Column::MemPercent => "MEM%",
+            Column::Mem => "MEM",
+            Column::Virt => "VIRT",
+            Column::Shr => "SHR",
+            Column::Swap => "SWAP",
+            Column::User => "User",
+            Column::State => "State",
+            Column::Threads => "Threads",
+        }
+    }
+
+    /// The sort a click on this column's header selects, if any.
+    pub fn sort(self) -> Option<ProcessSort> {
+        match self {
+            Column::Pid => Some(ProcessSort::Pid),
+            Column::Name => Some(ProcessSort::Name),
+            Column::Cpu => Some(ProcessSort::Cpu),
+            Column::Mem => Some(ProcessSort::Memory),
+            Column::MemPercent => Some(ProcessSort::MemoryPercent),
+            Column::Virt | Column::Shr => None,
+            Column::Swap => Some(ProcessSort::Swap),
+            Column::User => Some(ProcessSort::User),
+            Column::State => Some(ProcessSort::State),
+            Column::Threads => Some(ProcessSort::Threads),
+        }
+    }
+
+    fn width(self) -> u16 {
+        let values = match self {
+            Column::Name => NAME_WIDTH,
+            Column::Pid | Column::Cpu | Column::MemPercent | Column::State | Column::Threads => 8,
+            Column::Mem | Column::Virt | Column::Shr | Column::Swap | Column::User => 10,
+        };
+        values.max(self.title().len() as u16 + SORT_MARKER_WIDTH)
+    }
+
+    /// Name fills the space the fixed-width columns leave, so it is the one
+    /// that shrinks once nothing more can be dropped.
+    pub fn constraint(self) -> Constraint {
+        match self {
+            Column::Name => Constraint::Fill(1),
+            _ => Constraint::Length(self.width()),
+        }
+    }
+}
+
+/// The columns that fit in `width`, dropping those in `DROP_ORDER` one at a
+/// time until the rest fit, or only pinned and essential ones remain.
+pub fn fit(columns: &[Column], width: u16, pinned: &[Column]) -> Vec<Column> {
+    let needed = |shown: &[Column]| {
+        let gaps = SPACING * (shown.len() as u16).saturating_sub(1);
+        shown.iter().map(|column| column.width()).sum::<u16>() + gaps
+    };
+    let mut shown = columns.to_vec();
+    for column in DROP_ORDER {
+        if needed(&shown) <= width {
+            break;
+        }
+        if !pinned.contains(&column) {
+            shown.retain(|&shown| shown != column);
+        }
+    }
+    shown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drops_columns_in_order_unless_pinned() {
+        assert_eq!(fit(&PROCESS_COLUMNS, 200, &[]), PROCESS_COLUMNS.to_vec());
+        assert_eq!(
+            fit(&PROCESS_COLUMNS, 78, &[]),
+            vec![
+                Column::Pid,
+                Column::Name,
+                Column::Cpu,
+                Column::MemPercent,
+                Column::Mem,
+                Column::User,
+            ]
+        );
+        assert_eq!(
+            fit(&PROCESS_COLUMNS, 40, &[]),
+            vec![Column::Pid, Column::Name, Column::Cpu, Column::Mem]
+        );
+        assert_eq!(
+            fit(&PROCESS_COLUMNS, 78, &[Column::Threads]),
+            vec![
+                Column::Pid,
+                Column::Name,
+                Column::Cpu,
+                Column::MemPercent,
+                Column::Mem,
+                Column::Threads,
+            ]
+        );
+        assert_eq!(Column::named("Threads"), Some(Column::Threads));
+        assert_eq!(Column::named("colour"), None);
+    }
+}
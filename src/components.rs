@@ -1,3 +1,5 @@
+use std::{borrow::Cow, collections::VecDeque};
+
 use ratatui::{
     Frame,
     layout::{Constraint, Direction, Layout, Rect},
@@ -5,77 +7,473 @@ use ratatui::{
     symbols,
     text::{Line, Span},
     widgets::{
-        Axis, BarChart, Block, Borders, Cell, Chart, Dataset, Gauge, GraphType, Paragraph, Row,
+        Axis, Bar, BarChart, BarGroup, Block, Borders, Cell, Chart, Clear, Dataset, Gauge,
+        GraphType, Paragraph, Row, Scrollbar, ScrollbarOrientation, ScrollbarState, Sparkline,
         Table, Widget,
     },
 };
+use unicode_width::UnicodeWidthStr;
 
-use crate::{sys_info::SystemInfo, theme::Theme};
+use crate::{
+    alerts::Area,
+    app::{ChartWindow, CpuDisplay, InputMode, PendingAction, ProcessRow, SearchMode, View},
+    columns::Column,
+    history::History,
+    keys::Action,
+    remote::Connection,
+    sys_info::{BatteryStatus, DiskInfo, ProcessSort, SensorKind, Signal, SystemInfo},
+    theme::{Severity, Theme},
+    utils::{UnitSystem, find_ignore_case, split_command},
+};
 
-pub fn render_header<'a>(area: Rect, theme: &'a Theme, metrics: &'a SystemInfo) -> Paragraph<'a> {
-    let uptime = format_duration(metrics.uptime);
-    let time = chrono::Local::now().format("%H:%M:%S").to_string();
-    let header_text = format!(
-        " {}@{} | {} | Up: {} | Load: {:.2} {:.2} {:.2} | Processes: {} | Threads: {} ",
-        whoami::username(),
-        metrics.hostname,
-        time,
-        uptime,
-        metrics.load_average.one,
-        metrics.load_average.five,
-        metrics.load_average.fifteen,
-        metrics.process_count,
-        metrics.thread_count,
+pub fn render_header<'a>(area: Rect, theme: &'a Theme, app: &'a crate::app::App) -> Paragraph<'a> {
+    let metrics = &app.metrics;
+    // Each field with its rank, lowest first to go when the line is too long
+    let mut fields: Vec<(u8, Span)> = Vec::new();
+    let mut field = |rank: u8, span: Span<'a>| fields.push((rank, span));
+    // The local user means nothing next to a remote host's name
+    let host = if app.is_remote() {
+        metrics.hostname.clone()
+    } else {
+        format!("{}@{}", app.username, metrics.hostname)
+    };
+    field(3, Span::raw(host));
+    field(
+        7,
+        Span::raw(chrono::Local::now().format("%H:%M:%S").to_string()),
+    );
+    field(
+        4,
+        Span::raw(format!("Up: {}", format_duration(metrics.uptime))),
+    );
+    field(
+        8,
+        Span::raw(format!(
+            "Load: {:.2} {:.2} {:.2}",
+            metrics.load_average.one, metrics.load_average.five, metrics.load_average.fifteen
+        )),
+    );
+    if let Some(pressure) = &metrics.pressure {
+        field(
+            2,
+            Span::raw(format!(
+                "PSI cpu/mem/io: {:.1} {:.1} {:.1}",
+                pressure.cpu.some_avg10, pressure.memory.some_avg10, pressure.io.some_avg10
+            )),
+        );
+    }
+    if let Some(celsius) = metrics.cpu_temperature {
+        field(
+            6,
+            Span::styled(
+                format!(
+                    "CPU {:.0}°C{}",
+                    celsius,
+                    theme.critical_mark(theme.thresholds.temperature.classify(f64::from(celsius)))
+                ),
+                Style::default().fg(theme.get_temp_color(celsius)),
+            ),
+        );
+    }
+    let swap_percent = crate::utils::safe_percentage(metrics.swap_used, metrics.swap_total);
+    // A little is normal on most systems
+    if swap_percent > theme.thresholds.swap.warning {
+        field(
+            6,
+            Span::styled(
+                format!("Swap: {:.0}%", swap_percent),
+                Style::default().fg(theme.get_load_color(swap_percent as u64)),
+            ),
+        );
+    }
+    // Shown against the total while filters hide some processes
+    let process_count = match app.listed_process_count() {
+        Some(listed) => format!("{}/{}", listed, metrics.process_count),
+        None => metrics.process_count.to_string(),
+    };
+    field(5, Span::raw(format!("Processes: {}", process_count)));
+    field(1, Span::raw(format!("Threads: {}", metrics.thread_count)));
+    for (label, rate, history) in [
+        (
+            "csw/s",
+            metrics.context_switches_per_sec,
+            &metrics.context_switch_history,
+        ),
+        ("forks/s", metrics.forks_per_sec, &metrics.fork_history),
+    ] {
+        let Some(rate) = rate else {
+            continue;
+        };
+        field(
+            0,
+            Span::styled(
+                format!("{} {}", crate::utils::format_count(rate), label),
+                if is_spike(history, rate, app.spike_factor) {
+                    Style::default()
+                        .fg(theme.danger)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                },
+            ),
+        );
+    }
+    if let Some(battery) = &metrics.battery {
+        let arrow = match battery.status {
+            BatteryStatus::Charging => " ↑",
+            BatteryStatus::Discharging => " ↓",
+            BatteryStatus::Full | BatteryStatus::Unknown => "",
+        };
+        let time_remaining = battery
+            .time_remaining
+            .map(|remaining| {
+                let minutes = remaining.as_secs() / 60;
+                format!(" {}:{:02}", minutes / 60, minutes % 60)
+            })
+            .unwrap_or_default();
+        field(
+            5,
+            Span::styled(
+                format!("BAT {}%{}{}", battery.capacity, arrow, time_remaining),
+                Style::default().fg(battery_color(battery.capacity, theme)),
+            ),
+        );
+    }
+    let mut spans = Vec::new();
+    if app.paused {
+        spans.push(Span::styled(
+            " PAUSED ",
+            Style::default()
+                .fg(theme.bg_dark)
+                .bg(theme.warning)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    // Until it is back, the figures shown are the last the server sent
+    let link = match app.remote_connection() {
+        Some(Connection::Connecting | Connection::Reconnecting(_)) => Some(" RECONNECTING... "),
+        Some(Connection::Incompatible(_)) => Some(" INCOMPATIBLE SERVER "),
+        Some(Connection::Connected) | None => None,
+    };
+    if let Some(link) = link {
+        spans.push(Span::styled(
+            link,
+            Style::default()
+                .fg(theme.bg_dark)
+                .bg(theme.danger)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    let firing = app.alerts.firing().count();
+    if firing > 0 {
+        spans.push(Span::styled(
+            format!(" ⚠ {} ALERT{} ", firing, if firing == 1 { "" } else { "S" }),
+            Style::default()
+                .fg(theme.bg_dark)
+                .bg(theme.danger)
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    // Whole fields are dropped, so none is ever cut off mid-number
+    let badge_width: usize = spans.iter().map(Span::width).sum();
+    fit_ranked(
+        &mut fields,
+        usize::from(area.width).saturating_sub(badge_width + 2),
+        " | ",
     );
-    Paragraph::new(header_text)
+    spans.push(Span::raw(" "));
+    for (index, (_, field)) in fields.into_iter().enumerate() {
+        if index > 0 {
+            spans.push(Span::raw(" | "));
+        }
+        spans.push(field);
+    }
+    spans.push(Span::raw(" "));
+    Paragraph::new(Line::from(spans))
         .style(Style::default().fg(theme.text_bright).bg(theme.bg_dark))
         .alignment(ratatui::layout::Alignment::Center)
 }
 
-pub fn render_footer<'a>(
-    area: Rect,
-    theme: &'a Theme,
-    current_view: &'a str,
-    show_help: bool,
-) -> Paragraph<'a> {
-    let footer_text = if show_help {
-        "[q]uit [↑↓]scroll [c/m]sort [F1]help [f]fullcmd [space]pause [+-]speed"
-    } else {
-        "[F1]Help [↑↓]Select [c/m]Sort [f]FullCmd [space]Pause [+-]Speed [q]Quit"
-    };
-    Paragraph::new(footer_text)
+pub fn render_footer<'a>(area: Rect, theme: &'a Theme, app: &'a crate::app::App) -> Paragraph<'a> {
+    let keys = &app.keys;
+    let search = (app.input_mode == InputMode::Search)
+        .then_some((app.search_query.as_str(), app.search_mode));
+    let match_span = app.search_match_summary().map(|summary| {
+        Span::styled(
+            format!(
+                "Match {} [{}]  ",
+                summary,
+                keys.labels(&[Action::Sort(ProcessSort::Name), Action::PreviousMatch])
+            ),
+            Style::default()
+                .fg(theme.warning)
+                .add_modifier(Modifier::BOLD),
+        )
+    });
+    if let Some((query, mode)) = search {
+        let mut spans = vec![
+            Span::styled(
+                match mode {
+                    SearchMode::Filter => "Filter: ",
+                    SearchMode::Highlight => "Search: ",
+                },
+                Style::default().fg(theme.info).add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!("{}_  ", query),
+                Style::default().fg(theme.text_bright),
+            ),
+        ];
+        spans.extend(match_span);
+        spans.push(Span::styled(
+            "[Tab] Filter/Highlight [Enter] Keep [Esc] Cancel",
+            Style::default().fg(theme.text_dim),
+        ));
+        return Paragraph::new(Line::from(spans)).style(Style::default().bg(theme.bg_dark));
+    }
+    if let Some(status) = app.current_status() {
+        return Paragraph::new(status.text.as_str())
+            .style(
+                Style::default()
+                    .fg(if status.is_error {
+                        theme.danger
+                    } else {
+                        theme.success
+                    })
+                    .bg(theme.bg_dark),
+            )
+            .alignment(ratatui::layout::Alignment::Center);
+    }
+    if app.show_help {
+        // Only the overlay's own keys work while it is open
+        return Paragraph::new("[↑↓]Scroll [PgUp/PgDn]Page [F1/Esc/q]Close")
+            .style(Style::default().fg(theme.text_dim).bg(theme.bg_dark))
+            .alignment(ratatui::layout::Alignment::Center);
+    }
+    let mut hints: Vec<(u8, Span)> = footer_hints(app.current_view)
+        .into_iter()
+        .map(|(rank, actions, label)| {
+            (
+                rank,
+                Span::raw(format!("[{}]{}", keys.labels(actions), label)),
+            )
+        })
+        .collect();
+    let unseen = app.events.unseen();
+    let events_span = (unseen > 0).then(|| {
+        Span::styled(
+            format!(
+                "{} new event{} [{}]  ",
+                unseen,
+                if unseen == 1 { "" } else { "s" },
+                keys.label(Action::Events)
+            ),
+            Style::default()
+                .fg(theme.warning)
+                .add_modifier(Modifier::BOLD),
+        )
+    });
+    let match_width =
+        match_span.as_ref().map_or(0, Span::width) + events_span.as_ref().map_or(0, Span::width);
+    fit_ranked(
+        &mut hints,
+        usize::from(area.width).saturating_sub(match_width),
+        " ",
+    );
+    let mut spans: Vec<Span> = events_span.into_iter().chain(match_span).collect();
+    for (index, (_, hint)) in hints.into_iter().enumerate() {
+        if index > 0 {
+            spans.push(Span::raw(" "));
+        }
+        spans.push(hint);
+    }
+    Paragraph::new(Line::from(spans))
         .style(Style::default().fg(theme.text_dim).bg(theme.bg_dark))
         .alignment(ratatui::layout::Alignment::Center)
 }
 
+// The footer's hints for a view, left to right, each ranked by how much it
+// matters; the lowest ranked go first when the line is too long
+fn footer_hints(view: View) -> Vec<(u8, &'static [Action], &'static str)> {
+    let mut hints: Vec<(u8, &'static [Action], &'static str)> = vec![(9, &[Action::Help], "Help")];
+    hints.extend_from_slice(match view {
+        View::Overview => &[
+            (7, &[Action::Up, Action::Down], "Select"),
+            (
+                5,
+                &[
+                    Action::Sort(ProcessSort::Cpu),
+                    Action::Sort(ProcessSort::Memory),
+                ],
+                "Sort",
+            ),
+            (3, &[Action::FullCommand], "FullCmd"),
+        ],
+        View::Process => &[
+            (7, &[Action::Up, Action::Down], "Select"),
+            (
+                5,
+                &[
+                    Action::Sort(ProcessSort::Cpu),
+                    Action::Sort(ProcessSort::Memory),
+                    Action::Sort(ProcessSort::Pid),
+                    Action::Sort(ProcessSort::Name),
+                    Action::Sort(ProcessSort::User),
+                    Action::Sort(ProcessSort::Threads),
+                    Action::Sort(ProcessSort::Time),
+                    Action::Sort(ProcessSort::State),
+                ],
+                "Sort",
+            ),
+            (
+                3,
+                &[Action::PreviousSortColumn, Action::NextSortColumn],
+                "Column",
+            ),
+            (6, &[Action::Search], "Search"),
+            (2, &[Action::TreeView], "Tree"),
+            (6, &[Action::Kill], "Kill"),
+        ],
+        View::System => &[
+            (6, &[Action::CpuDisplay], "Cores"),
+            (5, &[Action::CoreGraphs], "Graphs"),
+        ],
+        View::Resources => &[(6, &[Action::ZoomIn, Action::ZoomOut], "Zoom")],
+        View::Network => &[
+            (7, &[Action::Up, Action::Down], "Interface"),
+            (5, &[Action::PageUp, Action::PageDown], "Connections"),
+        ],
+        View::Disks => &[
+            (7, &[Action::Up, Action::Down], "Scroll"),
+            (5, &[Action::PseudoFilesystems], "PseudoFS"),
+            (4, &[Action::Activate], "Expand"),
+        ],
+        View::Options => &[
+            (7, &[Action::Up, Action::Down], "Select"),
+            (
+                6,
+                &[Action::PreviousSortColumn, Action::NextSortColumn],
+                "Change",
+            ),
+        ],
+        View::Gpu | View::Sensors => &[(5, &[Action::Up, Action::Down], "Scroll")],
+        View::Services => &[
+            (7, &[Action::Up, Action::Down], "Select"),
+            (5, &[Action::Activate], "MainPID"),
+            (4, &[Action::FailedFirst], "FailedFirst"),
+        ],
+    });
+    hints.extend_from_slice(&[
+        (1, &[Action::Pause], "Pause"),
+        (0, &[Action::SlowerUpdates, Action::FasterUpdates], "Speed"),
+        (8, &[Action::Quit], "Quit"),
+    ]);
+    hints
+}
+
+/// The border of a block, in the danger color while an alert about its
+/// part of the screen is firing.
+pub fn alert_border(app: &crate::app::App, area: Area, theme: &Theme) -> Style {
+    Style::default().fg(if app.alerts.is_firing(area) {
+        theme.danger
+    } else {
+        theme.border
+    })
+}
+
+// Drops the lowest ranked fields, the leftmost of equals first, until the
+// rest fit in `width` joined by `separator`. One field is always kept.
+fn fit_ranked(fields: &mut Vec<(u8, Span)>, width: usize, separator: &str) {
+    let needed = |fields: &[(u8, Span)]| {
+        let text: usize = fields.iter().map(|(_, span)| span.width()).sum();
+        text + separator.chars().count() * fields.len().saturating_sub(1)
+    };
+    while fields.len() > 1 && needed(fields) > width {
+        let lowest = (0..fields.len()).min_by_key(|&index| fields[index].0);
+        fields.remove(lowest.unwrap_or(0));
+    }
+}
+
 pub fn render_system_view<'a>(
     area: Rect,
     theme: &'a Theme,
-    metrics: &'a SystemInfo,
+    app: &'a crate::app::App,
 ) -> Box<dyn FnOnce(&mut Frame) + 'a> {
+    let metrics = &app.metrics;
+    let has_cpufreq = !metrics.cpu_frequency_per_core.is_empty();
+    let cpu_display = if has_cpufreq {
+        app.cpu_display
+    } else {
+        CpuDisplay::Usage
+    };
+    // Meters for many cores take a line each, so the pane grows to fit them
+    let cores = metrics.cpu_usage_per_core.len() as u16;
+    let bars_width = Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .split(area)[0]
+        .width
+        .saturating_sub(2);
+    let meter_rows = match core_layout(usize::from(cores), Rect::new(0, 0, bars_width, 1)) {
+        CoreLayout::Meters { .. } => cores.div_ceil((bars_width / CORE_METER_WIDTH).max(1)),
+        CoreLayout::Bars { .. } => 0,
+    };
     let layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(10),
+            // Core graphs get room to be readable
+            if app.show_core_graphs {
+                Constraint::Min(10)
+            } else {
+                Constraint::Length((meter_rows + 2).max(10))
+            },
             Constraint::Length(12),
             Constraint::Min(8),
         ])
         .split(area);
+    // The graphs take the full width, leaving the CPU info to the header
+    let grid = if app.show_core_graphs {
+        core_grid(
+            metrics.cpu_core_history.len(),
+            Block::default().borders(Borders::ALL).inner(layout[0]),
+        )
+    } else {
+        None
+    };
     let cpu_layout = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+        .constraints(if grid.is_some() {
+            [Constraint::Percentage(100), Constraint::Percentage(0)]
+        } else {
+            [Constraint::Percentage(60), Constraint::Percentage(40)]
+        })
         .split(layout[0]);
     let cpu_block = Block::default()
         .title(Span::styled(
-            " CPU Usage ",
+            match (app.cpu_display, cpu_display) {
+                _ if grid.is_some() => " CPU History per Core [a] ",
+                _ if app.show_core_graphs => " CPU Usage [too small for core graphs] ",
+                (CpuDisplay::Usage, _) => " CPU Usage [v] ",
+                (_, CpuDisplay::Usage) => " CPU Usage [no cpufreq] ",
+                (_, CpuDisplay::Frequency) => " CPU Frequency GHz [v] ",
+                (_, CpuDisplay::Governor) => " CPU Governor [v] ",
+            },
             Style::default()
                 .fg(theme.text_bright)
                 .add_modifier(Modifier::BOLD),
         ))
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(theme.border));
+        .border_style(alert_border(app, Area::Cpu, theme));
     let cpu_area = cpu_block.inner(cpu_layout[0]);
-    let cpu_info = vec![
+    let governors = metrics.governor_counts();
+    let governor_text = match governors.as_slice() {
+        [] => "N/A".to_string(),
+        [(governor, _)] => governor.to_string(),
+        mixed => mixed
+            .iter()
+            .map(|(governor, count)| format!("{} ×{}", governor, count))
+            .collect::<Vec<_>>()
+            .join(", "),
+    };
+    let mut cpu_info = vec![
         Line::from(vec![
             Span::styled("Model: ", Style::default().fg(theme.text_dim)),
             Span::styled(&metrics.cpu_model, Style::default().fg(theme.text_primary)),
@@ -90,33 +488,49 @@ pub fn render_system_view<'a>(
         Line::from(vec![
             Span::styled("Frequency: ", Style::default().fg(theme.text_dim)),
             Span::styled(
-                format!("{} MHz", metrics.cpu_frequency),
+                metrics
+                    .cpu_frequency
+                    .map_or_else(|| "N/A".to_string(), |freq| format!("{} MHz", freq)),
                 Style::default().fg(theme.text_primary),
             ),
         ]),
         Line::from(vec![
             Span::styled("Temperature: ", Style::default().fg(theme.text_dim)),
             Span::styled(
-                format!("{:.1}°C", metrics.cpu_temperature),
-                Style::default().fg(if metrics.cpu_temperature > 80.0 {
-                    theme.danger
-                } else if metrics.cpu_temperature > 70.0 {
-                    theme.warning
-                } else {
-                    theme.success
-                }),
+                metrics.cpu_temperature.map_or_else(
+                    || "N/A".to_string(),
+                    |temp| {
+                        let severity = theme.thresholds.temperature.classify(f64::from(temp));
+                        format!("{:.1}°C{}", temp, theme.critical_mark(severity))
+                    },
+                ),
+                Style::default().fg(metrics
+                    .cpu_temperature
+                    .map_or(theme.text_dim, |temp| theme.get_temp_color(temp))),
             ),
         ]),
         Line::from(vec![
             Span::styled("Total Usage: ", Style::default().fg(theme.text_dim)),
-            Span::styled(
-                format!("{}%", metrics.cpu_total_usage),
-                Style::default()
-                    .fg(theme.get_usage_color(metrics.cpu_total_usage))
-                    .add_modifier(Modifier::BOLD),
-            ),
+            theme.percentage(crate::utils::format_percentage(
+                metrics.cpu_total_usage as f64,
+                theme.thresholds.usage,
+            )),
         ]),
     ];
+    if has_cpufreq {
+        cpu_info.push(Line::from(vec![
+            Span::styled("Governor: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                governor_text,
+                // Cores pinned to a different governor are worth noticing
+                Style::default().fg(if governors.len() > 1 {
+                    theme.warning
+                } else {
+                    theme.text_primary
+                }),
+            ),
+        ]));
+    }
     let cpu_info_block = Block::default()
         .title(Span::styled(
             " CPU Info ",
@@ -135,7 +549,7 @@ pub fn render_system_view<'a>(
                 .add_modifier(Modifier::BOLD),
         ))
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(theme.border));
+        .border_style(alert_border(app, Area::Memory, theme));
     let mem_area = mem_block.inner(layout[1]);
     let mem_percent = (metrics.memory_used as f64 / metrics.memory_total as f64 * 100.0) as u64;
     let swap_percent = if metrics.swap_total > 0 {
@@ -147,35 +561,39 @@ pub fn render_system_view<'a>(
         Line::from(vec![
             Span::styled("Total: ", Style::default().fg(theme.text_dim)),
             Span::styled(
-                format!("{:.1} GB", metrics.memory_total as f64 / 1024.0),
+                crate::utils::format_size(metrics.memory_total, crate::utils::MB, app.units),
                 Style::default().fg(theme.text_primary),
             ),
         ]),
         Line::from(vec![
             Span::styled("Used: ", Style::default().fg(theme.text_dim)),
             Span::styled(
-                format!("{:.1} GB", metrics.memory_used as f64 / 1024.0),
+                crate::utils::format_size(metrics.memory_used, crate::utils::MB, app.units),
                 Style::default()
                     .fg(theme.get_mem_color(mem_percent))
                     .add_modifier(Modifier::BOLD),
             ),
             Span::raw(" "),
             Span::styled(
-                format!("({}%)", mem_percent),
+                format!(
+                    "({}%{})",
+                    mem_percent,
+                    theme.critical_mark(theme.thresholds.memory.classify(mem_percent as f64))
+                ),
                 Style::default().fg(theme.get_mem_color(mem_percent)),
             ),
         ]),
         Line::from(vec![
             Span::styled("Available: ", Style::default().fg(theme.text_dim)),
             Span::styled(
-                format!("{:.1} GB", metrics.memory_available as f64 / 1024.0),
+                crate::utils::format_size(metrics.memory_available, crate::utils::MB, app.units),
                 Style::default().fg(theme.text_primary),
             ),
         ]),
         Line::from(vec![
             Span::styled("Cached: ", Style::default().fg(theme.text_dim)),
             Span::styled(
-                format!("{:.1} GB", metrics.memory_cached as f64 / 1024.0),
+                crate::utils::format_size(metrics.memory_cached, crate::utils::MB, app.units),
                 Style::default().fg(theme.text_secondary),
             ),
         ]),
@@ -183,32 +601,20 @@ pub fn render_system_view<'a>(
             Span::styled("Swap: ", Style::default().fg(theme.text_dim)),
             Span::styled(
                 format!(
-                    "{}/{} GB",
-                    metrics.swap_used / 1024,
-                    metrics.swap_total / 1024
+                    "{}/{}",
+                    crate::utils::format_size(metrics.swap_used, crate::utils::MB, app.units),
+                    crate::utils::format_size(metrics.swap_total, crate::utils::MB, app.units)
                 ),
-                Style::default().fg(if swap_percent > 50 {
-                    theme.danger
-                } else {
-                    theme.text_primary
-                }),
+                Style::default().fg(theme.text_primary),
             ),
             Span::raw(" "),
-            Span::styled(
-                format!("({}%)", swap_percent),
-                Style::default().fg(if swap_percent > 50 {
-                    theme.danger
-                } else {
-                    theme.warning
-                }),
-            ),
+            theme.percentage(crate::utils::format_percentage(
+                swap_percent as f64,
+                theme.thresholds.swap,
+            )),
         ]),
     ];
-    let mem_gauge = Gauge::default()
-        .block(Block::default())
-        .gauge_style(Style::default().fg(theme.get_mem_color(mem_percent)))
-        .percent(mem_percent as u16)
-        .label(format!("{}%", mem_percent));
+    let mem_bar = Paragraph::new(memory_bar(metrics, theme, mem_area.width, app.units));
     let mem_info_para = Paragraph::new(mem_info).block(Block::default());
     let sys_block = Block::default()
         .title(Span::styled(
@@ -219,7 +625,6 @@ pub fn render_system_view<'a>(
         ))
         .borders(Borders::ALL)
         .border_style(Style::default().fg(theme.border));
-    let sys_area = sys_block.inner(layout[2]);
     let sys_info = vec![
         Line::from(vec![
             Span::styled("OS: ", Style::default().fg(theme.text_dim)),
@@ -237,123 +642,378 @@ pub fn render_system_view<'a>(
             Span::styled(&metrics.hostname, Style::default().fg(theme.text_primary)),
         ]),
     ];
+    let sys_info_lines = sys_info.len();
     let sys_info_para = Paragraph::new(sys_info).block(Block::default());
-    let cpu_usage_data = metrics.cpu_usage_per_core.clone();
-    Box::new(move |f: &mut ratatui::Frame| {
-        let cpu_data: Vec<(&'static str, u64)> = cpu_usage_data
+    let bottom_panels = 1 + metrics.kernel.is_some() as u32 + metrics.battery.is_some() as u32;
+    let bottom_layout = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints((0..bottom_panels).map(|_| Constraint::Ratio(1, bottom_panels)))
+        .split(layout[2]);
+    let sys_layout_area = bottom_layout[0];
+    let kernel_layout_area = metrics.kernel.as_ref().map(|_| bottom_layout[1]);
+    let power_layout_area = metrics
+        .battery
+        .as_ref()
+        .map(|_| bottom_layout[bottom_layout.len() - 1]);
+    let sys_area = sys_block.inner(sys_layout_area);
+    let sys_info_para =
+        sys_info_para.scroll((app.scroll_skip(sys_info_lines, sys_area.height) as u16, 0));
+    let power_block = Block::default()
+        .title(Span::styled(
+            " Power ",
+            Style::default()
+                .fg(theme.text_bright)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+    let power_info: Vec<Line> = metrics
+        .battery
+        .iter()
+        .flat_map(|battery| {
+            let (status, rate_label) = match battery.status {
+                BatteryStatus::Charging => ("Charging", "Charge rate: "),
+                BatteryStatus::Discharging => ("Discharging", "Discharge rate: "),
+                BatteryStatus::Full => ("Full", "Rate: "),
+                BatteryStatus::Unknown => ("Unknown", "Rate: "),
+            };
+            vec![
+                Line::from(vec![
+                    Span::styled("Battery: ", Style::default().fg(theme.text_dim)),
+                    Span::styled(
+                        format!("{}%", battery.capacity),
+                        Style::default()
+                            .fg(battery_color(battery.capacity, theme))
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                ]),
+                Line::from(vec![
+                    Span::styled("Status: ", Style::default().fg(theme.text_dim)),
+                    Span::styled(status, Style::default().fg(theme.text_primary)),
+                ]),
+                Line::from(vec![
+                    Span::styled(rate_label, Style::default().fg(theme.text_dim)),
+                    Span::styled(
+                        battery
+                            .power
+                            .map_or_else(|| "N/A".to_string(), |watts| format!("{:.1} W", watts)),
+                        Style::default().fg(theme.text_primary),
+                    ),
+                ]),
+                Line::from(vec![
+                    Span::styled("Remaining: ", Style::default().fg(theme.text_dim)),
+                    Span::styled(
+                        battery
+                            .time_remaining
+                            .map_or_else(|| "N/A".to_string(), format_duration),
+                        Style::default().fg(theme.text_primary),
+                    ),
+                ]),
+            ]
+        })
+        .collect();
+    let power_lines = power_info.len();
+    let power_para = Paragraph::new(power_info).block(Block::default());
+    let power_para = match power_layout_area {
+        Some(power_layout_area) => {
+            let height = power_block.inner(power_layout_area).height;
+            power_para.scroll((app.scroll_skip(power_lines, height) as u16, 0))
+        }
+        None => power_para,
+    };
+    let kernel_block = Block::default()
+        .title(Span::styled(
+            " Kernel ",
+            Style::default()
+                .fg(theme.text_bright)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+    let file_handles = metrics
+        .kernel
+        .as_ref()
+        .and_then(|kernel| kernel.file_handles);
+    let file_handles_percent = file_handles
+        .map(|(allocated, max)| (allocated * 100).checked_div(max).unwrap_or(0).min(100));
+    let mut kernel_info = Vec::new();
+    if let (Some((allocated, max)), Some(percent)) = (file_handles, file_handles_percent) {
+        kernel_info.push(Line::from(vec![
+            Span::styled("File handles: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                format!("{} / {}", allocated, max),
+                Style::default()
+                    .fg(theme.get_usage_color(percent))
+                    .add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+    if let Some(kernel) = &metrics.kernel {
+        if let Some(ptys) = kernel.ptys {
+            kernel_info.push(Line::from(vec![
+                Span::styled("PTYs: ", Style::default().fg(theme.text_dim)),
+                Span::styled(
+                    match kernel.pty_max {
+                        Some(max) => format!("{} / {}", ptys, max),
+                        None => ptys.to_string(),
+                    },
+                    Style::default().fg(theme.text_primary),
+                ),
+            ]));
+        }
+        if let Some(entropy) = kernel.entropy {
+            kernel_info.push(Line::from(vec![
+                Span::styled("Entropy: ", Style::default().fg(theme.text_dim)),
+                Span::styled(
+                    format!("{} bits", entropy),
+                    Style::default().fg(theme.text_primary),
+                ),
+            ]));
+        }
+    }
+    // Three lines above the file handle gauge
+    let kernel_skip = match kernel_layout_area {
+        Some(_) => app.scroll_skip(kernel_info.len(), 3),
+        None => 0,
+    };
+    let kernel_para = Paragraph::new(kernel_info)
+        .block(Block::default())
+        .scroll((kernel_skip as u16, 0));
+    let file_handles_gauge = file_handles_percent.map(|percent| {
+        Gauge::default()
+            .gauge_style(Style::default().fg(theme.get_usage_color(percent)))
+            .percent(percent as u16)
+            .label(format!("{}%", percent))
+    });
+    let max_frequency = metrics
+        .cpu_frequency_per_core
+        .iter()
+        .flatten()
+        .copied()
+        .max()
+        .unwrap_or(0);
+    let bar_max = match cpu_display {
+        CpuDisplay::Frequency => max_frequency.max(1),
+        _ => 100,
+    };
+    // Each core's bar height, the figure printed with it and its color
+    let core_figures: Vec<(u64, String, Color)> = metrics
+        .cpu_usage_per_core
+        .iter()
+        .enumerate()
+        .map(|(i, &usage)| {
+            let load_color = theme.get_load_color(usage);
+            match cpu_display {
+                CpuDisplay::Usage => (usage, usage.to_string(), load_color),
+                CpuDisplay::Frequency => {
+                    let mhz = metrics.cpu_frequency_per_core.get(i).copied().flatten();
+                    let text = mhz.map_or_else(
+                        || "-".to_string(),
+                        |mhz| format!("{:.1}", mhz as f64 / 1000.0),
+                    );
+                    (mhz.unwrap_or(0), text, load_color)
+                }
+                CpuDisplay::Governor => {
+                    let governor = metrics.cpu_governors.get(i).cloned().flatten();
+                    let color = governor_color(governor.as_deref(), theme);
+                    let text = governor.map_or_else(
+                        || "-".to_string(),
+                        |governor| governor.chars().take(3).collect(),
+                    );
+                    (usage, text, color)
+                }
+            }
+        })
+        .collect();
+    let core_layout = core_layout(core_figures.len(), cpu_area);
+    let cpu_bars: Vec<Bar> = core_figures
+        .iter()
+        .enumerate()
+        .map(|(i, (value, text, color))| {
+            // The value is printed on the bar, so it takes the bar's color
+            // as its background to stay readable on any of them
+            Bar::default()
+                .label(Line::from(core_label(i)))
+                .value(*value)
+                .text_value(text.clone())
+                .style(Style::default().fg(*color))
+                .value_style(
+                    Style::default()
+                        .fg(theme.bg_dark)
+                        .bg(*color)
+                        .add_modifier(Modifier::BOLD),
+                )
+        })
+        .collect();
+    let percent_sign = if cpu_display == CpuDisplay::Usage {
+        "%"
+    } else {
+        ""
+    };
+    let window = ChartWindow::Minute.duration();
+    let core_data: Vec<Vec<(f64, f64)>> = match grid {
+        Some(_) => metrics
+            .cpu_core_history
             .iter()
-            .enumerate()
-            .map(|(i, &usage)| {
-                let label = if i < 10 {
-                    format!("C{}", i)
-                } else {
-                    format!("{}", i)
-                };
-                let leaked_str: &'static str = Box::leak(label.into_boxed_str());
-                (leaked_str, usage)
-            })
-            .collect();
-        let cpu_chart = BarChart::default()
-            .block(Block::default())
-            .bar_width(3)
-            .bar_gap(1)
-            .bar_style(Style::default().fg(theme.cpu_colors[0]))
-            .value_style(Style::default().fg(theme.text_secondary))
-            .label_style(Style::default().fg(theme.text_dim))
-            .data(&cpu_data);
+            .map(|history| chart_points(history, window))
+            .collect(),
+        None => Vec::new(),
+    };
+    Box::new(move |f: &mut ratatui::Frame| {
         let cpu_info_block_clone = cpu_info_block.clone();
         f.render_widget(cpu_block, cpu_layout[0]);
-        f.render_widget(cpu_chart, cpu_area);
-        f.render_widget(cpu_info_block, cpu_layout[1]);
-        f.render_widget(cpu_info_para, cpu_info_block_clone.inner(cpu_layout[1]));
+        match (grid, core_layout) {
+            (Some((columns, rows)), _) => {
+                render_core_graphs(f, cpu_area, theme, metrics, &core_data, columns, rows)
+            }
+            (None, CoreLayout::Bars { width }) => {
+                let cpu_chart = BarChart::default()
+                    .block(Block::default())
+                    .bar_width(width)
+                    .bar_gap(1)
+                    .max(bar_max)
+                    .value_style(Style::default().fg(theme.text_secondary))
+                    .label_style(Style::default().fg(theme.text_dim))
+                    .data(BarGroup::new(cpu_bars));
+                f.render_widget(cpu_chart, cpu_area)
+            }
+            (None, CoreLayout::Meters { columns }) => render_core_meters(
+                f,
+                cpu_area,
+                theme,
+                &core_figures,
+                bar_max,
+                percent_sign,
+                columns,
+            ),
+        }
+        if grid.is_none() {
+            f.render_widget(cpu_info_block, cpu_layout[1]);
+            f.render_widget(cpu_info_para, cpu_info_block_clone.inner(cpu_layout[1]));
+        }
         f.render_widget(mem_block, layout[1]);
         let mem_layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([Constraint::Length(6), Constraint::Min(1)])
             .split(mem_area);
         f.render_widget(mem_info_para, mem_layout[0]);
-        f.render_widget(mem_gauge, mem_layout[1]);
-        f.render_widget(sys_block, layout[2]);
+        f.render_widget(mem_bar, mem_layout[1]);
+        f.render_widget(sys_block, sys_layout_area);
         f.render_widget(sys_info_para, sys_area);
+        if let Some(kernel_layout_area) = kernel_layout_area {
+            let kernel_area = kernel_block.inner(kernel_layout_area);
+            f.render_widget(kernel_block, kernel_layout_area);
+            let kernel_layout = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(3), Constraint::Length(1)])
+                .split(kernel_area);
+            f.render_widget(kernel_para, kernel_layout[0]);
+            if let Some(gauge) = file_handles_gauge {
+                f.render_widget(gauge, kernel_layout[1]);
+            }
+        }
+        if let Some(power_layout_area) = power_layout_area {
+            let power_area = power_block.inner(power_layout_area);
+            f.render_widget(power_block, power_layout_area);
+            f.render_widget(power_para, power_area);
+        }
     })
 }
 
+const COMMAND_LABEL: &str = "Command: ";
+// Beyond this the detail pane would crowd out the table
+const MAX_COMMAND_LINES: usize = 6;
+
 pub fn render_process_view<'a>(
     area: Rect,
     theme: &'a Theme,
-    metrics: &'a SystemInfo,
-    selected_process: usize,
-    scroll_offset: usize,
-    max_rows: usize,
-    show_full_command: bool,
+    app: &'a crate::app::App,
+    process_rows: &'a [ProcessRow<'a>],
 ) -> Box<dyn FnOnce(&mut Frame) + 'a> {
+    let selected_process = app.selected_process;
+    let scroll_offset = app.process_scroll_offset;
+    let show_full_command = app.show_full_command;
+    // The detail pane is opt-in with Enter; hidden, its rows go to the table.
+    // The command wraps onto as many lines as it needs, up to a limit, in the
+    // width the usage chart leaves it
+    let text_width = usize::from(area.width.saturating_sub(2));
+    let text_width = if text_width >= 100 {
+        text_width - 32
+    } else {
+        text_width
+    };
+    let selected = process_rows.get(selected_process).map(|row| &*row.process);
+    let mut command_lines = selected.map_or_else(Vec::new, |process| {
+        crate::utils::wrap_command(
+            &process.full_command,
+            text_width.saturating_sub(COMMAND_LABEL.len()),
+        )
+    });
+    let cut_command = command_lines.len() > MAX_COMMAND_LINES;
+    command_lines.truncate(MAX_COMMAND_LINES);
+    let detail_height = if app.show_proc_details {
+        9 + command_lines.len().max(1) as u16
+    } else {
+        0
+    };
     let layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),
             Constraint::Min(1),
-            Constraint::Length(8),
+            Constraint::Length(detail_height),
         ])
         .split(area);
-    let header = Row::new(vec![
-        Cell::from("PID").style(
-            Style::default()
-                .fg(theme.text_bright)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Cell::from("Name").style(
-            Style::default()
-                .fg(theme.text_bright)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Cell::from("CPU%").style(
-            Style::default()
-                .fg(theme.text_bright)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Cell::from("MEM").style(
-            Style::default()
-                .fg(theme.text_bright)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Cell::from("User").style(
-            Style::default()
-                .fg(theme.text_bright)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Cell::from("State").style(
-            Style::default()
-                .fg(theme.text_bright)
-                .add_modifier(Modifier::BOLD),
-        ),
-        Cell::from("Threads").style(
-            Style::default()
-                .fg(theme.text_bright)
-                .add_modifier(Modifier::BOLD),
-        ),
-    ]);
+    // Mirror the table's own column layout so clicks land on the header
+    // that was drawn there
+    let table_area = Block::default().borders(Borders::ALL).inner(layout[1]);
+    let header_line = Rect {
+        height: table_area.height.min(1),
+        ..table_area
+    };
+    let rows_area = Rect {
+        y: table_area.y + header_line.height,
+        height: table_area.height - header_line.height,
+        ..table_area
+    };
+    let max_rows = usize::from(rows_area.height);
+    let columns = crate::columns::fit(
+        &crate::columns::PROCESS_COLUMNS,
+        table_area.width,
+        &app.pinned_columns,
+    );
+    let header = Row::new(
+        columns
+            .iter()
+            .map(|&column| process_header_cell(column, app, theme)),
+    );
+    let widths: Vec<Constraint> = columns.iter().map(|column| column.constraint()).collect();
+    let column_areas = Layout::horizontal(widths.clone())
+        .spacing(1)
+        .split(header_line);
+    let name_width = columns
+        .iter()
+        .position(|&column| column == Column::Name)
+        .map_or(0, |index| usize::from(column_areas[index].width));
     let start_idx = scroll_offset;
-    let end_idx = (scroll_offset + max_rows).min(metrics.processes.len());
-    let rows: Vec<Row> = metrics.processes[start_idx..end_idx]
+    let end_idx = (scroll_offset + max_rows).min(process_rows.len());
+    let rows: Vec<Row> = process_rows[start_idx.min(end_idx)..end_idx]
         .iter()
         .enumerate()
-        .map(|(i, process)| {
+        .map(|(i, row)| {
+            let process = &*row.process;
             let global_idx = start_idx + i;
             let is_selected = global_idx == selected_process;
-
-            let cpu_color = if process.cpu_usage > 50.0 {
-                theme.danger
-            } else if process.cpu_usage > 25.0 {
-                theme.warning
-            } else {
-                theme.success
-            };
-            let mem_color = if process.memory_percent > 10.0 {
-                theme.danger
-            } else if process.memory_percent > 5.0 {
-                theme.warning
-            } else {
-                theme.info
+            let is_match = app.is_highlighting() && app.matches_search(process);
+            let cpu_severity = theme.thresholds.process_cpu.classify(process.cpu_usage);
+            let cpu_color = theme.severity_color(cpu_severity);
+            let mem_severity = theme
+                .thresholds
+                .process_memory
+                .classify(process.memory_percent);
+            let mem_color = match mem_severity {
+                Severity::Normal => theme.info,
+                severity => theme.severity_color(severity),
             };
             let state_color = match process.state {
                 crate::sys_info::ProcessState::Running => theme.success,
@@ -361,97 +1021,276 @@ pub fn render_process_view<'a>(
                 crate::sys_info::ProcessState::Zombie => theme.danger,
                 _ => theme.warning,
             };
-            let bg_color = if is_selected {
-                theme.bg_lighter
+            let mut row_style = if is_selected || is_match {
+                theme.selected()
             } else if global_idx % 2 == 0 {
-                theme.bg_normal
+                Style::default().bg(theme.bg_normal)
             } else {
-                theme.bg_light
+                Style::default().bg(theme.bg_light)
             };
-            Row::new(vec![
-                Cell::from(process.pid.to_string()).style(Style::default().fg(theme.text_primary)),
-                Cell::from(if show_full_command && !process.full_command.is_empty() {
-                    process.full_command.clone()
-                } else {
-                    process.name.clone()
-                })
-                .style(Style::default().fg(theme.text_primary)),
-                Cell::from(format!("{:.1}", process.cpu_usage))
-                    .style(Style::default().fg(cpu_color).add_modifier(Modifier::BOLD)),
-                Cell::from(format!("{} MB", process.memory_usage))
-                    .style(Style::default().fg(mem_color).add_modifier(Modifier::BOLD)),
-                Cell::from(process.user.clone()).style(Style::default().fg(theme.text_secondary)),
-                Cell::from(process.state.to_string()).style(
+            let tagged = row.group_size.is_none() && app.tagged_pids.contains(&process.pid);
+            let group_label = row
+                .group_size
+                .map(|size| format!(" ×{}", size))
+                .unwrap_or_default();
+            let mut name_spans = Vec::new();
+            if tagged {
+                name_spans.push(Span::styled(
+                    "● ",
+                    Style::default()
+                        .fg(theme.warning)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            }
+            if !row.prefix.is_empty() {
+                name_spans.push(Span::styled(
+                    row.prefix.as_str(),
+                    Style::default().fg(theme.text_dim),
+                ));
+            }
+            let found = |name: &str| find_ignore_case(name, &app.search_query).filter(|_| is_match);
+            let found_style = Style::default()
+                .fg(theme.warning)
+                .add_modifier(Modifier::BOLD);
+            if show_full_command && !process.full_command.is_empty() {
+                // A long command loses its middle rather than the program's
+                // name at its end
+                let used = if tagged { 2 } else { 0 } + row.prefix.width() + group_label.width();
+                let command = crate::utils::truncate_middle(
+                    &process.full_command,
+                    name_width.saturating_sub(used),
+                );
+                let (path, program, args) = split_command(&command);
+                let parts = [
+                    (path, Style::default().fg(theme.text_dim)),
+                    (
+                        program,
+                        Style::default()
+                            .fg(theme.text_bright)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    (args, Style::default().fg(theme.text_secondary)),
+                ];
+                name_spans.extend(
+                    highlight_match(&parts, found(&command), found_style)
+                        .into_iter()
+                        .map(|span| Span::styled(span.content.into_owned(), span.style)),
+                );
+            } else {
+                // Names are left for the table to cut
+                let name = process.name.as_str();
+                name_spans.extend(highlight_match(
+                    &[(name, Style::default())],
+                    found(name),
+                    found_style,
+                ));
+            }
+            if !group_label.is_empty() {
+                name_spans.push(Span::styled(
+                    group_label,
+                    Style::default().fg(theme.info).add_modifier(Modifier::BOLD),
+                ));
+            }
+            let cells = columns.iter().map(|column| match column {
+                Column::Pid => text_cell(process.pid.to_string())
+                    .style(Style::default().fg(theme.text_primary)),
+                Column::Name => Cell::from(Line::from(std::mem::take(&mut name_spans)))
+                    .style(Style::default().fg(theme.text_primary)),
+                Column::Cpu => text_cell(process_figure(process, || {
+                    format!(
+                        "{:.1}{}",
+                        process.cpu_usage,
+                        theme.critical_mark(cpu_severity)
+                    )
+                }))
+                .style(Style::default().fg(cpu_color).add_modifier(Modifier::BOLD)),
+                Column::MemPercent => text_cell(process_figure(process, || {
+                    format!(
+                        "{:.1}{}",
+                        process.memory_percent,
+                        theme.critical_mark(mem_severity)
+                    )
+                }))
+                .style(Style::default().fg(mem_color).add_modifier(Modifier::BOLD)),
+                Column::Mem => text_cell(process_figure(process, || {
+                    crate::utils::format_size(process.memory_usage, crate::utils::MB, app.units)
+                }))
+                .style(Style::default().fg(mem_color).add_modifier(Modifier::BOLD)),
+                Column::Virt => text_cell(process_figure(process, || {
+                    crate::utils::format_bytes(process.virt_kb * 1024, app.units)
+                }))
+                .style(Style::default().fg(theme.text_secondary)),
+                Column::Shr => {
+                    text_cell(process.shared_kb.map_or(Cow::Borrowed("N/A"), |shared| {
+                        Cow::Owned(crate::utils::format_bytes(shared * 1024, app.units))
+                    }))
+                    .style(Style::default().fg(theme.text_secondary))
+                }
+                Column::Swap => text_cell(crate::utils::format_bytes(
+                    process.swap_kb * 1024,
+                    app.units,
+                ))
+                .style(Style::default().fg(theme.text_secondary)),
+                Column::User => text_cell(process.user.as_str())
+                    .style(Style::default().fg(theme.text_secondary)),
+                Column::State => text_cell(process.state.letter()).style(
                     Style::default()
                         .fg(state_color)
                         .add_modifier(Modifier::BOLD),
                 ),
-                Cell::from(process.threads.to_string())
-                    .style(Style::default().fg(theme.text_secondary)),
-            ])
-            .style(Style::default().bg(bg_color))
+                Column::Threads => {
+                    text_cell(process_figure(process, || process.threads.to_string()))
+                        .style(Style::default().fg(theme.text_secondary))
+                }
+            });
+            if is_selected && app.is_highlighting() {
+                // Matches share the selection background
+                row_style = row_style.add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+            }
+            Row::new(cells.collect::<Vec<_>>()).style(row_style)
         })
         .collect();
-    let table = Table::new(
-        rows,
-        vec![
-            Constraint::Length(8),
-            Constraint::Percentage(25),
-            Constraint::Length(8),
-            Constraint::Length(10),
-            Constraint::Length(10),
-            Constraint::Length(8),
-            Constraint::Length(8),
-        ],
-    )
-    .header(header)
-    .block(
+    app.layout.process_headers.replace(
+        columns
+            .iter()
+            .zip(column_areas.iter())
+            .filter_map(|(column, &area)| Some((area, column.sort()?)))
+            .collect(),
+    );
+    app.layout.process_rows.set(Some(rows_area));
+    let scrollbar = app.process_scrollbar(process_rows.len(), rows_area.height);
+    let table = Table::new(rows, widths).header(header).block(
         Block::default()
-            .title(" Processes ")
+            .title({
+                let mut title = " Processes ".to_string();
+                if app.proc_aggregated {
+                    title.push_str("[grouped] ");
+                } else if app.show_tree_view {
+                    title.push_str("[tree] ");
+                }
+                if let Some(container) = &app.container_filter {
+                    title.push_str(&format!("[container: {}] ", container));
+                }
+                if app.hide_kernel_threads {
+                    title.push_str("[kernel threads hidden] ");
+                }
+                if app.only_mine {
+                    title.push_str(&format!("[user: {}] ", app.username));
+                }
+                if !app.tagged_pids.is_empty() {
+                    title.push_str(&format!("[{} tagged] ", app.tagged_pids.len()));
+                }
+                if !app.search_query.is_empty() && app.search_mode == SearchMode::Filter {
+                    title.push_str(&format!("[/{}] ", app.search_query));
+                }
+                title
+            })
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(theme.border)),
+            .border_style(alert_border(app, Area::Processes, theme)),
     );
     let detail_block = Block::default()
-        .title(" Process Details ")
+        .title(" Process Details [Enter to hide] ")
         .borders(Borders::ALL)
         .border_style(Style::default().fg(theme.border_light));
-    let details = if selected_process < metrics.processes.len() {
-        let process = &metrics.processes[selected_process];
-        vec![
+    let details = if let Some(process) = selected {
+        let command = command_lines.iter().enumerate().map(|(index, &line)| {
+            let label = if index == 0 { COMMAND_LABEL } else { "" };
+            let mut spans = vec![
+                Span::styled(
+                    format!("{:<1$}", label, COMMAND_LABEL.len()),
+                    Style::default().fg(theme.text_dim),
+                ),
+                Span::styled(line.to_string(), Style::default().fg(theme.text_secondary)),
+            ];
+            if cut_command && index + 1 == MAX_COMMAND_LINES {
+                spans.push(Span::styled("…", Style::default().fg(theme.text_dim)));
+            }
+            Line::from(spans)
+        });
+        let mut details = vec![Line::from(vec![
+            Span::styled("PID: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                process.pid.to_string(),
+                Style::default().fg(theme.text_primary),
+            ),
+            Span::raw(" | "),
+            Span::styled("PPID: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                process.ppid.to_string(),
+                Style::default().fg(theme.text_primary),
+            ),
+            Span::raw(" | "),
+            Span::styled("FDs: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                process
+                    .fd_count
+                    .map_or_else(|| "N/A".to_string(), |count| count.to_string()),
+                Style::default().fg(theme.text_primary),
+            ),
+            Span::raw(" | "),
+            Span::styled("Container: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                process.container.clone().unwrap_or_else(|| "-".to_string()),
+                Style::default().fg(theme.text_primary),
+            ),
+        ])];
+        details.extend(command);
+        if details.len() == 1 {
+            details.push(Line::from(Span::styled(
+                COMMAND_LABEL,
+                Style::default().fg(theme.text_dim),
+            )));
+        }
+        details.extend([
             Line::from(vec![
-                Span::styled("PID: ", Style::default().fg(theme.text_dim)),
+                Span::styled("Start Time: ", Style::default().fg(theme.text_dim)),
                 Span::styled(
-                    process.pid.to_string(),
+                    process.start_time.clone(),
                     Style::default().fg(theme.text_primary),
                 ),
                 Span::raw(" | "),
-                Span::styled("PPID: ", Style::default().fg(theme.text_dim)),
+                Span::styled("Uptime: ", Style::default().fg(theme.text_dim)),
                 Span::styled(
-                    process.ppid.to_string(),
+                    format_duration(process.uptime),
                     Style::default().fg(theme.text_primary),
                 ),
             ]),
             Line::from(vec![
-                Span::styled("Command: ", Style::default().fg(theme.text_dim)),
+                Span::styled("RSS: ", Style::default().fg(theme.text_dim)),
                 Span::styled(
-                    &process.full_command,
-                    Style::default().fg(theme.text_secondary),
+                    process_figure(process, || {
+                        crate::utils::format_bytes(process.rss_kb * 1024, app.units)
+                    }),
+                    Style::default().fg(theme.text_primary),
                 ),
-            ]),
-            Line::from(vec![
-                Span::styled("Start Time: ", Style::default().fg(theme.text_dim)),
-                Span::styled(&process.start_time, Style::default().fg(theme.text_primary)),
                 Span::raw(" | "),
-                Span::styled("Uptime: ", Style::default().fg(theme.text_dim)),
+                Span::styled("VIRT: ", Style::default().fg(theme.text_dim)),
                 Span::styled(
-                    format_duration(process.uptime),
+                    process_figure(process, || {
+                        crate::utils::format_bytes(process.virt_kb * 1024, app.units)
+                    }),
+                    Style::default().fg(theme.text_primary),
+                ),
+                Span::raw(" | "),
+                Span::styled("SHR: ", Style::default().fg(theme.text_dim)),
+                Span::styled(
+                    process.shared_kb.map_or_else(
+                        || "N/A".to_string(),
+                        |shared| crate::utils::format_bytes(shared * 1024, app.units),
+                    ),
+                    Style::default().fg(theme.text_primary),
+                ),
+                Span::raw(" | "),
+                Span::styled("SWAP: ", Style::default().fg(theme.text_dim)),
+                Span::styled(
+                    crate::utils::format_bytes(process.swap_kb * 1024, app.units),
                     Style::default().fg(theme.text_primary),
                 ),
             ]),
             Line::from(vec![
                 Span::styled("Priority: ", Style::default().fg(theme.text_dim)),
                 Span::styled(
-                    process.priority.to_string(),
+                    process_figure(process, || process.priority.to_string()),
                     Style::default().fg(theme.text_primary),
                 ),
                 Span::raw(" | "),
@@ -464,152 +1303,234 @@ pub fn render_process_view<'a>(
             Line::from(vec![
                 Span::styled("I/O Read: ", Style::default().fg(theme.text_dim)),
                 Span::styled(
-                    format!("{} KB/s", process.read_speed),
+                    process.read_speed.map_or_else(
+                        || "N/A".to_string(),
+                        |speed| crate::utils::format_rate(speed * crate::utils::KB, app.units),
+                    ),
                     Style::default().fg(theme.success),
                 ),
                 Span::raw(" | "),
                 Span::styled("I/O Write: ", Style::default().fg(theme.text_dim)),
                 Span::styled(
-                    format!("{} KB/s", process.write_speed),
+                    process.write_speed.map_or_else(
+                        || "N/A".to_string(),
+                        |speed| crate::utils::format_rate(speed * crate::utils::KB, app.units),
+                    ),
                     Style::default().fg(theme.danger),
                 ),
             ]),
-        ]
+            Line::from(vec![
+                Span::styled("User: ", Style::default().fg(theme.text_dim)),
+                Span::styled(
+                    process.user.clone(),
+                    Style::default().fg(theme.text_primary),
+                ),
+                Span::raw(" | "),
+                Span::styled("State: ", Style::default().fg(theme.text_dim)),
+                Span::styled(
+                    process.state.to_string(),
+                    Style::default().fg(theme.text_primary),
+                ),
+                Span::raw(" | "),
+                Span::styled("Threads: ", Style::default().fg(theme.text_dim)),
+                Span::styled(
+                    process_figure(process, || process.threads.to_string()),
+                    Style::default().fg(theme.text_primary),
+                ),
+                Span::raw(" | "),
+                Span::styled("CPU: ", Style::default().fg(theme.text_dim)),
+                Span::styled(
+                    process_figure(process, || format!("{:.1}%", process.cpu_usage)),
+                    Style::default().fg(theme.text_primary),
+                ),
+                Span::raw(" | "),
+                Span::styled("MEM: ", Style::default().fg(theme.text_dim)),
+                Span::styled(
+                    process_figure(process, || format!("{:.1}%", process.memory_percent)),
+                    Style::default().fg(theme.text_primary),
+                ),
+            ]),
+        ]);
+        details
     } else {
         vec![Line::from("No process selected")]
     };
     let detail_para = Paragraph::new(details).block(Block::default());
-    let detail_block_clone = detail_block.clone();
+    let detail_area = detail_block.inner(layout[2]);
+    // Usage history of the selected process on the right, when there is room
+    let [text_area, chart_area] = if detail_area.width >= 100 {
+        Layout::horizontal([Constraint::Min(1), Constraint::Length(32)]).areas(detail_area)
+    } else {
+        [detail_area, Rect::default()]
+    };
+    let samples = process_rows
+        .get(selected_process)
+        .filter(|row| row.group_size.is_none())
+        .and_then(|row| app.process_history.get(row.process.pid));
+    let width = usize::from(chart_area.width);
+    let recent = |values: &VecDeque<u64>| -> Vec<u64> {
+        values
+            .iter()
+            .skip(values.len().saturating_sub(width))
+            .copied()
+            .collect()
+    };
+    let cpu_data = samples
+        .map(|samples| recent(&samples.cpu))
+        .unwrap_or_default();
+    let rss_data = samples
+        .map(|samples| recent(&samples.rss_kb))
+        .unwrap_or_default();
+    let cpu_title = format!(
+        "CPU {:.1}%",
+        cpu_data.last().map_or(0.0, |&tenths| tenths as f64 / 10.0)
+    );
+    let rss_title = format!(
+        "RSS {}",
+        crate::utils::format_bytes(rss_data.last().map_or(0, |&kb| kb * 1024), app.units)
+    );
+    // Processes can use several cores; scale to 100% until one does
+    let cpu_max = cpu_data.iter().copied().max().unwrap_or(0).max(1000);
     Box::new(move |f: &mut ratatui::Frame| {
         f.render_widget(table, layout[1]);
-        f.render_widget(detail_block_clone, layout[2]);
-        f.render_widget(detail_para, detail_block.inner(layout[2]));
+        render_scrollbar(f, layout[1], rows_area, theme, scrollbar);
+        f.render_widget(detail_block, layout[2]);
+        f.render_widget(detail_para, text_area);
+        if chart_area.is_empty() {
+            return;
+        }
+        let [cpu_area, rss_area] =
+            Layout::vertical([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)]).areas(chart_area);
+        let cpu_sparkline = Sparkline::default()
+            .block(
+                Block::default()
+                    .title(Span::styled(cpu_title, Style::default().fg(theme.text_dim))),
+            )
+            .data(&cpu_data)
+            .max(cpu_max)
+            .style(Style::default().fg(theme.success));
+        f.render_widget(cpu_sparkline, cpu_area);
+        let rss_sparkline = Sparkline::default()
+            .block(
+                Block::default()
+                    .title(Span::styled(rss_title, Style::default().fg(theme.text_dim))),
+            )
+            .data(&rss_data)
+            .style(Style::default().fg(theme.info));
+        f.render_widget(rss_sparkline, rss_area);
     })
 }
 
 pub fn render_resources_view<'a>(
     area: Rect,
     theme: &'a Theme,
-    metrics: &'a SystemInfo,
+    app: &'a crate::app::App,
 ) -> Box<dyn FnOnce(&mut Frame) + 'a> {
+    let metrics = &app.metrics;
+    let window = app.chart_window.duration();
+    let mut constraints = vec![
+        Constraint::Length(12),
+        Constraint::Length(12),
+        Constraint::Min(8),
+    ];
+    if metrics.pressure.is_some() {
+        constraints.push(Constraint::Length(5));
+    }
+    let irq_index = constraints.len();
+    if metrics.interrupts.is_some() {
+        constraints.push(Constraint::Length(8));
+    }
     let layout = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(12),
-            Constraint::Length(12),
-            Constraint::Min(8),
-        ])
+        .constraints(constraints)
         .split(area);
     let cpu_block = Block::default()
         .title(Span::styled(
-            " CPU History ",
+            format!(" CPU History [{}] ", app.chart_window.label()),
             Style::default()
                 .fg(theme.text_bright)
                 .add_modifier(Modifier::BOLD),
         ))
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(theme.border));
-    let cpu_area = cpu_block.inner(layout[0]);
-    let cpu_data: Vec<(f64, f64)> = metrics
-        .cpu_history
-        .iter()
-        .enumerate()
-        .map(|(i, &usage)| (i as f64, usage as f64))
-        .collect();
-    let cpu_data: &'static [(f64, f64)] = Box::leak(cpu_data.into_boxed_slice());
-    let mem_data: Vec<(f64, f64)> = metrics
-        .memory_history
-        .iter()
-        .enumerate()
-        .map(|(i, &usage)| (i as f64, usage as f64))
-        .collect();
-    let mem_data: &'static [(f64, f64)] = Box::leak(mem_data.into_boxed_slice());
-    let rx_data: Vec<(f64, f64)> = metrics
-        .net_rx_history
-        .iter()
-        .enumerate()
-        .map(|(i, &speed)| (i as f64, speed as f64))
-        .collect();
-    let rx_data: &'static [(f64, f64)] = Box::leak(rx_data.into_boxed_slice());
-    let tx_data: Vec<(f64, f64)> = metrics
-        .net_tx_history
-        .iter()
-        .enumerate()
-        .map(|(i, &speed)| (i as f64, speed as f64))
-        .collect();
-    let tx_data: &'static [(f64, f64)] = Box::leak(tx_data.into_boxed_slice());
+        .border_style(alert_border(app, Area::Cpu, theme));
+    // Temperatures share the CPU row once there are any to show
+    let temp_data = chart_points(&metrics.cpu_temp_history, window);
+    let [cpu_row, temp_row] = if temp_data.is_empty() {
+        [layout[0], Rect::default()]
+    } else {
+        Layout::horizontal([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .areas(layout[0])
+    };
+    let cpu_area = cpu_block.inner(cpu_row);
+    let cpu_data = chart_points(&metrics.cpu_history, window);
+    let mem_data = chart_points(&metrics.memory_history, window);
+    let rx_data = chart_points(&metrics.net_rx_history, window);
+    let tx_data = chart_points(&metrics.net_tx_history, window);
+    let (cpu_bounds, cpu_since) = time_axis(&cpu_data);
+    let (mem_bounds, mem_since) = time_axis(&mem_data);
+    let (net_bounds, net_since) = time_axis(&rx_data);
+    let (net_peak, net_labels) = rate_axis(&[&rx_data, &tx_data], 1024, app.units);
     Box::new(move |f: &mut ratatui::Frame| {
-        let cpu_chart = Chart::new(vec![
-            Dataset::default()
-                .name("CPU Usage")
-                .marker(symbols::Marker::Braille)
-                .graph_type(GraphType::Line)
-                .style(Style::default().fg(theme.cpu_colors[0]))
-                .data(cpu_data),
-        ])
-        .x_axis(
-            Axis::default()
-                .style(Style::default().fg(theme.text_dim))
-                .bounds([0.0, cpu_data.len() as f64 - 1.0])
-                .labels(vec![
-                    Span::styled("-60s", Style::default().fg(theme.text_dim)),
-                    Span::styled("now", Style::default().fg(theme.text_dim)),
-                ]),
-        )
-        .y_axis(
-            Axis::default()
-                .style(Style::default().fg(theme.text_dim))
-                .bounds([0.0, 100.0])
-                .labels(vec![
-                    Span::styled("0%", Style::default().fg(theme.text_dim)),
-                    Span::styled("50%", Style::default().fg(theme.text_dim)),
-                    Span::styled("100%", Style::default().fg(theme.text_dim)),
-                ]),
-        );
-        f.render_widget(cpu_block.clone(), layout[0]);
+        let cpu_chart = Chart::new(gradient_line(&cpu_data, 100.0, theme))
+            .x_axis(
+                Axis::default()
+                    .style(Style::default().fg(theme.text_dim))
+                    .bounds(cpu_bounds)
+                    .labels(vec![
+                        Span::styled(cpu_since, Style::default().fg(theme.text_dim)),
+                        Span::styled("now", Style::default().fg(theme.text_dim)),
+                    ]),
+            )
+            .y_axis(
+                Axis::default()
+                    .style(Style::default().fg(theme.text_dim))
+                    .bounds([0.0, 100.0])
+                    .labels(vec![
+                        Span::styled("0%", Style::default().fg(theme.text_dim)),
+                        Span::styled("50%", Style::default().fg(theme.text_dim)),
+                        Span::styled("100%", Style::default().fg(theme.text_dim)),
+                    ]),
+            );
+        f.render_widget(cpu_block.clone(), cpu_row);
         f.render_widget(cpu_chart, cpu_area);
+        if !temp_row.is_empty() {
+            render_temperature_chart(f, temp_row, theme, app, &temp_data);
+        }
         let mem_block = Block::default()
             .title(Span::styled(
-                " Memory History ",
+                format!(" Memory History [{}] ", app.chart_window.label()),
                 Style::default()
                     .fg(theme.text_bright)
                     .add_modifier(Modifier::BOLD),
             ))
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(theme.border));
+            .border_style(alert_border(app, Area::Memory, theme));
         let mem_area = mem_block.inner(layout[1]);
-        let mem_chart = Chart::new(vec![
-            Dataset::default()
-                .name("Memory Usage")
-                .marker(symbols::Marker::Braille)
-                .graph_type(GraphType::Line)
-                .style(Style::default().fg(theme.mem_colors[0]))
-                .data(mem_data),
-        ])
-        .x_axis(
-            Axis::default()
-                .style(Style::default().fg(theme.text_dim))
-                .bounds([0.0, mem_data.len() as f64 - 1.0])
-                .labels(vec![
-                    Span::styled("-60s", Style::default().fg(theme.text_dim)),
-                    Span::styled("now", Style::default().fg(theme.text_dim)),
-                ]),
-        )
-        .y_axis(
-            Axis::default()
-                .style(Style::default().fg(theme.text_dim))
-                .bounds([0.0, 100.0])
-                .labels(vec![
-                    Span::styled("0%", Style::default().fg(theme.text_dim)),
-                    Span::styled("50%", Style::default().fg(theme.text_dim)),
-                    Span::styled("100%", Style::default().fg(theme.text_dim)),
-                ]),
-        );
+        let mem_chart = Chart::new(gradient_line(&mem_data, 100.0, theme))
+            .x_axis(
+                Axis::default()
+                    .style(Style::default().fg(theme.text_dim))
+                    .bounds(mem_bounds)
+                    .labels(vec![
+                        Span::styled(mem_since, Style::default().fg(theme.text_dim)),
+                        Span::styled("now", Style::default().fg(theme.text_dim)),
+                    ]),
+            )
+            .y_axis(
+                Axis::default()
+                    .style(Style::default().fg(theme.text_dim))
+                    .bounds([0.0, 100.0])
+                    .labels(vec![
+                        Span::styled("0%", Style::default().fg(theme.text_dim)),
+                        Span::styled("50%", Style::default().fg(theme.text_dim)),
+                        Span::styled("100%", Style::default().fg(theme.text_dim)),
+                    ]),
+            );
         f.render_widget(mem_block, layout[1]);
         f.render_widget(mem_chart, mem_area);
         let net_block = Block::default()
             .title(Span::styled(
-                " Network History ",
+                format!(" Network History [{}] ", app.chart_window.label()),
                 Style::default()
                     .fg(theme.text_bright)
                     .add_modifier(Modifier::BOLD),
@@ -623,46 +1544,164 @@ pub fn render_resources_view<'a>(
                 .marker(symbols::Marker::Braille)
                 .graph_type(GraphType::Line)
                 .style(Style::default().fg(theme.net_colors[0]))
-                .data(rx_data),
+                .data(&rx_data),
             Dataset::default()
                 .name("Upload")
                 .marker(symbols::Marker::Braille)
                 .graph_type(GraphType::Line)
                 .style(Style::default().fg(theme.net_colors[1]))
-                .data(tx_data),
+                .data(&tx_data),
         ])
         .x_axis(
             Axis::default()
                 .style(Style::default().fg(theme.text_dim))
-                .bounds([0.0, rx_data.len() as f64 - 1.0])
+                .bounds(net_bounds)
                 .labels(vec![
-                    Span::styled("-45s", Style::default().fg(theme.text_dim)),
+                    Span::styled(net_since, Style::default().fg(theme.text_dim)),
                     Span::styled("now", Style::default().fg(theme.text_dim)),
                 ]),
         )
         .y_axis(
             Axis::default()
                 .style(Style::default().fg(theme.text_dim))
-                .bounds([0.0, 2000.0])
-                .labels(vec![
-                    Span::styled("0 KB/s", Style::default().fg(theme.text_dim)),
-                    Span::styled("1 MB/s", Style::default().fg(theme.text_dim)),
-                    Span::styled("2 MB/s", Style::default().fg(theme.text_dim)),
-                ]),
+                .bounds([0.0, net_peak])
+                .labels(
+                    net_labels
+                        .map(|label| Span::styled(label, Style::default().fg(theme.text_dim))),
+                ),
         );
         f.render_widget(net_block, layout[2]);
         f.render_widget(net_chart, net_area);
+        if let Some(pressure) = &metrics.pressure {
+            let psi_block = Block::default()
+                .title(Span::styled(
+                    " Pressure (avg10) ",
+                    Style::default()
+                        .fg(theme.text_bright)
+                        .add_modifier(Modifier::BOLD),
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border));
+            let psi_area = psi_block.inner(layout[3]);
+            f.render_widget(psi_block, layout[3]);
+            let psi_layout = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([
+                    Constraint::Ratio(1, 3),
+                    Constraint::Ratio(1, 3),
+                    Constraint::Ratio(1, 3),
+                ])
+                .split(psi_area);
+            let resources = [
+                (
+                    "CPU",
+                    pressure.cpu.some_avg10,
+                    &metrics.cpu_pressure_history,
+                ),
+                (
+                    "MEM",
+                    pressure.memory.some_avg10,
+                    &metrics.memory_pressure_history,
+                ),
+                ("IO", pressure.io.some_avg10, &metrics.io_pressure_history),
+            ];
+            for (i, (name, current, history)) in resources.into_iter().enumerate() {
+                // PSI averages are fractional percentages; keep two decimals.
+                // Only the newest samples fit the sparkline's width.
+                let width = psi_layout[i].width as usize;
+                let skip = history.values().len().saturating_sub(width);
+                let data: Vec<u64> = history
+                    .values()
+                    .skip(skip)
+                    .map(|sample| (sample * 100.0) as u64)
+                    .collect();
+                let sparkline = Sparkline::default()
+                    .block(Block::default().title(Span::styled(
+                        format!("{} {:.2}%", name, current),
+                        Style::default().fg(theme.text_dim),
+                    )))
+                    .data(&data)
+                    .max(10_000)
+                    .style(Style::default().fg(theme.get_cpu_color(i)));
+                f.render_widget(sparkline, psi_layout[i]);
+            }
+        }
+        if let Some(interrupts) = &metrics.interrupts {
+            let irq_block = Block::default()
+                .title(Span::styled(
+                    format!(
+                        " IRQ {}/s | softirq {}/s ",
+                        crate::utils::format_count(interrupts.irqs_per_sec),
+                        crate::utils::format_count(interrupts.softirqs_per_sec)
+                    ),
+                    Style::default()
+                        .fg(theme.text_bright)
+                        .add_modifier(Modifier::BOLD),
+                ))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(theme.border));
+            let irq_area = irq_block.inner(layout[irq_index]);
+            let mut irq_lines: Vec<Line> = interrupts
+                .top
+                .iter()
+                .map(|source| {
+                    Line::from(vec![
+                        Span::styled(
+                            format!("{:>6} ", source.irq),
+                            Style::default().fg(theme.text_dim),
+                        ),
+                        Span::styled(
+                            format!("{:<28} ", source.name),
+                            Style::default().fg(theme.text_primary),
+                        ),
+                        Span::styled(
+                            format!("{:>8}/s", crate::utils::format_count(source.per_sec)),
+                            Style::default().fg(theme.info).add_modifier(Modifier::BOLD),
+                        ),
+                    ])
+                })
+                .collect();
+            // A single core taking nearly all of the hottest IRQ is the
+            // classic sign of a NIC queue pinned to one CPU
+            if let Some(hottest) = interrupts.top.first() {
+                let mut shares: Vec<(usize, u64)> = hottest
+                    .per_cpu
+                    .iter()
+                    .copied()
+                    .enumerate()
+                    .filter(|(_, rate)| *rate > 0)
+                    .collect();
+                shares.sort_by_key(|(_, rate)| std::cmp::Reverse(*rate));
+                let distribution = shares
+                    .iter()
+                    .take(8)
+                    .map(|(cpu, rate)| format!("C{} {}%", cpu, rate * 100 / hottest.per_sec.max(1)))
+                    .collect::<Vec<_>>()
+                    .join("  ");
+                irq_lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("{} by CPU: ", hottest.name),
+                        Style::default().fg(theme.text_dim),
+                    ),
+                    Span::styled(distribution, Style::default().fg(theme.text_secondary)),
+                ]));
+            }
+            f.render_widget(irq_block, layout[irq_index]);
+            f.render_widget(Paragraph::new(irq_lines), irq_area);
+        }
     })
 }
 
 pub fn render_network_view<'a>(
     area: Rect,
     theme: &'a Theme,
-    metrics: &'a SystemInfo,
+    app: &'a crate::app::App,
 ) -> Box<dyn FnOnce(&mut Frame) + 'a> {
+    let metrics = &app.metrics;
     let layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
+            Constraint::Length(8),
             Constraint::Length(8),
             Constraint::Min(8),
             Constraint::Length(8),
@@ -670,7 +1709,7 @@ pub fn render_network_view<'a>(
         .split(area);
     let iface_block = Block::default()
         .title(Span::styled(
-            " Network Interfaces ",
+            " Network Interfaces [↑↓ select] ",
             Style::default()
                 .fg(theme.text_bright)
                 .add_modifier(Modifier::BOLD),
@@ -678,24 +1717,80 @@ pub fn render_network_view<'a>(
         .borders(Borders::ALL)
         .border_style(Style::default().fg(theme.border));
     let iface_area = iface_block.inner(layout[0]);
+    // Less one line for the header; the table follows the selection
+    let iface_height = usize::from(iface_area.height.saturating_sub(1)).max(1);
+    let iface_skip = app.selected_interface.saturating_sub(iface_height - 1);
     let iface_rows: Vec<Row> = metrics
         .network_interfaces
         .iter()
-        .map(|iface| {
+        .enumerate()
+        .skip(iface_skip)
+        .map(|(i, iface)| {
+            let row_style = if i == app.selected_interface {
+                theme.selected()
+            } else {
+                Style::default()
+            };
             Row::new(vec![
                 Cell::from(iface.name.clone()).style(Style::default().fg(theme.text_primary)),
                 Cell::from(iface.ip_address.clone())
                     .style(Style::default().fg(theme.text_secondary)),
-                Cell::from(format!("{:.1} MB/s", iface.rx_speed as f64 / 1024.0)).style(
+                Cell::from(crate::utils::format_rate(
+                    iface.rx_speed * crate::utils::KB,
+                    app.units,
+                ))
+                .style(
                     Style::default()
                         .fg(theme.net_colors[0])
                         .add_modifier(Modifier::BOLD),
                 ),
-                Cell::from(format!("{:.1} MB/s", iface.tx_speed as f64 / 1024.0)).style(
+                Cell::from(crate::utils::format_rate(
+                    iface.tx_speed * crate::utils::KB,
+                    app.units,
+                ))
+                .style(
                     Style::default()
                         .fg(theme.net_colors[1])
                         .add_modifier(Modifier::BOLD),
                 ),
+                Cell::from(
+                    iface
+                        .link_speed
+                        .map_or_else(|| "-".to_string(), |speed| format!("{} Mb/s", speed)),
+                )
+                .style(Style::default().fg(theme.text_secondary)),
+                Cell::from(iface.duplex.clone().unwrap_or_else(|| "-".to_string()))
+                    .style(Style::default().fg(theme.text_secondary)),
+                Cell::from(
+                    iface
+                        .mtu
+                        .map_or_else(|| "-".to_string(), |mtu| mtu.to_string()),
+                )
+                .style(Style::default().fg(theme.text_secondary)),
+                match &iface.wifi {
+                    Some(wifi) => match &wifi.ssid {
+                        Some(ssid) => {
+                            let quality = wifi.quality.unwrap_or(0);
+                            let mut text = format!(
+                                "{} {}",
+                                ssid,
+                                crate::utils::create_progress_bar(quality, 5)
+                            );
+                            if let Some(signal) = wifi.signal_dbm {
+                                text.push_str(&format!(" {} dBm", signal));
+                            }
+                            if let Some(bitrate) = wifi.bitrate {
+                                text.push_str(&format!(" {} Mb/s", bitrate));
+                            }
+                            Cell::from(text)
+                                .style(Style::default().fg(theme.get_usage_color(100 - quality)))
+                        }
+                        None => {
+                            Cell::from("not associated").style(Style::default().fg(theme.text_dim))
+                        }
+                    },
+                    None => Cell::from(""),
+                },
                 Cell::from(iface.status.clone()).style(Style::default().fg(
                     if iface.status == "up" {
                         theme.success
@@ -704,6 +1799,7 @@ pub fn render_network_view<'a>(
                     },
                 )),
             ])
+            .style(row_style)
         })
         .collect();
     let iface_table = Table::new(
@@ -713,10 +1809,53 @@ pub fn render_network_view<'a>(
             Constraint::Length(20),
             Constraint::Length(15),
             Constraint::Length(15),
+            Constraint::Length(12),
+            Constraint::Length(7),
+            Constraint::Length(6),
+            Constraint::Min(24),
             Constraint::Length(8),
         ],
     )
+    .header(
+        Row::new(vec![
+            "Interface",
+            "Address",
+            "RX",
+            "TX",
+            "Speed",
+            "Duplex",
+            "MTU",
+            "WiFi",
+            "Status",
+        ])
+        .style(Style::default().fg(theme.text_dim)),
+    )
     .block(Block::default());
+    let selected_iface = metrics.network_interfaces.get(app.selected_interface);
+    let iface_history = selected_iface.and_then(|iface| metrics.interface_history.get(&iface.name));
+    let window = ChartWindow::Minute;
+    let (iface_rx, iface_tx) = match iface_history {
+        Some(history) => (
+            chart_points(&history.rx, window.duration()),
+            chart_points(&history.tx, window.duration()),
+        ),
+        None => (Vec::new(), Vec::new()),
+    };
+    let (iface_bounds, iface_since) = time_axis(&iface_rx);
+    let (iface_peak, iface_labels) = rate_axis(&[&iface_rx, &iface_tx], 1024, app.units);
+    let history_block = Block::default()
+        .title(Span::styled(
+            match selected_iface {
+                Some(iface) => format!(" {} History [{}] ", iface.name, window.label()),
+                None => " Interface History ".to_string(),
+            },
+            Style::default()
+                .fg(theme.text_bright)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+    let history_area = history_block.inner(layout[1]);
     let conn_block = Block::default()
         .title(Span::styled(
             " Active Connections ",
@@ -726,7 +1865,7 @@ pub fn render_network_view<'a>(
         ))
         .borders(Borders::ALL)
         .border_style(Style::default().fg(theme.border));
-    let conn_area = conn_block.inner(layout[1]);
+    let conn_area = conn_block.inner(layout[2]);
     let connections = vec![
         (
             "TCP",
@@ -764,8 +1903,11 @@ pub fn render_network_view<'a>(
             "postgres",
         ),
     ];
+    let conn_skip = app.scroll_skip(connections.len(), conn_area.height);
+    let conn_scrollbar = app.scrollbar(connections.len(), conn_area.height);
     let conn_rows: Vec<Row> = connections
         .iter()
+        .skip(conn_skip)
         .map(|(proto, local, remote, state, process)| {
             let state_color = match *state {
                 "ESTABLISHED" => theme.success,
@@ -802,12 +1944,19 @@ pub fn render_network_view<'a>(
         ))
         .borders(Borders::ALL)
         .border_style(Style::default().fg(theme.border));
-    let stats_area = stats_block.inner(layout[2]);
+    let stats_area = stats_block.inner(layout[3]);
     let stats_text = vec![
         Line::from(vec![
             Span::styled("Total RX: ", Style::default().fg(theme.text_dim)),
             Span::styled(
-                format!("{:.2} GB", metrics.total_rx as f64 / 1024.0 / 1024.0),
+                crate::utils::format_bytes(
+                    metrics
+                        .network_interfaces
+                        .iter()
+                        .map(|iface| iface.rx_bytes)
+                        .sum(),
+                    app.units,
+                ),
                 Style::default()
                     .fg(theme.net_colors[0])
                     .add_modifier(Modifier::BOLD),
@@ -815,7 +1964,14 @@ pub fn render_network_view<'a>(
             Span::raw(" | "),
             Span::styled("Total TX: ", Style::default().fg(theme.text_dim)),
             Span::styled(
-                format!("{:.2} GB", metrics.total_tx as f64 / 1024.0 / 1024.0),
+                crate::utils::format_bytes(
+                    metrics
+                        .network_interfaces
+                        .iter()
+                        .map(|iface| iface.tx_bytes)
+                        .sum(),
+                    app.units,
+                ),
                 Style::default()
                     .fg(theme.net_colors[1])
                     .add_modifier(Modifier::BOLD),
@@ -824,7 +1980,7 @@ pub fn render_network_view<'a>(
         Line::from(vec![
             Span::styled("Current RX: ", Style::default().fg(theme.text_dim)),
             Span::styled(
-                format!("{} KB/s", metrics.total_rx),
+                crate::utils::format_rate(metrics.total_rx * crate::utils::KB, app.units),
                 Style::default()
                     .fg(theme.net_colors[0])
                     .add_modifier(Modifier::BOLD),
@@ -832,7 +1988,7 @@ pub fn render_network_view<'a>(
             Span::raw(" | "),
             Span::styled("Current TX: ", Style::default().fg(theme.text_dim)),
             Span::styled(
-                format!("{} KB/s", metrics.total_tx),
+                crate::utils::format_rate(metrics.total_tx * crate::utils::KB, app.units),
                 Style::default()
                     .fg(theme.net_colors[1])
                     .add_modifier(Modifier::BOLD),
@@ -843,18 +1999,103 @@ pub fn render_network_view<'a>(
     Box::new(move |f: &mut ratatui::Frame| {
         f.render_widget(iface_block, layout[0]);
         f.render_widget(iface_table, iface_area);
-        f.render_widget(conn_block, layout[1]);
+        let history_chart = Chart::new(vec![
+            Dataset::default()
+                .name("RX")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(theme.net_colors[0]))
+                .data(&iface_rx),
+            Dataset::default()
+                .name("TX")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(theme.net_colors[1]))
+                .data(&iface_tx),
+        ])
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(theme.text_dim))
+                .bounds(iface_bounds)
+                .labels(vec![
+                    Span::styled(iface_since, Style::default().fg(theme.text_dim)),
+                    Span::styled("now", Style::default().fg(theme.text_dim)),
+                ]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(theme.text_dim))
+                .bounds([0.0, iface_peak])
+                .labels(
+                    iface_labels
+                        .map(|label| Span::styled(label, Style::default().fg(theme.text_dim))),
+                ),
+        );
+        f.render_widget(history_block, layout[1]);
+        f.render_widget(history_chart, history_area);
+        f.render_widget(conn_block, layout[2]);
         f.render_widget(conn_table, conn_area);
-        f.render_widget(stats_block, layout[2]);
+        render_scrollbar(f, layout[2], conn_area, theme, conn_scrollbar);
+        f.render_widget(stats_block, layout[3]);
         f.render_widget(stats_para, stats_area);
     })
 }
 
+// Long mount points are cut in the middle to fit
+const MOUNT_WIDTH: u16 = 12;
+
 pub fn render_disks_view<'a>(
     area: Rect,
     theme: &'a Theme,
-    metrics: &'a SystemInfo,
+    app: &'a crate::app::App,
 ) -> Box<dyn FnOnce(&mut Frame) + 'a> {
+    let metrics = &app.metrics;
+    let disks = app.visible_disks();
+    let hidden = metrics.disks.len() - disks.len();
+    // Subvolumes and datasets collapse into one row per pool, sized from the
+    // pool itself since statvfs on each mount double-counts shared space.
+    let mut table_disks: Vec<DiskInfo> = Vec::new();
+    let mut seen_pools = std::collections::HashSet::new();
+    for disk in &disks {
+        let Some(pool) = disk
+            .pool
+            .as_ref()
+            .and_then(|name| metrics.pools.iter().find(|pool| &pool.name == name))
+        else {
+            table_disks.push((*disk).clone());
+            continue;
+        };
+        if !seen_pools.insert(&pool.name) {
+            continue;
+        }
+        let members: Vec<&DiskInfo> = disks
+            .iter()
+            .filter(|member| member.pool.as_ref() == Some(&pool.name))
+            .copied()
+            .collect();
+        table_disks.push(DiskInfo {
+            name: pool.name.clone(),
+            mount_point: match members.as_slice() {
+                [only] => only.mount_point.clone(),
+                _ if app.expand_pools => String::new(),
+                _ => format!("[+] {} mounts", members.len()),
+            },
+            total: pool.total,
+            used: pool.used,
+            free: pool.free,
+            usage: pool.usage,
+            device_type: disk.device_type.clone(),
+            fs_type: pool.kind.clone(),
+            temperature: disk.temperature,
+            ..DiskInfo::default()
+        });
+        if app.expand_pools && members.len() > 1 {
+            table_disks.extend(members.into_iter().map(|member| DiskInfo {
+                name: format!(" └ {}", member.name),
+                ..member.clone()
+            }));
+        }
+    }
     let layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -865,17 +2106,29 @@ pub fn render_disks_view<'a>(
         .split(area);
     let disk_block = Block::default()
         .title(Span::styled(
-            " Disk Usage ",
+            if hidden > 0 {
+                format!(" Disk Usage [{} pseudo hidden, P to show] ", hidden)
+            } else {
+                " Disk Usage ".to_string()
+            },
             Style::default()
                 .fg(theme.text_bright)
                 .add_modifier(Modifier::BOLD),
         ))
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(theme.border));
+        .border_style(alert_border(app, Area::Disks, theme));
     let disk_area = disk_block.inner(layout[1]);
-    let disk_rows: Vec<Row> = metrics
-        .disks
+    // Less one line for the header
+    let disk_rows_area = Rect {
+        y: disk_area.y + disk_area.height.min(1),
+        height: disk_area.height.saturating_sub(1),
+        ..disk_area
+    };
+    let disk_skip = app.scroll_skip(table_disks.len(), disk_rows_area.height);
+    let disk_scrollbar = app.scrollbar(table_disks.len(), disk_rows_area.height);
+    let disk_rows: Vec<Row> = table_disks
         .iter()
+        .skip(disk_skip)
         .map(|disk| {
             let usage_color = theme.get_usage_color(disk.usage);
             let bar_width: usize = 20;
@@ -887,23 +2140,59 @@ pub fn render_disks_view<'a>(
             );
             Row::new(vec![
                 Cell::from(disk.name.clone()).style(Style::default().fg(theme.text_primary)),
-                Cell::from(disk.mount_point.clone())
-                    .style(Style::default().fg(theme.text_secondary)),
-                Cell::from(format!("{} GB", disk.total))
-                    .style(Style::default().fg(theme.text_primary)),
-                Cell::from(format!("{} GB", disk.used)).style(
-                    Style::default()
-                        .fg(usage_color)
-                        .add_modifier(Modifier::BOLD),
-                ),
-                Cell::from(format!("{} GB", disk.free))
-                    .style(Style::default().fg(theme.text_primary)),
-                Cell::from(format!("{}%", disk.usage)).style(
+                Cell::from(
+                    crate::utils::truncate_middle(&disk.mount_point, usize::from(MOUNT_WIDTH))
+                        .into_owned(),
+                )
+                .style(Style::default().fg(theme.text_secondary)),
+                Cell::from(disk.fs_type.clone()).style(Style::default().fg(theme.text_secondary)),
+                Cell::from(if disk.device_type.is_empty() {
+                    "-".to_string()
+                } else {
+                    disk.device_type.clone()
+                })
+                .style(Style::default().fg(theme.text_dim)),
+                Cell::from(crate::utils::format_size(
+                    disk.total,
+                    crate::utils::GB,
+                    app.units,
+                ))
+                .style(Style::default().fg(theme.text_primary)),
+                Cell::from(crate::utils::format_size(
+                    disk.used,
+                    crate::utils::GB,
+                    app.units,
+                ))
+                .style(
                     Style::default()
                         .fg(usage_color)
                         .add_modifier(Modifier::BOLD),
                 ),
+                Cell::from(crate::utils::format_size(
+                    disk.free,
+                    crate::utils::GB,
+                    app.units,
+                ))
+                .style(Style::default().fg(theme.text_primary)),
+                Cell::from(Line::from(theme.percentage(
+                    crate::utils::format_percentage(disk.usage as f64, theme.thresholds.usage),
+                ))),
                 Cell::from(bar).style(Style::default().fg(usage_color)),
+                match disk.inodes_usage_pct {
+                    Some(pct) => Cell::from(Line::from(theme.percentage(
+                        crate::utils::format_percentage(pct as f64, theme.thresholds.usage),
+                    ))),
+                    None => Cell::from("-").style(Style::default().fg(theme.text_dim)),
+                },
+                Cell::from(
+                    disk.temperature
+                        .map_or_else(|| "-".to_string(), |temp| format!("{:.0}°C", temp)),
+                )
+                .style(
+                    Style::default().fg(disk
+                        .temperature
+                        .map_or(theme.text_dim, |temp| theme.get_temp_color(temp))),
+                ),
             ])
         })
         .collect();
@@ -911,18 +2200,41 @@ pub fn render_disks_view<'a>(
         disk_rows,
         vec![
             Constraint::Length(12),
-            Constraint::Length(12),
+            Constraint::Length(MOUNT_WIDTH),
+            Constraint::Length(9),
+            Constraint::Length(5),
             Constraint::Length(10),
             Constraint::Length(10),
             Constraint::Length(10),
             Constraint::Length(8),
             Constraint::Length(25),
+            Constraint::Length(7),
+            Constraint::Length(6),
         ],
     )
+    .header(
+        Row::new(vec![
+            "Device", "Mount", "FS", "Type", "Total", "Used", "Free", "Use%", "", "Inodes", "Temp",
+        ])
+        .style(Style::default().fg(theme.text_dim)),
+    )
     .block(Block::default());
+    let window = ChartWindow::Minute;
+    let read_data = chart_points(&metrics.disk_read_history, window.duration());
+    let write_data = chart_points(&metrics.disk_write_history, window.duration());
+    let (io_bounds, io_since) = time_axis(&read_data);
+    let (io_peak, io_labels) = rate_axis(&[&read_data, &write_data], 1024 * 1024, app.units);
+    let busiest = metrics
+        .disks
+        .iter()
+        .filter(|d| d.read_speed.is_some() || d.write_speed.is_some())
+        .max_by_key(|d| d.read_speed.unwrap_or(0) + d.write_speed.unwrap_or(0));
     let io_block = Block::default()
         .title(Span::styled(
-            " Disk I/O Statistics ",
+            match busiest {
+                Some(disk) => format!(" Disk I/O [{}] [busiest: {}] ", window.label(), disk.name),
+                None => format!(" Disk I/O [{}] ", window.label()),
+            },
             Style::default()
                 .fg(theme.text_bright)
                 .add_modifier(Modifier::BOLD),
@@ -930,52 +2242,453 @@ pub fn render_disks_view<'a>(
         .borders(Borders::ALL)
         .border_style(Style::default().fg(theme.border));
     let io_area = io_block.inner(layout[2]);
-    let total_read: u64 = metrics.disks.iter().map(|d| d.read_speed).sum();
-    let total_write: u64 = metrics.disks.iter().map(|d| d.write_speed).sum();
-    let io_text = vec![
-        Line::from(vec![
-            Span::styled("Total Read Speed: ", Style::default().fg(theme.text_dim)),
-            Span::styled(
-                format!("{} MB/s", total_read),
-                Style::default()
-                    .fg(theme.disk_colors[0])
-                    .add_modifier(Modifier::BOLD),
+    let total_read: Option<u64> = metrics.disks.iter().map(|d| d.read_speed).sum();
+    let total_write: Option<u64> = metrics.disks.iter().map(|d| d.write_speed).sum();
+    let most_inodes = match disks
+        .iter()
+        .filter_map(|d| Some((d, d.inodes_usage_pct?)))
+        .max_by_key(|(_, pct)| *pct)
+    {
+        Some((disk, pct)) => Span::styled(
+            format!(
+                "{} {}/{} ({}%)",
+                disk.mount_point, disk.inodes_used, disk.inodes_total, pct
             ),
-            Span::raw(" | "),
-            Span::styled("Total Write Speed: ", Style::default().fg(theme.text_dim)),
-            Span::styled(
-                format!("{} MB/s", total_write),
-                Style::default()
-                    .fg(theme.disk_colors[1])
-                    .add_modifier(Modifier::BOLD),
+            Style::default().fg(theme.get_usage_color(pct)),
+        ),
+        None => Span::styled("N/A", Style::default().fg(theme.text_primary)),
+    };
+    let io_line = Line::from(vec![
+        Span::styled("Read: ", Style::default().fg(theme.text_dim)),
+        Span::styled(
+            total_read.map_or_else(
+                || "N/A".to_string(),
+                |speed| crate::utils::format_rate(speed * crate::utils::MB, app.units),
             ),
-        ]),
-        Line::from(vec![
-            Span::styled("Busiest Disk: ", Style::default().fg(theme.text_dim)),
-            Span::styled(
-                metrics
-                    .disks
-                    .iter()
-                    .max_by_key(|d| d.read_speed + d.write_speed)
-                    .map(|d| d.name.clone())
-                    .unwrap_or_else(|| "N/A".to_string()),
-                Style::default().fg(theme.text_primary),
+            Style::default()
+                .fg(theme.disk_colors[0])
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(" | "),
+        Span::styled("Write: ", Style::default().fg(theme.text_dim)),
+        Span::styled(
+            total_write.map_or_else(
+                || "N/A".to_string(),
+                |speed| crate::utils::format_rate(speed * crate::utils::MB, app.units),
             ),
-        ]),
-    ];
-    let io_para = Paragraph::new(io_text).block(Block::default());
-    Box::new(move |f: &mut ratatui::Frame| {
-        f.render_widget(disk_block, layout[1]);
+            Style::default()
+                .fg(theme.disk_colors[1])
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(" | "),
+        Span::styled("Most Inodes Used: ", Style::default().fg(theme.text_dim)),
+        most_inodes,
+    ]);
+    let io_para = Paragraph::new(io_line);
+    Box::new(move |f: &mut ratatui::Frame| {
+        f.render_widget(disk_block, layout[1]);
         f.render_widget(disk_table, disk_area);
+        render_scrollbar(f, layout[1], disk_rows_area, theme, disk_scrollbar);
         f.render_widget(io_block, layout[2]);
-        f.render_widget(io_para, io_area);
+        let [summary_area, chart_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).areas(io_area);
+        f.render_widget(io_para, summary_area);
+        let io_chart = Chart::new(vec![
+            Dataset::default()
+                .name("Read")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(theme.disk_colors[0]))
+                .data(&read_data),
+            Dataset::default()
+                .name("Write")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(theme.disk_colors[1]))
+                .data(&write_data),
+        ])
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(theme.text_dim))
+                .bounds(io_bounds)
+                .labels(vec![
+                    Span::styled(io_since, Style::default().fg(theme.text_dim)),
+                    Span::styled("now", Style::default().fg(theme.text_dim)),
+                ]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(theme.text_dim))
+                .bounds([0.0, io_peak])
+                .labels(
+                    io_labels.map(|label| Span::styled(label, Style::default().fg(theme.text_dim))),
+                ),
+        );
+        f.render_widget(io_chart, chart_area);
+    })
+}
+
+pub fn render_gpu_view<'a>(
+    area: Rect,
+    theme: &'a Theme,
+    metrics: &'a SystemInfo,
+    units: UnitSystem,
+) -> Box<dyn FnOnce(&mut Frame) + 'a> {
+    let gpu_block = Block::default()
+        .title(Span::styled(
+            " GPUs ",
+            Style::default()
+                .fg(theme.text_bright)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+    if metrics.gpus.is_empty() {
+        let gpu_area = gpu_block.inner(area);
+        let message = Paragraph::new(Line::from(Span::styled(
+            "No supported GPU found",
+            Style::default().fg(theme.text_dim),
+        )));
+        return Box::new(move |f: &mut ratatui::Frame| {
+            f.render_widget(gpu_block, area);
+            f.render_widget(message, gpu_area);
+        });
+    }
+    let layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(metrics.gpus.len() as u16 + 3),
+            Constraint::Min(8),
+        ])
+        .split(area);
+    let gpu_area = gpu_block.inner(layout[0]);
+    let header = Row::new(vec![
+        "Name", "Vendor", "Util", "Clock", "VRAM", "Temp", "Power",
+    ])
+    .style(Style::default().fg(theme.text_dim));
+    let gpu_rows: Vec<Row> = metrics
+        .gpus
+        .iter()
+        .enumerate()
+        .map(|(i, gpu)| {
+            let utilization = gpu
+                .utilization
+                .map(|usage| format!("{}%", usage))
+                .unwrap_or_else(|| "N/A".to_string());
+            let clock = gpu
+                .clock
+                .map(|mhz| format!("{} MHz", mhz))
+                .unwrap_or_else(|| "N/A".to_string());
+            let memory = match (gpu.memory_used, gpu.memory_total) {
+                (Some(used), Some(total)) => format!(
+                    "{} / {}",
+                    crate::utils::format_size(used, crate::utils::MB, units),
+                    crate::utils::format_size(total, crate::utils::MB, units)
+                ),
+                _ => "N/A".to_string(),
+            };
+            let temperature = gpu
+                .temperature
+                .map(|temp| format!("{:.0}°C", temp))
+                .unwrap_or_else(|| "N/A".to_string());
+            let power = gpu
+                .power_draw
+                .map(|watts| format!("{:.1} W", watts))
+                .unwrap_or_else(|| "N/A".to_string());
+            Row::new(vec![
+                Cell::from(gpu.name.clone()).style(Style::default().fg(theme.get_cpu_color(i))),
+                Cell::from(gpu.vendor.clone()).style(Style::default().fg(theme.text_secondary)),
+                Cell::from(utilization).style(
+                    Style::default()
+                        .fg(theme.text_primary)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Cell::from(clock).style(Style::default().fg(theme.text_secondary)),
+                Cell::from(memory).style(Style::default().fg(theme.text_secondary)),
+                Cell::from(temperature).style(Style::default().fg(theme.text_secondary)),
+                Cell::from(power).style(Style::default().fg(theme.text_secondary)),
+            ])
+        })
+        .collect();
+    let gpu_table = Table::new(
+        gpu_rows,
+        vec![
+            Constraint::Min(20),
+            Constraint::Length(8),
+            Constraint::Length(8),
+            Constraint::Length(10),
+            Constraint::Length(20),
+            Constraint::Length(8),
+            Constraint::Length(10),
+        ],
+    )
+    .header(header)
+    .block(Block::default());
+    let history_block = Block::default()
+        .title(Span::styled(
+            " GPU Utilization History ",
+            Style::default()
+                .fg(theme.text_bright)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+    let history_area = history_block.inner(layout[1]);
+    let window = ChartWindow::Minute.duration();
+    let history_data: Vec<Vec<(f64, f64)>> = metrics
+        .gpu_history
+        .iter()
+        .map(|history| chart_points(history, window))
+        .collect();
+    let (history_bounds, history_since) =
+        time_axis(history_data.first().map_or(&[], Vec::as_slice));
+    Box::new(move |f: &mut ratatui::Frame| {
+        let datasets: Vec<Dataset> = history_data
+            .iter()
+            .zip(&metrics.gpus)
+            .enumerate()
+            .map(|(i, (data, gpu))| {
+                Dataset::default()
+                    .name(gpu.name.clone())
+                    .marker(symbols::Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::default().fg(theme.get_cpu_color(i)))
+                    .data(data)
+            })
+            .collect();
+        let history_chart = Chart::new(datasets)
+            .x_axis(
+                Axis::default()
+                    .style(Style::default().fg(theme.text_dim))
+                    .bounds(history_bounds)
+                    .labels(vec![
+                        Span::styled(history_since, Style::default().fg(theme.text_dim)),
+                        Span::styled("now", Style::default().fg(theme.text_dim)),
+                    ]),
+            )
+            .y_axis(
+                Axis::default()
+                    .style(Style::default().fg(theme.text_dim))
+                    .bounds([0.0, 100.0])
+                    .labels(vec![
+                        Span::styled("0%", Style::default().fg(theme.text_dim)),
+                        Span::styled("50%", Style::default().fg(theme.text_dim)),
+                        Span::styled("100%", Style::default().fg(theme.text_dim)),
+                    ]),
+            );
+        f.render_widget(gpu_block, layout[0]);
+        f.render_widget(gpu_table, gpu_area);
+        f.render_widget(history_block, layout[1]);
+        f.render_widget(history_chart, history_area);
+    })
+}
+
+pub fn render_sensors_view<'a>(
+    area: Rect,
+    theme: &'a Theme,
+    metrics: &'a SystemInfo,
+) -> Box<dyn FnOnce(&mut Frame) + 'a> {
+    let sensors_block = Block::default()
+        .title(Span::styled(
+            " Sensors ",
+            Style::default()
+                .fg(theme.text_bright)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+    let sensors_area = sensors_block.inner(area);
+    if metrics.sensors.is_empty() {
+        let message = Paragraph::new(Line::from(Span::styled(
+            "No sensors found",
+            Style::default().fg(theme.text_dim),
+        )));
+        return Box::new(move |f: &mut ratatui::Frame| {
+            f.render_widget(sensors_block, area);
+            f.render_widget(message, sensors_area);
+        });
+    }
+    let header = Row::new(vec!["Chip", "Sensor", "Value", "Critical"])
+        .style(Style::default().fg(theme.text_dim));
+    let sensor_rows: Vec<Row> = metrics
+        .sensors
+        .iter()
+        .map(|sensor| {
+            let precision = match sensor.kind {
+                SensorKind::Fan => 0,
+                SensorKind::Temperature => 1,
+                SensorKind::Voltage | SensorKind::Power => 2,
+            };
+            let value_color = match sensor.critical {
+                Some(critical) => {
+                    theme.get_usage_color((sensor.value / critical * 100.0).max(0.0) as u64)
+                }
+                None => theme.text_primary,
+            };
+            Row::new(vec![
+                Cell::from(sensor.chip.clone()).style(Style::default().fg(theme.text_secondary)),
+                Cell::from(sensor.label.clone()).style(Style::default().fg(theme.text_primary)),
+                Cell::from(format!(
+                    "{:.*} {}",
+                    precision,
+                    sensor.value,
+                    sensor.kind.unit()
+                ))
+                .style(
+                    Style::default()
+                        .fg(value_color)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Cell::from(sensor.critical.map_or_else(
+                    || "N/A".to_string(),
+                    |critical| format!("{:.*} {}", precision, critical, sensor.kind.unit()),
+                ))
+                .style(Style::default().fg(theme.text_dim)),
+            ])
+        })
+        .collect();
+    let sensors_table = Table::new(
+        sensor_rows,
+        vec![
+            Constraint::Length(16),
+            Constraint::Min(16),
+            Constraint::Length(14),
+            Constraint::Length(14),
+        ],
+    )
+    .header(header)
+    .block(Block::default());
+    Box::new(move |f: &mut ratatui::Frame| {
+        f.render_widget(sensors_block, area);
+        f.render_widget(sensors_table, sensors_area);
+    })
+}
+
+pub fn render_services_view<'a>(
+    area: Rect,
+    theme: &'a Theme,
+    app: &'a crate::app::App,
+) -> Box<dyn FnOnce(&mut Frame) + 'a> {
+    let services_block = Block::default()
+        .title(Span::styled(
+            if app.services_failed_first {
+                " Services [failed first] "
+            } else {
+                " Services "
+            },
+            Style::default()
+                .fg(theme.text_bright)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+    let services_area = services_block.inner(area);
+    let Some(services) = &app.metrics.services else {
+        let message = Paragraph::new(Line::from(Span::styled(
+            "systemd is not available on this system",
+            Style::default().fg(theme.text_dim),
+        )));
+        return Box::new(move |f: &mut ratatui::Frame| {
+            f.render_widget(services_block, area);
+            f.render_widget(message, services_area);
+        });
+    };
+    let header = Row::new(vec![
+        "Unit",
+        "Active",
+        "Sub",
+        "PID",
+        "CPU%",
+        "MEM",
+        "Description",
+    ])
+    .style(Style::default().fg(theme.text_dim));
+    // Keep the selected unit on screen; one row is taken by the header
+    let visible_rows = services_area.height.saturating_sub(1).max(1) as usize;
+    let start_idx = app
+        .selected_service
+        .saturating_sub(visible_rows - 1)
+        .min(services.len());
+    let rows: Vec<Row> = services
+        .iter()
+        .enumerate()
+        .skip(start_idx)
+        .take(visible_rows)
+        .map(|(i, service)| {
+            let state_color = match service.active_state.as_str() {
+                "failed" => theme.danger,
+                "active" => theme.success,
+                "activating" | "deactivating" | "reloading" => theme.warning,
+                _ => theme.text_dim,
+            };
+            let row_style = if i == app.selected_service {
+                theme.selected()
+            } else if i % 2 == 0 {
+                Style::default().bg(theme.bg_normal)
+            } else {
+                Style::default().bg(theme.bg_light)
+            };
+            Row::new(vec![
+                Cell::from(service.name.clone()).style(Style::default().fg(
+                    if service.is_failed() {
+                        theme.danger
+                    } else {
+                        theme.text_primary
+                    },
+                )),
+                Cell::from(service.active_state.clone()).style(
+                    Style::default()
+                        .fg(state_color)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Cell::from(service.sub_state.clone())
+                    .style(Style::default().fg(theme.text_secondary)),
+                Cell::from(
+                    service
+                        .main_pid
+                        .map_or_else(|| "-".to_string(), |pid| pid.to_string()),
+                )
+                .style(Style::default().fg(theme.text_primary)),
+                Cell::from(
+                    service
+                        .cpu_usage
+                        .map_or_else(|| "-".to_string(), |usage| format!("{:.1}", usage)),
+                )
+                .style(Style::default().fg(theme.text_primary)),
+                Cell::from(service.memory_usage.map_or_else(
+                    || "-".to_string(),
+                    |memory| crate::utils::format_size(memory, crate::utils::MB, app.units),
+                ))
+                .style(Style::default().fg(theme.text_primary)),
+                Cell::from(service.description.clone()).style(Style::default().fg(theme.text_dim)),
+            ])
+            .style(row_style)
+        })
+        .collect();
+    let services_table = Table::new(
+        rows,
+        vec![
+            Constraint::Percentage(25),
+            Constraint::Length(12),
+            Constraint::Length(10),
+            Constraint::Length(8),
+            Constraint::Length(6),
+            Constraint::Length(9),
+            Constraint::Min(10),
+        ],
+    )
+    .header(header)
+    .block(Block::default());
+    Box::new(move |f: &mut ratatui::Frame| {
+        f.render_widget(services_block, area);
+        f.render_widget(services_table, services_area);
     })
 }
 
 pub fn render_options_view<'a>(
     area: Rect,
     theme: &'a Theme,
-    app: &crate::app::App,
+    app: &'a crate::app::App,
 ) -> Box<dyn FnOnce(&mut Frame) + 'a> {
     let layout = Layout::default()
         .direction(Direction::Vertical)
@@ -983,7 +2696,7 @@ pub fn render_options_view<'a>(
         .split(area);
     let options_block = Block::default()
         .title(Span::styled(
-            " Options ",
+            " Options [↑↓ select, Enter/←→ change] ",
             Style::default()
                 .fg(theme.text_bright)
                 .add_modifier(Modifier::BOLD),
@@ -991,7 +2704,8 @@ pub fn render_options_view<'a>(
         .borders(Borders::ALL)
         .border_style(Style::default().fg(theme.border));
     let options_area = options_block.inner(layout[1]);
-    let options_text = vec![
+    // One line per entry of SETTINGS, in the same order
+    let mut options_text = vec![
         Line::from(vec![
             Span::styled("Update Interval: ", Style::default().fg(theme.text_dim)),
             Span::styled(
@@ -1048,13 +2762,40 @@ pub fn render_options_view<'a>(
             ),
             Span::raw(" [F6 to toggle]"),
         ]),
+        Line::from(vec![
+            Span::styled("Hide Kernel Threads: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                if app.hide_kernel_threads { "Yes" } else { "No" },
+                Style::default().fg(if app.hide_kernel_threads {
+                    theme.success
+                } else {
+                    theme.info
+                }),
+            ),
+            Span::raw(format!(
+                " [{} to toggle]",
+                app.keys.label(Action::HideKernelThreads)
+            )),
+        ]),
+        Line::from(vec![
+            Span::styled("Only My Processes: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                if app.only_mine { "Yes" } else { "No" },
+                Style::default().fg(if app.only_mine {
+                    theme.success
+                } else {
+                    theme.info
+                }),
+            ),
+            Span::raw(format!(" [{} to toggle]", app.keys.label(Action::OnlyMine))),
+        ]),
         Line::from(vec![
             Span::styled("Sort Column: ", Style::default().fg(theme.text_dim)),
             Span::styled(
                 format!("{:?}", app.process_sort),
                 Style::default().fg(theme.text_primary),
             ),
-            Span::raw(" [c/m/p/n to change]"),
+            Span::raw(" [←→ or c/m/p/n/u/t/T/S to change]"),
         ]),
         Line::from(vec![
             Span::styled("Sort Reverse: ", Style::default().fg(theme.text_dim)),
@@ -1066,9 +2807,34 @@ pub fn render_options_view<'a>(
                     theme.info
                 }),
             ),
+            Span::raw(" [Shift+←→ to toggle]"),
+        ]),
+        Line::from(vec![
+            Span::styled("Theme: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                app.theme_name.as_str(),
+                Style::default().fg(theme.text_primary),
+            ),
+            Span::raw(format!(
+                " [{} to cycle]",
+                app.keys.label(Action::CycleTheme)
+            )),
+        ]),
+        Line::from(vec![
+            Span::styled("Units: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                match app.units {
+                    UnitSystem::Binary => "Binary (KiB, MiB, GiB)",
+                    UnitSystem::Decimal => "Decimal (KB, MB, GB)",
+                },
+                Style::default().fg(theme.text_primary),
+            ),
             Span::raw(" [←→ to toggle]"),
         ]),
     ];
+    if let Some(line) = options_text.get_mut(app.selected_setting) {
+        line.style = theme.selected();
+    }
     let options_para = Paragraph::new(options_text).block(Block::default());
     Box::new(move |f: &mut ratatui::Frame| {
         f.render_widget(options_block, layout[1]);
@@ -1076,68 +2842,1078 @@ pub fn render_options_view<'a>(
     })
 }
 
-pub fn render_help_view<'a>(area: Rect, theme: &'a Theme) -> Box<dyn FnOnce(&mut Frame) + 'a> {
-    let help_block = Block::default()
+pub fn render_environment_popup<'a>(
+    area: Rect,
+    theme: &'a Theme,
+    popup: &'a crate::app::EnvironmentPopup,
+) -> Box<dyn FnOnce(&mut Frame) + 'a> {
+    const SECRET_MARKERS: [&str; 5] = ["TOKEN", "PASSWORD", "PASSWD", "SECRET", "KEY"];
+    let popup_block = Block::default()
         .title(Span::styled(
-            " Help - Key Bindings ",
+            format!(" Environment: {} ({}) ", popup.name, popup.pid),
             Style::default()
                 .fg(theme.text_bright)
                 .add_modifier(Modifier::BOLD),
         ))
+        .title_bottom(Span::styled(
+            if popup.reveal_secrets {
+                " [↑↓] Scroll [v] Hide secrets [e/Esc] Close "
+            } else {
+                " [↑↓] Scroll [v] Reveal secrets [e/Esc] Close "
+            },
+            Style::default().fg(theme.text_dim),
+        ))
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(theme.border));
-    let help_area = help_block.inner(area);
-    let help_text = vec![
-        Line::from(vec![Span::styled(
-            "Navigation:",
+        .border_style(Style::default().fg(theme.border_light))
+        .style(Style::default().bg(theme.bg_dark));
+    let popup_area = popup_block.inner(area);
+    let lines: Vec<Line> = match &popup.entries {
+        Ok(entries) if entries.is_empty() => vec![Line::from(Span::styled(
+            "No environment variables",
+            Style::default().fg(theme.text_dim),
+        ))],
+        Ok(entries) => entries
+            .iter()
+            .skip(popup.scroll)
+            .map(|(key, value)| {
+                let upper_key = key.to_uppercase();
+                let masked = !popup.reveal_secrets
+                    && SECRET_MARKERS
+                        .iter()
+                        .any(|marker| upper_key.contains(marker));
+                Line::from(vec![
+                    Span::styled(
+                        key.as_str(),
+                        Style::default().fg(theme.info).add_modifier(Modifier::BOLD),
+                    ),
+                    Span::styled("=", Style::default().fg(theme.text_dim)),
+                    if masked {
+                        Span::styled("********", Style::default().fg(theme.warning))
+                    } else {
+                        Span::styled(value.as_str(), Style::default().fg(theme.text_primary))
+                    },
+                ])
+            })
+            .collect(),
+        Err(message) => vec![Line::from(Span::styled(
+            message.as_str(),
+            Style::default().fg(theme.danger),
+        ))],
+    };
+    let popup_para = Paragraph::new(lines).wrap(ratatui::widgets::Wrap { trim: false });
+    Box::new(move |f: &mut ratatui::Frame| {
+        f.render_widget(Clear, area);
+        f.render_widget(popup_block, area);
+        f.render_widget(popup_para, popup_area);
+    })
+}
+
+pub fn render_open_files_popup<'a>(
+    area: Rect,
+    theme: &'a Theme,
+    popup: &'a crate::app::OpenFilesPopup,
+) -> Box<dyn FnOnce(&mut Frame) + 'a> {
+    let popup_block = Block::default()
+        .title(Span::styled(
+            format!(" Open Files: {} ({}) ", popup.name, popup.pid),
             Style::default()
                 .fg(theme.text_bright)
                 .add_modifier(Modifier::BOLD),
-        )]),
-        Line::from(vec![Span::raw("  [1-6]    Switch between views")]),
-        Line::from(vec![Span::raw("  [Tab]     Cycle through views")]),
-        Line::from(vec![Span::raw("  [q/Esc]   Quit the application")]),
-        Line::from(""),
-        Line::from(vec![Span::styled(
-            "Process View:",
+        ))
+        .title_bottom(Span::styled(
+            " [↑↓] Scroll [l/Esc] Close ",
+            Style::default().fg(theme.text_dim),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border_light))
+        .style(Style::default().bg(theme.bg_dark));
+    let popup_area = popup_block.inner(area);
+    let mut lines = Vec::new();
+    match &popup.files {
+        Ok(files) if files.files.is_empty() => lines.push(Line::from(Span::styled(
+            "No open files",
+            Style::default().fg(theme.text_dim),
+        ))),
+        Ok(files) => {
+            for group in files.files.chunk_by(|a, b| a.kind == b.kind) {
+                let heading = match group[0].kind {
+                    crate::sys_info::FdKind::File => "Files",
+                    crate::sys_info::FdKind::Socket => "Sockets",
+                    crate::sys_info::FdKind::Pipe => "Pipes",
+                    crate::sys_info::FdKind::Other => "Other",
+                };
+                lines.push(Line::from(Span::styled(
+                    format!("{} ({})", heading, group.len()),
+                    Style::default()
+                        .fg(theme.text_bright)
+                        .add_modifier(Modifier::BOLD),
+                )));
+                lines.extend(group.iter().map(|file| {
+                    Line::from(vec![
+                        Span::styled(format!("{:>6}  ", file.fd), Style::default().fg(theme.info)),
+                        Span::styled(
+                            file.target.as_str(),
+                            Style::default().fg(theme.text_primary),
+                        ),
+                    ])
+                }));
+            }
+            if files.total > files.files.len() {
+                lines.push(Line::from(Span::styled(
+                    format!("+{} more", files.total - files.files.len()),
+                    Style::default().fg(theme.text_dim),
+                )));
+            }
+        }
+        Err(message) => lines.push(Line::from(Span::styled(
+            message.as_str(),
+            Style::default().fg(theme.danger),
+        ))),
+    }
+    let popup_para = Paragraph::new(lines).scroll((popup.scroll as u16, 0));
+    Box::new(move |f: &mut ratatui::Frame| {
+        f.render_widget(Clear, area);
+        f.render_widget(popup_block, area);
+        f.render_widget(popup_para, popup_area);
+    })
+}
+
+pub fn render_events_popup<'a>(
+    area: Rect,
+    theme: &'a Theme,
+    app: &'a crate::app::App,
+) -> Box<dyn FnOnce(&mut Frame) + 'a> {
+    let popup_block = Block::default()
+        .title(Span::styled(
+            format!(" Events ({}) ", app.events.len()),
             Style::default()
                 .fg(theme.text_bright)
                 .add_modifier(Modifier::BOLD),
-        )]),
-        Line::from(vec![Span::raw("  [↑↓/jk]   Navigate processes")]),
-        Line::from(vec![Span::raw("  [Page Up/Down] Scroll page")]),
-        Line::from(vec![Span::raw("  [Home/End]    Jump to top/bottom")]),
-        Line::from(vec![Span::raw("  [Enter]       Show process details")]),
-        Line::from(vec![Span::raw(
-            "  [c/m/p/n]     Sort by CPU/Memory/PID/Name",
-        )]),
-        Line::from(vec![Span::raw("  [←→]          Toggle sort order")]),
-        Line::from(vec![Span::raw("  [f]           Toggle full command")]),
-        Line::from(""),
+        ))
+        .title_bottom(Span::styled(
+            format!(
+                " [↑↓] Scroll [{}/Esc] Close ",
+                app.keys.label(Action::Events)
+            ),
+            Style::default().fg(theme.text_dim),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border_light))
+        .style(Style::default().bg(theme.bg_dark));
+    let popup_area = popup_block.inner(area);
+    let mut lines: Vec<Line> = Vec::new();
+    for event in app.events.newest_first() {
+        // Output of a command follows on lines of its own, under the text
+        let mut text = event.text.lines();
+        lines.push(Line::from(vec![
+            Span::styled(
+                event.time.format("%H:%M:%S  ").to_string(),
+                Style::default().fg(theme.text_dim),
+            ),
+            Span::styled(
+                text.next().unwrap_or_default(),
+                Style::default().fg(theme.severity_color(event.severity)),
+            ),
+        ]));
+        lines.extend(text.map(|line| {
+            Line::from(Span::styled(
+                format!("          {}", line),
+                Style::default().fg(theme.text_secondary),
+            ))
+        }));
+    }
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled(
+            "Nothing has happened yet",
+            Style::default().fg(theme.text_dim),
+        )));
+    }
+    let popup_para = Paragraph::new(lines).scroll((app.events_scroll as u16, 0));
+    Box::new(move |f: &mut ratatui::Frame| {
+        f.render_widget(Clear, area);
+        f.render_widget(popup_block, area);
+        f.render_widget(popup_para, popup_area);
+    })
+}
+
+pub fn render_action_popup<'a>(
+    area: Rect,
+    theme: &'a Theme,
+    action: &'a PendingAction,
+) -> Box<dyn FnOnce(&mut Frame) + 'a> {
+    let (title, hints) = match action {
+        PendingAction::Kill { .. } => (
+            " Kill Process ",
+            " [←→] Signal [Enter] Confirm [Esc] Cancel ",
+        ),
+        PendingAction::SignalPicker { .. } => (
+            " Send Signal ",
+            " [↑↓] Select [Type] Filter [Enter] Send [Esc] Cancel ",
+        ),
+    };
+    let popup_block = Block::default()
+        .title(Span::styled(
+            title,
+            Style::default()
+                .fg(theme.text_bright)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .title_bottom(Span::styled(hints, Style::default().fg(theme.text_dim)))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.danger))
+        .style(Style::default().bg(theme.bg_dark));
+    let popup_area = popup_block.inner(area);
+    let targets = action.targets();
+    let selected_style = Style::default()
+        .fg(theme.bg_dark)
+        .bg(theme.danger)
+        .add_modifier(Modifier::BOLD);
+    let signal_label = |option: Signal| match crate::sys_info::signal_number(option) {
+        Some(number) => format!(" {} ({}) ", option.name(), number),
+        None => format!(" {} ", option.name()),
+    };
+    let mut lines = match targets {
+        [target] => vec![
+            Line::from(vec![
+                Span::styled("PID: ", Style::default().fg(theme.text_dim)),
+                Span::styled(
+                    target.pid.to_string(),
+                    Style::default().fg(theme.text_primary),
+                ),
+                Span::raw("  "),
+                Span::styled("User: ", Style::default().fg(theme.text_dim)),
+                Span::styled(
+                    target.user.as_str(),
+                    Style::default().fg(theme.text_primary),
+                ),
+            ]),
+            Line::from(vec![
+                Span::styled("Name: ", Style::default().fg(theme.text_dim)),
+                Span::styled(
+                    target.name.as_str(),
+                    Style::default()
+                        .fg(theme.text_bright)
+                        .add_modifier(Modifier::BOLD),
+                ),
+            ]),
+            Line::from(""),
+        ],
+        _ => {
+            let pids: Vec<String> = targets
+                .iter()
+                .map(|target| target.pid.to_string())
+                .collect();
+            vec![
+                Line::from(Span::styled(
+                    format!("{} tagged processes", targets.len()),
+                    Style::default()
+                        .fg(theme.text_bright)
+                        .add_modifier(Modifier::BOLD),
+                )),
+                Line::from(vec![
+                    Span::styled("PIDs: ", Style::default().fg(theme.text_dim)),
+                    Span::styled(
+                        truncate_list(&pids, popup_area.width.saturating_sub(6) as usize),
+                        Style::default().fg(theme.text_primary),
+                    ),
+                ]),
+                Line::from(""),
+            ]
+        }
+    };
+    match action {
+        PendingAction::Kill { signal, .. } => {
+            let signal_span = |option: Signal| {
+                if option == *signal {
+                    Span::styled(signal_label(option), selected_style)
+                } else {
+                    Span::styled(
+                        signal_label(option),
+                        Style::default().fg(theme.text_secondary),
+                    )
+                }
+            };
+            lines.push(Line::from(vec![
+                signal_span(Signal::Term),
+                Span::raw("  "),
+                signal_span(Signal::Kill),
+            ]));
+        }
+        PendingAction::SignalPicker {
+            query, selected, ..
+        } => {
+            lines.push(Line::from(vec![
+                Span::styled("Filter: ", Style::default().fg(theme.text_dim)),
+                Span::styled(
+                    format!("{}_", query),
+                    Style::default().fg(theme.text_bright),
+                ),
+            ]));
+            let matches = crate::app::filter_signals(query);
+            if matches.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    "No matching signal",
+                    Style::default().fg(theme.text_dim),
+                )));
+            }
+            for (index, option) in matches.into_iter().enumerate() {
+                let label = format!("{:<16}", signal_label(option));
+                lines.push(Line::from(if index == *selected {
+                    Span::styled(label, selected_style)
+                } else {
+                    Span::styled(label, Style::default().fg(theme.text_secondary))
+                }));
+            }
+        }
+    }
+    if action.is_armed() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            if targets.iter().any(|target| target.pid == 1) {
+                "This is init! Press Enter again to really send."
+            } else {
+                "This is xtop itself! Press Enter again to really send."
+            },
+            Style::default()
+                .fg(theme.danger)
+                .add_modifier(Modifier::BOLD),
+        )));
+    }
+    let popup_para = Paragraph::new(lines).alignment(ratatui::layout::Alignment::Center);
+    Box::new(move |f: &mut ratatui::Frame| {
+        f.render_widget(Clear, area);
+        f.render_widget(popup_block, area);
+        f.render_widget(popup_para, popup_area);
+    })
+}
+
+// Joins as many items as fit in `width`, ending with "…" when some are left out
+fn truncate_list(items: &[String], width: usize) -> String {
+    let mut joined = String::new();
+    for (index, item) in items.iter().enumerate() {
+        let separator = if joined.is_empty() { "" } else { " " };
+        let remaining = index + 1 < items.len();
+        // Leave room for the ellipsis unless this is the last item
+        let needed = joined.len() + separator.len() + item.len() + if remaining { 2 } else { 0 };
+        if needed > width {
+            joined.push_str(if joined.is_empty() { "…" } else { " …" });
+            break;
+        }
+        joined.push_str(separator);
+        joined.push_str(item);
+    }
+    joined
+}
+
+pub fn render_help_view<'a>(
+    area: Rect,
+    theme: &'a Theme,
+    app: &crate::app::App,
+) -> Box<dyn FnOnce(&mut Frame) + 'a> {
+    let keys = &app.keys;
+    let help_block = Block::default()
+        .title(Span::styled(
+            " Help - Key Bindings ",
+            Style::default()
+                .fg(theme.text_bright)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .title_bottom(Span::styled(
+            " [↑↓] Scroll [F1/Esc/q] Close ",
+            Style::default().fg(theme.text_dim),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border))
+        .style(Style::default().bg(theme.bg_dark));
+    let help_area = help_block.inner(area);
+    let heading = |title: &'static str| {
         Line::from(vec![Span::styled(
-            "General:",
+            title,
             Style::default()
                 .fg(theme.text_bright)
                 .add_modifier(Modifier::BOLD),
-        )]),
-        Line::from(vec![Span::raw("  [Space]    Pause/Resume updates")]),
+        )])
+    };
+    let entry = |keys: String, description: &str| {
+        Line::from(vec![Span::raw(format!(
+            "  {:<14}{}",
+            format!("[{}]", keys),
+            description
+        ))])
+    };
+    let views = [
+        View::Overview,
+        View::System,
+        View::Process,
+        View::Resources,
+        View::Network,
+        View::Disks,
+        View::Options,
+        View::Gpu,
+        View::Sensors,
+        View::Services,
+    ]
+    .map(Action::View);
+    let key = |action| keys.label(action);
+    let help_text = vec![
+        heading("Navigation:"),
+        entry(keys.labels(&views), "Switch between views"),
+        entry(key(Action::NextView), "Cycle through views"),
+        entry(
+            keys.labels(&[Action::Quit, Action::Back]),
+            "Quit the application",
+        ),
+        entry(key(Action::Suspend), "Suspend to the shell; fg resumes"),
+        Line::from(""),
+        heading("Process View:"),
+        entry(
+            keys.labels(&[Action::Up, Action::Down]),
+            "Navigate processes",
+        ),
+        entry(
+            keys.labels(&[Action::PageUp, Action::PageDown]),
+            "Scroll page",
+        ),
+        entry(format!("{}/gg", key(Action::Top)), "Jump to top"),
+        entry(key(Action::Bottom), "Jump to bottom"),
+        entry(
+            keys.labels(&[Action::HalfPageDown, Action::HalfPageUp]),
+            "Half a page down/up",
+        ),
+        entry(
+            "5j".to_string(),
+            "Repeat a motion, with count_prefix set in the config",
+        ),
+        entry(key(Action::Activate), "Toggle details, or expand a group"),
+        entry(key(Action::TreeView), "Toggle process tree"),
+        entry(key(Action::Aggregate), "Group processes by name"),
+        entry(
+            keys.labels(&[
+                Action::Sort(ProcessSort::Cpu),
+                Action::Sort(ProcessSort::Memory),
+                Action::Sort(ProcessSort::Pid),
+                Action::Sort(ProcessSort::Name),
+                Action::Sort(ProcessSort::Fds),
+                Action::Sort(ProcessSort::Swap),
+            ]),
+            "Sort by CPU/Memory (twice for MEM%)/PID/Name/FDs/Swap",
+        ),
+        entry(
+            keys.labels(&[
+                Action::Sort(ProcessSort::User),
+                Action::Sort(ProcessSort::Threads),
+                Action::Sort(ProcessSort::Time),
+                Action::Sort(ProcessSort::State),
+            ]),
+            "Sort by User/Threads/Time/State",
+        ),
+        entry(
+            keys.labels(&[Action::PreviousSortColumn, Action::NextSortColumn]),
+            "Previous/next sort column",
+        ),
+        entry(key(Action::ReverseSort), "Toggle sort order"),
+        entry(key(Action::FullCommand), "Toggle full command"),
+        entry(key(Action::ContainerFilter), "Filter by selected container"),
+        entry(key(Action::HideKernelThreads), "Hide kernel threads"),
+        entry(key(Action::OnlyMine), "Show only my processes"),
+        entry(key(Action::Environment), "Show environment variables"),
+        entry(key(Action::OpenFiles), "Show open files and sockets"),
+        entry(
+            key(Action::Events),
+            "Show the log of alerts and other events",
+        ),
+        entry(key(Action::Export), "Save a JSON snapshot of the metrics"),
+        entry(key(Action::Kill), "Kill selected or tagged processes"),
+        entry(
+            key(Action::Tag),
+            "Tag/untag process for kill, signal, renice",
+        ),
+        entry(
+            keys.labels(&[Action::ClearTags, Action::Back]),
+            "Clear all tags",
+        ),
+        entry(
+            key(Action::SignalPicker),
+            "Send any signal to selected process",
+        ),
+        entry(
+            keys.labels(&[Action::NiceDown, Action::NiceUp]),
+            "Lower/raise nice of selected process",
+        ),
+        entry(key(Action::Search), "Search processes by name or command"),
         Line::from(vec![Span::raw(
-            "  [+/-]      Increase/Decrease update speed",
+            "                [Tab] while typing: filter or highlight",
         )]),
-        Line::from(vec![Span::raw("  [r]        Reset selection")]),
-        Line::from(vec![Span::raw("  [F1]       Show/hide this help")]),
-        Line::from(vec![Span::raw("  [F5]       Toggle tree view")]),
-        Line::from(vec![Span::raw("  [F6]       Toggle process aggregation")]),
+        entry(
+            keys.labels(&[Action::Sort(ProcessSort::Name), Action::PreviousMatch]),
+            "Next/previous highlighted match",
+        ),
+        Line::from(""),
+        heading("System View:"),
+        entry(
+            key(Action::CpuDisplay),
+            "Cycle core bars: usage/MHz/governor",
+        ),
+        entry(key(Action::CoreGraphs), "Per-core history graphs"),
+        Line::from(""),
+        heading("Resources View:"),
+        entry(
+            keys.labels(&[Action::ZoomIn, Action::ZoomOut]),
+            "Zoom charts in/out: 30s, 1m, 5m, 15m",
+        ),
+        Line::from(""),
+        heading("Network View:"),
+        entry(
+            keys.labels(&[Action::Up, Action::Down]),
+            "Select the interface to chart",
+        ),
+        Line::from(""),
+        heading("Options View:"),
+        entry(keys.labels(&[Action::Up, Action::Down]), "Select a setting"),
+        entry(
+            keys.labels(&[
+                Action::Activate,
+                Action::PreviousSortColumn,
+                Action::NextSortColumn,
+            ]),
+            "Change it; the interval steps by 250ms",
+        ),
+        Line::from(""),
+        heading("Services View:"),
+        entry(key(Action::Activate), "Jump to main PID"),
+        entry(key(Action::FailedFirst), "Toggle failed units first"),
+        Line::from(""),
+        heading("Disks View:"),
+        entry(
+            key(Action::PseudoFilesystems),
+            "Show/hide tmpfs, overlay and loop mounts",
+        ),
+        entry(key(Action::Activate), "Expand/collapse btrfs and ZFS pools"),
+        Line::from(""),
+        heading("General:"),
+        entry(key(Action::Pause), "Pause/Resume updates"),
+        entry(
+            keys.labels(&[Action::SlowerUpdates, Action::FasterUpdates]),
+            "Increase/Decrease update speed",
+        ),
+        entry(key(Action::ResetSelection), "Reset selection"),
+        entry(key(Action::Help), "Show/hide this help"),
+        entry(key(Action::TreeView), "Toggle tree view"),
+        entry(key(Action::Aggregate), "Toggle process aggregation"),
+        entry(key(Action::CycleTheme), "Switch color theme"),
+        entry(
+            "Click".to_string(),
+            "Select a row, sort by a header, open a view",
+        ),
+        entry("Wheel".to_string(), "Scroll three lines at a time"),
     ];
-    let help_para = Paragraph::new(help_text)
-        .block(Block::default())
-        .wrap(ratatui::widgets::Wrap { trim: true });
+    // Unwrapped, so each line scrolls as one
+    let overflow = help_text
+        .len()
+        .saturating_sub(usize::from(help_area.height));
+    app.layout.help_scroll_limit.set(overflow);
+    let scroll = app.help_scroll.min(overflow);
+    let help_para = Paragraph::new(help_text).scroll((scroll as u16, 0));
     Box::new(move |f: &mut ratatui::Frame| {
+        f.render_widget(Clear, area);
         f.render_widget(help_block, area);
         f.render_widget(help_para, help_area);
     })
 }
 
+// Compares against the samples before the latest one, ignoring the zeros a
+// history is padded with until it fills up.
+fn is_spike(history: &History<u64>, current: u64, factor: f64) -> bool {
+    let previous: Vec<u64> = history
+        .values()
+        .rev()
+        .skip(1)
+        .filter(|&sample| sample > 0)
+        .collect();
+    if previous.len() < 3 {
+        return false;
+    }
+    let average = previous.iter().sum::<u64>() as f64 / previous.len() as f64;
+    current as f64 > average * factor
+}
+
+/// A cell of one line of text. Cells made straight from a `String` split it
+/// into lines and copy each, which adds up over a table redrawn every frame.
+pub fn text_cell<'a>(text: impl Into<Cow<'a, str>>) -> Cell<'a> {
+    Cell::from(Line::from(Span::raw(text)))
+}
+
+/// One of a process's CPU, memory, thread or priority figures, or N/A when
+/// they could not be read.
+pub fn process_figure(
+    process: &crate::sys_info::ProcessInfo,
+    figure: impl FnOnce() -> String,
+) -> Cow<'static, str> {
+    if process.restricted {
+        Cow::Borrowed("N/A")
+    } else {
+        Cow::Owned(figure())
+    }
+}
+
+/// A process table header cell, marked ▲ or ▼ and highlighted when the
+/// table is sorted by its column.
+pub fn process_header_cell(column: Column, app: &crate::app::App, theme: &Theme) -> Cell<'static> {
+    let style = Style::default()
+        .fg(theme.text_bright)
+        .add_modifier(Modifier::BOLD);
+    if column.sort() == Some(app.process_sort) {
+        let arrow = if app.sort_descending() { "▼" } else { "▲" };
+        Cell::from(format!("{} {}", column.title(), arrow)).style(style.patch(theme.selected()))
+    } else {
+        Cell::from(column.title()).style(style)
+    }
+}
+
+// Spans for consecutive styled parts of a line, with the byte range `found`
+// restyled on top wherever it falls
+fn highlight_match<'a>(
+    parts: &[(&'a str, Style)],
+    found: Option<(usize, usize)>,
+    found_style: Style,
+) -> Vec<Span<'a>> {
+    let mut spans = Vec::new();
+    let mut offset = 0;
+    for &(text, style) in parts {
+        let (start, end) = found.map_or((0, 0), |(start, end)| {
+            (
+                start.clamp(offset, offset + text.len()) - offset,
+                end.clamp(offset, offset + text.len()) - offset,
+            )
+        });
+        for (piece, style) in [
+            (&text[..start], style),
+            (&text[start..end], style.patch(found_style)),
+            (&text[end..], style),
+        ] {
+            if !piece.is_empty() {
+                spans.push(Span::styled(piece, style));
+            }
+        }
+        offset += text.len();
+    }
+    spans
+}
+
+/// Memory as a bar split into used, cached, buffers and free, with a legend
+/// line below it. Cache the kernel can drop reads differently from memory
+/// that is really in use.
+pub fn memory_bar(
+    metrics: &SystemInfo,
+    theme: &Theme,
+    width: u16,
+    units: UnitSystem,
+) -> Vec<Line<'static>> {
+    let total = metrics.memory_total;
+    let used = metrics.memory_used.min(total);
+    let cached = metrics.memory_cached.min(total - used);
+    let buffers = metrics.memory_buffers.min(total - used - cached);
+    let segments = [
+        ("used", used, theme.mem_colors[2], "█"),
+        ("cache", cached, theme.mem_colors[1], "█"),
+        ("buf", buffers, theme.mem_colors[0], "█"),
+        ("free", total - used - cached - buffers, theme.text_dim, "░"),
+    ];
+    let width = u64::from(width);
+    let mut covered = 0;
+    let mut drawn = 0;
+    let bar = segments
+        .iter()
+        .map(|&(_, size, color, symbol)| {
+            // Rounding the running total rather than each segment keeps the
+            // cells adding up to the width
+            covered += size;
+            let end = (covered * width + total / 2) / total.max(1);
+            let cells = end - drawn;
+            drawn = end;
+            Span::styled(symbol.repeat(cells as usize), Style::default().fg(color))
+        })
+        .collect::<Vec<_>>();
+    let legend = segments
+        .iter()
+        .flat_map(|&(label, size, color, _)| {
+            [
+                Span::styled("■", Style::default().fg(color)),
+                Span::styled(
+                    format!(
+                        "{} {} ",
+                        label,
+                        crate::utils::format_size(size, crate::utils::MB, units)
+                    ),
+                    Style::default().fg(theme.text_secondary),
+                ),
+            ]
+        })
+        .collect::<Vec<_>>();
+    vec![Line::from(bar), Line::from(legend)]
+}
+
+fn governor_color(governor: Option<&str>, theme: &Theme) -> Color {
+    match governor {
+        Some("performance") => theme.danger,
+        Some("powersave") => theme.success,
+        Some("schedutil" | "ondemand" | "conservative") => theme.info,
+        Some(_) => theme.warning,
+        None => theme.text_dim,
+    }
+}
+
+fn battery_color(capacity: u64, theme: &Theme) -> Color {
+    if capacity < 10 {
+        theme.danger
+    } else if capacity < 20 {
+        theme.warning
+    } else {
+        theme.text_bright
+    }
+}
+
+const CORE_CELL_WIDTH: u16 = 8;
+const CORE_CELL_HEIGHT: u16 = 2;
+// Narrowest column of `C17 [███░░]  62%` meters
+const CORE_METER_WIDTH: u16 = 16;
+
+/// How the System view fits a figure per core into its CPU pane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoreLayout {
+    // One bar per core, one column apart
+    Bars { width: u16 },
+    // A line per core, in columns, once bars would not all fit
+    Meters { columns: u16 },
+}
+
+// Core numbers under the bars, which must not be wider than them
+fn core_label(index: usize) -> String {
+    if index < 10 {
+        format!("C{}", index)
+    } else {
+        index.to_string()
+    }
+}
+
+/// Bars three columns wide when they all fit, then two, then meters in as
+/// few columns as hold every core, or as many as fit.
+pub fn core_layout(cores: usize, area: Rect) -> CoreLayout {
+    let label_width = core_label(cores.saturating_sub(1)).len() as u16;
+    for width in [3, 2] {
+        let needed = cores * usize::from(width + 1);
+        if width >= label_width && needed <= usize::from(area.width) + 1 {
+            return CoreLayout::Bars { width };
+        }
+    }
+    let fitting = (area.width / CORE_METER_WIDTH).max(1);
+    let rows = area.height.max(1);
+    CoreLayout::Meters {
+        columns: fitting.min((cores as u16).div_ceil(rows)).max(1),
+    }
+}
+
+// A `C17 [███░░]  62%` line per core, down each column in turn. Cores that
+// do not fit are counted on the last line instead.
+fn render_core_meters(
+    f: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+    figures: &[(u64, String, Color)],
+    max: u64,
+    unit: &str,
+    columns: u16,
+) {
+    let rows = usize::from(area.height);
+    let capacity = rows * usize::from(columns);
+    let shown = if figures.len() > capacity {
+        capacity.saturating_sub(1)
+    } else {
+        figures.len()
+    };
+    let label_width = format!("C{}", figures.len().saturating_sub(1)).len() + 1;
+    let text_width = figures
+        .iter()
+        .map(|(_, text, _)| text.chars().count() + unit.len())
+        .max()
+        .unwrap_or(0);
+    let column_areas = Layout::horizontal(vec![Constraint::Fill(1); usize::from(columns)])
+        .spacing(1)
+        .split(area);
+    let mut lines: Vec<Vec<Line>> = vec![Vec::new(); usize::from(columns)];
+    for (i, (value, text, color)) in figures.iter().take(shown).enumerate() {
+        let column = i / rows.max(1);
+        let width = usize::from(column_areas[column].width);
+        let bar_width = width.saturating_sub(label_width + text_width + 3);
+        let filled = (*value).min(max) as usize * bar_width / max.max(1) as usize;
+        lines[column].push(Line::from(vec![
+            Span::styled(
+                format!("{:<width$}", format!("C{}", i), width = label_width),
+                Style::default().fg(theme.text_dim),
+            ),
+            Span::styled("[", Style::default().fg(theme.text_dim)),
+            Span::styled("█".repeat(filled), Style::default().fg(*color)),
+            Span::styled(
+                "░".repeat(bar_width - filled),
+                Style::default().fg(theme.text_dim),
+            ),
+            Span::styled("]", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                format!(
+                    " {:>width$}",
+                    format!("{}{}", text, unit),
+                    width = text_width
+                ),
+                Style::default().fg(*color).add_modifier(Modifier::BOLD),
+            ),
+        ]));
+    }
+    if shown < figures.len() {
+        lines[usize::from(columns) - 1].push(Line::from(Span::styled(
+            format!("+{} more", figures.len() - shown),
+            Style::default().fg(theme.text_dim),
+        )));
+    }
+    for (column, lines) in lines.into_iter().enumerate() {
+        f.render_widget(Paragraph::new(lines), column_areas[column]);
+    }
+}
+
+// Columns and rows for a grid of `count` core charts within `area`, each cell
+// at least CORE_CELL_WIDTH by CORE_CELL_HEIGHT. Of the grids that fit, picks
+// the one whose cells come closest to three columns per row, as suits braille
+// lines; None when the cores cannot all fit.
+fn core_grid(count: usize, area: Rect) -> Option<(u16, u16)> {
+    if count == 0 {
+        return None;
+    }
+    (1..=count as u16)
+        .filter_map(|columns| {
+            let rows = (count as u16).div_ceil(columns);
+            let (width, height) = (area.width / columns, area.height / rows);
+            (width >= CORE_CELL_WIDTH && height >= CORE_CELL_HEIGHT).then_some((
+                width.min(height * 3),
+                columns,
+                rows,
+            ))
+        })
+        .max_by_key(|&(size, columns, _)| (size, std::cmp::Reverse(columns)))
+        .map(|(_, columns, rows)| (columns, rows))
+}
+
+// One small line chart per core, titled with its current usage
+fn render_core_graphs(
+    f: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+    metrics: &SystemInfo,
+    data: &[Vec<(f64, f64)>],
+    columns: u16,
+    rows: u16,
+) {
+    let (width, height) = (area.width / columns, area.height / rows);
+    let (bounds, _) = time_axis(data.first().map_or(&[], Vec::as_slice));
+    for (i, points) in data.iter().enumerate() {
+        let (column, row) = (i as u16 % columns, i as u16 / columns);
+        let cell = Rect::new(
+            area.x + column * width,
+            area.y + row * height,
+            width,
+            height,
+        );
+        let color = theme.get_cpu_color(i);
+        let usage = metrics.cpu_usage_per_core.get(i).copied().unwrap_or(0);
+        let block = Block::default().title(Span::styled(
+            format!("C{} {}%", i, usage),
+            Style::default().fg(color),
+        ));
+        let chart = Chart::new(vec![
+            Dataset::default()
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(color))
+                .data(points),
+        ])
+        .x_axis(Axis::default().bounds(bounds))
+        .y_axis(Axis::default().bounds([0.0, 100.0]));
+        f.render_widget(chart, block.inner(cell));
+        f.render_widget(block, cell);
+    }
+}
+
+// Draws `state` over the right border of a table's rows, from the top of
+// `rows` to its bottom
+fn render_scrollbar(
+    f: &mut Frame,
+    outer: Rect,
+    rows: Rect,
+    theme: &Theme,
+    state: Option<ScrollbarState>,
+) {
+    let Some(mut state) = state else {
+        return;
+    };
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .begin_symbol(None)
+        .end_symbol(None)
+        .track_style(Style::default().fg(theme.border))
+        .thumb_style(Style::default().fg(theme.info));
+    let area = Rect {
+        y: rows.y,
+        height: rows.height,
+        ..outer
+    };
+    f.render_stateful_widget(scrollbar, area, &mut state);
+}
+
+// The samples within `window` of a history's newest, as chart points placed
+// at their age in seconds before it
+pub fn chart_points(history: &History<u64>, window: std::time::Duration) -> Vec<(f64, f64)> {
+    history
+        .recent(window)
+        .map(|(age, value)| (-age.as_secs_f64(), value as f64))
+        .collect()
+}
+
+// CPU temperature over the chart window, in the color of the latest reading
+// with the 70°C and 80°C thresholds drawn across it
+fn render_temperature_chart(
+    f: &mut Frame,
+    area: Rect,
+    theme: &Theme,
+    app: &crate::app::App,
+    data: &[(f64, f64)],
+) {
+    let latest = data.last().map_or(0.0, |&(_, celsius)| celsius);
+    let block = Block::default()
+        .title(Span::styled(
+            format!(
+                " CPU Temperature {:.0}°C [{}] ",
+                latest,
+                app.chart_window.label()
+            ),
+            Style::default()
+                .fg(theme.text_bright)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.border));
+    let (bounds, since) = time_axis(data);
+    let top = data
+        .iter()
+        .map(|&(_, celsius)| celsius)
+        .fold(100.0, f64::max);
+    let limits = theme.thresholds.temperature;
+    let thresholds = [limits.warning, limits.critical]
+        .map(|celsius| [(bounds[0], celsius), (bounds[1], celsius)]);
+    let chart = Chart::new(vec![
+        Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(
+                Style::default()
+                    .fg(theme.warning)
+                    .add_modifier(Modifier::DIM),
+            )
+            .data(&thresholds[0]),
+        Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(
+                Style::default()
+                    .fg(theme.danger)
+                    .add_modifier(Modifier::DIM),
+            )
+            .data(&thresholds[1]),
+        Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(theme.get_temp_color(latest as f32)))
+            .data(data),
+    ])
+    .x_axis(
+        Axis::default()
+            .style(Style::default().fg(theme.text_dim))
+            .bounds(bounds)
+            .labels(vec![
+                Span::styled(since, Style::default().fg(theme.text_dim)),
+                Span::styled("now", Style::default().fg(theme.text_dim)),
+            ]),
+    )
+    .y_axis(
+        Axis::default()
+            .style(Style::default().fg(theme.text_dim))
+            .bounds([20.0, top])
+            .labels(vec![
+                Span::styled("20°C", Style::default().fg(theme.text_dim)),
+                Span::styled(format!("{:.0}°C", top), Style::default().fg(theme.text_dim)),
+            ]),
+    );
+    let chart_area = block.inner(area);
+    f.render_widget(block, area);
+    f.render_widget(chart, chart_area);
+}
+
+/// `data` as a line colored along the theme's `chart_gradient` by value,
+/// `peak` being the top of the scale. Each run of segments in one band is a
+/// dataset of its own, so a spike heats up as it rises. A theme whose
+/// gradient is all one color, as a 16-color one is, gets a single line.
+pub fn gradient_line<'a>(data: &'a [(f64, f64)], peak: f64, theme: &Theme) -> Vec<Dataset<'a>> {
+    let color = |from: usize, to: usize| {
+        let value = data[from].1.max(data[to].1);
+        theme.get_load_color((value / peak * 100.0).round() as u64)
+    };
+    let dataset = |points, color| {
+        Dataset::default()
+            .marker(symbols::Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::default().fg(color))
+            .data(points)
+    };
+    if data.len() == 1 {
+        return vec![dataset(data, color(0, 0))];
+    }
+    let mut datasets = Vec::new();
+    let mut start = 0;
+    for end in 1..data.len() {
+        let band = color(end - 1, end);
+        if end + 1 == data.len() || color(end, end + 1) != band {
+            datasets.push(dataset(&data[start..=end], band));
+            start = end;
+        }
+    }
+    datasets
+}
+
+// Y-axis top and labels for charts of rates in `unit` bytes per second,
+// scaled to a round figure above the highest point shown so that both
+// trickles and bursts fill the chart
+pub fn rate_axis(series: &[&[(f64, f64)]], unit: u64, units: UnitSystem) -> (f64, [String; 3]) {
+    let peak = crate::utils::nice_ceiling(
+        series
+            .iter()
+            .flat_map(|points| points.iter().map(|&(_, y)| y))
+            .fold(0.0, f64::max),
+    );
+    let label = |rate: f64| crate::utils::format_rate((rate * unit as f64) as u64, units);
+    (peak, ["0".to_string(), label(peak / 2.0), label(peak)])
+}
+
+// X-axis bounds and left-edge label for `chart_points`. The left edge is the
+// oldest point shown, which is short of the window until the history fills.
+pub fn time_axis(points: &[(f64, f64)]) -> ([f64; 2], String) {
+    let oldest = points.first().map_or(0.0, |&(x, _)| -x);
+    ([-oldest.max(1.0), 0.0], history_age(oldest.round() as u64))
+}
+
+fn history_age(secs: u64) -> String {
+    match (secs / 60, secs % 60) {
+        (0, secs) => format!("-{}s", secs),
+        (mins, 0) => format!("-{}m", mins),
+        (mins, secs) => format!("-{}m{:02}s", mins, secs),
+    }
+}
+
 fn format_duration(duration: std::time::Duration) -> String {
     let secs = duration.as_secs();
     if secs < 60 {
@@ -0,0 +1,366 @@
+use std::{
+    collections::BTreeMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    alerts::{AlertSpec, Alerts},
+    app::App,
+    cli::{ColorArg, SortArg, ViewArg},
+    columns::Column,
+    keys::{KeyBindings, KeySpec},
+    sys_info::ProcessSort,
+    theme::{Theme, Thresholds},
+    utils::UnitSystem,
+};
+
+/// Preferences read from `$XDG_CONFIG_HOME/xtop/config.toml`, and the
+/// session state saved next to it in `state.toml`. Every key is optional;
+/// state is applied over the config and command-line flags over both.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub interval_ms: Option<u64>,
+    // How far back the charts can reach, 600 by default
+    pub history_secs: Option<u64>,
+    pub view: Option<ViewArg>,
+    pub sort: Option<SortArg>,
+    pub sort_descending: Option<bool>,
+    pub show_full_command: Option<bool>,
+    pub tree: Option<bool>,
+    pub hide_kernel_threads: Option<bool>,
+    pub only_mine: Option<bool>,
+    pub theme: Option<String>,
+    // "binary" for KiB/MiB/GiB, "decimal" for 1000-based KB/MB/GB
+    pub units: Option<UnitSystem>,
+    // "truecolor", "256" or "16" when $COLORTERM and $TERM guess wrong
+    pub color: Option<ColorArg>,
+    // Set to false to stop xtop writing state.toml on exit
+    pub save_state: Option<bool>,
+    // Vim-style counts such as `5j`; digits then wait for a motion
+    pub count_prefix: Option<bool>,
+    // Set to false to give small terminals the tab bar's line back
+    pub show_tab_bar: Option<bool>,
+    // e.g. `temperature = { warning = 85, critical = 95 }`
+    pub thresholds: Option<Thresholds>,
+    // e.g. ["cpu > 90 for 30s", { rule = "disk /var > 90%", notify = true }]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub alerts: Vec<AlertSpec>,
+    // Process table columns never hidden on narrow terminals, e.g. ["user"]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pinned_columns: Vec<String>,
+    // Action name to key or keys, e.g. `kill = ["F9", "Ctrl+k"]`
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub keys: BTreeMap<String, KeySpec>,
+    // Anything else in the file, reported as a warning rather than an error
+    #[serde(flatten, skip_serializing)]
+    unknown: BTreeMap<String, toml::Value>,
+}
+
+fn config_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(base.join("xtop"))
+}
+
+pub fn config_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("config.toml"))
+}
+
+pub fn state_path() -> Option<PathBuf> {
+    Some(config_dir()?.join("state.toml"))
+}
+
+/// Finds a theme by name: a built-in one, or `themes/<name>.toml` in the
+/// config directory.
+pub fn load_theme(name: &str) -> Result<Theme, String> {
+    if let Some(theme) = Theme::named(name) {
+        return Ok(theme);
+    }
+    let path = config_dir()
+        .filter(|_| !name.contains(['/', '\\']))
+        .map(|dir| dir.join("themes").join(format!("{}.toml", name)))
+        .ok_or_else(|| format!("unknown theme '{}'", name))?;
+    match fs::read_to_string(&path) {
+        Ok(contents) => {
+            Theme::parse(&contents).map_err(|err| format!("{}: {}", path.display(), err))
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Err(format!(
+            "unknown theme '{}', and no {}",
+            name,
+            path.display()
+        )),
+        Err(err) => Err(format!("{}: {}", path.display(), err)),
+    }
+}
+
+/// Writes the session's adjustable settings to `state.toml`. The file is
+/// written beside the target and renamed over it, so an interrupted write
+/// leaves the previous state intact.
+pub fn save_state(app: &App) -> io::Result<()> {
+    let path = state_path().ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let contents = toml::to_string(&Config::from_app(app)).map_err(io::Error::other)?;
+    let temp = path.with_extension("toml.tmp");
+    fs::write(&temp, contents)?;
+    fs::rename(&temp, &path)
+}
+
+impl Config {
+    /// Reads a config or state file, returning the defaults when it does not
+    /// exist along with any warnings to print before the UI starts.
+    pub fn load(path: Option<&Path>) -> (Self, Vec<String>) {
+        let Some(path) = path else {
+            return (Self::default(), Vec::new());
+        };
+        match fs::read_to_string(path) {
+            Ok(contents) => match Self::parse(&contents) {
+                Ok(config) => {
+                    let warnings = config.warnings(&path.display().to_string());
+                    (config, warnings)
+                }
+                Err(err) => (
+                    Self::default(),
+                    vec![format!("{}: {}; using defaults", path.display(), err)],
+                ),
+            },
+            Err(err) if err.kind() == io::ErrorKind::NotFound => (Self::default(), Vec::new()),
+            Err(err) => (
+                Self::default(),
+                vec![format!("{}: {}; using defaults", path.display(), err)],
+            ),
+        }
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(contents)
+    }
+
+    fn warnings(&self, source: &str) -> Vec<String> {
+        let mut warnings: Vec<String> = self
+            .unknown
+            .keys()
+            .map(|key| format!("{}: unknown key '{}' ignored", source, key))
+            .collect();
+        if let Some(Err(err)) = self.theme.as_deref().map(load_theme) {
+            warnings.push(format!("{}: {}; using the default theme", source, err));
+        }
+        if let Some(thresholds) = &self.thresholds {
+            warnings.extend(
+                thresholds
+                    .warnings()
+                    .into_iter()
+                    .map(|warning| format!("{}: {}", source, warning)),
+            );
+        }
+        warnings.extend(
+            self.pinned_columns
+                .iter()
+                .filter(|name| Column::named(name).is_none())
+                .map(|name| format!("{}: unknown column '{}' not pinned", source, name)),
+        );
+        let (_, alert_warnings) = Alerts::new(&self.alerts);
+        warnings.extend(
+            alert_warnings
+                .into_iter()
+                .map(|warning| format!("{}: {}", source, warning)),
+        );
+        let (_, key_warnings) = KeyBindings::new(&self.keys);
+        warnings.extend(
+            key_warnings
+                .into_iter()
+                .map(|warning| format!("{}: {}", source, warning)),
+        );
+        warnings
+    }
+
+    /// The settings that can be changed while running, for `state.toml`.
+    pub fn from_app(app: &App) -> Self {
+        Self {
+            interval_ms: Some(app.update_interval.as_millis() as u64),
+            sort: Some(app.process_sort.into()),
+            sort_descending: Some(app.sort_descending()),
+            show_full_command: Some(app.show_full_command),
+            tree: Some(app.show_tree_view),
+            hide_kernel_threads: Some(app.hide_kernel_threads),
+            only_mine: Some(app.only_mine),
+            theme: Some(app.theme_name.clone()),
+            units: Some(app.units),
+            ..Self::default()
+        }
+    }
+
+    /// Applies the configured preferences over the app's built-in defaults.
+    pub fn apply(&self, app: &mut App) {
+        if let Some(interval) = self.interval_ms {
+            app.update_interval = Duration::from_millis(interval.clamp(250, 10_000));
+        }
+        if let Some(secs) = self.history_secs {
+            app.history_length = Duration::from_secs(secs.clamp(60, 86_400));
+        }
+        if let Some(view) = self.view {
+            app.current_view = view.into();
+        }
+        if let Some(sort) = self.sort {
+            let sort = ProcessSort::from(sort);
+            if app.process_sort != sort {
+                app.change_sort_column(sort);
+            }
+        }
+        if let Some(descending) = self.sort_descending
+            && app.sort_descending() != descending
+        {
+            app.toggle_sort_direction();
+        }
+        if let Some(show) = self.show_full_command {
+            app.show_full_command = show;
+        }
+        if let Some(tree) = self.tree {
+            app.show_tree_view = tree;
+        }
+        if let Some(hide) = self.hide_kernel_threads {
+            app.hide_kernel_threads = hide;
+        }
+        if let Some(only_mine) = self.only_mine {
+            app.only_mine = only_mine;
+        }
+        if let Some(name) = &self.theme
+            && let Ok(theme) = load_theme(name)
+        {
+            app.set_theme(name, theme);
+        }
+        if let Some(units) = self.units {
+            app.units = units;
+        }
+        if let Some(thresholds) = self.thresholds {
+            app.theme.thresholds = thresholds;
+        }
+        if let Some(counts) = self.count_prefix {
+            app.count_prefix = counts;
+        }
+        if let Some(show) = self.show_tab_bar {
+            app.show_tab_bar = show;
+        }
+        if !self.pinned_columns.is_empty() {
+            app.pinned_columns = self
+                .pinned_columns
+                .iter()
+                .filter_map(|name| Column::named(name))
+                .collect();
+        }
+        if !self.keys.is_empty() {
+            app.keys = KeyBindings::new(&self.keys).0;
+        }
+        if !self.alerts.is_empty() {
+            app.alerts = Alerts::new(&self.alerts).0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"
+interval_ms = 2000
+history_secs = 900
+view = "process"
+sort = "mem"
+sort_descending = false
+show_full_command = true
+tree = true
+theme = "default"
+units = "decimal"
+color = "256"
+show_tab_bar = false
+pinned_columns = ["user", "state"]
+alerts = ["memory > 95%", { rule = "disk /var > 90% for 1m", notify = true, exec = "logrotate -f /etc/logrotate.conf" }]
+"#;
+
+    #[test]
+    fn round_trips_a_sample_file() {
+        let config = Config::parse(SAMPLE).unwrap();
+        assert_eq!(config.interval_ms, Some(2000));
+        assert_eq!(config.history_secs, Some(900));
+        assert!(matches!(config.view, Some(ViewArg::Process)));
+        assert!(matches!(config.sort, Some(SortArg::Mem)));
+        assert_eq!(config.sort_descending, Some(false));
+        assert_eq!(config.show_tab_bar, Some(false));
+        assert_eq!(config.color, Some(ColorArg::Indexed256));
+        assert_eq!(config.units, Some(UnitSystem::Decimal));
+        assert_eq!(config.pinned_columns, vec!["user", "state"]);
+        assert_eq!(
+            config.alerts[1],
+            AlertSpec::Table {
+                rule: "disk /var > 90% for 1m".to_string(),
+                notify: true,
+                exec: Some("logrotate -f /etc/logrotate.conf".to_string()),
+                cooldown_secs: None,
+            }
+        );
+        assert!(config.warnings("config.toml").is_empty());
+        let written = toml::to_string(&config).unwrap();
+        assert_eq!(Config::parse(&written).unwrap(), config);
+    }
+
+    #[test]
+    fn warns_about_unknown_keys() {
+        let config = Config::parse("interval_ms = 500\ncolour = \"red\"\n").unwrap();
+        assert_eq!(config.interval_ms, Some(500));
+        assert_eq!(
+            config.warnings("config.toml"),
+            vec!["config.toml: unknown key 'colour' ignored".to_string()]
+        );
+        assert_eq!(Config::parse("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn reads_key_overrides() {
+        let config = Config::parse("[keys]\nkill = \"k\"\nquit = [\"q\", \"Ctrl+c\"]\n").unwrap();
+        assert_eq!(config.keys["kill"], KeySpec::One("k".to_string()));
+        assert_eq!(
+            config.warnings("config.toml"),
+            vec!["config.toml: key 'k' is bound to both kill and up; keeping kill".to_string()]
+        );
+    }
+
+    #[test]
+    fn reads_thresholds_over_the_defaults() {
+        let config = Config::parse(
+            "[thresholds]\ntemperature = { warning = 85, critical = 95 }\nusage = { warning = 90, critical = 80 }\n",
+        )
+        .unwrap();
+        let thresholds = config.thresholds.unwrap();
+        assert_eq!(thresholds.temperature.critical, 95.0);
+        assert_eq!(thresholds.memory, Thresholds::default().memory);
+        assert_eq!(
+            config.warnings("config.toml"),
+            vec!["config.toml: thresholds.usage: warning is above critical".to_string()]
+        );
+        let mut app = App::default();
+        config.apply(&mut app);
+        app.cycle_theme(true);
+        assert_eq!(app.theme.get_temp_color(80.0), app.theme.success);
+    }
+
+    #[test]
+    fn state_omits_unset_keys() {
+        let state = Config {
+            interval_ms: Some(500),
+            tree: Some(true),
+            ..Config::default()
+        };
+        assert_eq!(
+            toml::to_string(&state).unwrap(),
+            "interval_ms = 500\ntree = true\n"
+        );
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::{HashSet, VecDeque};
+
+use chrono::{DateTime, Local};
+
+use crate::{sys_info::ProcessInfo, theme::Severity};
+
+// Older events drop off the end past this many
+const CAPACITY: usize = 500;
+// How many of the busiest processes are watched for newcomers
+const TOP_PROCESSES: usize = 5;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Event {
+    pub time: DateTime<Local>,
+    pub severity: Severity,
+    pub text: String,
+}
+
+/// The notable things that happened this session, for answering "what
+/// happened while I wasn't looking".
+#[derive(Debug, Default)]
+pub struct EventLog {
+    events: VecDeque<Event>,
+    // Recorded since the panel was last open
+    unseen: usize,
+    // The previous refresh's top processes by CPU, None before the first
+    top_pids: Option<HashSet<u32>>,
+}
+
+impl EventLog {
+    pub fn push(&mut self, severity: Severity, text: String, time: DateTime<Local>) {
+        if self.events.len() == CAPACITY {
+            self.events.pop_front();
+        }
+        self.events.push_back(Event {
+            time,
+            severity,
+            text,
+        });
+        self.unseen = (self.unseen + 1).min(CAPACITY);
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Lines the events take up in the panel, one per line of their text.
+    pub fn line_count(&self) -> usize {
+        self.events
+            .iter()
+            .map(|event| event.text.lines().count().max(1))
+            .sum()
+    }
+
+    /// The events, newest first.
+    pub fn newest_first(&self) -> impl Iterator<Item = &Event> {
+        self.events.iter().rev()
+    }
+
+    pub fn unseen(&self) -> usize {
+        self.unseen
+    }
+
+    pub fn mark_seen(&mut self) {
+        self.unseen = 0;
+    }
+
+    /// Records the processes that have just joined the top few by CPU. The
+    /// first refresh only sets the baseline.
+    pub fn watch_top_processes(&mut self, processes: &[ProcessInfo], time: DateTime<Local>) {
+        let mut top: Vec<&ProcessInfo> = processes
+            .iter()
+            .filter(|process| process.cpu_usage > 0.0)
+            .collect();
+        top.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
+        top.truncate(TOP_PROCESSES);
+        let pids: HashSet<u32> = top.iter().map(|process| process.pid).collect();
+        if let Some(previous) = self.top_pids.replace(pids) {
+            for process in top
+                .iter()
+                .filter(|process| !previous.contains(&process.pid))
+            {
+                self.push(
+                    Severity::Normal,
+                    format!(
+                        "{} ({}) entered the top {} by CPU at {:.1}%",
+                        process.name, process.pid, TOP_PROCESSES, process.cpu_usage
+                    ),
+                    time,
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn busy(pid: u32, cpu_usage: f64) -> ProcessInfo {
+        ProcessInfo {
+            cpu_usage,
+            ..ProcessInfo::fixture(pid, 1, &format!("proc{}", pid))
+        }
+    }
+
+    #[test]
+    fn keeps_the_newest_events() {
+        let mut log = EventLog::default();
+        let time = Local::now();
+        for index in 0..CAPACITY + 3 {
+            log.push(Severity::Normal, index.to_string(), time);
+        }
+        assert_eq!(log.len(), CAPACITY);
+        assert_eq!(log.unseen(), CAPACITY);
+        let texts: Vec<&str> = log
+            .newest_first()
+            .map(|event| event.text.as_str())
+            .collect();
+        assert_eq!(texts[0], (CAPACITY + 2).to_string());
+        assert_eq!(texts[CAPACITY - 1], "3");
+        log.mark_seen();
+        assert_eq!(log.unseen(), 0);
+    }
+
+    #[test]
+    fn records_newcomers_to_the_top_processes() {
+        let mut log = EventLog::default();
+        let time = Local::now();
+        let mut processes: Vec<ProcessInfo> = (1..=6).map(|pid| busy(pid, pid as f64)).collect();
+        log.watch_top_processes(&processes, time);
+        assert_eq!(log.len(), 0);
+        // Process 1 overtakes process 2, the least busy of the top five
+        processes[0].cpu_usage = 50.0;
+        log.watch_top_processes(&processes, time);
+        let texts: Vec<&str> = log
+            .newest_first()
+            .map(|event| event.text.as_str())
+            .collect();
+        assert_eq!(texts, ["proc1 (1) entered the top 5 by CPU at 50.0%"]);
+        log.watch_top_processes(&processes, time);
+        assert_eq!(log.len(), 1);
+    }
+}
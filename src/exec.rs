@@ -0,0 +1,174 @@
+use std::{
+    collections::HashMap,
+    process::{Command, Output, Stdio},
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+// Only the end of a chatty command's output is kept
+const MAX_OUTPUT_LINES: usize = 10;
+
+/// How a command started by an alert ended.
+#[derive(Debug)]
+pub struct Finished {
+    pub command: String,
+    // The exit status, or why the command could not be run at all
+    pub status: Result<String, String>,
+    pub success: bool,
+    // The last lines of stdout and stderr together
+    pub output: Vec<String>,
+}
+
+/// Why a command was not started.
+#[derive(Debug, PartialEq)]
+pub enum Skipped {
+    // --allow-exec was not given
+    NotAllowed,
+    // It ran this long ago, within the rule's cooldown
+    CoolingDown(Duration),
+}
+
+/// Runs the `exec` commands of alert rules through the shell, each on a
+/// thread of its own, and hands back how they ended.
+pub struct CommandRunner {
+    // Set by --allow-exec; a config file alone can't run commands
+    pub allowed: bool,
+    // When each rule, by index, last started its command
+    last_run: HashMap<usize, Instant>,
+    sender: Sender<Finished>,
+    receiver: Receiver<Finished>,
+}
+
+impl Default for CommandRunner {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            allowed: false,
+            last_run: HashMap::new(),
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl CommandRunner {
+    pub fn run(
+        &mut self,
+        rule: usize,
+        command: &str,
+        cooldown: Duration,
+        now: Instant,
+    ) -> Result<(), Skipped> {
+        if !self.allowed {
+            return Err(Skipped::NotAllowed);
+        }
+        if let Some(&last) = self.last_run.get(&rule) {
+            let since = now.saturating_duration_since(last);
+            if since < cooldown {
+                return Err(Skipped::CoolingDown(since));
+            }
+        }
+        self.last_run.insert(rule, now);
+        let command = command.to_string();
+        let sender = self.sender.clone();
+        thread::spawn(move || {
+            let finished = match shell(&command).output() {
+                Ok(output) => finish(command, output),
+                Err(err) => Finished {
+                    command,
+                    status: Err(err.to_string()),
+                    success: false,
+                    output: Vec::new(),
+                },
+            };
+            // The app may have quit meanwhile
+            let _ = sender.send(finished);
+        });
+        Ok(())
+    }
+
+    /// The commands that have ended since the last call.
+    pub fn finished(&self) -> Vec<Finished> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.args(["-c", command]).stdin(Stdio::null());
+    shell
+}
+
+#[cfg(windows)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.args(["/C", command]).stdin(Stdio::null());
+    shell
+}
+
+fn finish(command: String, output: Output) -> Finished {
+    let text = [output.stdout, output.stderr]
+        .iter()
+        .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let lines: Vec<String> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::to_string)
+        .collect();
+    let skip = lines.len().saturating_sub(MAX_OUTPUT_LINES);
+    Finished {
+        command,
+        status: Ok(output.status.to_string()),
+        success: output.status.success(),
+        output: lines.into_iter().skip(skip).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wait(runner: &CommandRunner) -> Finished {
+        for _ in 0..100 {
+            if let Some(finished) = runner.finished().pop() {
+                return finished;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        panic!("command never finished");
+    }
+
+    #[test]
+    fn runs_only_when_allowed_and_cooled_down() {
+        let mut runner = CommandRunner::default();
+        let now = Instant::now();
+        let cooldown = Duration::from_secs(60);
+        assert_eq!(
+            runner.run(0, "true", cooldown, now),
+            Err(Skipped::NotAllowed)
+        );
+        runner.allowed = true;
+        assert_eq!(
+            runner.run(0, "echo hello; echo oops >&2", cooldown, now),
+            Ok(())
+        );
+        let finished = wait(&runner);
+        assert!(finished.success);
+        assert_eq!(finished.output, ["hello", "oops"]);
+        let later = now + Duration::from_secs(30);
+        assert_eq!(
+            runner.run(0, "true", cooldown, later),
+            Err(Skipped::CoolingDown(Duration::from_secs(30)))
+        );
+        // Each rule has its own cooldown
+        assert_eq!(runner.run(1, "exit 3", cooldown, later), Ok(()));
+        let finished = wait(&runner);
+        assert!(!finished.success);
+        assert_eq!(finished.status.as_deref(), Ok("exit status: 3"));
+        assert_eq!(runner.run(0, "true", cooldown, now + cooldown), Ok(()));
+    }
+}
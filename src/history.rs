@@ -0,0 +1,89 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+// Ten minutes at the default refresh interval
+pub const DEFAULT_CAPACITY: usize = 600;
+
+/// A rolling series of timestamped samples, oldest first. Once full, each
+/// new sample drops the oldest one.
+#[derive(Debug, Clone)]
+pub struct History<T> {
+    samples: VecDeque<(Instant, T)>,
+    capacity: usize,
+}
+
+impl<T: Copy> Default for History<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl<T: Copy> History<T> {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    pub fn push(&mut self, at: Instant, value: T) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((at, value));
+    }
+
+    /// Changes how many samples are kept, forgetting the oldest ones if it
+    /// shrinks.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        let excess = self.samples.len().saturating_sub(self.capacity);
+        self.samples.drain(..excess);
+    }
+
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = T> + ExactSizeIterator + '_ {
+        self.samples.iter().map(|&(_, value)| value)
+    }
+
+    /// The samples taken within `span` of the newest one, oldest first, each
+    /// with how long before the newest it was taken. Ages come from the
+    /// timestamps, so they stay true across changes of the update interval.
+    pub fn recent(&self, span: Duration) -> impl Iterator<Item = (Duration, T)> + '_ {
+        let newest = self.samples.back().map(|&(at, _)| at);
+        self.samples.iter().filter_map(move |&(at, value)| {
+            let age = newest?.duration_since(at);
+            (age <= span).then_some((age, value))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_newest_samples_and_their_ages() {
+        let start = Instant::now();
+        let mut history = History::new(5);
+        for second in 0..8 {
+            history.push(start + Duration::from_secs(second), second);
+        }
+        assert_eq!(history.values().collect::<Vec<_>>(), vec![3, 4, 5, 6, 7]);
+        assert_eq!(
+            history.recent(Duration::from_secs(2)).collect::<Vec<_>>(),
+            vec![
+                (Duration::from_secs(2), 5),
+                (Duration::from_secs(1), 6),
+                (Duration::ZERO, 7),
+            ]
+        );
+        history.set_capacity(2);
+        assert_eq!(history.values().collect::<Vec<_>>(), vec![6, 7]);
+        // A slower interval spaces the next sample further out
+        history.push(start + Duration::from_secs(12), 8);
+        let ages: Vec<Duration> = history.recent(Duration::MAX).map(|(age, _)| age).collect();
+        assert_eq!(ages, vec![Duration::from_secs(5), Duration::ZERO]);
+    }
+}
@@ -0,0 +1,644 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt,
+    str::FromStr,
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::{app::View, sys_info::ProcessSort};
+
+/// Everything a key can do outside popups and search input. Those modal
+/// keys stay fixed so a remapped letter can still be typed into a filter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Quit,
+    // Stops xtop as a shell job, like Ctrl-Z in any other program
+    Suspend,
+    // Clears tags, or quits when there are none
+    Back,
+    View(View),
+    NextView,
+    Down,
+    Up,
+    PageDown,
+    PageUp,
+    HalfPageDown,
+    HalfPageUp,
+    Top,
+    Bottom,
+    SlowerUpdates,
+    FasterUpdates,
+    Pause,
+    ResetSelection,
+    Activate,
+    FullCommand,
+    ContainerFilter,
+    HideKernelThreads,
+    OnlyMine,
+    Environment,
+    OpenFiles,
+    Events,
+    Export,
+    FailedFirst,
+    PseudoFilesystems,
+    CpuDisplay,
+    CoreGraphs,
+    ZoomIn,
+    ZoomOut,
+    CycleTheme,
+    // Sorting by name doubles as "next match" while matches are highlighted
+    Sort(ProcessSort),
+    PreviousMatch,
+    PreviousSortColumn,
+    NextSortColumn,
+    ReverseSort,
+    SignalPicker,
+    Search,
+    Help,
+    TreeView,
+    Aggregate,
+    NiceDown,
+    NiceUp,
+    Kill,
+    Tag,
+    ClearTags,
+}
+
+// Config name, action and default keys, in the order the help lists them
+const BINDINGS: [(&str, Action, &[&str]); 66] = [
+    ("quit", Action::Quit, &["q"]),
+    ("suspend", Action::Suspend, &["Ctrl+z"]),
+    ("back", Action::Back, &["Esc"]),
+    ("view_overview", Action::View(View::Overview), &["0"]),
+    ("view_system", Action::View(View::System), &["1"]),
+    ("view_process", Action::View(View::Process), &["2"]),
+    ("view_resources", Action::View(View::Resources), &["3"]),
+    ("view_network", Action::View(View::Network), &["4"]),
+    ("view_disks", Action::View(View::Disks), &["5"]),
+    ("view_options", Action::View(View::Options), &["6"]),
+    ("view_gpu", Action::View(View::Gpu), &["7"]),
+    ("view_sensors", Action::View(View::Sensors), &["8"]),
+    ("view_services", Action::View(View::Services), &["9"]),
+    ("next_view", Action::NextView, &["Tab"]),
+    ("down", Action::Down, &["Down", "j"]),
+    ("up", Action::Up, &["Up", "k"]),
+    ("page_down", Action::PageDown, &["PageDown", "J"]),
+    ("page_up", Action::PageUp, &["PageUp", "K"]),
+    ("half_page_down", Action::HalfPageDown, &["Ctrl+d"]),
+    ("half_page_up", Action::HalfPageUp, &["Ctrl+u"]),
+    // `gg` also jumps to the top while `g` itself is unbound
+    ("top", Action::Top, &["Home"]),
+    ("bottom", Action::Bottom, &["End", "G"]),
+    ("slower_updates", Action::SlowerUpdates, &["+"]),
+    ("faster_updates", Action::FasterUpdates, &["-"]),
+    ("pause", Action::Pause, &["Space"]),
+    ("reset_selection", Action::ResetSelection, &["r"]),
+    ("activate", Action::Activate, &["Enter"]),
+    ("full_command", Action::FullCommand, &["f"]),
+    ("container_filter", Action::ContainerFilter, &["C"]),
+    // K pages up, so H as in "hide"
+    ("hide_kernel_threads", Action::HideKernelThreads, &["H"]),
+    ("only_mine", Action::OnlyMine, &["o"]),
+    ("environment", Action::Environment, &["e"]),
+    // As in lsof
+    ("open_files", Action::OpenFiles, &["l"]),
+    ("events", Action::Events, &["L"]),
+    ("export", Action::Export, &["E"]),
+    ("failed_first", Action::FailedFirst, &["F"]),
+    ("pseudo_filesystems", Action::PseudoFilesystems, &["P"]),
+    ("cpu_display", Action::CpuDisplay, &["v"]),
+    ("core_graphs", Action::CoreGraphs, &["a"]),
+    ("zoom_in", Action::ZoomIn, &["]"]),
+    ("zoom_out", Action::ZoomOut, &["["]),
+    ("theme", Action::CycleTheme, &["Ctrl+t"]),
+    ("sort_cpu", Action::Sort(ProcessSort::Cpu), &["c"]),
+    ("sort_memory", Action::Sort(ProcessSort::Memory), &["m"]),
+    ("sort_pid", Action::Sort(ProcessSort::Pid), &["p"]),
+    ("sort_name", Action::Sort(ProcessSort::Name), &["n"]),
+    ("sort_fds", Action::Sort(ProcessSort::Fds), &["d"]),
+    ("sort_swap", Action::Sort(ProcessSort::Swap), &["w"]),
+    ("sort_user", Action::Sort(ProcessSort::User), &["u"]),
+    ("sort_threads", Action::Sort(ProcessSort::Threads), &["t"]),
+    ("sort_time", Action::Sort(ProcessSort::Time), &["T"]),
+    ("sort_state", Action::Sort(ProcessSort::State), &["S"]),
+    ("previous_match", Action::PreviousMatch, &["N"]),
+    (
+        "previous_sort_column",
+        Action::PreviousSortColumn,
+        &["Left"],
+    ),
+    ("next_sort_column", Action::NextSortColumn, &["Right"]),
+    (
+        "reverse_sort",
+        Action::ReverseSort,
+        &["Shift+Left", "Shift+Right"],
+    ),
+    ("signal", Action::SignalPicker, &["s"]),
+    ("search", Action::Search, &["/"]),
+    ("help", Action::Help, &["F1"]),
+    ("tree", Action::TreeView, &["F5"]),
+    ("aggregate", Action::Aggregate, &["F6"]),
+    ("nice_down", Action::NiceDown, &["F7"]),
+    ("nice_up", Action::NiceUp, &["F8"]),
+    ("kill", Action::Kill, &["F9", "x"]),
+    ("tag", Action::Tag, &["Insert"]),
+    ("clear_tags", Action::ClearTags, &["V"]),
+];
+
+impl Action {
+    /// The action's key in the `[keys]` config table.
+    pub fn name(self) -> &'static str {
+        BINDINGS
+            .iter()
+            .find(|(_, action, _)| *action == self)
+            .map_or("", |(name, _, _)| name)
+    }
+
+    // Actions a count prefix repeats, as in `5j`
+    fn takes_count(self) -> bool {
+        matches!(
+            self,
+            Action::Down
+                | Action::Up
+                | Action::PageDown
+                | Action::PageUp
+                | Action::HalfPageDown
+                | Action::HalfPageUp
+        )
+    }
+
+    /// Actions on processes that can only reach those of this machine, and
+    /// so are refused while watching a remote host or simulated processes.
+    pub fn is_local_only(self) -> bool {
+        matches!(
+            self,
+            Action::Kill
+                | Action::SignalPicker
+                | Action::NiceUp
+                | Action::NiceDown
+                | Action::Environment
+                | Action::OpenFiles
+        )
+    }
+
+    fn named(name: &str) -> Option<Self> {
+        BINDINGS
+            .iter()
+            .find(|(candidate, _, _)| *candidate == name)
+            .map(|(_, action, _)| *action)
+    }
+}
+
+/// A key with the modifiers that must be held. Shift is folded into the
+/// character for printable keys, since terminals report `J` as Shift+J.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Key {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl Key {
+    fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers =
+            modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            code => code,
+        };
+        Self { code, modifiers }
+    }
+}
+
+impl From<&KeyEvent> for Key {
+    fn from(event: &KeyEvent) -> Self {
+        Self::new(event.code, event.modifiers)
+    }
+}
+
+impl FromStr for Key {
+    type Err = String;
+
+    /// Parses names like `q`, `F9`, `Space`, `Shift+Left` or `Ctrl+d`.
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let (prefix, name) = if let Some(prefix) = spec.strip_suffix("++") {
+            (prefix, "+")
+        } else if spec == "+" {
+            ("", "+")
+        } else {
+            spec.rsplit_once('+').unwrap_or(("", spec))
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in prefix.split('+').filter(|part| !part.is_empty()) {
+            modifiers |= match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier '{}' in '{}'", modifier, spec)),
+            };
+        }
+        let mut chars = name.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match name.to_ascii_lowercase().as_str() {
+                "esc" | "escape" => KeyCode::Esc,
+                "enter" | "return" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pageup" | "pgup" => KeyCode::PageUp,
+                "pagedown" | "pgdn" => KeyCode::PageDown,
+                "insert" => KeyCode::Insert,
+                "delete" | "del" => KeyCode::Delete,
+                lower => match lower.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                    Some(n @ 1..=12) => KeyCode::F(n),
+                    _ => return Err(format!("unknown key '{}'", spec)),
+                },
+            },
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (modifier, label) in [
+            (KeyModifiers::CONTROL, "Ctrl+"),
+            (KeyModifiers::ALT, "Alt+"),
+            (KeyModifiers::SHIFT, "Shift+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(label)?;
+            }
+        }
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::Up => f.write_str("↑"),
+            KeyCode::Down => f.write_str("↓"),
+            KeyCode::Left => f.write_str("←"),
+            KeyCode::Right => f.write_str("→"),
+            KeyCode::PageUp => f.write_str("PgUp"),
+            KeyCode::PageDown => f.write_str("PgDn"),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// One key or a list of keys for an action in the `[keys]` config table. An
+/// empty list unbinds the action.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeySpec {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeySpec {
+    fn keys(&self) -> &[String] {
+        match self {
+            KeySpec::One(key) => std::slice::from_ref(key),
+            KeySpec::Many(keys) => keys,
+        }
+    }
+}
+
+/// The key table the event loop dispatches through. Actions named in the
+/// config replace their defaults; every other action keeps its default keys.
+#[derive(Debug, Clone)]
+pub struct KeyBindings {
+    actions: HashMap<Key, Action>,
+    // Bound keys per entry of `BINDINGS`, for the help and footer
+    keys: Vec<Vec<Key>>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self::new(&BTreeMap::new()).0
+    }
+}
+
+impl KeyBindings {
+    /// Builds the table from the `[keys]` overrides, along with warnings for
+    /// unknown actions, unparsable keys and keys claimed by two actions. A
+    /// configured key wins over another action's default.
+    pub fn new(overrides: &BTreeMap<String, KeySpec>) -> (Self, Vec<String>) {
+        let mut warnings: Vec<String> = overrides
+            .keys()
+            .filter(|name| Action::named(name).is_none())
+            .map(|name| format!("unknown action '{}' in [keys] ignored", name))
+            .collect();
+        let mut bindings = Self {
+            actions: HashMap::new(),
+            keys: vec![Vec::new(); BINDINGS.len()],
+        };
+        // Configured actions claim their keys before the defaults fill in
+        for configured in [true, false] {
+            for (index, (name, action, defaults)) in BINDINGS.iter().enumerate() {
+                let specs: Vec<&str> = match overrides.get(*name) {
+                    Some(spec) if configured => spec.keys().iter().map(String::as_str).collect(),
+                    None if !configured => defaults.to_vec(),
+                    _ => continue,
+                };
+                for spec in specs {
+                    let key = match spec.parse::<Key>() {
+                        Ok(key) => key,
+                        Err(err) => {
+                            warnings.push(format!("{} in [keys] {}", err, name));
+                            continue;
+                        }
+                    };
+                    if let Some(&owner) = bindings.actions.get(&key) {
+                        if owner != *action {
+                            warnings.push(format!(
+                                "key '{}' is bound to both {} and {}; keeping {}",
+                                key,
+                                owner.name(),
+                                name,
+                                owner.name()
+                            ));
+                        }
+                        continue;
+                    }
+                    bindings.actions.insert(key, *action);
+                    bindings.keys[index].push(key);
+                }
+            }
+        }
+        (bindings, warnings)
+    }
+
+    pub fn action(&self, event: &KeyEvent) -> Option<Action> {
+        self.actions.get(&Key::from(event)).copied()
+    }
+
+    /// Every key bound to the action, e.g. `F9/x`, or `-` when unbound.
+    pub fn label(&self, action: Action) -> String {
+        let keys = self.keys_for(action);
+        if keys.is_empty() {
+            return "-".to_string();
+        }
+        keys.iter()
+            .map(Key::to_string)
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    /// The first key of each action, e.g. `c/m/p` for a row of sort keys.
+    pub fn labels(&self, actions: &[Action]) -> String {
+        actions
+            .iter()
+            .map(|&action| {
+                self.keys_for(action)
+                    .first()
+                    .map_or_else(|| "-".to_string(), Key::to_string)
+            })
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    fn keys_for(&self, action: Action) -> &[Key] {
+        BINDINGS
+            .iter()
+            .position(|(_, candidate, _)| *candidate == action)
+            .map_or(&[], |index| &self.keys[index])
+    }
+}
+
+// How long a `g` or count prefix waits for the rest of the command
+const PENDING_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Keys held back while a multi-key command is typed: `gg`, and with counts
+/// enabled a numeric prefix such as `5j`. Anything that does not continue
+/// the command ends it, and a prefix left alone expires after a second.
+#[derive(Debug, Default)]
+pub struct PendingKeys {
+    go: bool,
+    // The digits typed so far; run as their own bindings if no motion follows
+    digits: Vec<(Key, u32)>,
+    since: Option<Instant>,
+}
+
+impl PendingKeys {
+    /// Takes one key press and returns the actions it completes, each with
+    /// the number of times to run it.
+    pub fn feed(
+        &mut self,
+        event: &KeyEvent,
+        bindings: &KeyBindings,
+        counts: bool,
+        now: Instant,
+    ) -> Vec<(Action, usize)> {
+        let mut ready = self.expire(bindings, now);
+        let key = Key::from(event);
+        let action = bindings.action(event);
+        let plain_char = match key.code {
+            KeyCode::Char(c) if key.modifiers.is_empty() => Some(c),
+            _ => None,
+        };
+        if std::mem::take(&mut self.go) {
+            self.since = None;
+            if plain_char == Some('g') {
+                ready.push((Action::Top, 1));
+                return ready;
+            }
+        }
+        if plain_char == Some('g') && action.is_none() {
+            ready.extend(self.flush(bindings));
+            self.go = true;
+            self.since = Some(now);
+            return ready;
+        }
+        if counts
+            && let Some(digit) = plain_char.and_then(|c| c.to_digit(10))
+            && (digit != 0 || !self.digits.is_empty())
+        {
+            self.digits.push((key, digit));
+            self.since = Some(now);
+            return ready;
+        }
+        match action {
+            Some(action) if action.takes_count() && !self.digits.is_empty() => {
+                let count = self.digits.drain(..).fold(0usize, |count, (_, digit)| {
+                    count.saturating_mul(10).saturating_add(digit as usize)
+                });
+                self.since = None;
+                ready.push((action, count));
+            }
+            action => {
+                ready.extend(self.flush(bindings));
+                ready.extend(action.map(|action| (action, 1)));
+            }
+        }
+        ready
+    }
+
+    /// Gives up on a prefix nobody finished, running held digits as the
+    /// keys they are bound to. Called on every tick of the event loop.
+    pub fn expire(&mut self, bindings: &KeyBindings, now: Instant) -> Vec<(Action, usize)> {
+        match self.since {
+            Some(since) if now.duration_since(since) >= PENDING_TIMEOUT => {
+                self.go = false;
+                self.flush(bindings)
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn flush(&mut self, bindings: &KeyBindings) -> Vec<(Action, usize)> {
+        self.since = None;
+        self.digits
+            .drain(..)
+            .filter_map(|(key, _)| bindings.actions.get(&key).map(|&action| (action, 1)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn parses_key_names() {
+        assert_eq!(
+            "q".parse(),
+            Ok(Key::new(KeyCode::Char('q'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            "F9".parse(),
+            Ok(Key::new(KeyCode::F(9), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            "ctrl+d".parse(),
+            Ok(Key::new(KeyCode::Char('d'), KeyModifiers::CONTROL))
+        );
+        assert_eq!("Shift+j".parse::<Key>(), "J".parse());
+        assert_eq!(
+            "+".parse(),
+            Ok(Key::new(KeyCode::Char('+'), KeyModifiers::NONE))
+        );
+        assert_eq!(
+            "Alt++".parse(),
+            Ok(Key::new(KeyCode::Char('+'), KeyModifiers::ALT))
+        );
+        assert!("Hyper+x".parse::<Key>().is_err());
+        assert!("F13".parse::<Key>().is_err());
+        assert_eq!("Shift+Left".parse::<Key>().unwrap().to_string(), "Shift+←");
+    }
+
+    #[test]
+    fn defaults_match_events_without_conflicts() {
+        let (keys, warnings) = KeyBindings::new(&BTreeMap::new());
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert_eq!(
+            keys.action(&key(KeyCode::Char('J'), KeyModifiers::SHIFT)),
+            Some(Action::PageDown)
+        );
+        assert_eq!(
+            keys.action(&key(KeyCode::Left, KeyModifiers::SHIFT)),
+            Some(Action::ReverseSort)
+        );
+        assert_eq!(
+            keys.action(&key(KeyCode::Left, KeyModifiers::NONE)),
+            Some(Action::PreviousSortColumn)
+        );
+        assert_eq!(keys.label(Action::Kill), "F9/x");
+    }
+
+    #[test]
+    fn pending_keys_build_gg_and_counts() {
+        let bindings = KeyBindings::default();
+        let now = Instant::now();
+        let mut pending = PendingKeys::default();
+        let mut feed = |c: char, counts: bool, at: Instant| {
+            pending.feed(
+                &key(KeyCode::Char(c), KeyModifiers::NONE),
+                &bindings,
+                counts,
+                at,
+            )
+        };
+        assert_eq!(feed('g', false, now), vec![]);
+        assert_eq!(feed('g', false, now), vec![(Action::Top, 1)]);
+        // A stray g does not swallow the next key
+        assert_eq!(feed('g', false, now), vec![]);
+        assert_eq!(feed('j', false, now), vec![(Action::Down, 1)]);
+        assert_eq!(feed('G', false, now), vec![(Action::Bottom, 1)]);
+
+        // Without counts, digits still switch views
+        assert_eq!(feed('5', false, now), vec![(Action::View(View::Disks), 1)]);
+        assert_eq!(feed('1', true, now), vec![]);
+        assert_eq!(feed('2', true, now), vec![]);
+        assert_eq!(feed('j', true, now), vec![(Action::Down, 12)]);
+        // Digits not followed by a motion fall back to their own bindings
+        assert_eq!(feed('3', true, now), vec![]);
+        assert_eq!(
+            feed('f', true, now),
+            vec![(Action::View(View::Resources), 1), (Action::FullCommand, 1)]
+        );
+        assert_eq!(feed('4', true, now), vec![]);
+        assert_eq!(
+            pending.expire(&bindings, now + PENDING_TIMEOUT),
+            vec![(Action::View(View::Network), 1)]
+        );
+        assert_eq!(
+            bindings.action(&key(KeyCode::Char('d'), KeyModifiers::CONTROL)),
+            Some(Action::HalfPageDown)
+        );
+    }
+
+    #[test]
+    fn overrides_replace_defaults_and_report_conflicts() {
+        let overrides = BTreeMap::from([
+            ("kill".to_string(), KeySpec::One("c".to_string())),
+            (
+                "quit".to_string(),
+                KeySpec::Many(vec!["Ctrl+q".to_string()]),
+            ),
+            ("sort_pid".to_string(), KeySpec::One("Ctrl+q".to_string())),
+            ("launch".to_string(), KeySpec::One("l".to_string())),
+        ]);
+        let (keys, warnings) = KeyBindings::new(&overrides);
+        assert_eq!(
+            keys.action(&key(KeyCode::Char('c'), KeyModifiers::NONE)),
+            Some(Action::Kill)
+        );
+        assert_eq!(
+            keys.action(&key(KeyCode::Char('x'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(
+            keys.action(&key(KeyCode::Char('q'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(
+            keys.action(&key(KeyCode::Char('q'), KeyModifiers::CONTROL)),
+            Some(Action::Quit)
+        );
+        assert_eq!(keys.label(Action::Sort(ProcessSort::Cpu)), "-");
+        assert_eq!(
+            warnings,
+            vec![
+                "unknown action 'launch' in [keys] ignored".to_string(),
+                "key 'Ctrl+q' is bound to both quit and sort_pid; keeping quit".to_string(),
+                "key 'c' is bound to both kill and sort_cpu; keeping kill".to_string(),
+            ]
+        );
+    }
+}
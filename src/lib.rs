@@ -0,0 +1,22 @@
+pub mod alerts;
+pub mod app;
+pub mod cli;
+pub mod columns;
+pub mod components;
+pub mod config;
+pub mod events;
+pub mod exec;
+pub mod history;
+pub mod keys;
+pub mod metrics_log;
+pub mod notify;
+pub mod once;
+pub mod process_groups;
+pub mod process_history;
+pub mod process_tree;
+pub mod remote;
+pub mod snapshot;
+pub mod sys_info;
+pub mod theme;
+pub mod ui;
+pub mod utils;
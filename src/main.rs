@@ -1,87 +1,325 @@
-mod app;
-mod components;
-mod sys_info;
-mod theme;
-mod ui;
-mod utils;
-
 use std::{
     io::{self, Stdout},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
+use clap::Parser;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, MouseButton,
+        MouseEvent, MouseEventKind,
+    },
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{Terminal, backend::CrosstermBackend};
 
-use app::App;
-use ui::ui;
+use xtop::{
+    alerts, app, app::App, cli, config, keys, metrics_log, once, remote, sys_info, theme, ui::ui,
+};
+
+// How long --connect waits for the first snapshot
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
 
 fn main() -> io::Result<()> {
-    enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
+    // Bad flags exit here, before the terminal leaves cooked mode
+    let cli = cli::Cli::parse();
+    if cli.dump_theme {
+        print!("{}", theme::EXAMPLE);
+        return Ok(());
+    }
+    // Asked for by name on the command line, so a missing theme is an error
+    let theme = match cli.theme.as_deref().map(config::load_theme).transpose() {
+        Ok(theme) => theme,
+        Err(err) => {
+            eprintln!("xtop: {}", err);
+            std::process::exit(2);
+        }
+    };
+    let (config, mut warnings) = config::Config::load(config::config_path().as_deref());
+    let save_state = !cli.no_save_state && config.save_state != Some(false);
+    let (state, state_warnings) = if save_state {
+        config::Config::load(config::state_path().as_deref())
+    } else {
+        Default::default()
+    };
+    warnings.extend(state_warnings);
+    if !cli.allow_exec
+        && config
+            .alerts
+            .iter()
+            .any(|spec| matches!(spec, alerts::AlertSpec::Table { exec: Some(_), .. }))
+    {
+        warnings.push("alert commands will not run without --allow-exec".to_string());
+    }
+    for warning in warnings {
+        eprintln!("xtop: {}", warning);
+    }
+    if cli.once {
+        let units = state.units.or(config.units).unwrap_or_default();
+        std::process::exit(once::run(cli.format.unwrap_or_default(), units));
+    }
+    if let Some(address) = &cli.serve {
+        let interval = cli.interval.or(config.interval_ms).unwrap_or(1000);
+        let listener = match std::net::TcpListener::bind(address) {
+            Ok(listener) => listener,
+            Err(err) => {
+                eprintln!("xtop: cannot serve on {}: {}", address, err);
+                std::process::exit(2);
+            }
+        };
+        eprintln!("xtop: serving on {}", listener.local_addr()?);
+        let interval = Duration::from_millis(interval.clamp(250, 10_000));
+        if let Err(err) = remote::serve(listener, interval) {
+            eprintln!("xtop: {}", err);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+    // Connected before the terminal is taken over, so failures print plainly
+    let remote = cli.connect.as_deref().map(|address| {
+        let client = remote::Client::connect(address);
+        match client.wait_for_snapshot(CONNECT_TIMEOUT) {
+            Ok(first) => (client, first),
+            Err(err) => {
+                eprintln!("xtop: cannot connect to {}: {}", address, err);
+                std::process::exit(1);
+            }
+        }
+    });
     let mut app = App::default();
+    if let Some((client, first)) = remote {
+        app.connect(client, first);
+    }
+    app.set_color_depth(if cli.no_color {
+        theme::ColorDepth::Monochrome
+    } else {
+        cli.color
+            .or(config.color)
+            .map_or_else(theme::ColorDepth::detect, Into::into)
+    });
+    config.apply(&mut app);
+    state.apply(&mut app);
+    cli.apply(&mut app);
+    if let (Some(theme), Some(name)) = (theme, &cli.theme) {
+        app.set_theme(name, theme);
+    }
+    if let Some(path) = &cli.log_metrics {
+        match metrics_log::MetricsLog::open(path, cli.log_max_size) {
+            Ok(log) => app.metrics_log = Some(log),
+            Err(err) => {
+                eprintln!("xtop: cannot log to {}: {}", path.display(), err);
+                std::process::exit(2);
+            }
+        }
+    }
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stdout()))?;
+    take_terminal(&mut terminal)?;
     let res = run_app(&mut terminal, &mut app);
+    release_terminal(&mut terminal)?;
+    match res {
+        Ok(()) if save_state => {
+            if let Err(err) = config::save_state(&app) {
+                eprintln!("xtop: could not save state: {}", err);
+            }
+        }
+        Ok(()) => {}
+        Err(err) => println!("Error: {:?}", err),
+    }
+    Ok(())
+}
+
+// Raw mode, the alternate screen and the mouse, for as long as xtop draws
+fn take_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> io::Result<()> {
+    enable_raw_mode()?;
+    execute!(
+        terminal.backend_mut(),
+        EnterAlternateScreen,
+        EnableMouseCapture
+    )
+}
+
+// Leaves the terminal as the shell had it
+fn release_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> io::Result<()> {
     disable_raw_mode()?;
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
         DisableMouseCapture
     )?;
-    terminal.show_cursor()?;
-    if let Err(err) = res {
-        println!("Error: {:?}", err);
-    }
+    terminal.show_cursor()
+}
+
+// Stops as a shell job would on Ctrl-Z, returning once `fg` continues us.
+// In raw mode the key never becomes SIGTSTP, so it is raised here after
+// handing the terminal back.
+#[cfg(unix)]
+fn suspend(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> io::Result<()> {
+    release_terminal(terminal)?;
+    nix::sys::signal::raise(nix::sys::signal::Signal::SIGTSTP).map_err(io::Error::from)?;
+    take_terminal(terminal)?;
+    // Whatever ran meanwhile drew over the screen, so nothing of the last
+    // frame can be assumed
+    terminal.clear()
+}
+
+#[cfg(not(unix))]
+fn suspend(_terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> io::Result<()> {
     Ok(())
 }
 
 fn run_app(terminal: &mut Terminal<CrosstermBackend<Stdout>>, app: &mut App) -> io::Result<()> {
+    let mut pending = keys::PendingKeys::default();
     loop {
         terminal.draw(|f| ui(f, app))?;
+        app.sync_layout();
         app.update_metrics();
+        app.poll_export();
+        app.poll_commands();
+        if run_actions(terminal, app, pending.expire(&app.keys, Instant::now()))? {
+            return Ok(());
+        }
         if event::poll(Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    match key.code {
-                        KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
-                        KeyCode::Char('1') => app.current_view = app::View::System,
-                        KeyCode::Char('2') => app.current_view = app::View::Process,
-                        KeyCode::Char('3') => app.current_view = app::View::Resources,
-                        KeyCode::Char('4') => app.current_view = app::View::Network,
-                        KeyCode::Char('5') => app.current_view = app::View::Disks,
-                        KeyCode::Tab => app.cycle_view(),
-                        KeyCode::Down | KeyCode::Char('j') => app.scroll_down(),
-                        KeyCode::Up | KeyCode::Char('k') => app.scroll_up(),
-                        KeyCode::PageDown | KeyCode::Char('J') => app.scroll_page_down(),
-                        KeyCode::PageUp | KeyCode::Char('K') => app.scroll_page_up(),
-                        KeyCode::Home => app.scroll_top(),
-                        KeyCode::End => app.scroll_bottom(),
-                        KeyCode::Char('+') => app.increase_update_delay(),
-                        KeyCode::Char('-') => app.decrease_update_delay(),
-                        KeyCode::Char(' ') => app.toggle_pause(),
-                        KeyCode::Char('r') => app.reset_selection(),
-                        KeyCode::Enter => app.toggle_process_details(),
-                        KeyCode::Char('f') => app.toggle_full_command(),
-                        KeyCode::Char('c') => app.change_sort_column(sys_info::ProcessSort::Cpu),
-                        KeyCode::Char('m') => app.change_sort_column(sys_info::ProcessSort::Memory),
-                        KeyCode::Char('p') => app.change_sort_column(sys_info::ProcessSort::Pid),
-                        KeyCode::Char('n') => app.change_sort_column(sys_info::ProcessSort::Name),
-                        KeyCode::F(1) => app.toggle_help(),
-                        KeyCode::F(5) => app.toggle_tree_view(),
-                        KeyCode::F(6) => app.toggle_proc_aggregation(),
-                        _ => {}
+            match event::read()? {
+                Event::Key(key) => {
+                    if key.kind == KeyEventKind::Press && app.input_mode == app::InputMode::Search {
+                        match key.code {
+                            KeyCode::Esc => app.cancel_search(),
+                            KeyCode::Enter => app.accept_search(),
+                            KeyCode::Tab => app.toggle_search_mode(),
+                            KeyCode::Down => app.scroll_down(),
+                            KeyCode::Up => app.scroll_up(),
+                            KeyCode::Backspace => app.edit_search(|query| {
+                                query.pop();
+                            }),
+                            KeyCode::Char(c) => app.edit_search(|query| query.push(c)),
+                            _ => {}
+                        }
+                    } else if key.kind == KeyEventKind::Press && app.show_help {
+                        // The overlay covers everything, so other keys are ignored
+                        match key.code {
+                            KeyCode::F(1) | KeyCode::Esc | KeyCode::Char('q') => app.toggle_help(),
+                            KeyCode::Down | KeyCode::Char('j') => app.scroll_help(1),
+                            KeyCode::Up | KeyCode::Char('k') => app.scroll_help(-1),
+                            KeyCode::PageDown => app.scroll_help(10),
+                            KeyCode::PageUp => app.scroll_help(-10),
+                            KeyCode::Home => app.scroll_help(isize::MIN),
+                            KeyCode::End => app.scroll_help(isize::MAX),
+                            _ => {}
+                        }
+                    } else if key.kind == KeyEventKind::Press
+                        && matches!(
+                            app.pending_action,
+                            Some(app::PendingAction::SignalPicker { .. })
+                        )
+                    {
+                        // Letters go to the filter, so only non-character keys act
+                        match key.code {
+                            KeyCode::Esc => app.cancel_pending_action(),
+                            KeyCode::Enter => app.confirm_pending_action(),
+                            KeyCode::Down => app.move_signal_selection(1),
+                            KeyCode::Up => app.move_signal_selection(-1),
+                            KeyCode::Backspace => app.edit_signal_query(|query| {
+                                query.pop();
+                            }),
+                            KeyCode::Char(c) => app.edit_signal_query(|query| query.push(c)),
+                            _ => {}
+                        }
+                    } else if key.kind == KeyEventKind::Press && app.pending_action.is_some() {
+                        match key.code {
+                            KeyCode::Esc | KeyCode::Char('n') => app.cancel_pending_action(),
+                            KeyCode::Enter => app.confirm_pending_action(),
+                            KeyCode::Left | KeyCode::Right | KeyCode::Tab => {
+                                app.toggle_kill_signal()
+                            }
+                            KeyCode::Char('t') => app.select_kill_signal(sys_info::Signal::Term),
+                            KeyCode::Char('k') => app.select_kill_signal(sys_info::Signal::Kill),
+                            _ => {}
+                        }
+                    } else if key.kind == KeyEventKind::Press && app.open_files.is_some() {
+                        match key.code {
+                            KeyCode::Char('l') | KeyCode::Esc => app.toggle_open_files(),
+                            KeyCode::Down | KeyCode::Char('j') => app.scroll_open_files(1),
+                            KeyCode::Up | KeyCode::Char('k') => app.scroll_open_files(-1),
+                            KeyCode::PageDown | KeyCode::Char('J') => app.scroll_open_files(10),
+                            KeyCode::PageUp | KeyCode::Char('K') => app.scroll_open_files(-10),
+                            KeyCode::Char('q') => return Ok(()),
+                            _ => {}
+                        }
+                    } else if key.kind == KeyEventKind::Press && app.environment.is_some() {
+                        match key.code {
+                            KeyCode::Char('e') | KeyCode::Esc => app.toggle_environment(),
+                            KeyCode::Char('v') => app.toggle_environment_secrets(),
+                            KeyCode::Down | KeyCode::Char('j') => app.scroll_environment(1),
+                            KeyCode::Up | KeyCode::Char('k') => app.scroll_environment(-1),
+                            KeyCode::PageDown | KeyCode::Char('J') => app.scroll_environment(10),
+                            KeyCode::PageUp | KeyCode::Char('K') => app.scroll_environment(-10),
+                            KeyCode::Char('q') => return Ok(()),
+                            _ => {}
+                        }
+                    } else if key.kind == KeyEventKind::Press && app.show_events {
+                        match key.code {
+                            KeyCode::Char('L') | KeyCode::Esc => app.toggle_events(),
+                            KeyCode::Down | KeyCode::Char('j') => app.scroll_events(1),
+                            KeyCode::Up | KeyCode::Char('k') => app.scroll_events(-1),
+                            KeyCode::PageDown | KeyCode::Char('J') => app.scroll_events(10),
+                            KeyCode::PageUp | KeyCode::Char('K') => app.scroll_events(-10),
+                            KeyCode::Home => app.scroll_events(isize::MIN),
+                            KeyCode::Char('q') => return Ok(()),
+                            _ => {}
+                        }
+                    } else if key.kind == KeyEventKind::Press {
+                        let ready = pending.feed(&key, &app.keys, app.count_prefix, Instant::now());
+                        if run_actions(terminal, app, ready)? {
+                            return Ok(());
+                        }
                     }
                 }
+                Event::Mouse(mouse) => handle_mouse(app, mouse),
+                _ => {}
             }
         }
-        if app.paused {
-            app.last_update = std::time::Instant::now();
+    }
+}
+
+// Returns true once one of the actions asked to quit
+fn run_actions(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    app: &mut App,
+    actions: Vec<(keys::Action, usize)>,
+) -> io::Result<bool> {
+    for (action, count) in actions {
+        match action {
+            keys::Action::Quit => return Ok(true),
+            keys::Action::Back if app.tagged_pids.is_empty() => return Ok(true),
+            keys::Action::Suspend => {
+                suspend(terminal)?;
+                continue;
+            }
+            _ => {}
         }
+        for _ in 0..count {
+            app.perform(action);
+        }
+    }
+    Ok(false)
+}
+
+fn handle_mouse(app: &mut App, mouse: MouseEvent) {
+    // Popups and the help overlay cover the layout the clicks were mapped to
+    let modal = app.input_mode != app::InputMode::Normal
+        || app.pending_action.is_some()
+        || app.environment.is_some()
+        || app.open_files.is_some()
+        || app.show_events
+        || app.show_help;
+    if modal {
+        return;
+    }
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => app.click(mouse.column, mouse.row),
+        MouseEventKind::ScrollDown => app.wheel(mouse.column, mouse.row, true),
+        MouseEventKind::ScrollUp => app.wheel(mouse.column, mouse.row, false),
+        _ => {}
     }
 }
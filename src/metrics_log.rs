@@ -0,0 +1,184 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local, SecondsFormat};
+
+use crate::sys_info::SystemInfo;
+
+const HEADER: &str = "timestamp,cpu_percent,memory_used_mb,memory_total_mb,swap_used_mb,\
+load_1,load_5,load_15,disk_read_mb_s,disk_write_mb_s,net_rx_kb_s,net_tx_kb_s\n";
+// Rows are buffered, at most this long, so a crash loses little
+const FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Appends one CSV row per refresh to a file, for graphing later. With a
+/// size limit, a full file is moved aside to `<path>.1` and a new one begun.
+pub struct MetricsLog {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    // Bytes in the file, counting those still buffered
+    size: u64,
+    max_size: Option<u64>,
+    last_flush: Instant,
+}
+
+impl MetricsLog {
+    pub fn open(path: &Path, max_size: Option<u64>) -> io::Result<Self> {
+        let (writer, size) = open_with_header(path)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer,
+            size,
+            max_size,
+            last_flush: Instant::now(),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Writes a row for `metrics`. An error, such as a full disk, leaves the
+    /// log unusable.
+    pub fn append(&mut self, metrics: &SystemInfo, time: DateTime<Local>) -> io::Result<()> {
+        let row = row(metrics, time);
+        if self.max_size.is_some_and(|max| {
+            self.size + row.len() as u64 > max && self.size > HEADER.len() as u64
+        }) {
+            self.rotate()?;
+        }
+        self.writer.write_all(row.as_bytes())?;
+        self.size += row.len() as u64;
+        if self.last_flush.elapsed() >= FLUSH_INTERVAL {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.last_flush = Instant::now();
+        self.writer.flush()
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.writer.flush()?;
+        let mut rotated = OsString::from(self.path.as_os_str());
+        rotated.push(".1");
+        fs::rename(&self.path, rotated)?;
+        (self.writer, self.size) = open_with_header(&self.path)?;
+        Ok(())
+    }
+}
+
+// Opens `path` for appending, starting it with the header when it is new
+fn open_with_header(path: &Path) -> io::Result<(BufWriter<File>, u64)> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut size = file.metadata()?.len();
+    let mut writer = BufWriter::new(file);
+    if size == 0 {
+        writer.write_all(HEADER.as_bytes())?;
+        writer.flush()?;
+        size = HEADER.len() as u64;
+    }
+    Ok((writer, size))
+}
+
+fn row(metrics: &SystemInfo, time: DateTime<Local>) -> String {
+    // Blank until every disk has two samples to compare, as in the Disks view
+    let disk_read: Option<u64> = metrics.disks.iter().map(|disk| disk.read_speed).sum();
+    let disk_write: Option<u64> = metrics.disks.iter().map(|disk| disk.write_speed).sum();
+    let optional = |value: Option<u64>| value.map_or_else(String::new, |value| value.to_string());
+    format!(
+        "{},{},{},{},{},{:.2},{:.2},{:.2},{},{},{},{}\n",
+        time.to_rfc3339_opts(SecondsFormat::Secs, false),
+        metrics.cpu_total_usage,
+        metrics.memory_used,
+        metrics.memory_total,
+        metrics.swap_used,
+        metrics.load_average.one,
+        metrics.load_average.five,
+        metrics.load_average.fifteen,
+        optional(disk_read),
+        optional(disk_write),
+        metrics.total_rx,
+        metrics.total_tx,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::sys_info::{DiskInfo, LoadAverage};
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("xtop-log-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("metrics.csv")
+    }
+
+    fn sample() -> SystemInfo {
+        SystemInfo {
+            cpu_total_usage: 42,
+            memory_used: 2048,
+            memory_total: 8192,
+            swap_used: 0,
+            load_average: LoadAverage {
+                one: 1.5,
+                five: 1.25,
+                fifteen: 1.0,
+            },
+            disks: vec![DiskInfo {
+                read_speed: Some(3),
+                write_speed: Some(1),
+                ..DiskInfo::default()
+            }],
+            total_rx: 120,
+            total_tx: 8,
+            ..SystemInfo::default()
+        }
+    }
+
+    #[test]
+    fn writes_the_header_only_to_a_new_file() {
+        let path = temp_path("header");
+        let time = Local.with_ymd_and_hms(2024, 5, 1, 12, 33, 5).unwrap();
+        for _ in 0..2 {
+            let mut log = MetricsLog::open(&path, None).unwrap();
+            log.append(&sample(), time).unwrap();
+            log.flush().unwrap();
+        }
+        let contents = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("timestamp,cpu_percent,"));
+        assert!(lines[1].starts_with("2024-05-01T12:33:05"));
+        assert!(lines[1].ends_with(",42,2048,8192,0,1.50,1.25,1.00,3,1,120,8"));
+        assert_eq!(lines[1], lines[2]);
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn moves_a_full_file_aside() {
+        let path = temp_path("rotate");
+        let time = Local::now();
+        let row_len = row(&sample(), time).len() as u64;
+        let mut log = MetricsLog::open(&path, Some(HEADER.len() as u64 + 2 * row_len)).unwrap();
+        for _ in 0..3 {
+            log.append(&sample(), time).unwrap();
+        }
+        log.flush().unwrap();
+        let rotated = fs::read_to_string(path.with_extension("csv.1")).unwrap();
+        assert_eq!(rotated.lines().count(), 3);
+        let current = fs::read_to_string(&path).unwrap();
+        assert_eq!(current.lines().count(), 2);
+        assert!(current.starts_with("timestamp,"));
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
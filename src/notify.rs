@@ -0,0 +1,134 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+// At most this many notifications in any window, however much rules flap
+const RATE_LIMIT: usize = 5;
+const RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Counts notifications over a sliding window, holding back those past
+/// the limit.
+#[derive(Debug, Default)]
+struct RateLimit {
+    sent: VecDeque<Instant>,
+    held_back: usize,
+}
+
+impl RateLimit {
+    // Whether one more may go out at `now`, counting it if so
+    fn admit(&mut self, now: Instant) -> bool {
+        while self
+            .sent
+            .front()
+            .is_some_and(|&sent| now.saturating_duration_since(sent) >= RATE_WINDOW)
+        {
+            self.sent.pop_front();
+        }
+        if self.sent.len() >= RATE_LIMIT {
+            self.held_back += 1;
+            return false;
+        }
+        self.sent.push_back(now);
+        true
+    }
+}
+
+/// Sends desktop notifications through notify-rust, which hands them to
+/// the desktop over D-Bus on Linux. Where that can't work, as over SSH or
+/// in a container, the first failure turns notifications off for the
+/// session. Without the `notify` feature nothing is ever sent.
+#[derive(Debug, Default)]
+pub struct Notifier {
+    limit: RateLimit,
+    // Why delivery failed, set from the thread showing the notification
+    failure: Arc<Mutex<Option<String>>>,
+    disabled: bool,
+}
+
+impl Notifier {
+    pub fn send(&mut self, summary: &str, body: &str, now: Instant) {
+        if !cfg!(feature = "notify") || self.disabled || !self.limit.admit(now) {
+            return;
+        }
+        let mut body = body.to_string();
+        match std::mem::take(&mut self.limit.held_back) {
+            0 => {}
+            1 => body.push_str("\n(1 earlier notification held back)"),
+            count => body.push_str(&format!("\n({} earlier notifications held back)", count)),
+        }
+        // Shown from a thread of its own, as a D-Bus timeout can take seconds
+        let summary = summary.to_string();
+        let failure = Arc::clone(&self.failure);
+        thread::spawn(move || {
+            if let Err(reason) = show(&summary, &body) {
+                failure.lock().unwrap().get_or_insert(reason);
+            }
+        });
+    }
+
+    /// The reason notifications stopped working, returned only the first
+    /// time it is asked for so it is reported once.
+    pub fn take_failure(&mut self) -> Option<String> {
+        let reason = self.failure.lock().unwrap().take()?;
+        if std::mem::replace(&mut self.disabled, true) {
+            return None;
+        }
+        Some(reason)
+    }
+}
+
+#[cfg(feature = "notify")]
+fn show(summary: &str, body: &str) -> Result<(), String> {
+    notify_rust::Notification::new()
+        .appname("xtop")
+        .summary(summary)
+        .body(body)
+        .show()
+        .map(drop)
+        .map_err(|err| err.to_string())
+}
+
+#[cfg(not(feature = "notify"))]
+fn show(_summary: &str, _body: &str) -> Result<(), String> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holds_back_notifications_past_the_limit() {
+        let mut limit = RateLimit::default();
+        let start = Instant::now();
+        let admitted = (0..20)
+            .filter(|&second| limit.admit(start + Duration::from_secs(second)))
+            .count();
+        assert_eq!(admitted, RATE_LIMIT);
+        assert_eq!(limit.held_back, 20 - RATE_LIMIT);
+        // A minute after the first went out there is room again
+        assert!(limit.admit(start + RATE_WINDOW));
+        assert!(!limit.admit(start + RATE_WINDOW));
+    }
+
+    fn fail(notifier: &Notifier, reason: &str) {
+        let mut failure = notifier.failure.lock().unwrap();
+        failure.get_or_insert(reason.to_string());
+    }
+
+    #[test]
+    fn reports_a_failure_once() {
+        let mut notifier = Notifier::default();
+        fail(&notifier, "no D-Bus");
+        fail(&notifier, "still no D-Bus");
+        assert_eq!(notifier.take_failure().as_deref(), Some("no D-Bus"));
+        fail(&notifier, "again");
+        assert_eq!(notifier.take_failure(), None);
+        // Nothing more is attempted once off
+        notifier.send("xtop", "cpu > 90", Instant::now());
+        assert!(notifier.limit.sent.is_empty());
+    }
+}
@@ -0,0 +1,180 @@
+use std::{
+    fmt::Write as _,
+    io::{self, Write},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    cli::FormatArg,
+    components,
+    sys_info::{Collector, SystemInfo},
+    utils::{self, UnitSystem},
+};
+
+// CPU usage and rates are differences, so a second sample follows this long
+// after the first
+const SAMPLE_GAP: Duration = Duration::from_millis(500);
+const TOP_PROCESSES: usize = 5;
+
+/// Collects one snapshot and prints it to stdout for `--once`, returning
+/// the exit code: 1 when nothing could be read at all, as where the only
+/// collector is the simulation.
+pub fn run(format: FormatArg, units: UnitSystem) -> i32 {
+    let mut collector = Collector::new();
+    let mut metrics = SystemInfo::empty();
+    collector.refresh(&mut metrics);
+    thread::sleep(SAMPLE_GAP);
+    collector.refresh(&mut metrics);
+    if !collected(&metrics) {
+        eprintln!("xtop: could not collect any metrics");
+        return 1;
+    }
+    let mut stdout = io::stdout().lock();
+    let written = match format {
+        FormatArg::Json => serde_json::to_writer_pretty(&mut stdout, &metrics)
+            .map_err(io::Error::from)
+            .and_then(|()| writeln!(stdout)),
+        FormatArg::Text => stdout.write_all(summary(&metrics, units).as_bytes()),
+    };
+    match written {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("xtop: {}", err);
+            1
+        }
+    }
+}
+
+// Whether the collector filled in anything of its own
+fn collected(metrics: &SystemInfo) -> bool {
+    metrics.memory_total != 0 || !metrics.processes.is_empty()
+}
+
+/// A few lines for a human: uptime, load, CPU, memory, the fullest disk and
+/// the busiest processes.
+pub fn summary(metrics: &SystemInfo, units: UnitSystem) -> String {
+    let mut text = String::new();
+    let load = &metrics.load_average;
+    let _ = writeln!(
+        text,
+        "{} ({}), up {}",
+        metrics.hostname,
+        metrics.os_name,
+        utils::format_duration_long(metrics.uptime)
+    );
+    let _ = writeln!(
+        text,
+        "Load:   {:.2} {:.2} {:.2}",
+        load.one, load.five, load.fifteen
+    );
+    let _ = writeln!(
+        text,
+        "CPU:    {}% of {} cores",
+        metrics.cpu_total_usage, metrics.cpu_count
+    );
+    let _ = writeln!(
+        text,
+        "Memory: {} of {} ({:.1}%), swap {} of {}",
+        utils::format_size(metrics.memory_used, utils::MB, units),
+        utils::format_size(metrics.memory_total, utils::MB, units),
+        utils::safe_percentage(metrics.memory_used, metrics.memory_total),
+        utils::format_size(metrics.swap_used, utils::MB, units),
+        utils::format_size(metrics.swap_total, utils::MB, units),
+    );
+    match metrics
+        .disks
+        .iter()
+        .filter(|disk| !disk.is_pseudo())
+        .max_by_key(|disk| disk.usage)
+    {
+        Some(disk) => {
+            let _ = writeln!(
+                text,
+                "Disk:   {} {}% full, {} free",
+                disk.mount_point,
+                disk.usage,
+                utils::format_size(disk.free, utils::GB, units)
+            );
+        }
+        None => text.push_str("Disk:   none\n"),
+    }
+    let mut processes: Vec<_> = metrics.processes.iter().collect();
+    processes.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
+    text.push_str("Top processes by CPU:\n");
+    for process in processes.into_iter().take(TOP_PROCESSES) {
+        let _ = writeln!(
+            text,
+            "  {:>7} {:>6} {:>10}  {}",
+            process.pid,
+            components::process_figure(process, || format!("{:.1}%", process.cpu_usage)),
+            components::process_figure(process, || {
+                utils::format_size(process.memory_usage, utils::MB, units)
+            }),
+            process.name
+        );
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sys_info::{DiskInfo, ProcessInfo};
+
+    #[test]
+    fn summarizes_in_a_few_lines() {
+        let metrics = SystemInfo {
+            hostname: "web1".to_string(),
+            uptime: Duration::from_secs(90_061),
+            memory_used: 4096,
+            memory_total: 16384,
+            disks: vec![
+                DiskInfo {
+                    mount_point: "/".to_string(),
+                    fs_type: "ext4".to_string(),
+                    usage: 40,
+                    free: 60,
+                    ..DiskInfo::default()
+                },
+                DiskInfo {
+                    mount_point: "/data".to_string(),
+                    fs_type: "xfs".to_string(),
+                    usage: 91,
+                    free: 9,
+                    ..DiskInfo::default()
+                },
+            ],
+            processes: (1..=7)
+                .map(|pid| ProcessInfo {
+                    cpu_usage: pid as f64,
+                    memory_usage: 512,
+                    ..ProcessInfo::fixture(pid, 1, &format!("worker{}", pid))
+                })
+                .collect(),
+            ..SystemInfo::default()
+        };
+        let text = summary(&metrics, UnitSystem::Binary);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "web1 (Linux), up 1d 1h 1m 1s");
+        assert!(lines[3].starts_with("Memory: 4.00 GiB of 16.0 GiB (25.0%)"));
+        assert_eq!(lines[4], "Disk:   /data 91% full, 9.00 GiB free");
+        assert_eq!(lines.len(), 6 + TOP_PROCESSES);
+        assert!(lines[6].ends_with("7.0%    512 MiB  worker7"));
+        assert!(lines[10].ends_with("worker3"));
+    }
+
+    #[test]
+    fn reports_a_snapshot_nothing_was_collected_into() {
+        let mut metrics = SystemInfo::empty();
+        assert!(!collected(&metrics));
+        // The simulation only moves figures it is given
+        #[cfg(not(any(target_os = "macos", windows)))]
+        {
+            Collector::new().refresh(&mut metrics);
+            assert!(!collected(&metrics));
+        }
+        metrics.memory_total = 16384;
+        assert!(collected(&metrics));
+    }
+}
@@ -0,0 +1,99 @@
+use std::{collections::HashMap, ops::Add};
+
+use crate::sys_info::ProcessInfo;
+
+/// Groups processes by executable name, in order of each name's first
+/// appearance. Each group carries a synthetic process holding the summed
+/// usage, which takes its PID and remaining fields from the first member.
+pub fn aggregate<'a>(processes: &[&'a ProcessInfo]) -> Vec<(ProcessInfo, Vec<&'a ProcessInfo>)> {
+    let mut index_by_name: HashMap<&str, usize> = HashMap::new();
+    let mut groups: Vec<(ProcessInfo, Vec<&'a ProcessInfo>)> = Vec::new();
+    for &process in processes {
+        match index_by_name.get(process.name.as_str()) {
+            Some(&index) => {
+                let (total, members) = &mut groups[index];
+                add_usage(total, process);
+                members.push(process);
+            }
+            None => {
+                index_by_name.insert(&process.name, groups.len());
+                groups.push((process.clone(), vec![process]));
+            }
+        }
+    }
+    groups
+}
+
+// Unknown only when no member reports the value
+fn add<T: Add<Output = T> + Default>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (None, None) => None,
+        (a, b) => Some(a.unwrap_or_default() + b.unwrap_or_default()),
+    }
+}
+
+fn add_usage(total: &mut ProcessInfo, process: &ProcessInfo) {
+    total.cpu_usage += process.cpu_usage;
+    total.memory_usage += process.memory_usage;
+    total.memory_percent += process.memory_percent;
+    total.virt_kb += process.virt_kb;
+    total.rss_kb += process.rss_kb;
+    total.shared_kb = add(total.shared_kb, process.shared_kb);
+    total.swap_kb += process.swap_kb;
+    total.threads += process.threads;
+    total.read_speed = add(total.read_speed, process.read_speed);
+    total.write_speed = add(total.write_speed, process.write_speed);
+    total.fd_count = add(total.fd_count, process.fd_count);
+    // Unknown only when no member could be read
+    total.restricted &= process.restricted;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, name: &str, cpu_usage: f64, read_speed: Option<u64>) -> ProcessInfo {
+        ProcessInfo {
+            cpu_usage,
+            memory_usage: 100,
+            threads: 4,
+            read_speed,
+            ..ProcessInfo::fixture(pid, 1, name)
+        }
+    }
+
+    #[test]
+    fn sums_usage_per_name() {
+        let processes = [
+            process(10, "chrome", 5.0, Some(3)),
+            process(11, "bash", 0.5, None),
+            process(12, "chrome", 2.5, None),
+        ];
+        let refs: Vec<&ProcessInfo> = processes.iter().collect();
+        let groups = aggregate(&refs);
+        assert_eq!(groups.len(), 2);
+        let (chrome, members) = &groups[0];
+        assert_eq!(chrome.pid, 10);
+        assert_eq!(chrome.cpu_usage, 7.5);
+        assert_eq!(chrome.memory_usage, 200);
+        assert_eq!(chrome.threads, 8);
+        assert_eq!(chrome.read_speed, Some(3));
+        assert_eq!(chrome.write_speed, None);
+        assert_eq!(members.len(), 2);
+        assert_eq!(groups[1].0.name, "bash");
+    }
+
+    #[test]
+    fn reads_a_group_when_any_member_could_be_read() {
+        let locked = ProcessInfo {
+            restricted: true,
+            ..process(20, "sshd", 0.0, None)
+        };
+        let readable = process(21, "sshd", 1.5, None);
+        let groups = aggregate(&[&locked, &locked]);
+        assert!(groups[0].0.restricted);
+        let groups = aggregate(&[&locked, &readable]);
+        assert!(!groups[0].0.restricted);
+        assert_eq!(groups[0].0.cpu_usage, 1.5);
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::VecDeque;
+
+// One minute at the default refresh interval
+const SAMPLES: usize = 60;
+// Recently selected processes keep their samples for flicking back and forth
+const REMEMBERED: usize = 4;
+
+/// CPU and RSS samples of one process, oldest first.
+#[derive(Debug, Default)]
+pub struct Samples {
+    pub pid: u32,
+    // CPU% in tenths, as sparklines take integers
+    pub cpu: VecDeque<u64>,
+    pub rss_kb: VecDeque<u64>,
+}
+
+/// Short usage histories for the selected process and the few selected just
+/// before it. Only processes that were selected are sampled, so the cost does
+/// not grow with the process count.
+#[derive(Debug, Default)]
+pub struct ProcessHistory {
+    // Most recently selected first
+    recent: VecDeque<Samples>,
+}
+
+impl ProcessHistory {
+    /// Appends a sample for `pid`, making it the most recent entry and
+    /// forgetting the least recently selected one beyond the limit.
+    pub fn record(&mut self, pid: u32, cpu_usage: f64, rss_kb: u64) {
+        let mut samples = match self.recent.iter().position(|samples| samples.pid == pid) {
+            Some(index) => self.recent.remove(index).unwrap_or_default(),
+            None => Samples {
+                pid,
+                ..Samples::default()
+            },
+        };
+        if samples.cpu.len() == SAMPLES {
+            samples.cpu.pop_front();
+            samples.rss_kb.pop_front();
+        }
+        samples.cpu.push_back((cpu_usage * 10.0).round() as u64);
+        samples.rss_kb.push_back(rss_kb);
+        self.recent.push_front(samples);
+        self.recent.truncate(REMEMBERED);
+    }
+
+    pub fn get(&self, pid: u32) -> Option<&Samples> {
+        self.recent.iter().find(|samples| samples.pid == pid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_a_bounded_window_for_a_few_pids() {
+        let mut history = ProcessHistory::default();
+        for tick in 0..100 {
+            history.record(7, tick as f64, tick);
+        }
+        let samples = history.get(7).unwrap();
+        assert_eq!(samples.cpu.len(), SAMPLES);
+        assert_eq!(samples.cpu.front(), Some(&400));
+        assert_eq!(samples.rss_kb.back(), Some(&99));
+
+        for pid in 1..=REMEMBERED as u32 {
+            history.record(pid, 1.0, 1);
+        }
+        assert!(history.get(7).is_none());
+        // Selecting an older PID again keeps its samples
+        history.record(1, 2.0, 2);
+        assert_eq!(history.get(1).unwrap().cpu.len(), 2);
+    }
+}
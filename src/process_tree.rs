@@ -0,0 +1,125 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::sys_info::ProcessInfo;
+
+const INIT_PID: u32 = 1;
+
+/// Flattens the processes into depth-first display order, each paired with
+/// the branch guides to draw before its name. Siblings keep their relative
+/// order from the input, so the active sort applies within each level.
+pub fn flatten<'a>(processes: &[&'a ProcessInfo]) -> Vec<(&'a ProcessInfo, String)> {
+    let present: HashSet<u32> = processes.iter().map(|process| process.pid).collect();
+    let mut children: HashMap<u32, Vec<&'a ProcessInfo>> = HashMap::new();
+    let mut roots = Vec::new();
+    for &process in processes {
+        match parent_of(process, &present) {
+            Some(parent) => children.entry(parent).or_default().push(process),
+            None => roots.push(process),
+        }
+    }
+    let mut rows = Vec::with_capacity(processes.len());
+    let mut visited = HashSet::new();
+    for root in roots {
+        push_subtree(root, "", None, &children, &mut visited, &mut rows);
+    }
+    // Members of a ppid cycle are unreachable from any root; show them flat
+    // rather than dropping them
+    for &process in processes {
+        push_subtree(process, "", None, &children, &mut visited, &mut rows);
+    }
+    rows
+}
+
+fn parent_of(process: &ProcessInfo, present: &HashSet<u32>) -> Option<u32> {
+    if process.ppid == process.pid {
+        return None;
+    }
+    if present.contains(&process.ppid) {
+        return Some(process.ppid);
+    }
+    // ppid 0 marks init and kernel threads; anything else lost its parent
+    // and the kernel reparents it to init
+    (process.ppid != 0 && process.pid != INIT_PID && present.contains(&INIT_PID))
+        .then_some(INIT_PID)
+}
+
+fn push_subtree<'a>(
+    process: &'a ProcessInfo,
+    indent: &str,
+    is_last: Option<bool>,
+    children: &HashMap<u32, Vec<&'a ProcessInfo>>,
+    visited: &mut HashSet<u32>,
+    rows: &mut Vec<(&'a ProcessInfo, String)>,
+) {
+    if !visited.insert(process.pid) {
+        return;
+    }
+    let (prefix, child_indent) = match is_last {
+        None => (String::new(), String::new()),
+        Some(true) => (format!("{}└─ ", indent), format!("{}   ", indent)),
+        Some(false) => (format!("{}├─ ", indent), format!("{}│  ", indent)),
+    };
+    rows.push((process, prefix));
+    let Some(kids) = children.get(&process.pid) else {
+        return;
+    };
+    for (index, &child) in kids.iter().enumerate() {
+        let is_last = index + 1 == kids.len();
+        push_subtree(child, &child_indent, Some(is_last), children, visited, rows);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn process(pid: u32, ppid: u32) -> ProcessInfo {
+        ProcessInfo::fixture(pid, ppid, "proc")
+    }
+
+    fn layout(processes: &[ProcessInfo]) -> Vec<(u32, String)> {
+        let refs: Vec<&ProcessInfo> = processes.iter().collect();
+        flatten(&refs)
+            .into_iter()
+            .map(|(process, prefix)| (process.pid, prefix))
+            .collect()
+    }
+
+    #[test]
+    fn keeps_children_under_parents_in_sibling_order() {
+        let processes = [
+            process(30, 10),
+            process(1, 0),
+            process(20, 1),
+            process(10, 1),
+            process(40, 10),
+        ];
+        assert_eq!(
+            layout(&processes),
+            vec![
+                (1, String::new()),
+                (20, "├─ ".to_string()),
+                (10, "└─ ".to_string()),
+                (30, "   ├─ ".to_string()),
+                (40, "   └─ ".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn attaches_orphans_to_init() {
+        let processes = [process(1, 0), process(50, 999)];
+        assert_eq!(
+            layout(&processes),
+            vec![(1, String::new()), (50, "└─ ".to_string())]
+        );
+    }
+
+    #[test]
+    fn survives_parent_cycles() {
+        let processes = [process(5, 6), process(6, 5), process(7, 7)];
+        let rows = layout(&processes);
+        assert_eq!(rows.len(), 3);
+        assert!(rows.iter().any(|(pid, _)| *pid == 7));
+    }
+}
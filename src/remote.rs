@@ -0,0 +1,320 @@
+use std::{
+    borrow::Cow,
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::sys_info::{Collector, SystemInfo};
+
+// Raised whenever a message changes shape, so mismatched peers refuse each
+// other instead of misreading fields
+pub const PROTOCOL_VERSION: u32 = 1;
+// A larger length prefix means the stream is not what we think it is
+const MAX_FRAME: usize = 64 * 1024 * 1024;
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+// Snapshots come at most 10s apart, so silence past this means a dead link
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// What goes over the wire, each as a big-endian u32 length and then JSON.
+/// Both sides open with `Hello`, after which the server sends snapshots.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message<'a> {
+    Hello { protocol: u32, version: String },
+    // Boxed, as a snapshot dwarfs a hello
+    Snapshot { metrics: Box<Cow<'a, SystemInfo>> },
+}
+
+impl Message<'_> {
+    fn hello() -> Self {
+        Message::Hello {
+            protocol: PROTOCOL_VERSION,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        }
+    }
+}
+
+pub fn encode(message: &Message) -> io::Result<Vec<u8>> {
+    let json = serde_json::to_vec(message).map_err(io::Error::other)?;
+    let length = u32::try_from(json.len()).map_err(io::Error::other)?;
+    let mut frame = length.to_be_bytes().to_vec();
+    frame.extend(json);
+    Ok(frame)
+}
+
+pub fn read_message(reader: &mut impl Read) -> io::Result<Message<'static>> {
+    let mut length = [0; 4];
+    reader.read_exact(&mut length)?;
+    let length = u32::from_be_bytes(length) as usize;
+    if length > MAX_FRAME {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} byte message is too large", length),
+        ));
+    }
+    let mut json = vec![0; length];
+    reader.read_exact(&mut json)?;
+    serde_json::from_slice(&json).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+// Checks the peer's opening message
+fn expect_hello(message: Message) -> io::Result<()> {
+    match message {
+        Message::Hello { protocol, .. } if protocol == PROTOCOL_VERSION => Ok(()),
+        Message::Hello { protocol, version } => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "peer is xtop {} speaking protocol {}, this one speaks {}",
+                version, protocol, PROTOCOL_VERSION
+            ),
+        )),
+        Message::Snapshot { .. } => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "peer did not start with a hello",
+        )),
+    }
+}
+
+#[derive(Default)]
+struct Subscribers {
+    streams: Vec<TcpStream>,
+    // The newest snapshot, for clients that connect between refreshes
+    latest: Option<Vec<u8>>,
+}
+
+/// Collects metrics every `interval` and sends them to every client
+/// connected to `listener`, until the process is killed.
+pub fn serve(listener: TcpListener, interval: Duration) -> io::Result<()> {
+    let subscribers = Arc::new(Mutex::new(Subscribers::default()));
+    let accepting = Arc::clone(&subscribers);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            // Greeted on a thread of its own so a silent peer holds up no one
+            let subscribers = Arc::clone(&accepting);
+            thread::spawn(move || {
+                let Ok(mut stream) = greet(stream) else {
+                    return;
+                };
+                let mut subscribers = subscribers.lock().unwrap();
+                let caught_up = match &subscribers.latest {
+                    Some(frame) => stream.write_all(frame).is_ok(),
+                    None => true,
+                };
+                if caught_up {
+                    subscribers.streams.push(stream);
+                }
+            });
+        }
+    });
+    let mut collector = Collector::new();
+    let mut metrics = SystemInfo::default();
+    loop {
+        collector.refresh(&mut metrics);
+        let frame = encode(&Message::Snapshot {
+            metrics: Box::new(Cow::Borrowed(&metrics)),
+        })?;
+        let mut subscribers = subscribers.lock().unwrap();
+        subscribers
+            .streams
+            .retain_mut(|stream| stream.write_all(&frame).is_ok());
+        subscribers.latest = Some(frame);
+        drop(subscribers);
+        thread::sleep(interval);
+    }
+}
+
+// Exchanges hellos with a new client. Its own hello is sent even to a
+// client that is refused, so the client can say why.
+fn greet(mut stream: TcpStream) -> io::Result<TcpStream> {
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let theirs = read_message(&mut stream)?;
+    stream.write_all(&encode(&Message::hello())?)?;
+    expect_hello(theirs)?;
+    Ok(stream)
+}
+
+/// The state of a client's link to its server.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Connection {
+    Connecting,
+    Connected,
+    // Lost or refused, with the reason; another attempt follows shortly
+    Reconnecting(String),
+    // The server speaks another protocol version, so there is no retrying
+    Incompatible(String),
+}
+
+#[derive(Debug)]
+struct ClientState {
+    connection: Connection,
+    latest: Option<SystemInfo>,
+}
+
+/// Receives snapshots from a server on a background thread, reconnecting
+/// whenever the link drops.
+pub struct Client {
+    state: Arc<Mutex<ClientState>>,
+}
+
+impl Client {
+    pub fn connect(address: &str) -> Self {
+        let state = Arc::new(Mutex::new(ClientState {
+            connection: Connection::Connecting,
+            latest: None,
+        }));
+        let shared = Arc::clone(&state);
+        let target = address.to_string();
+        thread::spawn(move || {
+            loop {
+                let error = match receive(&target, &shared) {
+                    Ok(()) => "server closed the connection".to_string(),
+                    Err(err) if err.kind() == io::ErrorKind::Unsupported => {
+                        shared.lock().unwrap().connection =
+                            Connection::Incompatible(err.to_string());
+                        return;
+                    }
+                    Err(err) => err.to_string(),
+                };
+                shared.lock().unwrap().connection = Connection::Reconnecting(error);
+                thread::sleep(RETRY_DELAY);
+            }
+        });
+        Self { state }
+    }
+
+    pub fn connection(&self) -> Connection {
+        self.state.lock().unwrap().connection.clone()
+    }
+
+    /// The snapshot received since the last call, if any.
+    pub fn take_snapshot(&self) -> Option<SystemInfo> {
+        self.state.lock().unwrap().latest.take()
+    }
+
+    /// Blocks until the first snapshot arrives, giving up after `timeout`
+    /// or as soon as the server turns out to be incompatible.
+    pub fn wait_for_snapshot(&self, timeout: Duration) -> Result<SystemInfo, String> {
+        let start = Instant::now();
+        loop {
+            if let Some(metrics) = self.take_snapshot() {
+                return Ok(metrics);
+            }
+            match self.connection() {
+                Connection::Incompatible(reason) => return Err(reason),
+                Connection::Reconnecting(reason) if start.elapsed() >= timeout => {
+                    return Err(reason);
+                }
+                _ if start.elapsed() >= timeout => return Err("timed out".to_string()),
+                _ => thread::sleep(Duration::from_millis(50)),
+            }
+        }
+    }
+}
+
+// One connection, from the handshake until it fails
+fn receive(address: &str, state: &Mutex<ClientState>) -> io::Result<()> {
+    let mut stream = open(address)?;
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    stream.write_all(&encode(&Message::hello())?)?;
+    expect_hello(read_message(&mut stream)?)?;
+    stream.set_read_timeout(Some(READ_TIMEOUT))?;
+    loop {
+        match read_message(&mut stream) {
+            Ok(Message::Snapshot { metrics }) => {
+                let mut state = state.lock().unwrap();
+                state.connection = Connection::Connected;
+                state.latest = Some((*metrics).into_owned());
+            }
+            Ok(Message::Hello { .. }) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+// Tries each address the name resolves to, without waiting minutes on one
+// that never answers
+fn open(address: &str) -> io::Result<TcpStream> {
+    let mut last_error = io::Error::new(io::ErrorKind::NotFound, "no address to connect to");
+    for addr in address.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, HANDSHAKE_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_error = err,
+        }
+    }
+    Err(last_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_round_trip() {
+        let metrics = SystemInfo {
+            hostname: "db1".to_string(),
+            uptime: Duration::from_secs(42),
+            ..SystemInfo::default()
+        };
+        let frame = encode(&Message::Snapshot {
+            metrics: Box::new(Cow::Borrowed(&metrics)),
+        })
+        .unwrap();
+        assert_eq!(
+            u32::from_be_bytes(frame[..4].try_into().unwrap()) as usize,
+            frame.len() - 4
+        );
+        let Message::Snapshot { metrics: received } = read_message(&mut &frame[..]).unwrap() else {
+            panic!("expected a snapshot");
+        };
+        assert_eq!(received.hostname, "db1");
+        assert_eq!(received.uptime, Duration::from_secs(42));
+        assert_eq!(received.processes.len(), metrics.processes.len());
+        let mut oversized = &(MAX_FRAME as u32 + 1).to_be_bytes()[..];
+        assert!(read_message(&mut oversized).is_err());
+    }
+
+    #[test]
+    fn streams_snapshots_to_a_client() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        thread::spawn(move || serve(listener, Duration::from_millis(50)));
+        let client = Client::connect(&address);
+        let metrics = client.wait_for_snapshot(Duration::from_secs(5)).unwrap();
+        assert_eq!(metrics.hostname, SystemInfo::default().hostname);
+        assert_eq!(client.connection(), Connection::Connected);
+    }
+
+    #[test]
+    fn refuses_another_protocol_version() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || serve(listener, Duration::from_millis(50)));
+        let mut stream = TcpStream::connect(address).unwrap();
+        let hello = Message::Hello {
+            protocol: PROTOCOL_VERSION + 1,
+            version: "9.9.9".to_string(),
+        };
+        stream.write_all(&encode(&hello).unwrap()).unwrap();
+        // The server answers with its own version, then hangs up
+        assert!(matches!(
+            read_message(&mut stream).unwrap(),
+            Message::Hello {
+                protocol: PROTOCOL_VERSION,
+                ..
+            }
+        ));
+        let mut rest = Vec::new();
+        stream.read_to_end(&mut rest).unwrap();
+        assert!(rest.is_empty());
+    }
+}
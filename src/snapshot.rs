@@ -0,0 +1,122 @@
+use std::{
+    env, fs, io,
+    path::PathBuf,
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error as _};
+
+use crate::sys_info::SystemInfo;
+
+/// A `Duration` as fractional seconds.
+pub mod seconds {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(duration.as_secs_f64())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Duration::try_from_secs_f64(f64::deserialize(deserializer)?).map_err(D::Error::custom)
+    }
+}
+
+pub mod optional_seconds {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(
+        duration: &Option<Duration>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        duration
+            .map(|duration| duration.as_secs_f64())
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<Duration>, D::Error> {
+        Option::<f64>::deserialize(deserializer)?
+            .map(|secs| Duration::try_from_secs_f64(secs).map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+/// An `Instant` as the seconds since it, the only way a reading of the
+/// monotonic clock means anything outside this process.
+pub mod seconds_since {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(instant: &Instant, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(instant.elapsed().as_secs_f64())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Instant, D::Error> {
+        let age = seconds::deserialize(deserializer)?;
+        let now = Instant::now();
+        Ok(now.checked_sub(age).unwrap_or(now))
+    }
+}
+
+/// `$XDG_DATA_HOME/xtop`, or the current directory when there is no home
+/// to put it under.
+pub fn export_dir() -> PathBuf {
+    env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map_or_else(PathBuf::new, |base| base.join("xtop"))
+}
+
+/// e.g. `xtop-2024-05-01T12-33-05.json`, with no colons for filesystems
+/// that refuse them.
+pub fn file_name(time: DateTime<Local>) -> String {
+    time.format("xtop-%Y-%m-%dT%H-%M-%S.json").to_string()
+}
+
+/// Serializes `metrics` as they are now, then leaves writing them into
+/// `dir` to a thread so a long process list doesn't hold up drawing.
+pub fn export(metrics: &SystemInfo, dir: PathBuf) -> JoinHandle<io::Result<PathBuf>> {
+    let json = serde_json::to_vec_pretty(metrics);
+    let path = dir.join(file_name(Local::now()));
+    thread::spawn(move || {
+        let json = json.map_err(io::Error::other)?;
+        if !dir.as_os_str().is_empty() {
+            fs::create_dir_all(&dir)?;
+        }
+        fs::write(&path, json)?;
+        Ok(path)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn names_files_by_local_time() {
+        let time = Local.with_ymd_and_hms(2024, 5, 1, 12, 33, 5).unwrap();
+        assert_eq!(file_name(time), "xtop-2024-05-01T12-33-05.json");
+    }
+
+    #[test]
+    fn writes_current_metrics_without_history() {
+        let metrics = SystemInfo {
+            uptime: Duration::from_millis(90_500),
+            ..SystemInfo::default()
+        };
+        let dir = env::temp_dir().join(format!("xtop-snapshot-{}", std::process::id()));
+        let path = export(&metrics, dir.clone()).join().unwrap().unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(json["uptime"], 90.5);
+        assert_eq!(json["hostname"], metrics.hostname.as_str());
+        assert!(json["processes"].is_array());
+        assert!(json["secs_since_update"].is_number());
+        assert!(json.get("cpu_history").is_none());
+    }
+}
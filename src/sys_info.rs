@@ -1,18 +1,496 @@
-use std::time::{Duration, Instant};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
 
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+use crate::{history::History, snapshot};
+
+#[cfg(target_os = "linux")]
+mod activity;
+#[cfg(target_os = "linux")]
+mod battery;
+#[cfg(target_os = "linux")]
+mod cgroup;
+#[cfg(target_os = "linux")]
+mod cpufreq;
+#[cfg(target_os = "linux")]
+mod drivetemp;
+#[cfg(target_os = "linux")]
+mod environ;
+#[cfg(target_os = "linux")]
+mod fds;
+mod gpu;
+#[cfg(target_os = "linux")]
+mod interrupts;
+#[cfg(target_os = "linux")]
+mod kernel;
+#[cfg(target_os = "linux")]
+mod kmsg;
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(target_os = "linux")]
+mod mounts;
+#[cfg(target_os = "linux")]
+mod netlink;
+#[cfg(target_os = "linux")]
+mod open_files;
+#[cfg(target_os = "linux")]
+mod pools;
+#[cfg(target_os = "linux")]
+mod pressure;
+#[cfg(unix)]
+mod priority;
+// Only the macOS and Windows collectors list processes themselves
+#[cfg(any(target_os = "macos", windows, test))]
+mod process_table;
+#[cfg(target_os = "linux")]
+mod sensors;
+#[cfg(unix)]
+mod signals;
+#[cfg(not(any(target_os = "macos", windows)))]
+mod simulated;
+#[cfg(target_os = "linux")]
+mod stat;
+#[cfg(target_os = "linux")]
+mod statm;
+#[cfg(target_os = "linux")]
+mod status;
+#[cfg(target_os = "linux")]
+mod sysfs;
+#[cfg(target_os = "linux")]
+mod systemd;
+#[cfg(target_os = "linux")]
+mod wifi;
+#[cfg(windows)]
+mod windows;
+
+#[cfg(target_os = "macos")]
+use macos as platform;
+#[cfg(not(any(target_os = "macos", windows)))]
+use simulated as platform;
+#[cfg(windows)]
+use windows as platform;
+
+// Sections off screen are refreshed only on every this many ticks
+const BACKGROUND_TICKS: u32 = 5;
+
+/// The parts of a refresh too costly to repeat every tick for a view that
+/// doesn't show them. Cheap global figures such as CPU, memory and load are
+/// always refreshed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    // Per-process memory, priority, descriptors and containers
+    ProcessDetails,
+    Sensors,
+    Services,
+    // Mounts, pools and drive temperatures
+    Disks,
+    // Link and Wi-Fi details of the network interfaces
+    Interfaces,
+}
+
+impl Section {
+    pub const ALL: [Section; 5] = [
+        Section::ProcessDetails,
+        Section::Sensors,
+        Section::Services,
+        Section::Disks,
+        Section::Interfaces,
+    ];
+}
+
+/// Which sections are due, counting the ticks since each was last refreshed.
+#[derive(Debug)]
+struct Schedule {
+    ticks_since: [u32; Section::ALL.len()],
+}
+
+impl Default for Schedule {
+    fn default() -> Self {
+        // Everything is due on the first tick
+        Self {
+            ticks_since: [BACKGROUND_TICKS; Section::ALL.len()],
+        }
+    }
+}
+
+impl Schedule {
+    // Counts a tick, returning the sections due on it: those visible, and
+    // the rest once they have waited BACKGROUND_TICKS
+    fn tick(&mut self, visible: &[Section]) -> Vec<Section> {
+        for ticks in &mut self.ticks_since {
+            *ticks += 1;
+        }
+        Section::ALL
+            .into_iter()
+            .filter(|section| {
+                visible.contains(section) || self.ticks_since[*section as usize] >= BACKGROUND_TICKS
+            })
+            .collect()
+    }
+
+    // The visible sections that missed the latest tick
+    fn stale(&self, visible: &[Section]) -> Vec<Section> {
+        visible
+            .iter()
+            .copied()
+            .filter(|section| self.ticks_since[*section as usize] > 0)
+            .collect()
+    }
+
+    fn refreshed(&mut self, section: Section) {
+        self.ticks_since[section as usize] = 0;
+    }
+}
+
+/// Each process's share of the CPU between samples: the change in its
+/// utime + stime over the change in jiffies spent by all CPUs together.
+/// Absolute tick counts say nothing about the present on their own.
+#[cfg(target_os = "linux")]
+#[derive(Debug, Default)]
+pub struct ProcCpuTracker {
+    // All CPUs' jiffies at the previous sample and at this one
+    previous_total: Option<u64>,
+    total: Option<u64>,
+    // Each process's ticks when last sampled, keyed by PID and start time
+    // so that a reused PID doesn't inherit the old process's ticks
+    ticks: HashMap<(u32, u64), u64>,
+    // The processes sampled since `begin`
+    seen: std::collections::HashSet<(u32, u64)>,
+}
+
+#[cfg(target_os = "linux")]
+impl ProcCpuTracker {
+    /// Starts a sample taken when all CPUs together had spent `total`
+    /// jiffies since boot.
+    pub fn begin(&mut self, total: u64) {
+        self.previous_total = self.total.replace(total);
+        self.seen.clear();
+    }
+
+    /// The percentage of the whole machine's CPU time the process used
+    /// since it was last sampled; times the core count, of a single core.
+    /// None the first time a process is seen.
+    pub fn usage(&mut self, pid: u32, start_time: u64, ticks: u64) -> Option<f64> {
+        let key = (pid, start_time);
+        self.seen.insert(key);
+        let previous = self.ticks.insert(key, ticks)?;
+        let elapsed = self.total?.checked_sub(self.previous_total?)?;
+        if elapsed == 0 {
+            return None;
+        }
+        Some((ticks.saturating_sub(previous) as f64 / elapsed as f64 * 100.0).min(100.0))
+    }
+
+    /// Ends a sample, forgetting the processes that have exited.
+    pub fn finish(&mut self) {
+        let seen = &self.seen;
+        self.ticks.retain(|key, _| seen.contains(key));
+    }
+}
+
+pub struct Collector {
+    platform: platform::Collector,
+    schedule: Schedule,
+    gpu: gpu::GpuCollector,
+    #[cfg(target_os = "linux")]
+    pressure: pressure::PressureCollector,
+    #[cfg(target_os = "linux")]
+    containers: cgroup::ContainerResolver,
+    #[cfg(target_os = "linux")]
+    fds: fds::FdCounter,
+    #[cfg(target_os = "linux")]
+    cpu: ProcCpuTracker,
+    #[cfg(target_os = "linux")]
+    systemd: systemd::SystemdCollector,
+    #[cfg(target_os = "linux")]
+    pools: pools::PoolCollector,
+    #[cfg(target_os = "linux")]
+    interrupts: interrupts::InterruptCollector,
+    #[cfg(target_os = "linux")]
+    activity: activity::ActivityCollector,
+    #[cfg(target_os = "linux")]
+    oom: kmsg::OomWatcher,
+}
+
+impl Default for Collector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Collector {
+    pub fn new() -> Self {
+        Self {
+            platform: platform::Collector::new(),
+            schedule: Schedule::default(),
+            gpu: gpu::GpuCollector::default(),
+            #[cfg(target_os = "linux")]
+            pressure: pressure::PressureCollector::default(),
+            #[cfg(target_os = "linux")]
+            containers: cgroup::ContainerResolver::default(),
+            #[cfg(target_os = "linux")]
+            fds: fds::FdCounter::default(),
+            #[cfg(target_os = "linux")]
+            cpu: ProcCpuTracker::default(),
+            #[cfg(target_os = "linux")]
+            systemd: systemd::SystemdCollector::default(),
+            #[cfg(target_os = "linux")]
+            pools: pools::PoolCollector::default(),
+            #[cfg(target_os = "linux")]
+            interrupts: interrupts::InterruptCollector::default(),
+            #[cfg(target_os = "linux")]
+            activity: activity::ActivityCollector::default(),
+            #[cfg(target_os = "linux")]
+            oom: kmsg::OomWatcher::default(),
+        }
+    }
+
+    /// Refreshes everything, as for a snapshot or a remote viewer.
+    pub fn refresh(&mut self, metrics: &mut SystemInfo) {
+        self.refresh_for(metrics, &Section::ALL);
+    }
+
+    /// Refreshes the global figures and the `visible` sections, leaving the
+    /// others as they were unless they are due a background refresh.
+    pub fn refresh_for(&mut self, metrics: &mut SystemInfo, visible: &[Section]) {
+        self.platform.refresh(metrics);
+        self.gpu.refresh(&mut metrics.gpus);
+        #[cfg(target_os = "linux")]
+        {
+            let cores = cpufreq::read_cpufreq();
+            metrics.cpu_frequency_per_core = cores.iter().map(|core| core.frequency).collect();
+            metrics.cpu_governors = cores.into_iter().map(|core| core.governor).collect();
+            let frequencies: Vec<u64> = metrics
+                .cpu_frequency_per_core
+                .iter()
+                .flatten()
+                .copied()
+                .collect();
+            if !frequencies.is_empty() {
+                metrics.cpu_frequency =
+                    Some(frequencies.iter().sum::<u64>() / frequencies.len() as u64);
+            }
+            metrics.battery = battery::read_battery();
+            metrics.pressure = self.pressure.refresh();
+            metrics.interrupts = self.interrupts.refresh();
+            metrics.kernel = kernel::read_kernel_stats();
+            metrics.oom_kills = self.oom.refresh();
+            let activity = self.activity.refresh();
+            metrics.context_switches_per_sec = activity.map(|(switches, _)| switches);
+            metrics.forks_per_sec = activity.map(|(_, forks)| forks);
+        }
+        for section in self.schedule.tick(visible) {
+            self.refresh_section(metrics, section);
+        }
+    }
+
+    /// Brings the `visible` sections up to date straight away, as when the
+    /// view showing them has just been switched to. Returns whether any
+    /// were behind.
+    pub fn catch_up(&mut self, metrics: &mut SystemInfo, visible: &[Section]) -> bool {
+        let stale = self.schedule.stale(visible);
+        for &section in &stale {
+            self.refresh_section(metrics, section);
+        }
+        !stale.is_empty()
+    }
+
+    fn refresh_section(&mut self, metrics: &mut SystemInfo, section: Section) {
+        self.schedule.refreshed(section);
+        #[cfg(target_os = "linux")]
+        match section {
+            // Made-up PIDs would read the details of real processes
+            Section::ProcessDetails if metrics.simulated => {}
+            Section::ProcessDetails => {
+                self.fds.begin_refresh();
+                let total_jiffies = stat::read_total_jiffies();
+                if let Some(total) = total_jiffies {
+                    self.cpu.begin(total);
+                }
+                // The other platforms report per-process CPU of one core
+                let cores = metrics.cpu_count.max(1) as f64;
+                for process in &mut metrics.processes {
+                    process.container = self.containers.resolve(process.pid);
+                    process.fd_count = self.fds.count(process.pid);
+                    if let Some(statm) = statm::read_statm(process.pid) {
+                        process.virt_kb = statm.virt_kb;
+                        process.rss_kb = statm.rss_kb;
+                        process.shared_kb = Some(statm.shared_kb);
+                        process.memory_usage = statm.rss_kb / 1024;
+                        process.memory_percent =
+                            statm.rss_kb as f64 / 1024.0 / metrics.memory_total.max(1) as f64
+                                * 100.0;
+                    }
+                    process.swap_kb = status::read_swap_kb(process.pid).unwrap_or(0);
+                    if let Some(stat) = stat::read_process_stat(process.pid) {
+                        process.priority = stat.priority;
+                        process.nice = stat.nice;
+                        if total_jiffies.is_some()
+                            && let Some(share) =
+                                self.cpu.usage(process.pid, stat.start_time, stat.cpu_ticks)
+                        {
+                            process.cpu_usage = share * cores;
+                        }
+                    }
+                }
+                if total_jiffies.is_some() {
+                    self.cpu.finish();
+                }
+            }
+            Section::Sensors => metrics.sensors = sensors::read_sensors(),
+            Section::Services => metrics.services = self.systemd.refresh(),
+            Section::Disks => {
+                metrics.disks = mounts::read_mounts();
+                metrics.pools = self.pools.refresh(&mut metrics.disks);
+                drivetemp::read_drive_temperatures(&mut metrics.disks);
+            }
+            Section::Interfaces => {
+                for iface in &mut metrics.network_interfaces {
+                    netlink::read_link_info(iface);
+                    iface.wifi = wifi::read_wifi(&iface.name);
+                }
+            }
+        }
+        #[cfg(not(target_os = "linux"))]
+        let _ = metrics;
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub use environ::read_environment;
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_environment(_pid: u32) -> std::io::Result<Vec<(String, String)>> {
+    Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+}
+
+// Descriptors listed per process; the rest are only counted
+pub const OPEN_FILES_LIMIT: usize = 200;
+
+/// What an open file descriptor points at, in the order they are listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FdKind {
+    File,
+    Socket,
+    Pipe,
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenFile {
+    pub fd: u32,
+    pub kind: FdKind,
+    // The path, or a description such as "TCP 127.0.0.1:631 LISTEN"
+    pub target: String,
+}
+
+/// The lowest-numbered `OPEN_FILES_LIMIT` descriptors of a process, sorted
+/// by kind, and how many it has open in all.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OpenFiles {
+    pub files: Vec<OpenFile>,
+    pub total: usize,
+}
+
+#[cfg(target_os = "linux")]
+pub use open_files::read_open_files;
+
+#[cfg(not(target_os = "linux"))]
+pub fn read_open_files(_pid: u32) -> std::io::Result<OpenFiles> {
+    Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+}
+
+#[cfg(unix)]
+pub use signals::{send_signal, signal_number};
+
+#[cfg(not(unix))]
+pub fn send_signal(_pid: u32, _signal: Signal) -> std::io::Result<()> {
+    Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+}
+
+#[cfg(not(unix))]
+pub fn signal_number(_signal: Signal) -> Option<i32> {
+    None
+}
+
+#[cfg(unix)]
+pub use priority::{get_nice, set_nice};
+
+#[cfg(not(unix))]
+pub fn get_nice(_pid: u32) -> std::io::Result<i32> {
+    Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+}
+
+#[cfg(not(unix))]
+pub fn set_nice(_pid: u32, _nice: i32) -> std::io::Result<()> {
+    Err(std::io::Error::from(std::io::ErrorKind::Unsupported))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Signal {
+    Hup,
+    Int,
+    Quit,
+    Kill,
+    Usr1,
+    Usr2,
+    Term,
+    Cont,
+    Stop,
+    Tstp,
+}
+
+impl Signal {
+    /// The signals offered by the signal picker, in the order shown.
+    pub const ALL: [Signal; 10] = [
+        Signal::Hup,
+        Signal::Int,
+        Signal::Quit,
+        Signal::Kill,
+        Signal::Usr1,
+        Signal::Usr2,
+        Signal::Term,
+        Signal::Cont,
+        Signal::Stop,
+        Signal::Tstp,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Signal::Hup => "SIGHUP",
+            Signal::Int => "SIGINT",
+            Signal::Quit => "SIGQUIT",
+            Signal::Kill => "SIGKILL",
+            Signal::Usr1 => "SIGUSR1",
+            Signal::Usr2 => "SIGUSR2",
+            Signal::Term => "SIGTERM",
+            Signal::Cont => "SIGCONT",
+            Signal::Stop => "SIGSTOP",
+            Signal::Tstp => "SIGTSTP",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemInfo {
     // System Information
     pub hostname: String,
     pub kernel_version: String,
     pub os_name: String,
+    #[serde(with = "snapshot::seconds")]
     pub uptime: Duration,
     // CPU Information
     pub cpu_count: usize,
     pub cpu_usage_per_core: Vec<u64>,
     pub cpu_total_usage: u64,
-    pub cpu_frequency: u64, // MHz
-    pub cpu_temperature: f32,
+    pub cpu_frequency: Option<u64>, // MHz
+    // Per-core cpufreq state, empty without a cpufreq driver
+    pub cpu_frequency_per_core: Vec<Option<u64>>, // MHz
+    pub cpu_governors: Vec<Option<String>>,
+    pub cpu_temperature: Option<f32>,
     pub cpu_model: String,
     // Memory Information
     pub memory_total: u64,     // MB
@@ -26,36 +504,204 @@ pub struct SystemInfo {
     pub swap_free: u64,        // MB
     // Disk Information
     pub disks: Vec<DiskInfo>,
+    pub pools: Vec<StoragePool>,
     // Network Information
     pub network_interfaces: Vec<NetworkInterface>,
     pub total_rx: u64, // KB/s
     pub total_tx: u64, // KB/s
+    // GPU Information
+    pub gpus: Vec<GpuInfo>,
+    // Power Information
+    pub battery: Option<BatteryInfo>,
+    // Sensor Information
+    pub sensors: Vec<SensorReading>,
+    // Service Information, None when systemd is not available
+    pub services: Option<Vec<ServiceInfo>>,
     // Process Information
     pub processes: Vec<ProcessInfo>,
+    // Whether the processes are made up rather than read from the system,
+    // so their PIDs may belong to real processes nobody picked
+    #[serde(default)]
+    pub simulated: bool,
     pub process_count: usize,
     pub thread_count: usize,
+    // Scheduler activity, None until two samples have been taken
+    pub context_switches_per_sec: Option<u64>,
+    pub forks_per_sec: Option<u64>,
     // Historical Data
-    pub cpu_history: Vec<u64>,
-    pub memory_history: Vec<u64>,
-    pub net_rx_history: Vec<u64>,
-    pub net_tx_history: Vec<u64>,
+    #[serde(skip)]
+    pub cpu_history: History<u64>,
+    // One per core, in the order of cpu_usage_per_core
+    #[serde(skip)]
+    pub cpu_core_history: Vec<History<u64>>,
+    #[serde(skip)]
+    pub memory_history: History<u64>,
+    // Whole degrees Celsius, leaving out implausible sensor glitches
+    #[serde(skip)]
+    pub cpu_temp_history: History<u64>,
+    #[serde(skip)]
+    pub net_rx_history: History<u64>,
+    #[serde(skip)]
+    pub net_tx_history: History<u64>,
+    // Keyed by name, for the interfaces currently present
+    #[serde(skip)]
+    pub interface_history: HashMap<String, InterfaceHistory>,
+    // Summed over all disks, MB/s
+    #[serde(skip)]
+    pub disk_read_history: History<u64>,
+    #[serde(skip)]
+    pub disk_write_history: History<u64>,
+    #[serde(skip)]
+    pub cpu_pressure_history: History<f64>,
+    #[serde(skip)]
+    pub memory_pressure_history: History<f64>,
+    #[serde(skip)]
+    pub io_pressure_history: History<f64>,
+    #[serde(skip)]
+    pub gpu_history: Vec<History<u64>>,
+    #[serde(skip)]
+    pub context_switch_history: History<u64>,
+    #[serde(skip)]
+    pub fork_history: History<u64>,
     // Load
     pub load_average: LoadAverage,
+    // Pressure Stall Information, None when the kernel lacks PSI
+    pub pressure: Option<SystemPressure>,
+    // Interrupt rates, None until two samples have been taken
+    pub interrupts: Option<InterruptStats>,
+    // Kernel table usage, None where /proc/sys is unavailable
+    pub kernel: Option<KernelStats>,
+    // Processes the OOM killer ended since the last refresh, where the
+    // kernel log is readable
+    pub oom_kills: Vec<OomKill>,
     // Update Timestamp
+    #[serde(rename = "secs_since_update", with = "snapshot::seconds_since")]
     pub last_update: Instant,
 }
 
-#[derive(Debug, Clone)]
+impl SystemInfo {
+    /// Nothing read yet, unlike the sample figures of `default`; what the
+    /// collector can't fill in stays empty.
+    pub fn empty() -> Self {
+        Self {
+            hostname: String::new(),
+            kernel_version: String::new(),
+            os_name: String::new(),
+            uptime: Duration::ZERO,
+            cpu_count: 0,
+            cpu_usage_per_core: Vec::new(),
+            cpu_total_usage: 0,
+            cpu_frequency: None,
+            cpu_frequency_per_core: Vec::new(),
+            cpu_governors: Vec::new(),
+            cpu_temperature: None,
+            cpu_model: String::new(),
+            memory_total: 0,
+            memory_used: 0,
+            memory_free: 0,
+            memory_available: 0,
+            memory_cached: 0,
+            memory_buffers: 0,
+            swap_total: 0,
+            swap_used: 0,
+            swap_free: 0,
+            disks: Vec::new(),
+            pools: Vec::new(),
+            network_interfaces: Vec::new(),
+            total_rx: 0,
+            total_tx: 0,
+            gpus: Vec::new(),
+            battery: None,
+            sensors: Vec::new(),
+            services: None,
+            processes: Vec::new(),
+            simulated: false,
+            process_count: 0,
+            thread_count: 0,
+            context_switches_per_sec: None,
+            forks_per_sec: None,
+            cpu_history: History::default(),
+            cpu_core_history: Vec::new(),
+            memory_history: History::default(),
+            cpu_temp_history: History::default(),
+            net_rx_history: History::default(),
+            net_tx_history: History::default(),
+            interface_history: HashMap::new(),
+            disk_read_history: History::default(),
+            disk_write_history: History::default(),
+            gpu_history: Vec::new(),
+            context_switch_history: History::default(),
+            fork_history: History::default(),
+            cpu_pressure_history: History::default(),
+            memory_pressure_history: History::default(),
+            io_pressure_history: History::default(),
+            load_average: LoadAverage {
+                one: 0.0,
+                five: 0.0,
+                fifteen: 0.0,
+            },
+            pressure: None,
+            interrupts: None,
+            kernel: None,
+            oom_kills: Vec::new(),
+            last_update: Instant::now(),
+        }
+    }
+
+    /// Governors in use and how many cores run each, most common first.
+    pub fn governor_counts(&self) -> Vec<(&str, usize)> {
+        let mut counts: Vec<(&str, usize)> = Vec::new();
+        for governor in self.cpu_governors.iter().flatten() {
+            match counts.iter_mut().find(|(name, _)| name == governor) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((governor, 1)),
+            }
+        }
+        counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        counts
+    }
+
+    /// Takes on everything in `fresh`, such as a snapshot from a remote
+    /// host, except the histories, which carry on from those kept here.
+    pub fn update_from(&mut self, mut fresh: SystemInfo) {
+        fresh.cpu_history = std::mem::take(&mut self.cpu_history);
+        fresh.cpu_core_history = std::mem::take(&mut self.cpu_core_history);
+        fresh.memory_history = std::mem::take(&mut self.memory_history);
+        fresh.cpu_temp_history = std::mem::take(&mut self.cpu_temp_history);
+        fresh.net_rx_history = std::mem::take(&mut self.net_rx_history);
+        fresh.net_tx_history = std::mem::take(&mut self.net_tx_history);
+        fresh.interface_history = std::mem::take(&mut self.interface_history);
+        fresh.disk_read_history = std::mem::take(&mut self.disk_read_history);
+        fresh.disk_write_history = std::mem::take(&mut self.disk_write_history);
+        fresh.cpu_pressure_history = std::mem::take(&mut self.cpu_pressure_history);
+        fresh.memory_pressure_history = std::mem::take(&mut self.memory_pressure_history);
+        fresh.io_pressure_history = std::mem::take(&mut self.io_pressure_history);
+        fresh.gpu_history = std::mem::take(&mut self.gpu_history);
+        fresh.context_switch_history = std::mem::take(&mut self.context_switch_history);
+        fresh.fork_history = std::mem::take(&mut self.fork_history);
+        *self = fresh;
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiskInfo {
     pub name: String,
     pub mount_point: String,
-    pub total: u64,       // GB
-    pub used: u64,        // GB
-    pub free: u64,        // GB
-    pub usage: u64,       // Percentage
-    pub read_speed: u64,  // MB/s
-    pub write_speed: u64, // MB/s
-    pub device_type: String,
+    pub total: u64,               // GB
+    pub used: u64,                // GB
+    pub free: u64,                // GB
+    pub usage: u64,               // Percentage
+    pub read_speed: Option<u64>,  // MB/s
+    pub write_speed: Option<u64>, // MB/s
+    pub device_type: String,      // NVMe, SSD or HDD; empty when not block-backed
+    pub fs_type: String,
+    pub inodes_total: u64,
+    pub inodes_used: u64,
+    // None when the filesystem has no fixed inode table (btrfs, most network filesystems)
+    pub inodes_usage_pct: Option<u64>,
+    pub temperature: Option<f32>,
+    // btrfs filesystem or ZFS pool the mount is a subvolume/dataset of
+    pub pool: Option<String>,
 }
 
 impl Default for DiskInfo {
@@ -67,14 +713,49 @@ impl Default for DiskInfo {
             used: 0,
             free: 0,
             usage: 0,
-            read_speed: 0,
-            write_speed: 0,
+            read_speed: None,
+            write_speed: None,
             device_type: "".to_string(),
+            fs_type: "".to_string(),
+            inodes_total: 0,
+            inodes_used: 0,
+            inodes_usage_pct: None,
+            temperature: None,
+            pool: None,
         }
     }
 }
 
-#[derive(Debug, Clone)]
+// Memory-backed and image filesystems that would otherwise flood the Disks
+// view, e.g. one squashfs loop mount per installed snap.
+const PSEUDO_FILESYSTEMS: [&str; 6] = [
+    "tmpfs", "devtmpfs", "ramfs", "overlay", "squashfs", "efivarfs",
+];
+
+// Space of a btrfs filesystem or ZFS pool as a whole, which statvfs on any
+// one of its mounts misreports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoragePool {
+    pub name: String,
+    pub kind: String, // btrfs or zfs
+    pub total: u64,   // GB
+    pub used: u64,    // GB
+    pub free: u64,    // GB
+    pub usage: u64,   // Percentage
+}
+
+impl DiskInfo {
+    pub fn inode_usage(total: u64, free: u64) -> Option<u64> {
+        let used = total.saturating_sub(free);
+        (total > 0).then(|| used * 100 / total)
+    }
+
+    pub fn is_pseudo(&self) -> bool {
+        PSEUDO_FILESYSTEMS.contains(&self.fs_type.as_str()) || self.name.starts_with("loop")
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NetworkInterface {
     pub name: String,
     pub rx_bytes: u64,
@@ -84,9 +765,153 @@ pub struct NetworkInterface {
     pub ip_address: String,
     pub mac_address: String,
     pub status: String,
+    pub link_speed: Option<u64>, // Mb/s
+    pub mtu: Option<u32>,
+    pub duplex: Option<String>,
+    pub wifi: Option<WifiInfo>,
+}
+
+/// Receive and transmit rates of one network interface, KB/s.
+#[derive(Debug, Clone, Default)]
+pub struct InterfaceHistory {
+    pub rx: History<u64>,
+    pub tx: History<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WifiInfo {
+    // None when the card is not associated with a network
+    pub ssid: Option<String>,
+    pub quality: Option<u64>, // Percentage
+    pub signal_dbm: Option<i32>,
+    pub bitrate: Option<u64>, // Mb/s
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GpuInfo {
+    pub name: String,
+    pub vendor: String,
+    pub utilization: Option<u64>,  // Percentage
+    pub memory_used: Option<u64>,  // MB
+    pub memory_total: Option<u64>, // MB
+    pub temperature: Option<f32>,
+    pub power_draw: Option<f64>, // W
+    pub clock: Option<u64>,      // MHz
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatteryInfo {
+    pub capacity: u64, // Percentage
+    pub status: BatteryStatus,
+    pub power: Option<f64>, // W
+    // Time to empty while discharging, time to full while charging
+    #[serde(with = "snapshot::optional_seconds")]
+    pub time_remaining: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum BatteryStatus {
+    Charging,
+    Discharging,
+    Full,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PressureInfo {
+    // Percentage of time some tasks were stalled
+    pub some_avg10: f64,
+    pub some_avg60: f64,
+    pub some_avg300: f64,
+    // Percentage of time all non-idle tasks were stalled
+    pub full_avg10: f64,
+    pub full_avg60: f64,
+    pub full_avg300: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemPressure {
+    pub cpu: PressureInfo,
+    pub memory: PressureInfo,
+    pub io: PressureInfo,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OomKill {
+    pub pid: u32,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KernelStats {
+    pub file_handles: Option<(u64, u64)>, // (allocated, max)
+    pub ptys: Option<u64>,
+    pub pty_max: Option<u64>,
+    pub entropy: Option<u64>, // bits
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterruptStats {
+    pub irqs_per_sec: u64,
+    pub softirqs_per_sec: u64,
+    // Busiest IRQ lines, highest rate first
+    pub top: Vec<IrqSource>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IrqSource {
+    pub irq: String,
+    pub name: String,
+    pub per_sec: u64,
+    pub per_cpu: Vec<u64>, // Per second
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SensorReading {
+    pub chip: String,
+    pub label: String,
+    pub kind: SensorKind,
+    pub value: f64,
+    pub critical: Option<f64>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SensorKind {
+    Temperature, // °C
+    Fan,         // RPM
+    Voltage,     // V
+    Power,       // W
+}
+
+impl SensorKind {
+    pub fn unit(&self) -> &'static str {
+        match self {
+            SensorKind::Temperature => "°C",
+            SensorKind::Fan => "RPM",
+            SensorKind::Voltage => "V",
+            SensorKind::Power => "W",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceInfo {
+    pub name: String,
+    pub description: String,
+    pub active_state: String,
+    pub sub_state: String,
+    pub main_pid: Option<u32>,
+    pub cpu_usage: Option<f64>,    // Percentage
+    pub memory_usage: Option<u64>, // MB
+}
+
+impl ServiceInfo {
+    pub fn is_failed(&self) -> bool {
+        self.active_state == "failed"
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessInfo {
     pub pid: u32,
     pub ppid: u32,
@@ -95,19 +920,73 @@ pub struct ProcessInfo {
     pub full_command: String,
     pub user: String,
     pub cpu_usage: f64,      // Percentage
-    pub memory_usage: u64,   // MB
-    pub memory_percent: f64, // Percentage
+    pub memory_usage: u64,   // MB, resident
+    pub memory_percent: f64, // Percentage of RSS
+    pub virt_kb: u64,
+    pub rss_kb: u64,
+    pub shared_kb: Option<u64>,
+    pub swap_kb: u64,
     pub state: ProcessState,
     pub priority: i32,
     pub nice: i32,
     pub threads: u32,
     pub start_time: String,
+    #[serde(with = "snapshot::seconds")]
     pub uptime: Duration,
-    pub read_speed: u64,  // KB/s
-    pub write_speed: u64, // KB/s
+    pub read_speed: Option<u64>,  // KB/s
+    pub write_speed: Option<u64>, // KB/s
+    pub container: Option<String>,
+    pub fd_count: Option<u32>,
+    // Whether the CPU, memory, thread and priority figures could not be
+    // read, so they are placeholders rather than measurements
+    #[serde(default)]
+    pub restricted: bool,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+// kthreadd, the parent of every kernel thread
+const KTHREADD_PID: u32 = 2;
+
+impl ProcessInfo {
+    /// Kernel threads have no command line and are children of kthreadd.
+    pub fn is_kernel_thread(&self) -> bool {
+        self.pid == KTHREADD_PID || (self.ppid == KTHREADD_PID && self.full_command.is_empty())
+    }
+}
+
+#[cfg(test)]
+impl ProcessInfo {
+    /// An idle, sleeping process for tests to adjust.
+    pub fn fixture(pid: u32, ppid: u32, name: &str) -> Self {
+        Self {
+            pid,
+            ppid,
+            name: name.to_string(),
+            command: String::new(),
+            full_command: String::new(),
+            user: "user".to_string(),
+            cpu_usage: 0.0,
+            memory_usage: 0,
+            memory_percent: 0.0,
+            virt_kb: 0,
+            rss_kb: 0,
+            shared_kb: None,
+            swap_kb: 0,
+            state: ProcessState::Sleeping,
+            priority: 20,
+            nice: 0,
+            threads: 1,
+            start_time: String::new(),
+            uptime: Duration::ZERO,
+            read_speed: None,
+            write_speed: None,
+            container: None,
+            fd_count: None,
+            restricted: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum ProcessState {
     Running,
     Sleeping,
@@ -120,27 +999,36 @@ pub enum ProcessState {
     Waking,
     Parked,
     Idle,
+    Suspended,
 }
 
-impl std::fmt::Display for ProcessState {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl ProcessState {
+    /// The one-letter code `ps` shows for the state.
+    pub fn letter(self) -> &'static str {
         match self {
-            ProcessState::Running => write!(f, "R"),
-            ProcessState::Sleeping => write!(f, "S"),
-            ProcessState::Waiting => write!(f, "D"),
-            ProcessState::Zombie => write!(f, "Z"),
-            ProcessState::Stopped => write!(f, "T"),
-            ProcessState::Tracing => write!(f, "t"),
-            ProcessState::Dead => write!(f, "X"),
-            ProcessState::Wakekill => write!(f, "K"),
-            ProcessState::Waking => write!(f, "W"),
-            ProcessState::Parked => write!(f, "P"),
-            ProcessState::Idle => write!(f, "I"),
+            ProcessState::Running => "R",
+            ProcessState::Sleeping => "S",
+            ProcessState::Waiting => "D",
+            ProcessState::Zombie => "Z",
+            ProcessState::Stopped => "T",
+            ProcessState::Tracing => "t",
+            ProcessState::Dead => "X",
+            ProcessState::Wakekill => "K",
+            ProcessState::Waking => "W",
+            ProcessState::Parked => "P",
+            ProcessState::Idle => "I",
+            ProcessState::Suspended => "T",
         }
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+impl std::fmt::Display for ProcessState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.letter())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct LoadAverage {
     pub one: f64,
     pub five: f64,
@@ -153,10 +1041,13 @@ pub enum ProcessSort {
     Name,
     Cpu,
     Memory,
+    MemoryPercent,
     User,
     Time,
     Threads,
     State,
+    Fds,
+    Swap,
 }
 
 impl Default for SystemInfo {
@@ -178,8 +1069,10 @@ impl Default for SystemInfo {
             cpu_count,
             cpu_usage_per_core,
             cpu_total_usage: 45,
-            cpu_frequency: 3600,
-            cpu_temperature: 65.5,
+            cpu_frequency: Some(3600),
+            cpu_frequency_per_core: Vec::new(),
+            cpu_governors: Vec::new(),
+            cpu_temperature: Some(65.5),
             cpu_model: "Intel Core i7-12700K".to_string(),
             memory_total,
             memory_used,
@@ -198,9 +1091,15 @@ impl Default for SystemInfo {
                     used: 256,
                     free: 256,
                     usage: 50,
-                    read_speed: 120,
-                    write_speed: 45,
+                    read_speed: Some(120),
+                    write_speed: Some(45),
                     device_type: "NVMe".to_string(),
+                    fs_type: "ext4".to_string(),
+                    inodes_total: 32_768_000,
+                    inodes_used: 1_245_184,
+                    inodes_usage_pct: Some(3),
+                    temperature: Some(42.0),
+                    pool: None,
                 },
                 DiskInfo {
                     name: "sda".to_string(),
@@ -209,12 +1108,19 @@ impl Default for SystemInfo {
                     used: 512,
                     free: 512,
                     usage: 50,
-                    read_speed: 45,
-                    write_speed: 23,
+                    read_speed: Some(45),
+                    write_speed: Some(23),
                     device_type: "SSD".to_string(),
+                    fs_type: "btrfs".to_string(),
+                    inodes_total: 0,
+                    inodes_used: 0,
+                    inodes_usage_pct: None,
+                    temperature: Some(36.0),
+                    pool: None,
                 },
             ],
 
+            pools: Vec::new(),
             network_interfaces: vec![NetworkInterface {
                 name: "eth0".to_string(),
                 rx_bytes: 1024 * 1024 * 1024,
@@ -224,21 +1130,47 @@ impl Default for SystemInfo {
                 ip_address: "192.168.1.100".to_string(),
                 mac_address: "00:11:22:33:44:55".to_string(),
                 status: "up".to_string(),
+                link_speed: Some(1000),
+                mtu: Some(1500),
+                duplex: Some("full".to_string()),
+                wifi: None,
             }],
             total_rx: 1200,
             total_tx: 450,
+            gpus: Vec::new(),
+            battery: None,
+            sensors: Vec::new(),
+            services: None,
             processes: generate_sample_processes(),
+            simulated: true,
             process_count: 150,
             thread_count: 1200,
-            cpu_history: vec![45, 50, 55, 60, 65, 70, 65, 60, 55, 50, 45, 40],
-            memory_history: vec![50, 52, 54, 56, 58, 60, 62, 64, 66, 68, 70, 72],
-            net_rx_history: vec![800, 850, 900, 950, 1000, 1050, 1100, 1150, 1200],
-            net_tx_history: vec![300, 325, 350, 375, 400, 425, 450, 475, 500],
+            context_switches_per_sec: None,
+            forks_per_sec: None,
+            cpu_history: History::default(),
+            cpu_core_history: Vec::new(),
+            memory_history: History::default(),
+            cpu_temp_history: History::default(),
+            net_rx_history: History::default(),
+            net_tx_history: History::default(),
+            interface_history: HashMap::new(),
+            disk_read_history: History::default(),
+            disk_write_history: History::default(),
+            gpu_history: Vec::new(),
+            context_switch_history: History::default(),
+            fork_history: History::default(),
+            cpu_pressure_history: History::default(),
+            memory_pressure_history: History::default(),
+            io_pressure_history: History::default(),
             load_average: LoadAverage {
                 one: 1.25,
                 five: 1.85,
                 fifteen: 2.15,
             },
+            pressure: None,
+            interrupts: None,
+            kernel: None,
+            oom_kills: Vec::new(),
             last_update: now,
         }
     }
@@ -317,15 +1249,111 @@ fn generate_sample_processes() -> Vec<ProcessInfo> {
             cpu_usage: *cpu,
             memory_usage: *memory,
             memory_percent: (*memory as f64 / 16384.0) * 100.0,
+            virt_kb: *memory * 1024 * 4,
+            rss_kb: *memory * 1024,
+            shared_kb: Some(*memory * 1024 / 8),
+            swap_kb: 0,
             state: *state,
             priority: 20,
             nice: 0,
             threads: (i as u32 + 1) * 2,
             start_time: "10:30:15".to_string(),
             uptime: Duration::from_secs(3600 * i as u64),
-            read_speed: (i as u64 * 10) % 100,
-            write_speed: (i as u64 * 5) % 50,
+            read_speed: Some((i as u64 * 10) % 100),
+            write_speed: Some((i as u64 * 5) % 50),
+            container: None,
+            fd_count: None,
+            restricted: false,
         });
     }
     processes
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refreshes_hidden_sections_in_the_background() {
+        let mut schedule = Schedule::default();
+        let all = schedule.tick(&[]);
+        assert_eq!(all, Section::ALL);
+        for &section in &all {
+            schedule.refreshed(section);
+        }
+        let visible = [Section::Services];
+        for _ in 1..BACKGROUND_TICKS {
+            let due = schedule.tick(&visible);
+            assert_eq!(due, visible);
+            schedule.refreshed(Section::Services);
+        }
+        assert_eq!(schedule.tick(&visible), Section::ALL);
+    }
+
+    #[test]
+    fn leaves_simulated_processes_alone() {
+        let mut collector = Collector::new();
+        let mut metrics = SystemInfo::default();
+        let before: Vec<(u32, u64)> = metrics
+            .processes
+            .iter()
+            .map(|process| (process.pid, process.memory_usage))
+            .collect();
+        collector.refresh_section(&mut metrics, Section::ProcessDetails);
+        let after: Vec<(u32, u64)> = metrics
+            .processes
+            .iter()
+            .map(|process| (process.pid, process.memory_usage))
+            .collect();
+        assert_eq!(after, before);
+        assert!(
+            metrics
+                .processes
+                .iter()
+                .all(|process| process.fd_count.is_none())
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn works_out_cpu_usage_from_tick_deltas() {
+        let mut tracker = ProcCpuTracker::default();
+        tracker.begin(1_000);
+        // Nothing to compare against on the first sample
+        assert_eq!(tracker.usage(10, 5, 400), None);
+        assert_eq!(tracker.usage(11, 5, 50), None);
+        tracker.finish();
+        // 400 jiffies in all; on four cores, 100 of them are a whole core
+        tracker.begin(1_400);
+        assert_eq!(tracker.usage(10, 5, 500), Some(25.0));
+        assert_eq!(tracker.usage(11, 5, 50), Some(0.0));
+        tracker.finish();
+        // Process 11 exits and its PID is reused
+        tracker.begin(1_800);
+        assert_eq!(tracker.usage(10, 5, 540), Some(10.0));
+        assert_eq!(tracker.usage(11, 90, 20), None);
+        tracker.finish();
+        assert_eq!(tracker.ticks.len(), 2);
+        tracker.begin(2_200);
+        assert_eq!(tracker.usage(11, 90, 60), Some(10.0));
+        tracker.finish();
+        // Process 10 has exited
+        assert_eq!(tracker.ticks.len(), 1);
+    }
+
+    #[test]
+    fn catches_up_only_sections_behind() {
+        let mut schedule = Schedule::default();
+        for section in Section::ALL {
+            schedule.refreshed(section);
+        }
+        assert!(schedule.stale(&[Section::Sensors]).is_empty());
+        schedule.tick(&[Section::Services]);
+        schedule.refreshed(Section::Services);
+        // Switching from Services to Sensors
+        assert_eq!(
+            schedule.stale(&[Section::Sensors, Section::Services]),
+            [Section::Sensors]
+        );
+    }
+}
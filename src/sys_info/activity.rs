@@ -0,0 +1,53 @@
+use std::{fs, time::Instant};
+
+#[derive(Default)]
+pub struct ActivityCollector {
+    // (taken at, context switches, forks) since boot
+    last_sample: Option<(Instant, u64, u64)>,
+}
+
+impl ActivityCollector {
+    /// Context switches and forks per second since the previous refresh.
+    pub fn refresh(&mut self) -> Option<(u64, u64)> {
+        let stat = fs::read_to_string("/proc/stat").ok()?;
+        let (context_switches, forks) = parse_counters(&stat)?;
+        let now = Instant::now();
+        let (at, previous_switches, previous_forks) =
+            self.last_sample.replace((now, context_switches, forks))?;
+        let elapsed = now.duration_since(at).as_secs_f64();
+        if elapsed <= 0.0 {
+            return None;
+        }
+        let rate = |current: u64, previous: u64| {
+            (current.saturating_sub(previous) as f64 / elapsed) as u64
+        };
+        Some((
+            rate(context_switches, previous_switches),
+            rate(forks, previous_forks),
+        ))
+    }
+}
+
+// "processes" counts forks since boot, not running processes
+fn parse_counters(stat: &str) -> Option<(u64, u64)> {
+    let counter = |prefix: &str| {
+        stat.lines()
+            .find_map(|line| line.strip_prefix(prefix))
+            .and_then(|value| value.trim().parse().ok())
+    };
+    Some((counter("ctxt ")?, counter("processes ")?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ctxt_and_processes() {
+        assert_eq!(
+            parse_counters("cpu  1 2 3\nctxt 225755\nbtime 1\nprocesses 7088\n"),
+            Some((225755, 7088))
+        );
+        assert_eq!(parse_counters("ctxt 1\n"), None);
+    }
+}
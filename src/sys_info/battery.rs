@@ -0,0 +1,66 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use super::{
+    BatteryInfo, BatteryStatus,
+    sysfs::{read_string, read_u64},
+};
+
+pub fn read_battery() -> Option<BatteryInfo> {
+    let mut batteries: Vec<PathBuf> = fs::read_dir("/sys/class/power_supply")
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("BAT"))
+        .map(|entry| entry.path())
+        .collect();
+    batteries.sort();
+    batteries.first().and_then(|path| parse_battery(path))
+}
+
+fn parse_battery(path: &Path) -> Option<BatteryInfo> {
+    let capacity = read_u64(&path.join("capacity"))?.min(100);
+    let status = match read_string(&path.join("status")).as_deref() {
+        Some("Charging") => BatteryStatus::Charging,
+        Some("Discharging") => BatteryStatus::Discharging,
+        Some("Full") => BatteryStatus::Full,
+        _ => BatteryStatus::Unknown,
+    };
+    // Drivers report either energy (µWh, µW) or charge (µAh, µA) counters.
+    let power_now = read_u64(&path.join("power_now"));
+    let (rate, now, full) = match power_now {
+        Some(power) => (
+            Some(power),
+            read_u64(&path.join("energy_now")),
+            read_u64(&path.join("energy_full")),
+        ),
+        None => (
+            read_u64(&path.join("current_now")),
+            read_u64(&path.join("charge_now")),
+            read_u64(&path.join("charge_full")),
+        ),
+    };
+    let power = match power_now {
+        Some(power) => Some(power as f64 / 1_000_000.0),
+        None => rate
+            .zip(read_u64(&path.join("voltage_now")))
+            .map(|(current, voltage)| current as f64 * voltage as f64 / 1_000_000_000_000.0),
+    };
+    let remaining = match status {
+        BatteryStatus::Discharging => now,
+        BatteryStatus::Charging => full.zip(now).map(|(full, now)| full.saturating_sub(now)),
+        _ => None,
+    };
+    let time_remaining = rate
+        .filter(|&rate| rate > 0)
+        .zip(remaining)
+        .map(|(rate, remaining)| Duration::from_secs(remaining * 3600 / rate));
+    Some(BatteryInfo {
+        capacity,
+        status,
+        power: power.filter(|&watts| watts > 0.0),
+        time_remaining,
+    })
+}
@@ -0,0 +1,125 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use super::sysfs::read_string;
+
+// Runtime-specific wrappers around the container ID in cgroup path segments
+const ID_PREFIXES: [&str; 5] = [
+    "docker-",
+    "cri-containerd-",
+    "crio-",
+    "libpod-",
+    "containerd-",
+];
+
+#[derive(Default)]
+pub struct ContainerResolver {
+    // Container ID -> display name, so metadata is only read once per container
+    names: HashMap<String, String>,
+}
+
+impl ContainerResolver {
+    pub fn resolve(&mut self, pid: u32) -> Option<String> {
+        let cgroup = read_string(&Path::new("/proc").join(pid.to_string()).join("cgroup"))?;
+        let id = container_id(&cgroup)?;
+        let name = self
+            .names
+            .entry(id)
+            .or_insert_with_key(|id| docker_name(id).unwrap_or_else(|| short_id(id)));
+        Some(name.clone())
+    }
+}
+
+fn container_id(cgroup: &str) -> Option<String> {
+    let mut pod = None;
+    for line in cgroup.lines() {
+        // hierarchy-ID:controller-list:cgroup-path
+        let path = line.splitn(3, ':').nth(2)?;
+        for segment in path.split('/').rev() {
+            let segment = segment.trim_end_matches(".scope");
+            let segment = ID_PREFIXES
+                .iter()
+                .find_map(|prefix| segment.strip_prefix(prefix))
+                .unwrap_or(segment);
+            if segment.len() == 64 && segment.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Some(segment.to_string());
+            }
+            // kubepods-burstable-pod<uid>.slice with the systemd driver,
+            // pod<uid> with cgroupfs
+            let slice = segment.trim_end_matches(".slice");
+            if pod.is_none()
+                && let Some(uid) = slice
+                    .strip_prefix("pod")
+                    .or_else(|| slice.rsplit_once("-pod").map(|(_, uid)| uid))
+                    .filter(|uid| {
+                        !uid.is_empty()
+                            && uid
+                                .bytes()
+                                .all(|b| b.is_ascii_hexdigit() || b == b'_' || b == b'-')
+                    })
+            {
+                pod = Some(format!("pod-{}", uid.replace('_', "-")));
+            }
+        }
+    }
+    pod
+}
+
+fn docker_name(id: &str) -> Option<String> {
+    let config = fs::read_to_string(
+        Path::new("/var/lib/docker/containers")
+            .join(id)
+            .join("config.v2.json"),
+    )
+    .ok()?;
+    let config: serde_json::Value = serde_json::from_str(&config).ok()?;
+    let name = config.get("Name")?.as_str()?.trim_start_matches('/');
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+fn short_id(id: &str) -> String {
+    if id.starts_with("pod-") {
+        id.to_string()
+    } else {
+        id.chars().take(12).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ID: &str = "3f4e1c2b9a8d7e6f5a4b3c2d1e0f9a8b7c6d5e4f3a2b1c0d9e8f7a6b5c4d3e2f";
+
+    #[test]
+    fn detects_docker_and_containerd_scopes() {
+        assert_eq!(
+            container_id(&format!("0::/system.slice/docker-{}.scope", ID)).as_deref(),
+            Some(ID)
+        );
+        assert_eq!(
+            container_id(&format!(
+                "0::/kubepods.slice/kubepods-burstable.slice/kubepods-burstable-pod1a2b_3c4d.slice/cri-containerd-{}.scope",
+                ID
+            ))
+            .as_deref(),
+            Some(ID)
+        );
+    }
+
+    #[test]
+    fn falls_back_to_pod_uid() {
+        assert_eq!(
+            container_id("0::/kubepods/besteffort/pod1a2b-3c4d").as_deref(),
+            Some("pod-1a2b-3c4d")
+        );
+    }
+
+    #[test]
+    fn host_processes_have_no_container() {
+        assert_eq!(
+            container_id("0::/user.slice/user-1000.slice/session-2.scope"),
+            None
+        );
+        assert_eq!(container_id("0::/init.scope"), None);
+    }
+}
@@ -0,0 +1,35 @@
+use std::{fs, path::Path};
+
+use super::sysfs::{read_string, read_u64};
+
+pub struct CoreFrequency {
+    pub frequency: Option<u64>, // MHz
+    pub governor: Option<String>,
+}
+
+/// Per-core cpufreq state, ordered by CPU number. Empty on machines (and
+/// most VMs) without a cpufreq driver.
+pub fn read_cpufreq() -> Vec<CoreFrequency> {
+    let Ok(entries) = fs::read_dir("/sys/devices/system/cpu") else {
+        return Vec::new();
+    };
+    let mut cores: Vec<(u32, CoreFrequency)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let name = entry.file_name();
+            let index = name.to_str()?.strip_prefix("cpu")?.parse().ok()?;
+            let cpufreq = entry.path().join("cpufreq");
+            cpufreq.exists().then(|| (index, read_core(&cpufreq)))
+        })
+        .collect();
+    cores.sort_by_key(|(index, _)| *index);
+    cores.into_iter().map(|(_, core)| core).collect()
+}
+
+fn read_core(cpufreq: &Path) -> CoreFrequency {
+    CoreFrequency {
+        // sysfs reports kHz
+        frequency: read_u64(&cpufreq.join("scaling_cur_freq")).map(|khz| khz / 1000),
+        governor: read_string(&cpufreq.join("scaling_governor")),
+    }
+}
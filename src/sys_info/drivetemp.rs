@@ -0,0 +1,58 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::{
+    DiskInfo,
+    sysfs::{read_string, read_u64},
+};
+
+pub fn read_drive_temperatures(disks: &mut [DiskInfo]) {
+    let temperatures = drive_sensors();
+    for disk in disks {
+        // Older kernels register the nvme sensor on the PCI function rather
+        // than the controller, which sits two levels up.
+        disk.temperature = block_device(&disk.name).and_then(|device| {
+            device
+                .ancestors()
+                .take(3)
+                .find_map(|path| temperatures.get(path))
+                .copied()
+        });
+    }
+}
+
+// nvme and drivetemp hwmon chips hang off the same device node that the
+// block device's "device" link resolves to.
+fn drive_sensors() -> HashMap<PathBuf, f32> {
+    let Ok(entries) = fs::read_dir("/sys/class/hwmon") else {
+        return HashMap::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|hwmon| {
+            matches!(
+                read_string(&hwmon.join("name")).as_deref(),
+                Some("nvme" | "drivetemp")
+            )
+        })
+        .filter_map(|hwmon| {
+            let device = fs::canonicalize(hwmon.join("device")).ok()?;
+            let temperature = read_u64(&hwmon.join("temp1_input"))? as f32 / 1000.0;
+            Some((device, temperature))
+        })
+        .collect()
+}
+
+fn block_device(name: &str) -> Option<PathBuf> {
+    let name = name.trim_start_matches("/dev/");
+    let mut block = fs::canonicalize(Path::new("/sys/class/block").join(name)).ok()?;
+    // Partitions live underneath their parent disk
+    if block.join("partition").exists() {
+        block.pop();
+    }
+    fs::canonicalize(block.join("device")).ok()
+}
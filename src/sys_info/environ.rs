@@ -0,0 +1,36 @@
+use std::{fs, io, path::Path};
+
+pub fn read_environment(pid: u32) -> io::Result<Vec<(String, String)>> {
+    let environ = fs::read(Path::new("/proc").join(pid.to_string()).join("environ"))?;
+    Ok(parse_environment(&environ))
+}
+
+fn parse_environment(environ: &[u8]) -> Vec<(String, String)> {
+    environ
+        .split(|&byte| byte == 0)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let entry = String::from_utf8_lossy(entry);
+            match entry.split_once('=') {
+                Some((key, value)) => (key.to_string(), value.to_string()),
+                None => (entry.into_owned(), String::new()),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_on_nul_and_first_equals() {
+        assert_eq!(
+            parse_environment(b"PATH=/usr/bin\0OPTS=a=b\0\0"),
+            vec![
+                ("PATH".to_string(), "/usr/bin".to_string()),
+                ("OPTS".to_string(), "a=b".to_string()),
+            ]
+        );
+    }
+}
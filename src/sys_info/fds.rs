@@ -0,0 +1,30 @@
+use std::{collections::HashMap, fs, path::Path};
+
+// Scanning /proc/[pid]/fd for every process is the slowest part of a
+// refresh on busy hosts, so counts are only redone every few ticks.
+const RECOUNT_TICKS: u32 = 5;
+
+#[derive(Default)]
+pub struct FdCounter {
+    ticks: u32,
+    counts: HashMap<u32, Option<u32>>,
+}
+
+impl FdCounter {
+    pub fn begin_refresh(&mut self) {
+        if self.ticks == 0 {
+            self.counts.clear();
+        }
+        self.ticks = (self.ticks + 1) % RECOUNT_TICKS;
+    }
+
+    pub fn count(&mut self, pid: u32) -> Option<u32> {
+        *self.counts.entry(pid).or_insert_with(|| count_fds(pid))
+    }
+}
+
+fn count_fds(pid: u32) -> Option<u32> {
+    // EPERM for other users' processes simply leaves the count unknown
+    let entries = fs::read_dir(Path::new("/proc").join(pid.to_string()).join("fd")).ok()?;
+    Some(entries.count() as u32)
+}
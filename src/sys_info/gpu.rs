@@ -0,0 +1,189 @@
+#[cfg(target_os = "linux")]
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+#[cfg(feature = "nvml-wrapper")]
+use nvml_wrapper::{
+    Nvml,
+    enum_wrappers::device::{Clock, TemperatureSensor},
+};
+
+use super::GpuInfo;
+#[cfg(target_os = "linux")]
+use super::sysfs::{read_string, read_u64};
+
+#[derive(Default)]
+pub struct GpuCollector {
+    #[cfg(feature = "nvml-wrapper")]
+    nvml: Option<Nvml>,
+    initialized: bool,
+    // Last RC6 residency (ms) per i915 card, used to derive busy percentage
+    #[cfg(target_os = "linux")]
+    rc6_samples: HashMap<String, (u64, Instant)>,
+}
+
+impl GpuCollector {
+    pub fn refresh(&mut self, gpus: &mut Vec<GpuInfo>) {
+        if !self.initialized {
+            self.initialized = true;
+            // libnvidia-ml is loaded at runtime, so a missing driver simply
+            // leaves the GPU list empty.
+            #[cfg(feature = "nvml-wrapper")]
+            {
+                self.nvml = Nvml::init().ok();
+            }
+        }
+        gpus.clear();
+        #[cfg(feature = "nvml-wrapper")]
+        if let Some(nvml) = &self.nvml {
+            gpus.extend(nvidia_gpus(nvml));
+        }
+        #[cfg(target_os = "linux")]
+        {
+            let skip_nvidia = !gpus.is_empty();
+            let sysfs_gpus = self.sysfs_gpus(skip_nvidia);
+            gpus.extend(sysfs_gpus);
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn sysfs_gpus(&mut self, skip_nvidia: bool) -> Vec<GpuInfo> {
+        let Ok(entries) = fs::read_dir("/sys/class/drm") else {
+            return Vec::new();
+        };
+        let mut cards: Vec<(String, PathBuf)> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                // Only primary nodes: renderD* duplicates a card and
+                // card0-DP-1 style entries are connectors.
+                let index = name.strip_prefix("card")?;
+                if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+                    return None;
+                }
+                Some((name, entry.path()))
+            })
+            .collect();
+        cards.sort_by_key(|(name, _)| name[4..].parse::<u32>().unwrap_or(u32::MAX));
+        let mut gpus = Vec::new();
+        for (card, path) in cards {
+            let device = path.join("device");
+            let vendor = match read_string(&device.join("vendor")).as_deref() {
+                Some("0x1002") => "AMD",
+                Some("0x8086") => "Intel",
+                Some("0x10de") if skip_nvidia => continue,
+                Some("0x10de") => "NVIDIA",
+                _ => continue,
+            };
+            let driver = fs::read_link(device.join("driver"))
+                .ok()
+                .and_then(|link| {
+                    link.file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                })
+                .unwrap_or_default();
+            let hwmon = first_hwmon(&device);
+            let mut utilization = read_u64(&device.join("gpu_busy_percent"));
+            let mut clock = None;
+            if driver == "i915" {
+                if utilization.is_none() {
+                    utilization = self.rc6_busy_percent(&card, &path);
+                }
+                clock = read_u64(&path.join("gt_cur_freq_mhz"))
+                    .or_else(|| read_u64(&path.join("gt/gt0/rps_cur_freq_mhz")));
+            } else if let Some(sclk) = read_string(&device.join("pp_dpm_sclk")) {
+                // amdgpu marks the active DPM level with a trailing '*'
+                clock = sclk
+                    .lines()
+                    .find(|line| line.ends_with('*'))
+                    .and_then(|line| line.split_whitespace().nth(1))
+                    .and_then(|freq| freq.trim_end_matches("Mhz").parse().ok());
+            }
+            let name = read_string(&device.join("product_name"))
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| format!("{} {}", vendor, card));
+            gpus.push(GpuInfo {
+                name,
+                vendor: vendor.to_string(),
+                utilization: utilization.map(|usage| usage.min(100)),
+                memory_used: read_u64(&device.join("mem_info_vram_used"))
+                    .map(|bytes| bytes / 1024 / 1024),
+                memory_total: read_u64(&device.join("mem_info_vram_total"))
+                    .map(|bytes| bytes / 1024 / 1024),
+                temperature: hwmon
+                    .as_ref()
+                    .and_then(|hwmon| read_u64(&hwmon.join("temp1_input")))
+                    .map(|millidegrees| millidegrees as f32 / 1000.0),
+                power_draw: hwmon
+                    .as_ref()
+                    .and_then(|hwmon| {
+                        read_u64(&hwmon.join("power1_average"))
+                            .or_else(|| read_u64(&hwmon.join("power1_input")))
+                    })
+                    .map(|microwatts| microwatts as f64 / 1_000_000.0),
+                clock,
+            });
+        }
+        gpus
+    }
+
+    /// i915 has no busy counter; the GPU is busy whenever it is not in RC6.
+    #[cfg(target_os = "linux")]
+    fn rc6_busy_percent(&mut self, card: &str, path: &Path) -> Option<u64> {
+        let residency = read_u64(&path.join("gt/gt0/rc6_residency_ms"))
+            .or_else(|| read_u64(&path.join("power/rc6_residency_ms")))?;
+        let now = Instant::now();
+        let previous = self
+            .rc6_samples
+            .insert(card.to_string(), (residency, now))?;
+        let elapsed = now.duration_since(previous.1).as_millis() as u64;
+        let idle = residency.saturating_sub(previous.0);
+        (elapsed > 0).then(|| 100u64.saturating_sub(idle * 100 / elapsed))
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn first_hwmon(device: &Path) -> Option<PathBuf> {
+    let mut hwmons: Vec<PathBuf> = fs::read_dir(device.join("hwmon"))
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    hwmons.sort();
+    hwmons.into_iter().next()
+}
+
+#[cfg(feature = "nvml-wrapper")]
+fn nvidia_gpus(nvml: &Nvml) -> Vec<GpuInfo> {
+    const MB: u64 = 1024 * 1024;
+    let count = nvml.device_count().unwrap_or(0);
+    (0..count)
+        .filter_map(|index| nvml.device_by_index(index).ok())
+        .map(|device| {
+            let memory = device.memory_info().ok();
+            GpuInfo {
+                name: device.name().unwrap_or_else(|_| "NVIDIA GPU".to_string()),
+                vendor: "NVIDIA".to_string(),
+                utilization: device
+                    .utilization_rates()
+                    .map(|rates| rates.gpu as u64)
+                    .ok(),
+                memory_used: memory.as_ref().map(|memory| memory.used / MB),
+                memory_total: memory.as_ref().map(|memory| memory.total / MB),
+                temperature: device
+                    .temperature(TemperatureSensor::Gpu)
+                    .map(|temp| temp as f32)
+                    .ok(),
+                power_draw: device
+                    .power_usage()
+                    .map(|milliwatts| milliwatts as f64 / 1000.0)
+                    .ok(),
+                clock: device.clock_info(Clock::Graphics).map(u64::from).ok(),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,153 @@
+use std::{collections::HashMap, fs, time::Instant};
+
+use super::{InterruptStats, IrqSource};
+
+const TOP_SOURCES: usize = 5;
+
+#[derive(Default)]
+pub struct InterruptCollector {
+    last_sample: Option<Sample>,
+}
+
+struct Sample {
+    at: Instant,
+    intr: u64,
+    softirq: u64,
+    // IRQ id -> per-CPU counts
+    lines: HashMap<String, Vec<u64>>,
+}
+
+impl InterruptCollector {
+    pub fn refresh(&mut self) -> Option<InterruptStats> {
+        let stat = fs::read_to_string("/proc/stat").ok()?;
+        let (intr, softirq) = parse_stat_totals(&stat)?;
+        let interrupts = fs::read_to_string("/proc/interrupts").unwrap_or_default();
+        let (names, lines) = parse_interrupts(&interrupts);
+        let sample = Sample {
+            at: Instant::now(),
+            intr,
+            softirq,
+            lines,
+        };
+        let previous = self.last_sample.replace(sample);
+        let (previous, current) = (previous?, self.last_sample.as_ref()?);
+        let elapsed = current.at.duration_since(previous.at).as_secs_f64();
+        if elapsed <= 0.0 {
+            return None;
+        }
+        let rate = |now: u64, before: u64| (now.saturating_sub(before) as f64 / elapsed) as u64;
+        let mut top: Vec<IrqSource> = current
+            .lines
+            .iter()
+            .filter_map(|(irq, counts)| {
+                let before = previous.lines.get(irq)?;
+                let per_cpu: Vec<u64> = counts
+                    .iter()
+                    .enumerate()
+                    .map(|(cpu, &count)| rate(count, before.get(cpu).copied().unwrap_or(0)))
+                    .collect();
+                Some(IrqSource {
+                    irq: irq.clone(),
+                    name: names.get(irq).cloned().unwrap_or_default(),
+                    per_sec: per_cpu.iter().sum(),
+                    per_cpu,
+                })
+            })
+            .filter(|source| source.per_sec > 0)
+            .collect();
+        top.sort_by_key(|source| std::cmp::Reverse(source.per_sec));
+        top.truncate(TOP_SOURCES);
+        Some(InterruptStats {
+            irqs_per_sec: rate(current.intr, previous.intr),
+            softirqs_per_sec: rate(current.softirq, previous.softirq),
+            top,
+        })
+    }
+}
+
+// The first number on the intr and softirq lines is the total since boot
+fn parse_stat_totals(stat: &str) -> Option<(u64, u64)> {
+    let total = |prefix: &str| {
+        stat.lines()
+            .find_map(|line| line.strip_prefix(prefix))
+            .and_then(|rest| rest.split_whitespace().next()?.parse().ok())
+    };
+    Some((total("intr ")?, total("softirq ")?))
+}
+
+type InterruptLines = (HashMap<String, String>, HashMap<String, Vec<u64>>);
+
+// The header lists one column per online CPU. Rows carry up to that many
+// counts followed by chip, hwirq and trigger columns whose number and shape
+// differ between kernels and architectures, then the device names.
+fn parse_interrupts(contents: &str) -> InterruptLines {
+    let mut lines = contents.lines();
+    let cpus = lines
+        .next()
+        .map_or(0, |header| header.split_whitespace().count());
+    let mut names = HashMap::new();
+    let mut counts = HashMap::new();
+    for line in lines {
+        let Some((irq, rest)) = line.split_once(':') else {
+            continue;
+        };
+        let irq = irq.trim().to_string();
+        let mut tokens = rest.split_whitespace().peekable();
+        let mut per_cpu = Vec::with_capacity(cpus);
+        while per_cpu.len() < cpus
+            && let Some(count) = tokens.peek().and_then(|token| token.parse::<u64>().ok())
+        {
+            per_cpu.push(count);
+            tokens.next();
+        }
+        let description: Vec<&str> = tokens.collect();
+        let name = if irq.bytes().all(|b| b.is_ascii_digit()) {
+            // Device names follow the trigger type, e.g. "IR-PCI-MSI 524288-edge nvme0q7"
+            let start = description
+                .iter()
+                .rposition(|token| {
+                    ["edge", "level", "fasteoi", "simple"]
+                        .iter()
+                        .any(|trigger| token.ends_with(trigger))
+                })
+                .map_or(description.len().saturating_sub(1), |index| index + 1);
+            description[start.min(description.len())..].join(" ")
+        } else {
+            // Architecture-specific rows such as "LOC: ... Local timer interrupts"
+            description.join(" ")
+        };
+        names.insert(irq.clone(), name);
+        counts.insert(irq, per_cpu);
+    }
+    (names, counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_stat_totals() {
+        assert_eq!(
+            parse_stat_totals("cpu  1 2 3\nintr 60056 0 0 1\nctxt 5\nsoftirq 34896 0 12564\n"),
+            Some((60056, 34896))
+        );
+    }
+
+    #[test]
+    fn parses_interrupt_rows() {
+        let (names, counts) = parse_interrupts(
+            "           CPU0       CPU1\n\
+             \x20 0:         36          0   IO-APIC   2-edge      timer\n\
+             \x20129:      9120        512  IR-PCI-MSI 524288-edge      nvme0q7\n\
+             \x20 9:          0          0   IO-APIC   9-fasteoi   acpi\n\
+             NMI:          3          4   Non-maskable interrupts\n\
+             ERR:          0\n",
+        );
+        assert_eq!(names["129"], "nvme0q7");
+        assert_eq!(counts["129"], vec![9120, 512]);
+        assert_eq!(names["9"], "acpi");
+        assert_eq!(names["NMI"], "Non-maskable interrupts");
+        assert_eq!(counts["ERR"], vec![0]);
+    }
+}
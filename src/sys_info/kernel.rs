@@ -0,0 +1,38 @@
+use std::path::Path;
+
+use super::{
+    KernelStats,
+    sysfs::{read_string, read_u64},
+};
+
+/// None when none of the files are readable, as in some containers.
+pub fn read_kernel_stats() -> Option<KernelStats> {
+    let stats = KernelStats {
+        file_handles: read_string(Path::new("/proc/sys/fs/file-nr"))
+            .and_then(|file_nr| parse_file_nr(&file_nr)),
+        ptys: read_u64(Path::new("/proc/sys/kernel/pty/nr")),
+        pty_max: read_u64(Path::new("/proc/sys/kernel/pty/max")),
+        entropy: read_u64(Path::new("/proc/sys/kernel/random/entropy_avail")),
+    };
+    (stats.file_handles.is_some() || stats.ptys.is_some() || stats.entropy.is_some())
+        .then_some(stats)
+}
+
+// "allocated  free  max"; free has always been 0 since Linux 2.6
+fn parse_file_nr(file_nr: &str) -> Option<(u64, u64)> {
+    let mut fields = file_nr.split_whitespace().map(|field| field.parse().ok());
+    let allocated = fields.next()??;
+    let max = fields.nth(1)??;
+    Some((allocated, max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_file_nr() {
+        assert_eq!(parse_file_nr("277\t0\t613832"), Some((277, 613832)));
+        assert_eq!(parse_file_nr("277"), None);
+    }
+}
@@ -0,0 +1,86 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Read, Seek, SeekFrom},
+    os::unix::fs::OpenOptionsExt,
+};
+
+use super::OomKill;
+
+/// Follows the kernel log for OOM kills. Reading `/dev/kmsg` takes root or
+/// CAP_SYSLOG on most systems; without them this finds nothing.
+pub struct OomWatcher {
+    kmsg: Option<File>,
+}
+
+impl Default for OomWatcher {
+    fn default() -> Self {
+        let kmsg = OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open("/dev/kmsg")
+            .ok()
+            // Only kills from now on, not those already in the buffer
+            .filter(|mut file| file.seek(SeekFrom::End(0)).is_ok());
+        Self { kmsg }
+    }
+}
+
+impl OomWatcher {
+    /// The kills logged since the last call.
+    pub fn refresh(&mut self) -> Vec<OomKill> {
+        let Some(kmsg) = &mut self.kmsg else {
+            return Vec::new();
+        };
+        let mut kills = Vec::new();
+        // Each read returns one whole record
+        let mut record = [0; 8192];
+        loop {
+            match kmsg.read(&mut record) {
+                Ok(0) => break,
+                Ok(length) => {
+                    kills.extend(parse_oom_kill(&String::from_utf8_lossy(&record[..length])))
+                }
+                // Records were overwritten before we got to them
+                Err(err) if err.raw_os_error() == Some(libc::EPIPE) => {}
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+        kills
+    }
+}
+
+// e.g. `3,1234,56789,-;Out of memory: Killed process 4242 (stress) total-vm:...`,
+// or the same after `Memory cgroup out of memory:` for a cgroup limit
+fn parse_oom_kill(record: &str) -> Option<OomKill> {
+    let (_, message) = record.split_once(';')?;
+    let rest = message.split_once("Killed process ")?.1;
+    let (pid, rest) = rest.split_once(" (")?;
+    let (name, _) = rest.split_once(')')?;
+    Some(OomKill {
+        pid: pid.parse().ok()?,
+        name: name.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_killed_process() {
+        let kill = parse_oom_kill(
+            "3,1234,56789,-;Out of memory: Killed process 4242 (stress-ng) total-vm:1048576kB, anon-rss:524288kB\n",
+        )
+        .unwrap();
+        assert_eq!(kill.pid, 4242);
+        assert_eq!(kill.name, "stress-ng");
+        let kill = parse_oom_kill(
+            "3,1300,60000,-;Memory cgroup out of memory: Killed process 77 (java) total-vm:10kB\n",
+        )
+        .unwrap();
+        assert_eq!(kill.name, "java");
+        assert!(parse_oom_kill("6,1301,60001,-;eth0: link up\n").is_none());
+        assert!(parse_oom_kill("4,1302,60002,-;oom_reaper: reaped process 77 (java)\n").is_none());
+    }
+}
@@ -0,0 +1,788 @@
+use std::{
+    collections::HashMap,
+    ffi::{CStr, CString, c_char, c_int, c_void},
+    mem,
+    net::Ipv4Addr,
+    ptr,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use super::{
+    DiskInfo, LoadAverage, NetworkInterface, ProcessInfo, ProcessState, SystemInfo,
+    process_table::ProcessTable,
+};
+
+const MB: u64 = 1024 * 1024;
+const GB: u64 = 1024 * 1024 * 1024;
+
+// Leading fields of `struct if_data` from <net/if_var.h>; getifaddrs hands one
+// of these out through `ifa_data` for every AF_LINK entry.
+#[repr(C)]
+struct IfData {
+    ifi_type: u8,
+    ifi_typelen: u8,
+    ifi_physical: u8,
+    ifi_addrlen: u8,
+    ifi_hdrlen: u8,
+    ifi_recvquota: u8,
+    ifi_xmitquota: u8,
+    ifi_unused1: u8,
+    ifi_mtu: u32,
+    ifi_metric: u32,
+    ifi_baudrate: u32,
+    ifi_ipackets: u32,
+    ifi_ierrors: u32,
+    ifi_opackets: u32,
+    ifi_oerrors: u32,
+    ifi_collisions: u32,
+    ifi_ibytes: u32,
+    ifi_obytes: u32,
+}
+
+#[derive(Clone, Copy)]
+struct ProcessSample {
+    cpu_time: Option<u64>, // ns, unknown when the task details are unreadable
+    disk_io: Option<(u64, u64)>,
+}
+
+#[derive(Default)]
+struct InterfaceCounters {
+    last_rx: u32,
+    last_tx: u32,
+    rx_bytes: u64,
+    tx_bytes: u64,
+}
+
+#[derive(Default)]
+pub struct Collector {
+    initialized: bool,
+    last_refresh: Option<Instant>,
+    timebase: (u32, u32),
+    cpu_ticks: Vec<[u64; 4]>,
+    processes: ProcessTable,
+    samples: HashMap<u32, ProcessSample>,
+    users: HashMap<u32, String>,
+    interfaces: HashMap<String, InterfaceCounters>,
+}
+
+impl Collector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn refresh(&mut self, metrics: &mut SystemInfo) {
+        let now = Instant::now();
+        let elapsed = self
+            .last_refresh
+            .map(|last| now.duration_since(last))
+            .unwrap_or_default();
+        self.last_refresh = Some(now);
+        if !self.initialized {
+            self.initialized = true;
+            self.timebase = timebase();
+            self.refresh_static(metrics);
+        }
+        self.refresh_cpu(metrics);
+        self.refresh_memory(metrics);
+        self.refresh_processes(metrics, elapsed);
+        self.refresh_disks(metrics);
+        self.refresh_network(metrics, elapsed);
+        metrics.uptime = uptime();
+        metrics.load_average = load_average();
+        metrics.last_update = now;
+    }
+
+    fn refresh_static(&mut self, metrics: &mut SystemInfo) {
+        metrics.hostname = hostname().unwrap_or_else(|| "localhost".to_string());
+        metrics.kernel_version = sysctl_string("kern.osrelease").unwrap_or_default();
+        metrics.os_name = match sysctl_string("kern.osproductversion") {
+            Some(version) => format!("macOS {}", version),
+            None => "macOS".to_string(),
+        };
+        metrics.cpu_model = sysctl_string("machdep.cpu.brand_string").unwrap_or_default();
+        metrics.cpu_frequency = sysctl_value::<u64>("hw.cpufrequency").map(|hz| hz / 1_000_000);
+        metrics.cpu_temperature = None;
+        metrics.simulated = false;
+    }
+
+    fn refresh_cpu(&mut self, metrics: &mut SystemInfo) {
+        let ticks = cpu_ticks();
+        if ticks.is_empty() {
+            return;
+        }
+        metrics.cpu_usage_per_core = ticks
+            .iter()
+            .enumerate()
+            .map(|(i, current)| match self.cpu_ticks.get(i) {
+                Some(previous) => usage_from_ticks(previous, current),
+                None => 0,
+            })
+            .collect();
+        metrics.cpu_count = ticks.len();
+        metrics.cpu_total_usage =
+            metrics.cpu_usage_per_core.iter().sum::<u64>() / metrics.cpu_count as u64;
+        self.cpu_ticks = ticks;
+    }
+
+    fn refresh_memory(&mut self, metrics: &mut SystemInfo) {
+        let total = sysctl_value::<u64>("hw.memsize").unwrap_or(0);
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) }.max(0) as u64;
+        if let Some(stats) = vm_statistics() {
+            let pages = |count: u32| count as u64 * page_size;
+            let app = pages(stats.internal_page_count).saturating_sub(pages(stats.purgeable_count));
+            let used = app + pages(stats.wire_count) + pages(stats.compressor_page_count);
+            metrics.memory_total = total / MB;
+            metrics.memory_used = used.min(total) / MB;
+            metrics.memory_free = pages(stats.free_count) / MB;
+            metrics.memory_available = total.saturating_sub(used) / MB;
+            metrics.memory_cached =
+                (pages(stats.external_page_count) + pages(stats.purgeable_count)) / MB;
+            metrics.memory_buffers = 0;
+        }
+        if let Some(swap) = sysctl_value::<libc::xsw_usage>("vm.swapusage") {
+            metrics.swap_total = swap.xsu_total / MB;
+            metrics.swap_used = swap.xsu_used / MB;
+            metrics.swap_free = swap.xsu_avail / MB;
+        }
+    }
+
+    fn refresh_processes(&mut self, metrics: &mut SystemInfo, elapsed: Duration) {
+        let memory_total = metrics.memory_total * MB;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let mut samples = HashMap::new();
+        self.processes.begin(&metrics.processes);
+        for pid in list_pids() {
+            let Some(info) = task_info(pid) else {
+                continue;
+            };
+            let bsd = info.bsd();
+            let task = match &info {
+                TaskInfo::Full(info) => Some(&info.ptinfo),
+                TaskInfo::BsdOnly(_) => None,
+            };
+            let cpu_time =
+                task.map(|task| self.mach_to_ns(task.pti_total_user + task.pti_total_system));
+            let sample = ProcessSample {
+                cpu_time,
+                disk_io: disk_io(pid),
+            };
+            let previous = self.samples.get(&bsd.pbi_pid).copied();
+            let cpu_usage = match (cpu_time, previous.and_then(|previous| previous.cpu_time)) {
+                (Some(current), Some(previous)) if !elapsed.is_zero() => {
+                    current.saturating_sub(previous) as f64 / elapsed.as_nanos() as f64 * 100.0
+                }
+                _ => 0.0,
+            };
+            let io_rate = |select: fn((u64, u64)) -> u64| {
+                let current = select(sample.disk_io?);
+                let rate = match previous.and_then(|previous| previous.disk_io) {
+                    Some(previous) if !elapsed.is_zero() => {
+                        current.saturating_sub(select(previous)) as f64
+                            / 1024.0
+                            / elapsed.as_secs_f64()
+                    }
+                    _ => 0.0,
+                };
+                Some(rate as u64)
+            };
+            // Names and arguments only change across exec, so they are read
+            // once per (pid, start time) instead of on every refresh.
+            let users = &mut self.users;
+            let process = self.processes.update(
+                &mut metrics.processes,
+                bsd.pbi_pid,
+                bsd.pbi_start_tvsec,
+                || describe_process(pid, bsd, user_name(users, bsd.pbi_uid)),
+            );
+            process.ppid = bsd.pbi_ppid;
+            process.restricted = task.is_none();
+            match task {
+                Some(task) => {
+                    let resident = task.pti_resident_size;
+                    process.cpu_usage = cpu_usage;
+                    process.memory_usage = resident / MB;
+                    process.memory_percent = if memory_total > 0 {
+                        resident as f64 / memory_total as f64 * 100.0
+                    } else {
+                        0.0
+                    };
+                    process.virt_kb = task.pti_virtual_size / 1024;
+                    process.rss_kb = resident / 1024;
+                    process.priority = task.pti_priority;
+                    process.threads = task.pti_threadnum.max(0) as u32;
+                }
+                None => {
+                    process.cpu_usage = 0.0;
+                    process.memory_usage = 0;
+                    process.memory_percent = 0.0;
+                    process.virt_kb = 0;
+                    process.rss_kb = 0;
+                    process.priority = 0;
+                    process.threads = 0;
+                }
+            }
+            process.state = process_state(bsd.pbi_status);
+            process.nice = bsd.pbi_nice;
+            process.uptime = Duration::from_secs(now.saturating_sub(bsd.pbi_start_tvsec));
+            process.read_speed = io_rate(|(read, _)| read);
+            process.write_speed = io_rate(|(_, written)| written);
+            samples.insert(bsd.pbi_pid, sample);
+        }
+        self.processes.finish(&mut metrics.processes);
+        self.samples = samples;
+        metrics.process_count = metrics.processes.len();
+        metrics.thread_count = metrics.processes.iter().map(|p| p.threads as usize).sum();
+    }
+
+    fn refresh_disks(&mut self, metrics: &mut SystemInfo) {
+        let mut mounts: *mut libc::statfs = ptr::null_mut();
+        let count = unsafe { libc::getmntinfo(&mut mounts, libc::MNT_NOWAIT) };
+        if count <= 0 || mounts.is_null() {
+            return;
+        }
+        let mounts = unsafe { std::slice::from_raw_parts(mounts, count as usize) };
+        metrics.disks = mounts
+            .iter()
+            .filter(|fs| fs.f_flags & libc::MNT_LOCAL as u32 != 0 && fs.f_blocks > 0)
+            .filter_map(|fs| {
+                let mount_point = c_chars_to_string(&fs.f_mntonname);
+                let fs_type = c_chars_to_string(&fs.f_fstypename);
+                if !is_user_visible_mount(&mount_point, &fs_type) {
+                    return None;
+                }
+                let block_size = fs.f_bsize as u64;
+                let total = fs.f_blocks * block_size;
+                let used = fs.f_blocks.saturating_sub(fs.f_bfree) * block_size;
+                let free = fs.f_bavail * block_size;
+                let device = c_chars_to_string(&fs.f_mntfromname);
+                Some(DiskInfo {
+                    name: device.trim_start_matches("/dev/").to_string(),
+                    mount_point,
+                    total: total / GB,
+                    used: used / GB,
+                    free: free / GB,
+                    usage: used * 100 / total,
+                    read_speed: None,
+                    write_speed: None,
+                    device_type: String::new(),
+                    fs_type,
+                    inodes_total: fs.f_files,
+                    inodes_used: fs.f_files.saturating_sub(fs.f_ffree),
+                    inodes_usage_pct: DiskInfo::inode_usage(fs.f_files, fs.f_ffree),
+                    temperature: None,
+                    pool: None,
+                })
+            })
+            .collect();
+    }
+
+    fn refresh_network(&mut self, metrics: &mut SystemInfo, elapsed: Duration) {
+        let mut addrs: *mut libc::ifaddrs = ptr::null_mut();
+        if unsafe { libc::getifaddrs(&mut addrs) } != 0 {
+            return;
+        }
+        let mut interfaces: Vec<NetworkInterface> = Vec::new();
+        let mut ipv4: HashMap<String, String> = HashMap::new();
+        let mut cursor = addrs;
+        while !cursor.is_null() {
+            let entry = unsafe { &*cursor };
+            cursor = entry.ifa_next;
+            if entry.ifa_addr.is_null() {
+                continue;
+            }
+            let name = unsafe { CStr::from_ptr(entry.ifa_name) }
+                .to_string_lossy()
+                .into_owned();
+            match unsafe { (*entry.ifa_addr).sa_family } as c_int {
+                libc::AF_INET => {
+                    let addr = unsafe { &*(entry.ifa_addr as *const libc::sockaddr_in) };
+                    ipv4.entry(name).or_insert_with(|| {
+                        Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr)).to_string()
+                    });
+                }
+                libc::AF_LINK if !entry.ifa_data.is_null() => {
+                    let data = unsafe { &*(entry.ifa_data as *const IfData) };
+                    let up = entry.ifa_flags & (libc::IFF_UP | libc::IFF_RUNNING) as u32
+                        == (libc::IFF_UP | libc::IFF_RUNNING) as u32;
+                    let counters =
+                        self.interfaces
+                            .entry(name.clone())
+                            .or_insert_with(|| InterfaceCounters {
+                                last_rx: data.ifi_ibytes,
+                                last_tx: data.ifi_obytes,
+                                ..Default::default()
+                            });
+                    // The AF_LINK counters are 32 bits wide and wrap every 4 GB,
+                    // so totals are accumulated from wrapping deltas.
+                    let rx_delta = data.ifi_ibytes.wrapping_sub(counters.last_rx) as u64;
+                    let tx_delta = data.ifi_obytes.wrapping_sub(counters.last_tx) as u64;
+                    counters.last_rx = data.ifi_ibytes;
+                    counters.last_tx = data.ifi_obytes;
+                    counters.rx_bytes += rx_delta;
+                    counters.tx_bytes += tx_delta;
+                    let per_second = |delta: u64| {
+                        if elapsed.is_zero() {
+                            0
+                        } else {
+                            (delta as f64 / 1024.0 / elapsed.as_secs_f64()) as u64
+                        }
+                    };
+                    let link = unsafe { &*(entry.ifa_addr as *const libc::sockaddr_dl) };
+                    interfaces.push(NetworkInterface {
+                        rx_bytes: counters.rx_bytes,
+                        tx_bytes: counters.tx_bytes,
+                        rx_speed: per_second(rx_delta),
+                        tx_speed: per_second(tx_delta),
+                        ip_address: String::new(),
+                        mac_address: mac_address(link),
+                        status: if up { "up" } else { "down" }.to_string(),
+                        link_speed: (data.ifi_baudrate > 0)
+                            .then_some(data.ifi_baudrate as u64 / 1_000_000),
+                        mtu: Some(data.ifi_mtu),
+                        duplex: None,
+                        wifi: None,
+                        name,
+                    });
+                }
+                _ => {}
+            }
+        }
+        unsafe { libc::freeifaddrs(addrs) };
+        for iface in &mut interfaces {
+            if let Some(ip) = ipv4.remove(&iface.name) {
+                iface.ip_address = ip;
+            }
+        }
+        interfaces.retain(|iface| iface.status == "up" && !iface.ip_address.is_empty());
+        self.interfaces
+            .retain(|name, _| interfaces.iter().any(|iface| &iface.name == name));
+        let external = interfaces
+            .iter()
+            .filter(|iface| !iface.name.starts_with("lo"));
+        metrics.total_rx = external.clone().map(|iface| iface.rx_speed).sum();
+        metrics.total_tx = external.map(|iface| iface.tx_speed).sum();
+        metrics.network_interfaces = interfaces;
+    }
+
+    fn mach_to_ns(&self, ticks: u64) -> u64 {
+        let (numer, denom) = self.timebase;
+        if denom == 0 {
+            return ticks;
+        }
+        (ticks as u128 * numer as u128 / denom as u128) as u64
+    }
+}
+
+fn usage_from_ticks(previous: &[u64; 4], current: &[u64; 4]) -> u64 {
+    let delta = |i: usize| current[i].saturating_sub(previous[i]);
+    let busy = delta(libc::CPU_STATE_USER as usize)
+        + delta(libc::CPU_STATE_SYSTEM as usize)
+        + delta(libc::CPU_STATE_NICE as usize);
+    let total = busy + delta(libc::CPU_STATE_IDLE as usize);
+    (busy * 100).checked_div(total).unwrap_or(0).min(100)
+}
+
+fn process_state(status: u32) -> ProcessState {
+    match status {
+        libc::SRUN => ProcessState::Running,
+        libc::SSLEEP => ProcessState::Sleeping,
+        libc::SSTOP => ProcessState::Stopped,
+        libc::SZOMB => ProcessState::Zombie,
+        _ => ProcessState::Idle,
+    }
+}
+
+fn is_user_visible_mount(mount_point: &str, fs_type: &str) -> bool {
+    if matches!(fs_type, "devfs" | "autofs") {
+        return false;
+    }
+    // The sealed system volume is mounted on "/" and the writable half of the
+    // APFS volume group under /System/Volumes/Data; the rest of that directory
+    // holds VM, Preboot and update volumes nobody wants in a disk table.
+    !mount_point.starts_with("/System/Volumes/") || mount_point == "/System/Volumes/Data"
+}
+
+// Layout of a KERN_PROCARGS2 buffer: argc as a native int, the executable
+// path, NUL padding, then argc NUL-terminated arguments followed by the
+// environment.
+fn parse_procargs(buf: &[u8]) -> Option<(String, Vec<String>)> {
+    let argc = i32::from_ne_bytes(buf.get(..4)?.try_into().ok()?).max(0) as usize;
+    let rest = &buf[4..];
+    let exec_end = rest.iter().position(|&b| b == 0)?;
+    let exec_path = String::from_utf8_lossy(&rest[..exec_end]).into_owned();
+    let args = rest[exec_end..]
+        .split(|&b| b == 0)
+        .skip_while(|arg| arg.is_empty())
+        .take(argc)
+        .map(|arg| String::from_utf8_lossy(arg).into_owned())
+        .collect();
+    Some((exec_path, args))
+}
+
+fn user_name(users: &mut HashMap<u32, String>, uid: libc::uid_t) -> String {
+    users
+        .entry(uid)
+        .or_insert_with(|| lookup_user(uid).unwrap_or_else(|| uid.to_string()))
+        .clone()
+}
+
+// The parts of a process that stay the same until it exits or execs
+fn describe_process(pid: c_int, bsd: &libc::proc_bsdinfo, user: String) -> ProcessInfo {
+    let mut name = c_chars_to_string(&bsd.pbi_name);
+    if name.is_empty() {
+        name = c_chars_to_string(&bsd.pbi_comm);
+    }
+    let (command, full_command) = match process_args(pid) {
+        Some((exec_path, args)) if !args.is_empty() => (exec_path, args.join(" ")),
+        Some((exec_path, _)) => (exec_path.clone(), exec_path),
+        None => (name.clone(), String::new()),
+    };
+    ProcessInfo {
+        pid: bsd.pbi_pid,
+        ppid: bsd.pbi_ppid,
+        name,
+        command,
+        full_command,
+        user,
+        cpu_usage: 0.0,
+        memory_usage: 0,
+        memory_percent: 0.0,
+        virt_kb: 0,
+        rss_kb: 0,
+        shared_kb: None,
+        swap_kb: 0,
+        state: process_state(bsd.pbi_status),
+        priority: 0,
+        nice: bsd.pbi_nice,
+        threads: 0,
+        start_time: chrono::DateTime::from_timestamp(bsd.pbi_start_tvsec as i64, 0)
+            .map(|start| {
+                start
+                    .with_timezone(&chrono::Local)
+                    .format("%H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_default(),
+        uptime: Duration::ZERO,
+        read_speed: None,
+        write_speed: None,
+        container: None,
+        fd_count: None,
+        restricted: false,
+    }
+}
+
+fn process_args(pid: c_int) -> Option<(String, Vec<String>)> {
+    let arg_max = sysctl_value::<c_int>("kern.argmax")?.max(0) as usize;
+    let mut buf = vec![0u8; arg_max];
+    let mut mib = [libc::CTL_KERN, libc::KERN_PROCARGS2, pid];
+    let mut size = buf.len();
+    let rc = unsafe {
+        libc::sysctl(
+            mib.as_mut_ptr(),
+            mib.len() as u32,
+            buf.as_mut_ptr().cast(),
+            &mut size,
+            ptr::null_mut(),
+            0,
+        )
+    };
+    if rc != 0 {
+        return None;
+    }
+    buf.truncate(size);
+    parse_procargs(&buf)
+}
+
+fn list_pids() -> Vec<c_int> {
+    let count = unsafe { libc::proc_listallpids(ptr::null_mut(), 0) };
+    if count <= 0 {
+        return Vec::new();
+    }
+    // Leave headroom for processes spawned between the two calls.
+    let mut pids: Vec<c_int> = vec![0; count as usize + 64];
+    let count = unsafe {
+        libc::proc_listallpids(
+            pids.as_mut_ptr().cast(),
+            (pids.len() * mem::size_of::<c_int>()) as c_int,
+        )
+    };
+    pids.truncate(count.max(0) as usize);
+    pids.retain(|&pid| pid > 0);
+    pids
+}
+
+// What proc_pidinfo would tell about a process
+enum TaskInfo {
+    Full(libc::proc_taskallinfo),
+    // Task details of other users' processes need privileges; the BSD half
+    // is still readable, so the process is listed without its CPU, memory,
+    // thread and priority figures.
+    BsdOnly(libc::proc_bsdinfo),
+}
+
+impl TaskInfo {
+    fn bsd(&self) -> &libc::proc_bsdinfo {
+        match self {
+            TaskInfo::Full(info) => &info.pbsd,
+            TaskInfo::BsdOnly(bsd) => bsd,
+        }
+    }
+}
+
+fn task_info(pid: c_int) -> Option<TaskInfo> {
+    let mut info: libc::proc_taskallinfo = unsafe { mem::zeroed() };
+    let size = mem::size_of::<libc::proc_taskallinfo>() as c_int;
+    let rc = unsafe {
+        libc::proc_pidinfo(
+            pid,
+            libc::PROC_PIDTASKALLINFO,
+            0,
+            (&mut info as *mut libc::proc_taskallinfo).cast(),
+            size,
+        )
+    };
+    if rc == size {
+        return Some(TaskInfo::Full(info));
+    }
+    let mut bsd: libc::proc_bsdinfo = unsafe { mem::zeroed() };
+    let size = mem::size_of::<libc::proc_bsdinfo>() as c_int;
+    let rc = unsafe {
+        libc::proc_pidinfo(
+            pid,
+            libc::PROC_PIDTBSDINFO,
+            0,
+            (&mut bsd as *mut libc::proc_bsdinfo).cast(),
+            size,
+        )
+    };
+    (rc == size).then_some(TaskInfo::BsdOnly(bsd))
+}
+
+fn disk_io(pid: c_int) -> Option<(u64, u64)> {
+    let mut usage: libc::rusage_info_v2 = unsafe { mem::zeroed() };
+    let rc = unsafe {
+        libc::proc_pid_rusage(
+            pid,
+            libc::RUSAGE_INFO_V2,
+            (&mut usage as *mut libc::rusage_info_v2).cast(),
+        )
+    };
+    (rc == 0).then_some((usage.ri_diskio_bytesread, usage.ri_diskio_byteswritten))
+}
+
+// libc deprecates its mach bindings in favour of the mach2 crate; these
+// and timebase() are the only ones used, so they are allowed one by one
+#[allow(deprecated)]
+fn mach_host() -> libc::mach_port_t {
+    unsafe { libc::mach_host_self() }
+}
+
+#[allow(deprecated)]
+fn mach_task() -> libc::mach_port_t {
+    unsafe { libc::mach_task_self() }
+}
+
+fn cpu_ticks() -> Vec<[u64; 4]> {
+    let mut cpu_count: libc::natural_t = 0;
+    let mut info: libc::processor_info_array_t = ptr::null_mut();
+    let mut info_count: libc::mach_msg_type_number_t = 0;
+    let rc = unsafe {
+        libc::host_processor_info(
+            mach_host(),
+            libc::PROCESSOR_CPU_LOAD_INFO,
+            &mut cpu_count,
+            &mut info,
+            &mut info_count,
+        )
+    };
+    if rc != libc::KERN_SUCCESS || info.is_null() {
+        return Vec::new();
+    }
+    let loads = unsafe {
+        std::slice::from_raw_parts(
+            info as *const libc::processor_cpu_load_info,
+            cpu_count as usize,
+        )
+    };
+    let ticks = loads
+        .iter()
+        .map(|load| {
+            [
+                load.cpu_ticks[libc::CPU_STATE_USER as usize] as u64,
+                load.cpu_ticks[libc::CPU_STATE_SYSTEM as usize] as u64,
+                load.cpu_ticks[libc::CPU_STATE_IDLE as usize] as u64,
+                load.cpu_ticks[libc::CPU_STATE_NICE as usize] as u64,
+            ]
+        })
+        .collect();
+    unsafe {
+        libc::vm_deallocate(
+            mach_task(),
+            info as libc::vm_address_t,
+            info_count as libc::vm_size_t * mem::size_of::<libc::integer_t>(),
+        );
+    }
+    ticks
+}
+
+fn vm_statistics() -> Option<libc::vm_statistics64> {
+    let mut stats: libc::vm_statistics64 = unsafe { mem::zeroed() };
+    let mut count = libc::HOST_VM_INFO64_COUNT;
+    let rc = unsafe {
+        libc::host_statistics64(
+            mach_host(),
+            libc::HOST_VM_INFO64,
+            (&mut stats as *mut libc::vm_statistics64).cast(),
+            &mut count,
+        )
+    };
+    (rc == libc::KERN_SUCCESS).then_some(stats)
+}
+
+#[allow(deprecated)]
+fn timebase() -> (u32, u32) {
+    let mut info = libc::mach_timebase_info { numer: 0, denom: 0 };
+    if unsafe { libc::mach_timebase_info(&mut info) } == 0 {
+        (info.numer, info.denom)
+    } else {
+        (1, 1)
+    }
+}
+
+fn uptime() -> Duration {
+    let Some(boot) = sysctl_value::<libc::timeval>("kern.boottime") else {
+        return Duration::ZERO;
+    };
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH + Duration::from_secs(boot.tv_sec.max(0) as u64))
+        .unwrap_or_default()
+}
+
+fn load_average() -> LoadAverage {
+    let mut loads = [0.0f64; 3];
+    unsafe { libc::getloadavg(loads.as_mut_ptr(), 3) };
+    LoadAverage {
+        one: loads[0],
+        five: loads[1],
+        fifteen: loads[2],
+    }
+}
+
+fn hostname() -> Option<String> {
+    let mut buf = [0 as c_char; 256];
+    if unsafe { libc::gethostname(buf.as_mut_ptr(), buf.len()) } != 0 {
+        return None;
+    }
+    Some(c_chars_to_string(&buf))
+}
+
+fn lookup_user(uid: libc::uid_t) -> Option<String> {
+    let mut passwd: libc::passwd = unsafe { mem::zeroed() };
+    let mut buf = vec![0 as c_char; 1024];
+    let mut result: *mut libc::passwd = ptr::null_mut();
+    let rc =
+        unsafe { libc::getpwuid_r(uid, &mut passwd, buf.as_mut_ptr(), buf.len(), &mut result) };
+    if rc != 0 || result.is_null() {
+        return None;
+    }
+    Some(
+        unsafe { CStr::from_ptr(passwd.pw_name) }
+            .to_string_lossy()
+            .into_owned(),
+    )
+}
+
+fn mac_address(link: &libc::sockaddr_dl) -> String {
+    let data = link.sdl_data.as_ptr() as *const u8;
+    let bytes = unsafe {
+        std::slice::from_raw_parts(data.add(link.sdl_nlen as usize), link.sdl_alen as usize)
+    };
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn sysctl_string(name: &str) -> Option<String> {
+    let name = CString::new(name).ok()?;
+    let mut len = 0;
+    let rc =
+        unsafe { libc::sysctlbyname(name.as_ptr(), ptr::null_mut(), &mut len, ptr::null_mut(), 0) };
+    if rc != 0 {
+        return None;
+    }
+    let mut buf = vec![0u8; len];
+    let rc = unsafe {
+        libc::sysctlbyname(
+            name.as_ptr(),
+            buf.as_mut_ptr().cast(),
+            &mut len,
+            ptr::null_mut(),
+            0,
+        )
+    };
+    if rc != 0 {
+        return None;
+    }
+    buf.truncate(len);
+    let value = CStr::from_bytes_until_nul(&buf).ok()?;
+    Some(value.to_string_lossy().trim().to_string())
+}
+
+fn sysctl_value<T: Copy>(name: &str) -> Option<T> {
+    let name = CString::new(name).ok()?;
+    let mut value = mem::MaybeUninit::<T>::zeroed();
+    let mut len = mem::size_of::<T>();
+    let rc = unsafe {
+        libc::sysctlbyname(
+            name.as_ptr(),
+            value.as_mut_ptr() as *mut c_void,
+            &mut len,
+            ptr::null_mut(),
+            0,
+        )
+    };
+    (rc == 0 && len == mem::size_of::<T>()).then(|| unsafe { value.assume_init() })
+}
+
+fn c_chars_to_string(chars: &[c_char]) -> String {
+    let bytes: Vec<u8> = chars
+        .iter()
+        .take_while(|&&c| c != 0)
+        .map(|&c| c as u8)
+        .collect();
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_from_ticks() {
+        assert_eq!(usage_from_ticks(&[100, 50, 850, 0], &[160, 70, 870, 0]), 80);
+        assert_eq!(usage_from_ticks(&[10, 10, 10, 10], &[10, 10, 10, 10]), 0);
+    }
+
+    #[test]
+    fn test_parse_procargs() {
+        let mut buf = 2i32.to_ne_bytes().to_vec();
+        buf.extend_from_slice(b"/usr/bin/top\0\0\0\0top\0-o\0PATH=/usr/bin\0");
+        let (exec_path, args) = parse_procargs(&buf).unwrap();
+        assert_eq!(exec_path, "/usr/bin/top");
+        assert_eq!(args, vec!["top", "-o"]);
+    }
+
+    #[test]
+    fn test_is_user_visible_mount() {
+        assert!(is_user_visible_mount("/", "apfs"));
+        assert!(is_user_visible_mount("/System/Volumes/Data", "apfs"));
+        assert!(!is_user_visible_mount("/System/Volumes/VM", "apfs"));
+        assert!(!is_user_visible_mount("/dev", "devfs"));
+    }
+}
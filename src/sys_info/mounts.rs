@@ -0,0 +1,158 @@
+use std::{
+    collections::HashSet,
+    ffi::CString,
+    fs,
+    mem::MaybeUninit,
+    path::{Path, PathBuf},
+};
+
+use super::{DiskInfo, sysfs::read_string};
+
+const GB: u64 = 1024 * 1024 * 1024;
+
+pub fn read_mounts() -> Vec<DiskInfo> {
+    let Ok(mounts) = fs::read_to_string("/proc/mounts") else {
+        return Vec::new();
+    };
+    let mut seen = HashSet::new();
+    parse_mounts(&mounts)
+        .into_iter()
+        // Bind mounts and repeated mounts of the same point would be counted twice
+        .filter(|(_, mount_point, _)| seen.insert(mount_point.clone()))
+        .filter_map(|(device, mount_point, fs_type)| {
+            let stat = statvfs(&mount_point)?;
+            // proc, sysfs, cgroup and friends have no blocks at all
+            if stat.f_blocks == 0 {
+                return None;
+            }
+            let block_size = stat.f_frsize;
+            let total = stat.f_blocks * block_size;
+            let used = stat.f_blocks.saturating_sub(stat.f_bfree) * block_size;
+            let free = stat.f_bavail * block_size;
+            Some(DiskInfo {
+                name: device.trim_start_matches("/dev/").to_string(),
+                mount_point,
+                total: total / GB,
+                used: used / GB,
+                free: free / GB,
+                usage: used * 100 / total,
+                read_speed: None,
+                write_speed: None,
+                device_type: device_type(&device),
+                fs_type,
+                inodes_total: stat.f_files,
+                inodes_used: stat.f_files.saturating_sub(stat.f_ffree),
+                inodes_usage_pct: DiskInfo::inode_usage(stat.f_files, stat.f_ffree),
+                temperature: None,
+                pool: None,
+            })
+        })
+        .collect()
+}
+
+// Each line is "device mount-point fs-type options dump pass"
+fn parse_mounts(contents: &str) -> Vec<(String, String, String)> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let device = unescape(fields.next()?);
+            let mount_point = unescape(fields.next()?);
+            let fs_type = fields.next()?.to_string();
+            Some((device, mount_point, fs_type))
+        })
+        .collect()
+}
+
+// The kernel writes space, tab, newline and backslash as \ooo octal escapes
+fn unescape(field: &str) -> String {
+    let mut bytes = Vec::with_capacity(field.len());
+    let mut rest = field.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = (byte == b'\\')
+            .then(|| tail.get(..3))
+            .flatten()
+            .and_then(|digits| u8::from_str_radix(std::str::from_utf8(digits).ok()?, 8).ok());
+        match escaped {
+            Some(value) => {
+                bytes.push(value);
+                rest = &tail[3..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn statvfs(mount_point: &str) -> Option<libc::statvfs> {
+    let path = CString::new(mount_point).ok()?;
+    let mut stat = MaybeUninit::<libc::statvfs>::uninit();
+    let rc = unsafe { libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) };
+    (rc == 0).then(|| unsafe { stat.assume_init() })
+}
+
+fn device_type(device: &str) -> String {
+    let Some(queue) = block_queue(device) else {
+        return String::new();
+    };
+    let name = queue
+        .parent()
+        .and_then(Path::file_name)
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    if name.starts_with("nvme") {
+        return "NVMe".to_string();
+    }
+    match read_string(&queue.join("rotational")).as_deref() {
+        Some("1") => "HDD".to_string(),
+        Some("0") => "SSD".to_string(),
+        _ => String::new(),
+    }
+}
+
+fn block_queue(device: &str) -> Option<PathBuf> {
+    if !device.starts_with("/dev/") {
+        return None;
+    }
+    // /dev/mapper/* and /dev/disk/by-* are symlinks to the real node
+    let device = fs::canonicalize(device).ok()?;
+    let name = device.file_name()?;
+    let mut block = fs::canonicalize(Path::new("/sys/class/block").join(name)).ok()?;
+    // Partitions live underneath their parent disk, which owns the queue
+    if block.join("partition").exists() {
+        block.pop();
+    }
+    Some(block.join("queue"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_mount_lines() {
+        assert_eq!(
+            parse_mounts(
+                "/dev/nvme0n1p2 / ext4 rw,relatime 0 0\n\
+                 tmpfs /run tmpfs rw,nosuid 0 0"
+            ),
+            vec![
+                (
+                    "/dev/nvme0n1p2".to_string(),
+                    "/".to_string(),
+                    "ext4".to_string()
+                ),
+                ("tmpfs".to_string(), "/run".to_string(), "tmpfs".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn unescapes_octal_sequences() {
+        assert_eq!(unescape("/media/My\\040Disk"), "/media/My Disk");
+        assert_eq!(unescape("/trailing\\04"), "/trailing\\04");
+    }
+}
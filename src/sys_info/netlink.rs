@@ -0,0 +1,20 @@
+use std::path::Path;
+
+use super::{
+    NetworkInterface,
+    sysfs::{read_string, read_u64},
+};
+
+pub fn read_link_info(iface: &mut NetworkInterface) {
+    let path = Path::new("/sys/class/net").join(&iface.name);
+    if !path.exists() {
+        return;
+    }
+    // Virtual interfaces report -1 (or u32::MAX) when there is no link speed
+    iface.link_speed = read_string(&path.join("speed"))
+        .and_then(|speed| speed.parse::<i64>().ok())
+        .filter(|&speed| speed > 0 && speed < u32::MAX as i64)
+        .map(|speed| speed as u64);
+    iface.mtu = read_u64(&path.join("mtu")).map(|mtu| mtu as u32);
+    iface.duplex = read_string(&path.join("duplex")).filter(|duplex| duplex != "unknown");
+}
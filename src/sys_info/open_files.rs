@@ -0,0 +1,199 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    path::Path,
+};
+
+use super::{FdKind, OPEN_FILES_LIMIT, OpenFile, OpenFiles};
+
+pub fn read_open_files(pid: u32) -> io::Result<OpenFiles> {
+    let proc = Path::new("/proc").join(pid.to_string());
+    let mut fds: Vec<u32> = fs::read_dir(proc.join("fd"))?
+        .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+        .collect();
+    fds.sort_unstable();
+    let total = fds.len();
+    // A descriptor closed since the listing is simply left out
+    let targets: Vec<(u32, String)> = fds
+        .into_iter()
+        .take(OPEN_FILES_LIMIT)
+        .filter_map(|fd| {
+            let target = fs::read_link(proc.join("fd").join(fd.to_string())).ok()?;
+            Some((fd, target.to_string_lossy().into_owned()))
+        })
+        .collect();
+    let sockets = if targets
+        .iter()
+        .any(|(_, target)| target.starts_with("socket:["))
+    {
+        socket_names(&proc)
+    } else {
+        HashMap::new()
+    };
+    let mut files: Vec<OpenFile> = targets
+        .into_iter()
+        .map(|(fd, target)| classify(fd, target, &sockets))
+        .collect();
+    files.sort_by_key(|file| (file.kind, file.fd));
+    Ok(OpenFiles { files, total })
+}
+
+fn classify(fd: u32, target: String, sockets: &HashMap<u64, String>) -> OpenFile {
+    let inode = |prefix: &str| {
+        target
+            .strip_prefix(prefix)?
+            .strip_suffix(']')?
+            .parse::<u64>()
+            .ok()
+    };
+    let (kind, target) = if let Some(inode) = inode("socket:[") {
+        let name = sockets.get(&inode).cloned().unwrap_or(target);
+        (FdKind::Socket, name)
+    } else if target.starts_with("pipe:[") {
+        (FdKind::Pipe, target)
+    } else if target.starts_with('/') {
+        (FdKind::File, target)
+    } else {
+        (FdKind::Other, target)
+    };
+    OpenFile { fd, kind, target }
+}
+
+// Socket inodes to a readable description, from the tables of the process's
+// own network namespace
+fn socket_names(proc: &Path) -> HashMap<u64, String> {
+    let net = proc.join("net");
+    let mut names = HashMap::new();
+    for (file, protocol) in [
+        ("tcp", "TCP"),
+        ("tcp6", "TCP"),
+        ("udp", "UDP"),
+        ("udp6", "UDP"),
+    ] {
+        if let Ok(table) = fs::read_to_string(net.join(file)) {
+            names.extend(parse_inet_table(&table, protocol));
+        }
+    }
+    if let Ok(table) = fs::read_to_string(net.join("unix")) {
+        names.extend(parse_unix_table(&table));
+    }
+    names
+}
+
+// /proc/net/tcp: "0: 0100007F:0277 00000000:0000 0A ... 0 0 12345 ..." with
+// local and remote address, state and, tenth, the inode
+fn parse_inet_table<'a>(
+    table: &'a str,
+    protocol: &'a str,
+) -> impl Iterator<Item = (u64, String)> + 'a {
+    table.lines().skip(1).filter_map(move |line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let local = parse_address(fields.get(1)?)?;
+        let remote = parse_address(fields.get(2)?)?;
+        let state = u8::from_str_radix(fields.get(3)?, 16).ok()?;
+        let inode = fields.get(9)?.parse().ok()?;
+        let name = match (protocol, tcp_state(state)) {
+            ("TCP", "LISTEN") => format!("TCP {} LISTEN", local),
+            ("TCP", state) => format!("TCP {} → {} {}", local, remote, state),
+            // UDP sockets only have a remote end once connected
+            _ if remote.port() == 0 => format!("{} {}", protocol, local),
+            _ => format!("{} {} → {}", protocol, local, remote),
+        };
+        Some((inode, name))
+    })
+}
+
+// The kernel prints each 32-bit word of the address as a native-endian hex
+// number, and the port in host order
+fn parse_address(field: &str) -> Option<SocketAddr> {
+    let (ip, port) = field.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let word = |index: usize| {
+        let hex = ip.get(index * 8..(index + 1) * 8)?;
+        Some(u32::from_str_radix(hex, 16).ok()?.to_ne_bytes())
+    };
+    let ip = match ip.len() {
+        8 => IpAddr::from(Ipv4Addr::from(word(0)?)),
+        32 => {
+            let mut octets = [0; 16];
+            for (index, chunk) in octets.chunks_mut(4).enumerate() {
+                chunk.copy_from_slice(&word(index)?);
+            }
+            IpAddr::from(Ipv6Addr::from(octets))
+        }
+        _ => return None,
+    };
+    Some(SocketAddr::new(ip, port))
+}
+
+fn tcp_state(state: u8) -> &'static str {
+    match state {
+        0x01 => "ESTABLISHED",
+        0x02 => "SYN_SENT",
+        0x03 => "SYN_RECV",
+        0x04 => "FIN_WAIT1",
+        0x05 => "FIN_WAIT2",
+        0x06 => "TIME_WAIT",
+        0x07 => "CLOSE",
+        0x08 => "CLOSE_WAIT",
+        0x09 => "LAST_ACK",
+        0x0A => "LISTEN",
+        0x0B => "CLOSING",
+        _ => "UNKNOWN",
+    }
+}
+
+// /proc/net/unix: "Num RefCount Protocol Flags Type St Inode Path", the path
+// missing for unnamed sockets
+fn parse_unix_table(table: &str) -> impl Iterator<Item = (u64, String)> + '_ {
+    table.lines().skip(1).filter_map(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let inode = fields.get(6)?.parse().ok()?;
+        let path = fields.get(7).copied().unwrap_or("(unnamed)");
+        Some((inode, format!("UNIX {}", path)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_sockets_from_the_net_tables() {
+        let tcp = "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:0277 00000000:0000 0A 00000000:00000000 00:00000000 00000000     0        0 1001 1 0 100 0 0 10 0
+   1: 0501A8C0:C8A2 22D8B85D:01BB 01 00000000:00000000 00:00000000 00000000  1000        0 1002 1 0 20 4 30 10 -1
+";
+        let udp6 = "  sl  local_address                         remote_address                        st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode ref pointer drops
+  0: 00000000000000000000000001000000:14E9 00000000000000000000000000000000:0000 07 00000000:00000000 00:00000000 00000000   100        0 1003 2 0 0
+";
+        let unix = "Num       RefCount Protocol Flags    Type St Inode Path
+0000000000000000: 00000002 00000000 00010000 0001 01 1004 /run/dbus/system_bus_socket
+0000000000000000: 00000003 00000000 00000000 0001 03 1005
+";
+        let names: HashMap<u64, String> = parse_inet_table(tcp, "TCP")
+            .chain(parse_inet_table(udp6, "UDP"))
+            .chain(parse_unix_table(unix))
+            .collect();
+        assert_eq!(names[&1001], "TCP 127.0.0.1:631 LISTEN");
+        assert_eq!(
+            names[&1002],
+            "TCP 192.168.1.5:51362 → 93.184.216.34:443 ESTABLISHED"
+        );
+        assert_eq!(names[&1003], "UDP [::1]:5353");
+        assert_eq!(names[&1004], "UNIX /run/dbus/system_bus_socket");
+        assert_eq!(names[&1005], "UNIX (unnamed)");
+    }
+
+    #[test]
+    fn groups_descriptors_by_what_they_point_at() {
+        let sockets = HashMap::from([(7, "UNIX (unnamed)".to_string())]);
+        let kind = |target: &str| classify(3, target.to_string(), &sockets);
+        assert_eq!(kind("/var/log/syslog").kind, FdKind::File);
+        assert_eq!(kind("pipe:[42]").kind, FdKind::Pipe);
+        assert_eq!(kind("anon_inode:[eventfd]").kind, FdKind::Other);
+        assert_eq!(kind("socket:[7]").target, "UNIX (unnamed)");
+        assert_eq!(kind("socket:[8]").target, "socket:[8]");
+    }
+}
@@ -0,0 +1,170 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::Path,
+    process::Command,
+    time::{Duration, Instant},
+};
+
+use super::{
+    DiskInfo, StoragePool,
+    sysfs::{read_string, read_u64},
+};
+
+const GB: u64 = 1024 * 1024 * 1024;
+
+// Listing pools forks zpool, so it runs less often than the statvfs reads.
+const ZPOOL_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+pub struct PoolCollector {
+    zpools: Vec<StoragePool>,
+    last_zpool_listing: Option<Instant>,
+}
+
+impl PoolCollector {
+    /// Tags btrfs and ZFS mounts with the pool they belong to and returns the
+    /// pools with their real allocatable space.
+    pub fn refresh(&mut self, disks: &mut [DiskInfo]) -> Vec<StoragePool> {
+        let mut pools = Vec::new();
+        if disks.iter().any(|disk| disk.fs_type == "btrfs") {
+            pools.extend(btrfs_pools(disks));
+        }
+        if disks.iter().any(|disk| disk.fs_type == "zfs") {
+            if self
+                .last_zpool_listing
+                .is_none_or(|listing| listing.elapsed() >= ZPOOL_INTERVAL)
+            {
+                self.last_zpool_listing = Some(Instant::now());
+                self.zpools = list_zpools().unwrap_or_default();
+            }
+            for disk in disks.iter_mut().filter(|disk| disk.fs_type == "zfs") {
+                // Datasets are named pool/dataset/child
+                let pool = disk.name.split('/').next().unwrap_or_default();
+                if self.zpools.iter().any(|zpool| zpool.name == pool) {
+                    disk.pool = Some(pool.to_string());
+                }
+            }
+            pools.extend(self.zpools.iter().cloned());
+        }
+        pools
+    }
+}
+
+fn btrfs_pools(disks: &mut [DiskInfo]) -> Vec<StoragePool> {
+    let Ok(entries) = fs::read_dir("/sys/fs/btrfs") else {
+        return Vec::new();
+    };
+    // Block device name -> filesystem UUID
+    let mut owners = HashMap::new();
+    let mut pools = Vec::new();
+    for fs_dir in entries.flatten().map(|entry| entry.path()) {
+        let Ok(devices) = fs::read_dir(fs_dir.join("devices")) else {
+            continue;
+        };
+        let uuid = fs_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let mut device_bytes = 0;
+        let mut first_device = None;
+        for device in devices.flatten() {
+            let name = device.file_name().to_string_lossy().into_owned();
+            // Block device sizes are always in 512-byte sectors
+            device_bytes += read_u64(&device.path().join("size")).unwrap_or(0) * 512;
+            first_device.get_or_insert_with(|| name.clone());
+            owners.insert(name, uuid.clone());
+        }
+        let Some(usage) = btrfs_usage(&fs_dir.join("allocation"), device_bytes) else {
+            continue;
+        };
+        let label = read_string(&fs_dir.join("label")).filter(|label| !label.is_empty());
+        let name = label.or(first_device).unwrap_or_else(|| uuid.clone());
+        pools.push((uuid, StoragePool::new(name, "btrfs", usage.0, usage.1)));
+    }
+    for disk in disks.iter_mut().filter(|disk| disk.fs_type == "btrfs") {
+        let device = fs::canonicalize(Path::new("/dev").join(&disk.name)).ok();
+        let owner = device
+            .as_deref()
+            .and_then(Path::file_name)
+            .and_then(|name| owners.get(name.to_string_lossy().as_ref()));
+        disk.pool = owner.and_then(|uuid| {
+            pools
+                .iter()
+                .find(|(pool_uuid, _)| pool_uuid == uuid)
+                .map(|(_, pool)| pool.name.clone())
+        });
+    }
+    pools.into_iter().map(|(_, pool)| pool).collect()
+}
+
+// Mirrors the "Free (estimated)" figure of `btrfs filesystem usage`: the
+// unused part of allocated data chunks, plus unallocated device space
+// scaled down by the data profile's redundancy (2x for raid1, ...).
+fn btrfs_usage(allocation: &Path, device_bytes: u64) -> Option<(u64, u64)> {
+    let read = |group: &str, file: &str| read_u64(&allocation.join(group).join(file));
+    let data_total = read("data", "total_bytes")?;
+    let data_used = read("data", "bytes_used")?;
+    let data_disk_total = read("data", "disk_total").unwrap_or(data_total);
+    let mut used = data_used;
+    let mut allocated = data_disk_total;
+    for group in ["metadata", "system"] {
+        used += read(group, "bytes_used").unwrap_or(0);
+        allocated += read(group, "disk_total").unwrap_or(0);
+    }
+    let data_ratio = (data_disk_total as f64 / data_total.max(1) as f64).max(1.0);
+    let unallocated = device_bytes.saturating_sub(allocated);
+    let free = data_total.saturating_sub(data_used) + (unallocated as f64 / data_ratio) as u64;
+    Some((used, free))
+}
+
+fn list_zpools() -> Option<Vec<StoragePool>> {
+    let output = Command::new("zpool")
+        .args(["list", "-Hp", "-o", "name,alloc,free"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    Some(parse_zpool_list(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// -H prints one tab-separated line per pool, -p prints exact byte counts
+fn parse_zpool_list(output: &str) -> Vec<StoragePool> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            let name = fields.next()?;
+            let used = fields.next()?.parse().ok()?;
+            let free = fields.next()?.parse().ok()?;
+            Some(StoragePool::new(name.to_string(), "zfs", used, free))
+        })
+        .collect()
+}
+
+impl StoragePool {
+    fn new(name: String, kind: &str, used_bytes: u64, free_bytes: u64) -> Self {
+        let total_bytes = used_bytes + free_bytes;
+        Self {
+            name,
+            kind: kind.to_string(),
+            total: total_bytes / GB,
+            used: used_bytes / GB,
+            free: free_bytes / GB,
+            usage: (used_bytes * 100).checked_div(total_bytes).unwrap_or(0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_zpool_list() {
+        let pools = parse_zpool_list("tank\t1073741824\t3221225472\nbad line\n");
+        assert_eq!(pools.len(), 1);
+        assert_eq!(pools[0].name, "tank");
+        assert_eq!(pools[0].total, 4);
+        assert_eq!(pools[0].usage, 25);
+    }
+}
@@ -0,0 +1,85 @@
+use std::path::Path;
+
+use super::{PressureInfo, SystemPressure, sysfs::read_string};
+
+#[derive(Default)]
+pub struct PressureCollector {
+    // Whether /proc/pressure is usable; probed once since kernels without
+    // PSI (or booted with psi=0) never gain it at runtime.
+    available: Option<bool>,
+}
+
+impl PressureCollector {
+    pub fn refresh(&mut self) -> Option<SystemPressure> {
+        if self.available == Some(false) {
+            return None;
+        }
+        let pressure = read_pressure();
+        self.available.get_or_insert(pressure.is_some());
+        pressure
+    }
+}
+
+fn read_pressure() -> Option<SystemPressure> {
+    Some(SystemPressure {
+        cpu: read_resource("cpu")?,
+        memory: read_resource("memory")?,
+        io: read_resource("io")?,
+    })
+}
+
+fn read_resource(resource: &str) -> Option<PressureInfo> {
+    let contents = read_string(&Path::new("/proc/pressure").join(resource))?;
+    parse_pressure(&contents)
+}
+
+fn parse_pressure(contents: &str) -> Option<PressureInfo> {
+    let mut info = PressureInfo::default();
+    let mut found = false;
+    for line in contents.lines() {
+        let mut fields = line.split_whitespace();
+        let kind = fields.next();
+        let mut avg = [0.0; 3];
+        for field in fields {
+            let (key, value) = field.split_once('=')?;
+            let slot = match key {
+                "avg10" => 0,
+                "avg60" => 1,
+                "avg300" => 2,
+                _ => continue,
+            };
+            avg[slot] = value.parse().ok()?;
+        }
+        match kind {
+            Some("some") => {
+                [info.some_avg10, info.some_avg60, info.some_avg300] = avg;
+                found = true;
+            }
+            Some("full") => [info.full_avg10, info.full_avg60, info.full_avg300] = avg,
+            _ => {}
+        }
+    }
+    found.then_some(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_some_and_full_lines() {
+        let info = parse_pressure(
+            "some avg10=1.50 avg60=0.75 avg300=0.10 total=12345\n\
+             full avg10=0.50 avg60=0.25 avg300=0.00 total=678",
+        )
+        .unwrap();
+        assert_eq!(info.some_avg10, 1.5);
+        assert_eq!(info.some_avg300, 0.1);
+        assert_eq!(info.full_avg60, 0.25);
+    }
+
+    #[test]
+    fn rejects_empty_contents() {
+        assert!(parse_pressure("").is_none());
+    }
+}
@@ -0,0 +1,37 @@
+use std::io;
+
+use nix::errno::Errno;
+
+fn to_id(pid: u32) -> io::Result<libc::id_t> {
+    libc::id_t::try_from(pid).map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))
+}
+
+fn to_io_error(errno: Errno) -> io::Error {
+    match errno {
+        Errno::ESRCH => io::Error::from(io::ErrorKind::NotFound),
+        // Linux reports EACCES when an unprivileged user lowers nice
+        Errno::EACCES => io::Error::from(io::ErrorKind::PermissionDenied),
+        errno => io::Error::from(errno),
+    }
+}
+
+pub fn get_nice(pid: u32) -> io::Result<i32> {
+    let id = to_id(pid)?;
+    // -1 is a valid nice value, so errno is the only way to tell failure apart
+    Errno::clear();
+    let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, id) };
+    match Errno::last() {
+        Errno::UnknownErrno => Ok(nice),
+        errno => Err(to_io_error(errno)),
+    }
+}
+
+pub fn set_nice(pid: u32, nice: i32) -> io::Result<()> {
+    let id = to_id(pid)?;
+    let rc = unsafe { libc::setpriority(libc::PRIO_PROCESS, id, nice) };
+    if rc == 0 {
+        Ok(())
+    } else {
+        Err(to_io_error(Errno::last()))
+    }
+}
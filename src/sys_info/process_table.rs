@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+
+use super::ProcessInfo;
+
+struct Entry {
+    // Where the process is in the list
+    index: usize,
+    // Tells a reused PID from the process that had it before
+    started: u64,
+}
+
+/// Keeps the process list up to date in place. Each refresh updates the
+/// changing figures of the processes it already has, and only reads the
+/// name, command, user and start time of those that are new.
+///
+/// A refresh calls `begin`, then `update` for every live process, then
+/// `finish`, which drops the processes that exited.
+#[derive(Default)]
+pub struct ProcessTable {
+    entries: HashMap<u32, Entry>,
+    // Whether each process in the list was updated by this refresh
+    seen: Vec<bool>,
+}
+
+impl ProcessTable {
+    /// Starts a refresh of `processes`, which may have been reordered or
+    /// replaced since the last one.
+    pub fn begin(&mut self, processes: &[ProcessInfo]) {
+        for entry in self.entries.values_mut() {
+            entry.index = usize::MAX;
+        }
+        for (index, process) in processes.iter().enumerate() {
+            if let Some(entry) = self.entries.get_mut(&process.pid) {
+                entry.index = index;
+            }
+        }
+        self.entries.retain(|_, entry| entry.index != usize::MAX);
+        self.seen.clear();
+        self.seen.resize(processes.len(), false);
+    }
+
+    /// The entry of the process `pid` started at `started`, added with
+    /// `describe` if it is new or its PID has been reused.
+    pub fn update<'a>(
+        &mut self,
+        processes: &'a mut Vec<ProcessInfo>,
+        pid: u32,
+        started: u64,
+        describe: impl FnOnce() -> ProcessInfo,
+    ) -> &'a mut ProcessInfo {
+        let index = match self.entries.get_mut(&pid) {
+            Some(entry) if entry.started == started => entry.index,
+            Some(entry) => {
+                entry.started = started;
+                processes[entry.index] = describe();
+                entry.index
+            }
+            None => {
+                let index = processes.len();
+                processes.push(describe());
+                self.seen.push(false);
+                self.entries.insert(pid, Entry { index, started });
+                index
+            }
+        };
+        self.seen[index] = true;
+        &mut processes[index]
+    }
+
+    /// Ends a refresh, removing the processes it didn't see.
+    pub fn finish(&mut self, processes: &mut Vec<ProcessInfo>) {
+        let seen = &self.seen;
+        self.entries.retain(|_, entry| seen[entry.index]);
+        let mut index = 0;
+        processes.retain(|_| {
+            index += 1;
+            seen[index - 1]
+        });
+        // Indices are brought up to date by the next `begin`
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashSet, time::Instant};
+
+    use super::*;
+
+    fn pids(processes: &[ProcessInfo]) -> Vec<u32> {
+        processes.iter().map(|process| process.pid).collect()
+    }
+
+    #[test]
+    fn updates_in_place_and_describes_only_newcomers() {
+        let mut table = ProcessTable::default();
+        let mut processes = Vec::new();
+        let mut described = Vec::new();
+        let mut refresh = |processes: &mut Vec<ProcessInfo>, live: &[(u32, u64)]| {
+            table.begin(processes);
+            for &(pid, started) in live {
+                let process = table.update(processes, pid, started, || {
+                    described.push(pid);
+                    ProcessInfo::fixture(pid, 1, &format!("proc{}", pid))
+                });
+                process.threads += 1;
+            }
+            table.finish(processes);
+        };
+        refresh(&mut processes, &[(1, 0), (2, 0), (3, 0)]);
+        // The app sorts the list between refreshes
+        processes.reverse();
+        refresh(&mut processes, &[(1, 0), (3, 0), (4, 0)]);
+        assert_eq!(pids(&processes), [3, 1, 4]);
+        assert_eq!(processes[0].threads, 3);
+        assert_eq!(processes[2].threads, 2);
+        // PID 3 is reused by a process started later
+        refresh(&mut processes, &[(1, 0), (3, 9), (4, 0)]);
+        assert_eq!(pids(&processes), [3, 1, 4]);
+        assert_eq!(processes[0].threads, 2);
+        assert_eq!(described, [1, 2, 3, 4, 3]);
+    }
+
+    // What a collector reads for one process. The system calls that
+    // fetch it are the same before and after, so they are left out.
+    struct Sample {
+        pid: u32,
+        started: u64,
+        name: String,
+        exe: String,
+        args: Vec<String>,
+        uid: u32,
+        cpu: f64,
+        rss_kb: u64,
+    }
+
+    // 10 processes exit and 10 start between refreshes, and CPU shifts
+    fn samples(refresh: u32, count: u32) -> Vec<Sample> {
+        let first = refresh * 10 + 1;
+        (first..first + count)
+            .map(|pid| Sample {
+                pid,
+                started: 1_700_000_000 + u64::from(pid),
+                name: format!("proc{}", pid),
+                exe: format!("/usr/bin/proc{}", pid),
+                args: vec![format!("/usr/bin/proc{}", pid), "--serve".to_string()],
+                uid: pid % 4,
+                cpu: f64::from(pid.wrapping_mul(2_654_435_761).wrapping_add(refresh) % 1000) / 10.0,
+                rss_kb: u64::from(pid) * 4,
+            })
+            .collect()
+    }
+
+    fn start_time(started: u64) -> String {
+        chrono::DateTime::from_timestamp(started as i64, 0)
+            .map(|start| {
+                start
+                    .with_timezone(&chrono::Local)
+                    .format("%H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_default()
+    }
+
+    fn describe(sample: &Sample, user: String) -> ProcessInfo {
+        ProcessInfo {
+            command: sample.exe.clone(),
+            full_command: sample.args.join(" "),
+            user,
+            start_time: start_time(sample.started),
+            ..ProcessInfo::fixture(sample.pid, 1, &sample.name)
+        }
+    }
+
+    fn figures(process: &mut ProcessInfo, sample: &Sample) {
+        process.cpu_usage = sample.cpu;
+        process.rss_kb = sample.rss_kb;
+        process.memory_usage = sample.rss_kb / 1024;
+    }
+
+    // The app sorts the list after every refresh
+    fn sort(processes: &mut [ProcessInfo]) {
+        processes.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
+    }
+
+    // Run with `cargo test --release -- --ignored --nocapture refresh_cost`
+    #[test]
+    #[ignore]
+    fn refresh_cost_on_5000_processes() {
+        const PROCESSES: u32 = 5000;
+        const REFRESHES: u32 = 100;
+        let refreshes: Vec<Vec<Sample>> = (0..REFRESHES)
+            .map(|refresh| samples(refresh, PROCESSES))
+            .collect();
+        let users: HashMap<u32, String> = (0..4).map(|uid| (uid, format!("user{}", uid))).collect();
+
+        // macOS before: names, commands and users cached per (pid, start),
+        // cloned into a new list every refresh with the start time
+        // formatted again
+        let mut cache: HashMap<u32, (u64, ProcessInfo)> = HashMap::new();
+        let mut processes = Vec::new();
+        let start = Instant::now();
+        for samples in &refreshes {
+            let mut rebuilt = Vec::new();
+            // Stands for the collector's sample map, which prunes the cache
+            let mut live = HashSet::new();
+            for sample in samples {
+                live.insert(sample.pid);
+                let is_known = matches!(cache.get(&sample.pid), Some((started, _)) if *started == sample.started);
+                if !is_known {
+                    let user = users[&sample.uid].clone();
+                    cache.insert(sample.pid, (sample.started, describe(sample, user)));
+                }
+                let cached = &cache[&sample.pid].1;
+                let mut process = ProcessInfo {
+                    name: cached.name.clone(),
+                    command: cached.command.clone(),
+                    full_command: cached.full_command.clone(),
+                    user: cached.user.clone(),
+                    start_time: start_time(sample.started),
+                    ..ProcessInfo::fixture(sample.pid, 1, "")
+                };
+                figures(&mut process, sample);
+                rebuilt.push(process);
+            }
+            cache.retain(|pid, _| live.contains(pid));
+            processes = rebuilt;
+            sort(&mut processes);
+        }
+        let macos = start.elapsed();
+        assert_eq!(processes.len(), PROCESSES as usize);
+
+        // Windows before: every process described afresh, then sorted by PID
+        let start = Instant::now();
+        for samples in &refreshes {
+            let mut rebuilt: Vec<ProcessInfo> = samples
+                .iter()
+                .map(|sample| {
+                    let mut process = describe(sample, users[&sample.uid].clone());
+                    figures(&mut process, sample);
+                    process
+                })
+                .collect();
+            rebuilt.sort_by_key(|process| process.pid);
+            processes = rebuilt;
+            sort(&mut processes);
+        }
+        let windows = start.elapsed();
+        assert_eq!(processes.len(), PROCESSES as usize);
+
+        // After, on both: the app's sorted list updated in place
+        let mut table = ProcessTable::default();
+        let mut processes = Vec::new();
+        let start = Instant::now();
+        for samples in &refreshes {
+            table.begin(&processes);
+            let mut live = HashSet::new();
+            for sample in samples {
+                live.insert(sample.pid);
+                let process = table.update(&mut processes, sample.pid, sample.started, || {
+                    describe(sample, users[&sample.uid].clone())
+                });
+                figures(process, sample);
+            }
+            table.finish(&mut processes);
+            assert_eq!(live.len(), processes.len());
+            sort(&mut processes);
+        }
+        let updated = start.elapsed();
+        assert_eq!(processes.len(), PROCESSES as usize);
+        println!(
+            "{} refreshes of {} processes: macOS before {:?}, Windows before {:?}, in place {:?}",
+            REFRESHES, PROCESSES, macos, windows, updated
+        );
+        assert!(updated < macos && updated < windows);
+    }
+}
@@ -0,0 +1,79 @@
+use std::{fs, path::Path};
+
+use super::{
+    SensorKind, SensorReading,
+    sysfs::{read_string, read_u64},
+};
+
+pub fn read_sensors() -> Vec<SensorReading> {
+    let Ok(entries) = fs::read_dir("/sys/class/hwmon") else {
+        return Vec::new();
+    };
+    let mut chips: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+    chips.sort();
+    chips.iter().flat_map(|chip| read_chip(chip)).collect()
+}
+
+fn read_chip(path: &Path) -> Vec<SensorReading> {
+    let chip = read_string(&path.join("name")).unwrap_or_else(|| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    });
+    let Ok(entries) = fs::read_dir(path) else {
+        return Vec::new();
+    };
+    let mut inputs: Vec<(SensorKind, u32, String)> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.file_name().to_string_lossy().into_owned();
+            let (prefix, suffix) = file_name.split_once('_')?;
+            if suffix != "input" && suffix != "average" {
+                return None;
+            }
+            let (kind, index) = [
+                ("temp", SensorKind::Temperature),
+                ("fan", SensorKind::Fan),
+                ("in", SensorKind::Voltage),
+                ("power", SensorKind::Power),
+            ]
+            .into_iter()
+            .find_map(|(name, kind)| Some((kind, prefix.strip_prefix(name)?.parse().ok()?)))?;
+            // Power meters may expose both; prefer the instantaneous input.
+            if suffix == "average" && path.join(format!("{}_input", prefix)).exists() {
+                return None;
+            }
+            Some((kind, index, file_name))
+        })
+        .collect();
+    inputs.sort_by_key(|(kind, index, _)| (*kind as u8, *index));
+    inputs
+        .into_iter()
+        .filter_map(|(kind, index, file_name)| {
+            // Unreadable inputs (EACCES, EIO from flaky drivers) are dropped
+            // individually so the rest of the chip still shows up.
+            let raw = read_string(&path.join(&file_name))?.parse::<i64>().ok()?;
+            let prefix = file_name.split_once('_')?.0;
+            let label = read_string(&path.join(format!("{}_label", prefix))).unwrap_or_else(|| {
+                format!("{}{}", prefix.trim_end_matches(char::is_numeric), index)
+            });
+            let scale = match kind {
+                SensorKind::Temperature | SensorKind::Voltage => 1_000.0,
+                SensorKind::Fan => 1.0,
+                SensorKind::Power => 1_000_000.0,
+            };
+            let critical = ["crit", "max"]
+                .iter()
+                .find_map(|limit| read_u64(&path.join(format!("{}_{}", prefix, limit))))
+                .filter(|&limit| limit > 0)
+                .map(|limit| limit as f64 / scale);
+            Some(SensorReading {
+                chip: chip.clone(),
+                label,
+                kind,
+                value: raw as f64 / scale,
+                critical,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,35 @@
+use std::io;
+
+use nix::{errno::Errno, sys::signal, unistd::Pid};
+
+use super::Signal;
+
+fn to_nix(signal: Signal) -> signal::Signal {
+    match signal {
+        Signal::Hup => signal::Signal::SIGHUP,
+        Signal::Int => signal::Signal::SIGINT,
+        Signal::Quit => signal::Signal::SIGQUIT,
+        Signal::Kill => signal::Signal::SIGKILL,
+        Signal::Usr1 => signal::Signal::SIGUSR1,
+        Signal::Usr2 => signal::Signal::SIGUSR2,
+        Signal::Term => signal::Signal::SIGTERM,
+        Signal::Cont => signal::Signal::SIGCONT,
+        Signal::Stop => signal::Signal::SIGSTOP,
+        Signal::Tstp => signal::Signal::SIGTSTP,
+    }
+}
+
+/// The platform's number for the signal, which differs between Linux and
+/// the BSDs for everything but the classic low signals.
+pub fn signal_number(signal: Signal) -> Option<i32> {
+    Some(to_nix(signal) as i32)
+}
+
+pub fn send_signal(pid: u32, signal: Signal) -> io::Result<()> {
+    let pid = i32::try_from(pid).map_err(|_| io::Error::from(io::ErrorKind::InvalidInput))?;
+    signal::kill(Pid::from_raw(pid), to_nix(signal)).map_err(|errno| match errno {
+        // std has no ErrorKind for ESRCH
+        Errno::ESRCH => io::Error::from(io::ErrorKind::NotFound),
+        errno => io::Error::from(errno),
+    })
+}
@@ -0,0 +1,41 @@
+use super::SystemInfo;
+
+#[derive(Default)]
+pub struct Collector;
+
+impl Collector {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn refresh(&mut self, metrics: &mut SystemInfo) {
+        metrics.simulated = true;
+        for usage in &mut metrics.cpu_usage_per_core {
+            let change = rand::random::<u64>() % 10;
+            let direction = if rand::random::<bool>() { 1 } else { -1 };
+            *usage = (*usage as i64 + change as i64 * direction).clamp(0, 100) as u64;
+        }
+        metrics.cpu_total_usage =
+            metrics.cpu_usage_per_core.iter().sum::<u64>() / metrics.cpu_count.max(1) as u64;
+        let mem_change = rand::random::<u64>() % 50;
+        let mem_direction = if rand::random::<bool>() { 1 } else { -1 };
+        metrics.memory_used = (metrics.memory_used as i64 + mem_change as i64 * mem_direction)
+            .clamp(0, metrics.memory_total as i64) as u64;
+        metrics.total_rx =
+            (metrics.total_rx as i64 + rand::random::<i64>() % 200 - 100).clamp(0, 5000) as u64;
+        metrics.total_tx =
+            (metrics.total_tx as i64 + rand::random::<i64>() % 100 - 50).clamp(0, 2500) as u64;
+        for process in &mut metrics.processes {
+            let cpu_change = rand::random::<f64>() % 5.0;
+            let cpu_direction = if rand::random::<bool>() { 1.0 } else { -1.0 };
+            process.cpu_usage = (process.cpu_usage + cpu_change * cpu_direction).clamp(0.0, 100.0);
+            let mem_change = rand::random::<u64>() % 10;
+            let mem_direction = if rand::random::<bool>() { 1 } else { -1 };
+            process.memory_usage = (process.memory_usage as i64 + mem_change as i64 * mem_direction)
+                .clamp(0, 2000) as u64;
+            process.rss_kb = process.memory_usage * 1024;
+            process.memory_percent =
+                process.memory_usage as f64 / metrics.memory_total.max(1) as f64 * 100.0;
+        }
+    }
+}
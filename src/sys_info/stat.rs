@@ -0,0 +1,85 @@
+use std::path::Path;
+
+use super::sysfs::read_string;
+
+/// What the collector uses of /proc/<pid>/stat.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProcessStat {
+    pub priority: i32,
+    pub nice: i32,
+    // utime + stime, in jiffies
+    pub cpu_ticks: u64,
+    // Jiffies after boot the process started at
+    pub start_time: u64,
+}
+
+pub fn read_process_stat(pid: u32) -> Option<ProcessStat> {
+    parse_process_stat(&read_string(
+        &Path::new("/proc").join(pid.to_string()).join("stat"),
+    )?)
+}
+
+/// Jiffies spent by all CPUs together since boot, from the "cpu" line of
+/// /proc/stat.
+pub fn read_total_jiffies() -> Option<u64> {
+    parse_total_jiffies(&read_string(Path::new("/proc/stat"))?)
+}
+
+// The command name in parentheses may itself contain spaces and ')', so
+// fields are counted from the last ')', the state being field 3. utime and
+// stime are fields 14 and 15, priority and nice 18 and 19, starttime 22.
+fn parse_process_stat(stat: &str) -> Option<ProcessStat> {
+    let (_, rest) = stat.rsplit_once(')')?;
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    let field = |number: usize| fields.get(number - 3).copied();
+    let ticks = |number: usize| field(number)?.parse::<u64>().ok();
+    Some(ProcessStat {
+        priority: field(18)?.parse().ok()?,
+        nice: field(19)?.parse().ok()?,
+        cpu_ticks: ticks(14)? + ticks(15)?,
+        start_time: ticks(22)?,
+    })
+}
+
+// Guest time is already counted in user and nice, so only the first eight
+// columns are summed
+fn parse_total_jiffies(stat: &str) -> Option<u64> {
+    let line = stat.lines().find_map(|line| line.strip_prefix("cpu "))?;
+    line.split_whitespace()
+        .take(8)
+        .map(|column| column.parse::<u64>().ok())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fields_after_command_name() {
+        assert_eq!(
+            parse_process_stat(
+                "4242 (Web Content) S 1 4242 4242 0 -1 4194560 1 0 0 0 5 3 0 0 25 5 12 0 900"
+            ),
+            Some(ProcessStat {
+                priority: 25,
+                nice: 5,
+                cpu_ticks: 8,
+                start_time: 900,
+            })
+        );
+        let stat =
+            parse_process_stat("7 (a) b) R 1 7 7 0 -1 0 0 0 0 0 0 0 0 0 0 -20 1 0 1").unwrap();
+        assert_eq!((stat.priority, stat.nice), (0, -20));
+        assert_eq!(parse_process_stat("7 (a) R 1 7"), None);
+    }
+
+    #[test]
+    fn sums_the_cpu_line() {
+        assert_eq!(
+            parse_total_jiffies("cpu  10 1 20 300 4 0 5 0 7 0\ncpu0 5 0 10 150 2 0 3 0 0 0\n"),
+            Some(340)
+        );
+        assert_eq!(parse_total_jiffies("intr 1\n"), None);
+    }
+}
@@ -0,0 +1,22 @@
+use std::path::Path;
+
+use super::sysfs::read_string;
+
+pub struct Statm {
+    pub virt_kb: u64,
+    pub rss_kb: u64,
+    pub shared_kb: u64,
+}
+
+pub fn read_statm(pid: u32) -> Option<Statm> {
+    let statm = read_string(&Path::new("/proc").join(pid.to_string()).join("statm"))?;
+    let page_kb = unsafe { libc::sysconf(libc::_SC_PAGESIZE) }.max(1024) as u64 / 1024;
+    let mut pages = statm
+        .split_whitespace()
+        .map(|field| field.parse::<u64>().ok());
+    Some(Statm {
+        virt_kb: pages.next()?? * page_kb,
+        rss_kb: pages.next()?? * page_kb,
+        shared_kb: pages.next()?? * page_kb,
+    })
+}
@@ -0,0 +1,12 @@
+use std::path::Path;
+
+use super::sysfs::read_string;
+
+pub fn read_swap_kb(pid: u32) -> Option<u64> {
+    let status = read_string(&Path::new("/proc").join(pid.to_string()).join("status"))?;
+    // Kernel threads have no Vm* lines at all
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmSwap:"))
+        .and_then(|value| value.trim().trim_end_matches("kB").trim().parse().ok())
+}
@@ -0,0 +1,11 @@
+use std::{fs, path::Path};
+
+pub fn read_string(path: &Path) -> Option<String> {
+    fs::read_to_string(path)
+        .ok()
+        .map(|contents| contents.trim().to_string())
+}
+
+pub fn read_u64(path: &Path) -> Option<u64> {
+    read_string(path)?.parse().ok()
+}
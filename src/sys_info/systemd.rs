@@ -0,0 +1,135 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+    process::Command,
+    time::{Duration, Instant},
+};
+
+use super::{
+    ServiceInfo,
+    sysfs::{read_string, read_u64},
+};
+
+// Listing units forks systemctl, so it runs less often than the cgroup
+// accounting reads.
+const LIST_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Default)]
+pub struct SystemdCollector {
+    available: Option<bool>,
+    services: Vec<ServiceInfo>,
+    last_listing: Option<Instant>,
+    cpu_samples: HashMap<String, (u64, Instant)>,
+}
+
+impl SystemdCollector {
+    pub fn refresh(&mut self) -> Option<Vec<ServiceInfo>> {
+        if self.available == Some(false) {
+            return None;
+        }
+        if self
+            .last_listing
+            .is_none_or(|listing| listing.elapsed() >= LIST_INTERVAL)
+        {
+            self.last_listing = Some(Instant::now());
+            match list_services() {
+                Some(services) => {
+                    self.available = Some(true);
+                    self.services = services;
+                }
+                None if self.available.is_none() => {
+                    self.available = Some(false);
+                    return None;
+                }
+                None => {}
+            }
+        }
+        let now = Instant::now();
+        let mut cpu_samples = HashMap::new();
+        for service in &mut self.services {
+            let cgroup = Path::new("/sys/fs/cgroup/system.slice").join(&service.name);
+            service.memory_usage =
+                read_u64(&cgroup.join("memory.current")).map(|bytes| bytes / 1024 / 1024);
+            let usage = read_string(&cgroup.join("cpu.stat")).and_then(|stat| {
+                stat.lines()
+                    .find_map(|line| line.strip_prefix("usage_usec "))
+                    .and_then(|usec| usec.parse::<u64>().ok())
+            });
+            service.cpu_usage = usage.and_then(|usage| {
+                let (previous, at) = self.cpu_samples.get(&service.name)?;
+                let elapsed = now.duration_since(*at).as_micros() as f64;
+                (elapsed > 0.0).then(|| usage.saturating_sub(*previous) as f64 / elapsed * 100.0)
+            });
+            if let Some(usage) = usage {
+                cpu_samples.insert(service.name.clone(), (usage, now));
+            }
+        }
+        self.cpu_samples = cpu_samples;
+        Some(self.services.clone())
+    }
+}
+
+fn list_services() -> Option<Vec<ServiceInfo>> {
+    let output = Command::new("systemctl")
+        .args([
+            "list-units",
+            "--type=service",
+            "--all",
+            "--no-pager",
+            "--output=json",
+        ])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let units: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).ok()?;
+    let field = |unit: &serde_json::Value, key: &str| {
+        unit.get(key)
+            .and_then(|value| value.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let mut services: Vec<ServiceInfo> = units
+        .iter()
+        .map(|unit| ServiceInfo {
+            name: field(unit, "unit"),
+            description: field(unit, "description"),
+            active_state: field(unit, "active"),
+            sub_state: field(unit, "sub"),
+            main_pid: None,
+            cpu_usage: None,
+            memory_usage: None,
+        })
+        .filter(|service| !service.name.is_empty())
+        .collect();
+    let main_pids = main_pids(services.iter().map(|service| service.name.as_str()));
+    for service in &mut services {
+        service.main_pid = main_pids.get(&service.name).copied();
+    }
+    Some(services)
+}
+
+fn main_pids<'a>(units: impl Iterator<Item = &'a str>) -> HashMap<String, u32> {
+    let Ok(output) = Command::new("systemctl")
+        .args(["show", "--no-pager", "--property=Id,MainPID", "--"])
+        .args(units)
+        .output()
+    else {
+        return HashMap::new();
+    };
+    // One "Key=value" block per unit, separated by blank lines
+    String::from_utf8_lossy(&output.stdout)
+        .split("\n\n")
+        .filter_map(|block| {
+            let mut id = None;
+            let mut pid = None;
+            for line in block.lines() {
+                match line.split_once('=') {
+                    Some(("Id", value)) => id = Some(value.to_string()),
+                    Some(("MainPID", value)) => pid = value.parse::<u32>().ok(),
+                    _ => {}
+                }
+            }
+            Some((id?, pid.filter(|&pid| pid > 0)?))
+        })
+        .collect()
+}
@@ -0,0 +1,122 @@
+use std::{ffi::c_void, path::Path};
+
+use super::{WifiInfo, sysfs::read_string};
+
+const SIOCGIWESSID: libc::c_ulong = 0x8B1B;
+const SIOCGIWRATE: libc::c_ulong = 0x8B21;
+const IW_ESSID_MAX_SIZE: usize = 32;
+
+// struct iwreq from <linux/wireless.h>
+#[repr(C)]
+struct IwRequest {
+    name: [libc::c_char; libc::IFNAMSIZ],
+    data: IwData,
+}
+
+#[repr(C)]
+union IwData {
+    point: IwPoint,
+    param: IwParam,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct IwPoint {
+    pointer: *mut c_void,
+    length: u16,
+    flags: u16,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct IwParam {
+    value: i32,
+    fixed: u8,
+    disabled: u8,
+    flags: u16,
+}
+
+pub fn read_wifi(name: &str) -> Option<WifiInfo> {
+    if !Path::new("/sys/class/net")
+        .join(name)
+        .join("wireless")
+        .exists()
+    {
+        return None;
+    }
+    let (quality, signal_dbm) = read_proc_wireless(name).unzip();
+    let ssid = read_ssid(name);
+    Some(WifiInfo {
+        bitrate: ssid.as_ref().and_then(|_| read_bitrate(name)),
+        ssid,
+        quality,
+        signal_dbm,
+    })
+}
+
+// /proc/net/wireless: "wlan0: 0000   70.  -40.  -256  ..." (link out of 70)
+fn read_proc_wireless(name: &str) -> Option<(u64, i32)> {
+    let wireless = read_string(Path::new("/proc/net/wireless"))?;
+    wireless.lines().skip(2).find_map(|line| {
+        let (iface, rest) = line.split_once(':')?;
+        if iface.trim() != name {
+            return None;
+        }
+        let mut fields = rest.split_whitespace().skip(1);
+        let link: f64 = fields.next()?.trim_end_matches('.').parse().ok()?;
+        let level: f64 = fields.next()?.trim_end_matches('.').parse().ok()?;
+        Some(((link * 100.0 / 70.0).clamp(0.0, 100.0) as u64, level as i32))
+    })
+}
+
+fn wireless_ioctl(name: &str, request: libc::c_ulong, data: IwData) -> Option<IwData> {
+    let mut iwreq = IwRequest {
+        name: [0; libc::IFNAMSIZ],
+        data,
+    };
+    for (dst, src) in iwreq
+        .name
+        .iter_mut()
+        .zip(name.bytes().take(libc::IFNAMSIZ - 1))
+    {
+        *dst = src as libc::c_char;
+    }
+    unsafe {
+        let socket = libc::socket(libc::AF_INET, libc::SOCK_DGRAM, 0);
+        if socket < 0 {
+            return None;
+        }
+        let result = libc::ioctl(socket, request as _, &mut iwreq);
+        libc::close(socket);
+        (result >= 0).then_some(iwreq.data)
+    }
+}
+
+fn read_ssid(name: &str) -> Option<String> {
+    let mut buffer = [0u8; IW_ESSID_MAX_SIZE + 1];
+    let data = IwData {
+        point: IwPoint {
+            pointer: buffer.as_mut_ptr() as *mut c_void,
+            length: buffer.len() as u16,
+            flags: 0,
+        },
+    };
+    let length = unsafe { wireless_ioctl(name, SIOCGIWESSID, data)?.point.length } as usize;
+    let ssid = String::from_utf8_lossy(&buffer[..length.min(IW_ESSID_MAX_SIZE)])
+        .trim_end_matches('\0')
+        .to_string();
+    (!ssid.is_empty()).then_some(ssid)
+}
+
+fn read_bitrate(name: &str) -> Option<u64> {
+    let data = IwData {
+        param: IwParam {
+            value: 0,
+            fixed: 0,
+            disabled: 0,
+            flags: 0,
+        },
+    };
+    let bitrate = unsafe { wireless_ioctl(name, SIOCGIWRATE, data)?.param.value };
+    (bitrate > 0).then_some(bitrate as u64 / 1_000_000)
+}
@@ -0,0 +1,422 @@
+use std::{
+    collections::HashMap,
+    ffi::c_void,
+    mem,
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+use sysinfo::{Disks, Networks, ProcessesToUpdate, System, Users};
+use windows_sys::Wdk::System::SystemInformation::{
+    NtQuerySystemInformation, SystemProcessInformation,
+};
+
+use super::{
+    DiskInfo, LoadAverage, NetworkInterface, ProcessInfo, ProcessState, SystemInfo,
+    process_table::ProcessTable,
+};
+
+const MB: u64 = 1024 * 1024;
+const GB: u64 = 1024 * 1024 * 1024;
+
+const STATUS_INFO_LENGTH_MISMATCH: i32 = 0xC000_0004_u32 as i32;
+const THREAD_STATE_WAITING: u32 = 5;
+const WAIT_REASON_SUSPENDED: u32 = 5;
+
+// SYSTEM_PROCESS_INFORMATION and SYSTEM_THREAD_INFORMATION as laid out by
+// ntdll; windows-sys only ships a truncated version of the former.
+#[repr(C)]
+struct SystemProcessInfo {
+    next_entry_offset: u32,
+    number_of_threads: u32,
+    working_set_private_size: i64,
+    hard_fault_count: u32,
+    number_of_threads_high_watermark: u32,
+    cycle_time: u64,
+    create_time: i64,
+    user_time: i64,
+    kernel_time: i64,
+    image_name: [usize; 2],
+    base_priority: i32,
+    unique_process_id: usize,
+    inherited_from_unique_process_id: usize,
+    handle_count: u32,
+    session_id: u32,
+    unique_process_key: usize,
+    peak_virtual_size: usize,
+    virtual_size: usize,
+    page_fault_count: u32,
+    peak_working_set_size: usize,
+    working_set_size: usize,
+    quota_peak_paged_pool_usage: usize,
+    quota_paged_pool_usage: usize,
+    quota_peak_non_paged_pool_usage: usize,
+    quota_non_paged_pool_usage: usize,
+    pagefile_usage: usize,
+    peak_pagefile_usage: usize,
+    private_page_count: usize,
+    read_operation_count: i64,
+    write_operation_count: i64,
+    other_operation_count: i64,
+    read_transfer_count: i64,
+    write_transfer_count: i64,
+    other_transfer_count: i64,
+}
+
+#[repr(C)]
+struct SystemThreadInfo {
+    kernel_time: i64,
+    user_time: i64,
+    create_time: i64,
+    wait_time: u32,
+    start_address: usize,
+    client_id: [usize; 2],
+    priority: i32,
+    base_priority: i32,
+    context_switches: u32,
+    thread_state: u32,
+    wait_reason: u32,
+}
+
+struct ThreadSummary {
+    threads: u32,
+    base_priority: i32,
+    suspended: bool,
+}
+
+pub struct Collector {
+    system: System,
+    disks: Disks,
+    networks: Networks,
+    users: Users,
+    processes: ProcessTable,
+    last_refresh: Option<Instant>,
+}
+
+impl Default for Collector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Collector {
+    pub fn new() -> Self {
+        Self {
+            system: System::new(),
+            disks: Disks::new(),
+            networks: Networks::new(),
+            users: Users::new_with_refreshed_list(),
+            processes: ProcessTable::default(),
+            last_refresh: None,
+        }
+    }
+
+    pub fn refresh(&mut self, metrics: &mut SystemInfo) {
+        let now = Instant::now();
+        let elapsed = self
+            .last_refresh
+            .map(|last| now.duration_since(last))
+            .unwrap_or_default();
+        if self.last_refresh.is_none() {
+            metrics.hostname = System::host_name().unwrap_or_else(|| "localhost".to_string());
+            metrics.kernel_version = System::kernel_version().unwrap_or_default();
+            metrics.os_name = System::long_os_version().unwrap_or_else(|| "Windows".to_string());
+            metrics.cpu_temperature = None;
+            metrics.simulated = false;
+        }
+        self.last_refresh = Some(now);
+        self.system.refresh_cpu_all();
+        self.system.refresh_memory();
+        self.system.refresh_processes(ProcessesToUpdate::All, true);
+        self.disks.refresh(true);
+        self.networks.refresh(true);
+        self.refresh_cpu(metrics);
+        self.refresh_memory(metrics);
+        self.refresh_processes(metrics, elapsed);
+        self.refresh_disks(metrics, elapsed);
+        self.refresh_network(metrics, elapsed);
+        metrics.uptime = Duration::from_secs(System::uptime());
+        let load = System::load_average();
+        metrics.load_average = LoadAverage {
+            one: load.one,
+            five: load.five,
+            fifteen: load.fifteen,
+        };
+        metrics.last_update = now;
+    }
+
+    fn refresh_cpu(&self, metrics: &mut SystemInfo) {
+        let cpus = self.system.cpus();
+        if cpus.is_empty() {
+            return;
+        }
+        metrics.cpu_count = cpus.len();
+        metrics.cpu_usage_per_core = cpus
+            .iter()
+            .map(|cpu| cpu.cpu_usage().round().clamp(0.0, 100.0) as u64)
+            .collect();
+        metrics.cpu_total_usage = self.system.global_cpu_usage().round().clamp(0.0, 100.0) as u64;
+        metrics.cpu_model = cpus[0].brand().trim().to_string();
+        metrics.cpu_frequency = Some(cpus[0].frequency()).filter(|&mhz| mhz > 0);
+    }
+
+    fn refresh_memory(&self, metrics: &mut SystemInfo) {
+        metrics.memory_total = self.system.total_memory() / MB;
+        metrics.memory_used = self.system.used_memory() / MB;
+        metrics.memory_free = self.system.free_memory() / MB;
+        metrics.memory_available = self.system.available_memory() / MB;
+        // Windows has no page cache or buffer split comparable to Linux.
+        metrics.memory_cached = 0;
+        metrics.memory_buffers = 0;
+        metrics.swap_total = self.system.total_swap() / MB;
+        metrics.swap_used = self.system.used_swap() / MB;
+        metrics.swap_free = self.system.free_swap() / MB;
+    }
+
+    fn refresh_processes(&mut self, metrics: &mut SystemInfo, elapsed: Duration) {
+        let summaries = thread_summaries();
+        let memory_total = self.system.total_memory();
+        let per_second = |bytes: u64| {
+            if elapsed.is_zero() {
+                0
+            } else {
+                (bytes as f64 / 1024.0 / elapsed.as_secs_f64()) as u64
+            }
+        };
+        self.processes.begin(&metrics.processes);
+        for process in self.system.processes().values() {
+            let pid = process.pid().as_u32();
+            let summary = summaries.get(&pid);
+            // Names, commands and owners are only read for new processes
+            let entry =
+                self.processes
+                    .update(&mut metrics.processes, pid, process.start_time(), || {
+                        describe_process(process, &self.users)
+                    });
+            let disk = process.disk_usage();
+            entry.ppid = process.parent().map(|pid| pid.as_u32()).unwrap_or(0);
+            entry.cpu_usage = process.cpu_usage() as f64;
+            entry.memory_usage = process.memory() / MB;
+            entry.memory_percent = if memory_total > 0 {
+                process.memory() as f64 / memory_total as f64 * 100.0
+            } else {
+                0.0
+            };
+            entry.virt_kb = process.virtual_memory() / 1024;
+            entry.rss_kb = process.memory() / 1024;
+            entry.state = if summary.is_some_and(|summary| summary.suspended) {
+                ProcessState::Suspended
+            } else {
+                ProcessState::Running
+            };
+            entry.priority = summary.map(|summary| summary.base_priority).unwrap_or(0);
+            entry.threads = summary.map(|summary| summary.threads).unwrap_or(0);
+            entry.uptime = Duration::from_secs(process.run_time());
+            entry.read_speed = Some(per_second(disk.read_bytes));
+            entry.write_speed = Some(per_second(disk.written_bytes));
+        }
+        self.processes.finish(&mut metrics.processes);
+        metrics.process_count = metrics.processes.len();
+        metrics.thread_count = metrics.processes.iter().map(|p| p.threads as usize).sum();
+    }
+
+    fn refresh_disks(&self, metrics: &mut SystemInfo, elapsed: Duration) {
+        let per_second = |bytes: u64| {
+            if elapsed.is_zero() {
+                0
+            } else {
+                (bytes as f64 / MB as f64 / elapsed.as_secs_f64()) as u64
+            }
+        };
+        metrics.disks = self
+            .disks
+            .list()
+            .iter()
+            .filter(|disk| disk.total_space() > 0)
+            .map(|disk| {
+                let total = disk.total_space();
+                let free = disk.available_space();
+                let used = total.saturating_sub(free);
+                let usage = disk.usage();
+                let label = disk.name().to_string_lossy().into_owned();
+                let mount_point = disk.mount_point().display().to_string();
+                DiskInfo {
+                    // Drive letters are how Windows users identify volumes;
+                    // the volume label is shown alongside when there is one.
+                    name: if label.is_empty() {
+                        mount_point.trim_end_matches('\\').to_string()
+                    } else {
+                        label
+                    },
+                    mount_point,
+                    total: total / GB,
+                    used: used / GB,
+                    free: free / GB,
+                    usage: used * 100 / total,
+                    read_speed: Some(per_second(usage.read_bytes)),
+                    write_speed: Some(per_second(usage.written_bytes)),
+                    device_type: disk.kind().to_string(),
+                    fs_type: disk.file_system().to_string_lossy().into_owned(),
+                    // NTFS has no fixed inode table to run out of
+                    inodes_total: 0,
+                    inodes_used: 0,
+                    inodes_usage_pct: None,
+                    temperature: None,
+                    pool: None,
+                }
+            })
+            .collect();
+    }
+
+    fn refresh_network(&self, metrics: &mut SystemInfo, elapsed: Duration) {
+        let per_second = |bytes: u64| {
+            if elapsed.is_zero() {
+                0
+            } else {
+                (bytes as f64 / 1024.0 / elapsed.as_secs_f64()) as u64
+            }
+        };
+        // sysinfo keys adapters by their friendly name ("Ethernet", "Wi-Fi"),
+        // which is what the Network view should show.
+        let mut interfaces: Vec<NetworkInterface> = self
+            .networks
+            .list()
+            .iter()
+            .filter_map(|(name, data)| {
+                let ip = data
+                    .ip_networks()
+                    .iter()
+                    .map(|network| network.addr)
+                    .find(IpAddr::is_ipv4)?;
+                Some(NetworkInterface {
+                    name: name.clone(),
+                    rx_bytes: data.total_received(),
+                    tx_bytes: data.total_transmitted(),
+                    rx_speed: per_second(data.received()),
+                    tx_speed: per_second(data.transmitted()),
+                    ip_address: ip.to_string(),
+                    mac_address: data.mac_address().to_string(),
+                    status: "up".to_string(),
+                    link_speed: None,
+                    mtu: Some(data.mtu() as u32),
+                    duplex: None,
+                    wifi: None,
+                })
+            })
+            .collect();
+        interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+        let external = interfaces
+            .iter()
+            .filter(|iface| !iface.name.starts_with("Loopback"));
+        metrics.total_rx = external.clone().map(|iface| iface.rx_speed).sum();
+        metrics.total_tx = external.map(|iface| iface.tx_speed).sum();
+        metrics.network_interfaces = interfaces;
+    }
+}
+
+// The parts of a process that stay the same until it exits
+fn describe_process(process: &sysinfo::Process, users: &Users) -> ProcessInfo {
+    let name = process.name().to_string_lossy().into_owned();
+    let command = process
+        .exe()
+        .map(|exe| exe.display().to_string())
+        .unwrap_or_else(|| name.clone());
+    let full_command = process
+        .cmd()
+        .iter()
+        .map(|arg| arg.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let user = process
+        .user_id()
+        .and_then(|uid| users.get_user_by_id(uid))
+        .map(|user| user.name().to_string())
+        .unwrap_or_default();
+    ProcessInfo {
+        pid: process.pid().as_u32(),
+        ppid: 0,
+        name,
+        command,
+        full_command,
+        user,
+        cpu_usage: 0.0,
+        memory_usage: 0,
+        memory_percent: 0.0,
+        virt_kb: 0,
+        rss_kb: 0,
+        shared_kb: None,
+        swap_kb: 0,
+        state: ProcessState::Running,
+        priority: 0,
+        nice: 0,
+        threads: 0,
+        start_time: chrono::DateTime::from_timestamp(process.start_time() as i64, 0)
+            .map(|start| {
+                start
+                    .with_timezone(&chrono::Local)
+                    .format("%H:%M:%S")
+                    .to_string()
+            })
+            .unwrap_or_default(),
+        uptime: Duration::ZERO,
+        read_speed: None,
+        write_speed: None,
+        container: None,
+        fd_count: None,
+        restricted: false,
+    }
+}
+
+// Windows has no per-process run state: a process counts as suspended when
+// every one of its threads is waiting with the Suspended wait reason.
+fn thread_summaries() -> HashMap<u32, ThreadSummary> {
+    let mut summaries = HashMap::new();
+    let mut buf: Vec<u64> = vec![0; 256 * 1024];
+    loop {
+        let mut needed = 0u32;
+        let status = unsafe {
+            NtQuerySystemInformation(
+                SystemProcessInformation,
+                buf.as_mut_ptr() as *mut c_void,
+                (buf.len() * mem::size_of::<u64>()) as u32,
+                &mut needed,
+            )
+        };
+        if status == STATUS_INFO_LENGTH_MISMATCH {
+            buf.resize(needed as usize / mem::size_of::<u64>() + 16 * 1024, 0);
+            continue;
+        }
+        if status < 0 {
+            return summaries;
+        }
+        break;
+    }
+    let base = buf.as_ptr() as *const u8;
+    let mut offset = 0usize;
+    loop {
+        let process = unsafe { &*(base.add(offset) as *const SystemProcessInfo) };
+        let threads = unsafe {
+            std::slice::from_raw_parts(
+                base.add(offset + mem::size_of::<SystemProcessInfo>()) as *const SystemThreadInfo,
+                process.number_of_threads as usize,
+            )
+        };
+        summaries.insert(
+            process.unique_process_id as u32,
+            ThreadSummary {
+                threads: process.number_of_threads,
+                base_priority: process.base_priority,
+                suspended: !threads.is_empty()
+                    && threads.iter().all(|thread| {
+                        thread.thread_state == THREAD_STATE_WAITING
+                            && thread.wait_reason == WAIT_REASON_SUSPENDED
+                    }),
+            },
+        );
+        if process.next_entry_offset == 0 {
+            break;
+        }
+        offset += process.next_entry_offset as usize;
+    }
+    summaries
+}
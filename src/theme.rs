@@ -1,6 +1,184 @@
-use ratatui::style::Color;
+use std::{slice, str::FromStr};
 
-#[derive(Clone)]
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::Span,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::Percentage;
+
+/// The built-in themes, in the order the theme key cycles through them.
+pub const BUILT_IN: [&str; 2] = ["default", "light"];
+
+// Every key of a theme file, in the order the example lists them
+const KEYS: [&str; 19] = [
+    "bg_dark",
+    "bg_normal",
+    "bg_light",
+    "bg_lighter",
+    "border",
+    "border_light",
+    "text_primary",
+    "text_secondary",
+    "text_dim",
+    "text_bright",
+    "success",
+    "warning",
+    "danger",
+    "info",
+    "cpu_colors",
+    "mem_colors",
+    "net_colors",
+    "disk_colors",
+    "chart_gradient",
+];
+
+// The 16 ANSI colors as xterm draws them by default
+const ANSI_COLORS: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+// The channel levels of the 256-color palette's 6x6x6 cube
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// How far past its thresholds a reading is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Normal,
+    Warning,
+    Critical,
+}
+
+/// The readings a metric has to exceed to show as a warning and as
+/// critical.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Threshold {
+    pub warning: f64,
+    pub critical: f64,
+}
+
+impl Threshold {
+    const fn new(warning: f64, critical: f64) -> Self {
+        Self { warning, critical }
+    }
+
+    pub fn classify(self, value: f64) -> Severity {
+        if value > self.critical {
+            Severity::Critical
+        } else if value > self.warning {
+            Severity::Warning
+        } else {
+            Severity::Normal
+        }
+    }
+}
+
+/// Where each kind of metric turns to warning and critical colors, set in
+/// the config's `[thresholds]` table.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Thresholds {
+    // CPU, disk and inode usage in percent
+    pub usage: Threshold,
+    // Share of RAM in use
+    pub memory: Threshold,
+    // CPU and drive temperatures in °C
+    pub temperature: Threshold,
+    // One process's CPU%, where a busy core is 100
+    pub process_cpu: Threshold,
+    // One process's share of RAM
+    pub process_memory: Threshold,
+    // Share of swap in use; the header shows it past the warning level
+    pub swap: Threshold,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            usage: Threshold::new(70.0, 85.0),
+            memory: Threshold::new(70.0, 90.0),
+            temperature: Threshold::new(70.0, 80.0),
+            process_cpu: Threshold::new(25.0, 50.0),
+            process_memory: Threshold::new(5.0, 10.0),
+            swap: Threshold::new(10.0, 50.0),
+        }
+    }
+}
+
+impl Thresholds {
+    /// Complaints about thresholds whose warning level is above critical.
+    pub fn warnings(&self) -> Vec<String> {
+        [
+            ("usage", self.usage),
+            ("memory", self.memory),
+            ("temperature", self.temperature),
+            ("process_cpu", self.process_cpu),
+            ("process_memory", self.process_memory),
+            ("swap", self.swap),
+        ]
+        .into_iter()
+        .filter(|(_, threshold)| threshold.warning > threshold.critical)
+        .map(|(name, _)| format!("thresholds.{}: warning is above critical", name))
+        .collect()
+    }
+}
+
+/// How many colors the terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepth {
+    TrueColor,
+    Indexed256,
+    Ansi16,
+    // None at all, as `NO_COLOR` asks
+    Monochrome,
+}
+
+impl ColorDepth {
+    /// Guesses from `$COLORTERM` and `$TERM`, assuming the least when they
+    /// say nothing, unless `$NO_COLOR` is set.
+    pub fn detect() -> Self {
+        Self::from_env(
+            std::env::var("NO_COLOR").ok().as_deref(),
+            std::env::var("COLORTERM").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    fn from_env(no_color: Option<&str>, colorterm: Option<&str>, term: Option<&str>) -> Self {
+        let term = term.unwrap_or_default();
+        // Set but empty does not count
+        if no_color.is_some_and(|value| !value.is_empty()) {
+            Self::Monochrome
+        } else if matches!(colorterm, Some("truecolor" | "24bit")) || term.ends_with("-direct") {
+            Self::TrueColor
+        } else if term.contains("256color") {
+            Self::Indexed256
+        } else {
+            Self::Ansi16
+        }
+    }
+}
+
+/// The default theme written out as a theme file, for `--dump-theme`.
+pub const EXAMPLE: &str = include_str!("themes/example.toml");
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Theme {
     // Background colors
     pub bg_dark: Color,
@@ -27,6 +205,10 @@ pub struct Theme {
     pub disk_colors: [Color; 4],
     // Chart
     pub chart_gradient: [Color; 5],
+    // Every color is the terminal's own, so meaning goes in modifiers and
+    // marks instead
+    pub monochrome: bool,
+    pub thresholds: Thresholds,
 }
 
 impl Default for Theme {
@@ -44,7 +226,7 @@ impl Default for Theme {
             text_dim: Color::Rgb(127, 132, 156),       // #7f849c
             text_bright: Color::Rgb(230, 235, 255),    // #e6ebff
             success: Color::Rgb(166, 227, 161),        // #a6e3a1
-            warning: Color::Rgb(249, 226, 175),        // #f9dfaf
+            warning: Color::Rgb(249, 226, 175),        // #f9e2af
             danger: Color::Rgb(243, 139, 168),         // #f38ba8
             info: Color::Rgb(137, 180, 250),           // #89b4fa
             // CPU core colors
@@ -84,28 +266,426 @@ impl Default for Theme {
                 Color::Rgb(255, 184, 108), // Orange
                 Color::Rgb(255, 119, 119), // Red
             ],
+            monochrome: false,
+            thresholds: Thresholds::default(),
         }
     }
 }
 
 impl Theme {
+    /// Looks up a built-in theme by the name used in the config file.
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "default" | "dark" => Some(Self::default()),
+            "light" => Some(Self::light()),
+            _ => None,
+        }
+    }
+
+    /// For terminals with a white or solarized-light background. `bg_dark`
+    /// stays the backdrop and `text_bright` the strongest text, so here they
+    /// are the lightest and darkest colors; the status colors are deep
+    /// enough to carry `bg_dark` text on badges.
+    pub fn light() -> Self {
+        Self {
+            bg_dark: Color::Rgb(250, 250, 250),      // #fafafa
+            bg_normal: Color::Rgb(239, 240, 243),    // #eff0f3
+            bg_light: Color::Rgb(228, 229, 235),     // #e4e5eb
+            bg_lighter: Color::Rgb(204, 208, 222),   // #ccd0de
+            border: Color::Rgb(172, 176, 190),       // #acb0be
+            border_light: Color::Rgb(140, 143, 161), // #8c8fa1
+            text_primary: Color::Rgb(76, 79, 105),   // #4c4f69
+            text_secondary: Color::Rgb(92, 95, 119), // #5c5f77
+            text_dim: Color::Rgb(108, 111, 133),     // #6c6f85
+            text_bright: Color::Rgb(17, 17, 27),     // #11111b
+            success: Color::Rgb(47, 125, 32),        // #2f7d20
+            warning: Color::Rgb(176, 104, 0),        // #b06800
+            danger: Color::Rgb(210, 15, 57),         // #d20f39
+            info: Color::Rgb(30, 102, 245),          // #1e66f5
+            cpu_colors: [
+                Color::Rgb(30, 102, 245), // Blue
+                Color::Rgb(200, 60, 160), // Pink
+                Color::Rgb(47, 125, 32),  // Green
+                Color::Rgb(213, 80, 0),   // Orange
+                Color::Rgb(210, 15, 57),  // Red
+                Color::Rgb(136, 57, 239), // Purple
+                Color::Rgb(176, 104, 0),  // Yellow
+                Color::Rgb(23, 146, 153), // Cyan
+            ],
+            mem_colors: [
+                Color::Rgb(47, 125, 32), // Green (0-70%)
+                Color::Rgb(176, 104, 0), // Yellow (70-90%)
+                Color::Rgb(210, 15, 57), // Red (90-100%)
+            ],
+            net_colors: [
+                Color::Rgb(47, 125, 32),  // Download (green)
+                Color::Rgb(30, 102, 245), // Upload (blue)
+            ],
+            disk_colors: [
+                Color::Rgb(30, 102, 245), // Read
+                Color::Rgb(210, 15, 57),  // Write
+                Color::Rgb(47, 125, 32),  // Usage
+                Color::Rgb(176, 104, 0),  // Available
+            ],
+            chart_gradient: [
+                Color::Rgb(30, 102, 245), // Blue
+                Color::Rgb(23, 146, 153), // Teal
+                Color::Rgb(47, 125, 32),  // Green
+                Color::Rgb(213, 80, 0),   // Orange
+                Color::Rgb(210, 15, 57),  // Red
+            ],
+            monochrome: false,
+            thresholds: Thresholds::default(),
+        }
+    }
+
+    /// Only the terminal's default colors: selections are reversed and
+    /// critical values marked with "!".
+    pub fn monochrome() -> Self {
+        Self {
+            monochrome: true,
+            ..Self::default().map_colors(|_| Color::Reset)
+        }
+    }
+
+    /// A selected row, tab or setting.
+    pub fn selected(&self) -> Style {
+        let style = Style::default().bg(self.bg_lighter);
+        if self.monochrome {
+            style.add_modifier(Modifier::REVERSED)
+        } else {
+            style
+        }
+    }
+
+    /// Appended to a value that is past its critical threshold, which only
+    /// the color says otherwise.
+    pub fn critical_mark(&self, severity: Severity) -> &'static str {
+        if self.monochrome && severity == Severity::Critical {
+            "!"
+        } else {
+            ""
+        }
+    }
+
+    /// A formatted percentage in the color of its severity, marked when
+    /// critical.
+    pub fn percentage(&self, percentage: Percentage) -> Span<'static> {
+        let mark = self.critical_mark(percentage.severity);
+        Span::styled(
+            percentage.text + mark,
+            Style::default()
+                .fg(self.severity_color(percentage.severity))
+                .add_modifier(Modifier::BOLD),
+        )
+    }
+
+    pub fn severity_color(&self, severity: Severity) -> Color {
+        match severity {
+            Severity::Normal => self.success,
+            Severity::Warning => self.warning,
+            Severity::Critical => self.danger,
+        }
+    }
+
+    /// Reads a theme file. Keys it leaves out keep the default theme's
+    /// colors; anything that is not a color, or a list of the wrong length,
+    /// is an error naming the key.
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let table: toml::Table =
+            toml::from_str(contents).map_err(|err| err.message().to_string())?;
+        let mut theme = Self::default();
+        for (key, value) in &table {
+            let colors = theme
+                .colors_mut(key)
+                .ok_or_else(|| format!("unknown key '{}'", key))?;
+            let values = match value {
+                toml::Value::String(_) if colors.len() == 1 => slice::from_ref(value),
+                toml::Value::Array(values) if colors.len() > 1 => values.as_slice(),
+                _ if colors.len() == 1 => return Err(format!("{}: expected a color", key)),
+                _ => {
+                    return Err(format!(
+                        "{}: expected a list of {} colors",
+                        key,
+                        colors.len()
+                    ));
+                }
+            };
+            if values.len() != colors.len() {
+                return Err(format!(
+                    "{}: expected {} colors, found {}",
+                    key,
+                    colors.len(),
+                    values.len()
+                ));
+            }
+            for (color, value) in colors.iter_mut().zip(values) {
+                *color = value.as_str().and_then(parse_color).ok_or_else(|| {
+                    format!("{}: {} is not a color, such as \"#cdd6f4\"", key, value)
+                })?;
+            }
+        }
+        Ok(theme)
+    }
+
+    /// The theme as close as `depth` colors allow.
+    pub fn with_depth(self, depth: ColorDepth) -> Self {
+        match depth {
+            ColorDepth::TrueColor => self,
+            ColorDepth::Indexed256 => self.map_colors(nearest_indexed),
+            ColorDepth::Ansi16 => self.ansi16(),
+            ColorDepth::Monochrome => Self {
+                thresholds: self.thresholds,
+                ..Self::monochrome()
+            },
+        }
+    }
+
+    fn map_colors(mut self, map: impl Fn(Color) -> Color) -> Self {
+        for key in KEYS {
+            for color in self.colors_mut(key).into_iter().flatten() {
+                *color = map(*color);
+            }
+        }
+        self
+    }
+
+    // The nearest colors mostly do, but the dark backgrounds all come out
+    // black and the status colors drift, so those are picked by hand
+    fn ansi16(self) -> Self {
+        let light = rgb(self.bg_dark).is_some_and(|(r, g, b)| {
+            u32::from(r) * 299 + u32::from(g) * 587 + u32::from(b) * 114 > 128_000
+        });
+        let mut theme = self.map_colors(nearest_ansi);
+        let (backdrop, selection, border, strong) = if light {
+            (Color::White, Color::Gray, Color::DarkGray, Color::Black)
+        } else {
+            (Color::Black, Color::Blue, Color::DarkGray, Color::White)
+        };
+        theme.bg_dark = backdrop;
+        theme.bg_normal = backdrop;
+        theme.bg_light = backdrop;
+        theme.bg_lighter = selection;
+        theme.border = border;
+        theme.border_light = border;
+        theme.text_bright = strong;
+        theme.success = Color::Green;
+        theme.warning = if light { Color::Magenta } else { Color::Yellow };
+        theme.danger = Color::Red;
+        theme.info = Color::Cyan;
+        theme.mem_colors = [theme.success, theme.warning, theme.danger];
+        theme.net_colors = [theme.success, theme.info];
+        // A single color, so history charts stay one line
+        theme.chart_gradient = [theme.info; 5];
+        theme
+    }
+
+    // The colors a theme file key sets, one for most keys
+    fn colors_mut(&mut self, key: &str) -> Option<&mut [Color]> {
+        Some(match key {
+            "bg_dark" => slice::from_mut(&mut self.bg_dark),
+            "bg_normal" => slice::from_mut(&mut self.bg_normal),
+            "bg_light" => slice::from_mut(&mut self.bg_light),
+            "bg_lighter" => slice::from_mut(&mut self.bg_lighter),
+            "border" => slice::from_mut(&mut self.border),
+            "border_light" => slice::from_mut(&mut self.border_light),
+            "text_primary" => slice::from_mut(&mut self.text_primary),
+            "text_secondary" => slice::from_mut(&mut self.text_secondary),
+            "text_dim" => slice::from_mut(&mut self.text_dim),
+            "text_bright" => slice::from_mut(&mut self.text_bright),
+            "success" => slice::from_mut(&mut self.success),
+            "warning" => slice::from_mut(&mut self.warning),
+            "danger" => slice::from_mut(&mut self.danger),
+            "info" => slice::from_mut(&mut self.info),
+            "cpu_colors" => &mut self.cpu_colors,
+            "mem_colors" => &mut self.mem_colors,
+            "net_colors" => &mut self.net_colors,
+            "disk_colors" => &mut self.disk_colors,
+            "chart_gradient" => &mut self.chart_gradient,
+            _ => return None,
+        })
+    }
+
     pub fn get_cpu_color(&self, index: usize) -> Color {
         self.cpu_colors[index % self.cpu_colors.len()]
     }
 
+    /// Picks a color along `chart_gradient` for a 0-100 load, so busier
+    /// means hotter.
+    pub fn get_load_color(&self, percentage: u64) -> Color {
+        let last = self.chart_gradient.len() - 1;
+        self.chart_gradient[(percentage.min(100) as usize * last + 50) / 100]
+    }
+
     pub fn get_mem_color(&self, percentage: u64) -> Color {
-        match percentage {
-            0..=70 => self.mem_colors[0],
-            71..=90 => self.mem_colors[1],
-            _ => self.mem_colors[2],
-        }
+        self.mem_colors[self.thresholds.memory.classify(percentage as f64) as usize]
+    }
+
+    pub fn get_temp_color(&self, celsius: f32) -> Color {
+        self.severity_color(self.thresholds.temperature.classify(f64::from(celsius)))
     }
 
     pub fn get_usage_color(&self, percentage: u64) -> Color {
-        match percentage {
-            0..=70 => self.success,
-            71..=85 => self.warning,
-            _ => self.danger,
+        self.severity_color(self.thresholds.usage.classify(percentage as f64))
+    }
+}
+
+/// Reads "#rrggbb", or one of the names ratatui gives the terminal's own
+/// colors.
+fn parse_color(text: &str) -> Option<Color> {
+    match text.strip_prefix('#') {
+        Some(hex) if hex.len() == 6 && hex.bytes().all(|byte| byte.is_ascii_hexdigit()) => {
+            let channel = |at: usize| u8::from_str_radix(&hex[at..at + 2], 16).ok();
+            Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+        }
+        Some(_) => None,
+        None => Color::from_str(text).ok(),
+    }
+}
+
+fn rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Rgb(r, g, b) => Some((r, g, b)),
+        _ => None,
+    }
+}
+
+fn distance((r1, g1, b1): (u8, u8, u8), (r2, g2, b2): (u8, u8, u8)) -> u32 {
+    [(r1, r2), (g1, g2), (b1, b2)]
+        .iter()
+        .map(|&(a, b)| u32::from(a.abs_diff(b)).pow(2))
+        .sum()
+}
+
+/// The closest color of the 256-color palette's cube or gray ramp.
+fn nearest_indexed(color: Color) -> Color {
+    let Some(target) = rgb(color) else {
+        return color;
+    };
+    let level = |channel: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&index| CUBE_LEVELS[index].abs_diff(channel))
+            .unwrap_or(0)
+    };
+    let (r, g, b) = (level(target.0), level(target.1), level(target.2));
+    let cube = (CUBE_LEVELS[r], CUBE_LEVELS[g], CUBE_LEVELS[b]);
+    // Grays run from 8 to 238 in steps of 10
+    let average = (u32::from(target.0) + u32::from(target.1) + u32::from(target.2)) / 3;
+    let step = (average.saturating_sub(3) / 10).min(23) as u8;
+    let gray = 8 + step * 10;
+    if distance(target, (gray, gray, gray)) < distance(target, cube) {
+        Color::Indexed(232 + step)
+    } else {
+        Color::Indexed(16 + 36 * r as u8 + 6 * g as u8 + b as u8)
+    }
+}
+
+fn nearest_ansi(color: Color) -> Color {
+    let Some(target) = rgb(color) else {
+        return color;
+    };
+    ANSI_COLORS
+        .iter()
+        .min_by_key(|&&(_, ansi)| distance(target, ansi))
+        .map_or(color, |&(ansi, _)| ansi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_example_file_is_the_default_theme() {
+        assert_eq!(Theme::parse(EXAMPLE), Ok(Theme::default()));
+        assert_eq!(Theme::parse(""), Ok(Theme::default()));
+    }
+
+    #[test]
+    fn overrides_only_the_colors_given() {
+        let theme = Theme::parse("text_dim = \"#FFFFFF\"\nnet_colors = [\"green\", \"#0000ff\"]\n")
+            .unwrap();
+        assert_eq!(theme.text_dim, Color::Rgb(255, 255, 255));
+        assert_eq!(theme.net_colors, [Color::Green, Color::Rgb(0, 0, 255)]);
+        assert_eq!(theme.text_bright, Theme::default().text_bright);
+    }
+
+    #[test]
+    fn names_the_key_a_mistake_is_in() {
+        let error = |contents: &str| Theme::parse(contents).unwrap_err();
+        assert_eq!(
+            error("info = \"#89b4f\""),
+            "info: \"#89b4f\" is not a color, such as \"#cdd6f4\""
+        );
+        assert_eq!(
+            error("mem_colors = [\"red\", \"green\"]"),
+            "mem_colors: expected 3 colors, found 2"
+        );
+        assert_eq!(
+            error("cpu_colors = \"red\""),
+            "cpu_colors: expected a list of 8 colors"
+        );
+        assert_eq!(error("danger = [\"red\"]"), "danger: expected a color");
+        assert_eq!(error("colour = \"red\""), "unknown key 'colour'");
+    }
+
+    #[test]
+    fn picks_the_nearest_palette_colors() {
+        assert_eq!(nearest_indexed(Color::Rgb(255, 0, 0)), Color::Indexed(196));
+        assert_eq!(
+            nearest_indexed(Color::Rgb(128, 128, 128)),
+            Color::Indexed(244)
+        );
+        assert_eq!(nearest_indexed(Color::Rgb(24, 24, 37)), Color::Indexed(234));
+        assert_eq!(nearest_indexed(Color::Green), Color::Green);
+        assert_eq!(nearest_ansi(Color::Rgb(137, 180, 250)), Color::LightBlue);
+        let theme = Theme::default().with_depth(ColorDepth::Indexed256);
+        assert!(matches!(theme.text_primary, Color::Indexed(_)));
+        for theme in [Theme::default(), Theme::light()] {
+            let theme = theme.with_depth(ColorDepth::Ansi16);
+            let status = [theme.success, theme.warning, theme.danger];
+            assert!(status.iter().all(|&color| color != theme.bg_dark));
+            assert_ne!(status[0], status[1]);
+            assert_ne!(status[1], status[2]);
+            assert_ne!(theme.bg_lighter, theme.bg_normal);
         }
     }
+
+    #[test]
+    fn detects_color_depth_from_the_environment() {
+        let depth = |colorterm, term| ColorDepth::from_env(None, colorterm, term);
+        assert_eq!(
+            depth(Some("truecolor"), Some("xterm-256color")),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(depth(None, Some("xterm-direct")), ColorDepth::TrueColor);
+        assert_eq!(depth(None, Some("screen-256color")), ColorDepth::Indexed256);
+        assert_eq!(depth(None, Some("linux")), ColorDepth::Ansi16);
+        assert_eq!(depth(None, None), ColorDepth::Ansi16);
+        assert_eq!(
+            ColorDepth::from_env(Some("1"), Some("truecolor"), None),
+            ColorDepth::Monochrome
+        );
+        assert_eq!(
+            ColorDepth::from_env(Some(""), Some("truecolor"), None),
+            ColorDepth::TrueColor
+        );
+    }
+
+    #[test]
+    fn colors_values_past_their_thresholds() {
+        let theme = Theme::default();
+        assert_eq!(theme.get_usage_color(70), theme.success);
+        assert_eq!(theme.get_usage_color(71), theme.warning);
+        assert_eq!(theme.get_usage_color(85), theme.warning);
+        assert_eq!(theme.get_usage_color(86), theme.danger);
+        assert_eq!(theme.get_mem_color(70), theme.mem_colors[0]);
+        assert_eq!(theme.get_mem_color(90), theme.mem_colors[1]);
+        assert_eq!(theme.get_mem_color(91), theme.mem_colors[2]);
+        assert_eq!(theme.get_temp_color(80.0), theme.warning);
+        assert_eq!(theme.get_temp_color(80.5), theme.danger);
+        let cpu = Thresholds::default().process_cpu;
+        assert_eq!(cpu.classify(25.0), Severity::Normal);
+        assert_eq!(cpu.classify(50.0), Severity::Warning);
+        assert_eq!(cpu.classify(50.1), Severity::Critical);
+    }
 }
@@ -1,47 +1,109 @@
+use std::borrow::Cow;
+
 use ratatui::{
     Frame,
-    layout::{Constraint, Direction, Layout, Rect},
-    style::{Style, Stylize},
-    text::Span,
-    widgets::{Paragraph, Widget},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Axis, Block, Borders, Cell, Chart, Paragraph, Row, Table, Tabs, Wrap},
 };
+use unicode_width::UnicodeWidthStr;
 
 use crate::{
-    app::{App, View},
+    alerts::Area,
+    app::{App, ChartWindow, PendingAction, View},
+    columns::{Column, OVERVIEW_COLUMNS},
     components,
-    sys_info::DiskInfo,
-    theme::Theme,
+    keys::Action,
+    sys_info::{DiskInfo, Signal},
+    theme::{Severity, Theme},
 };
 
+// Below this the views' fixed-height panes no longer fit
+pub const MIN_WIDTH: u16 = 60;
+pub const MIN_HEIGHT: u16 = 16;
+
 pub fn ui(f: &mut Frame, app: &App) {
-    let theme = Theme::default();
+    let theme = &app.theme;
     let size = f.size();
+    if size.width < MIN_WIDTH || size.height < MIN_HEIGHT {
+        render_too_small(f, size, app, theme);
+        return;
+    }
     let main_layout = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Min(1), Constraint::Length(3)])
+        .constraints([
+            Constraint::Length(1),
+            Constraint::Length(u16::from(app.show_tab_bar)),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
         .split(size);
-    let content_area = main_layout[0];
-    let footer_area = main_layout[1];
-    let content_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
-        .split(content_area);
-    let top_area = content_layout[0];
-    render_top_area(f, top_area, app, &theme);
-    let bottom_area = content_layout[1];
-    render_bottom_area(f, bottom_area, app, &theme);
-    let footer = components::render_footer(
-        footer_area,
-        &theme,
-        &view_to_str(app.current_view),
-        app.show_help,
-    );
+    let header_area = main_layout[0];
+    let tabs_area = main_layout[1];
+    let content_area = main_layout[2];
+    let footer_area = main_layout[3];
+    app.layout.content.set(Some(content_area));
+    app.layout.scroll_limit.set(0);
+    let header = components::render_header(header_area, theme, app);
+    f.render_widget(header, header_area);
+    match app.current_view {
+        View::Overview => render_overview(f, content_area, app, theme),
+        View::System => components::render_system_view(content_area, theme, app)(f),
+        View::Process => {
+            components::render_process_view(content_area, theme, app, &app.process_rows())(f)
+        }
+        View::Resources => components::render_resources_view(content_area, theme, app)(f),
+        View::Network => components::render_network_view(content_area, theme, app)(f),
+        View::Disks => components::render_disks_view(content_area, theme, app)(f),
+        View::Options => components::render_options_view(content_area, theme, app)(f),
+        View::Gpu => components::render_gpu_view(content_area, theme, &app.metrics, app.units)(f),
+        View::Sensors => components::render_sensors_view(content_area, theme, &app.metrics)(f),
+        View::Services => components::render_services_view(content_area, theme, app)(f),
+    }
+    let footer = components::render_footer(footer_area, theme, app);
     f.render_widget(footer, footer_area);
+    render_view_tabs(f, tabs_area, app, theme);
+    if let Some(popup) = &app.environment {
+        let popup_area = centered_rect(80, 80, size);
+        components::render_environment_popup(popup_area, theme, popup)(f);
+    }
+    if let Some(popup) = &app.open_files {
+        components::render_open_files_popup(centered_rect(80, 80, size), theme, popup)(f);
+    }
+    if app.show_events {
+        components::render_events_popup(centered_rect(80, 80, size), theme, app)(f);
+    }
+    if let Some(action) = &app.pending_action {
+        let width = 56.min(size.width);
+        let height = match action {
+            PendingAction::Kill { .. } => 9,
+            // Target lines, filter line, every signal and the warning
+            PendingAction::SignalPicker { .. } => 8 + Signal::ALL.len() as u16,
+        }
+        .min(size.height);
+        let popup_area = Rect::new(
+            (size.width - width) / 2,
+            (size.height - height) / 2,
+            width,
+            height,
+        );
+        components::render_action_popup(popup_area, theme, action)(f);
+    }
     if app.show_help {
-        render_help_overlay(f, size, &theme);
+        components::render_help_view(centered_rect(80, 80, size), theme, app)(f);
     }
 }
 
+fn render_overview(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
+    let content_layout = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+        .split(area);
+    render_top_area(f, content_layout[0], app, theme);
+    render_bottom_area(f, content_layout[1], app, theme);
+}
+
 fn render_top_area(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     let top_layout = Layout::default()
         .direction(Direction::Horizontal)
@@ -52,127 +114,118 @@ fn render_top_area(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
 }
 
 fn render_cpu_chart(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
-    let cpu_block = ratatui::widgets::Block::default()
+    let cpu_block = Block::default()
         .title(Span::styled(
             " CPU Usage History ",
             Style::default()
                 .fg(theme.text_bright)
-                .add_modifier(ratatui::style::Modifier::BOLD),
+                .add_modifier(Modifier::BOLD),
         ))
-        .borders(ratatui::widgets::Borders::ALL)
-        .border_style(Style::default().fg(theme.border));
+        .borders(Borders::ALL)
+        .border_style(components::alert_border(app, Area::Cpu, theme));
     let cpu_area = cpu_block.inner(area);
-    let cpu_data: Vec<(f64, f64)> = app
-        .metrics
-        .cpu_history
-        .iter()
-        .enumerate()
-        .map(|(i, &usage)| (i as f64, usage as f64))
-        .collect();
-    let cpu_data: &'static [(f64, f64)] = Box::leak(cpu_data.into_boxed_slice());
-    let cpu_chart = ratatui::widgets::Chart::new(vec![
-        ratatui::widgets::Dataset::default()
-            .name("CPU Usage")
-            .marker(ratatui::symbols::Marker::Braille)
-            .graph_type(ratatui::widgets::GraphType::Line)
-            .style(Style::default().fg(theme.cpu_colors[0]))
-            .data(cpu_data),
-    ])
-    .x_axis(
-        ratatui::widgets::Axis::default()
-            .style(Style::default().fg(theme.text_dim))
-            .bounds([0.0, cpu_data.len() as f64 - 1.0])
-            .labels(vec![
-                Span::styled("-60s", Style::default().fg(theme.text_dim)),
-                Span::styled("now", Style::default().fg(theme.text_dim)),
-            ]),
-    )
-    .y_axis(
-        ratatui::widgets::Axis::default()
-            .style(Style::default().fg(theme.text_dim))
-            .bounds([0.0, 100.0])
-            .labels(vec![
-                Span::styled("0%", Style::default().fg(theme.text_dim)),
-                Span::styled("50%", Style::default().fg(theme.text_dim)),
-                Span::styled("100%", Style::default().fg(theme.text_dim)),
-            ]),
-    );
+    let cpu_data =
+        components::chart_points(&app.metrics.cpu_history, ChartWindow::Minute.duration());
+    let (cpu_bounds, cpu_since) = components::time_axis(&cpu_data);
+    let cpu_chart = Chart::new(components::gradient_line(&cpu_data, 100.0, theme))
+        .x_axis(
+            Axis::default()
+                .style(Style::default().fg(theme.text_dim))
+                .bounds(cpu_bounds)
+                .labels(vec![
+                    Span::styled(cpu_since, Style::default().fg(theme.text_dim)),
+                    Span::styled("now", Style::default().fg(theme.text_dim)),
+                ]),
+        )
+        .y_axis(
+            Axis::default()
+                .style(Style::default().fg(theme.text_dim))
+                .bounds([0.0, 100.0])
+                .labels(vec![
+                    Span::styled("0%", Style::default().fg(theme.text_dim)),
+                    Span::styled("50%", Style::default().fg(theme.text_dim)),
+                    Span::styled("100%", Style::default().fg(theme.text_dim)),
+                ]),
+        );
     f.render_widget(cpu_block, area);
     f.render_widget(cpu_chart, cpu_area);
 }
 
 fn render_cpu_info(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
-    let info_block = ratatui::widgets::Block::default()
+    let info_block = Block::default()
         .title(Span::styled(
             " CPU Info ",
             Style::default()
                 .fg(theme.text_bright)
-                .add_modifier(ratatui::style::Modifier::BOLD),
+                .add_modifier(Modifier::BOLD),
         ))
-        .borders(ratatui::widgets::Borders::ALL)
+        .borders(Borders::ALL)
         .border_style(Style::default().fg(theme.border));
     let info_area = info_block.inner(area);
-    let temp_color = if app.metrics.cpu_temperature > 80.0 {
-        theme.danger
-    } else if app.metrics.cpu_temperature > 70.0 {
-        theme.warning
-    } else {
-        theme.success
-    };
+    let temp_color = app
+        .metrics
+        .cpu_temperature
+        .map_or(theme.text_dim, |temp| theme.get_temp_color(temp));
     let cpu_usage_color = theme.get_usage_color(app.metrics.cpu_total_usage);
-    let temp_bar = create_thermal_bar(app.metrics.cpu_temperature, theme);
+    let temp_bar = app
+        .metrics
+        .cpu_temperature
+        .map(|temp| create_thermal_bar(temp, theme))
+        .unwrap_or_default();
     let usage_bar = create_usage_bar(app.metrics.cpu_total_usage, theme);
     let info_text = vec![
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::styled("Model: ", Style::default().fg(theme.text_dim)),
-            ratatui::text::Span::styled(
+        Line::from(vec![
+            Span::styled("Model: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
                 &app.metrics.cpu_model,
                 Style::default().fg(theme.text_primary),
             ),
         ]),
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::styled("Cores: ", Style::default().fg(theme.text_dim)),
-            ratatui::text::Span::styled(
+        Line::from(vec![
+            Span::styled("Cores: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
                 format!("{}", app.metrics.cpu_count),
                 Style::default().fg(theme.text_primary),
             ),
         ]),
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::styled("Freq: ", Style::default().fg(theme.text_dim)),
-            ratatui::text::Span::styled(
-                format!("{} MHz", app.metrics.cpu_frequency),
+        Line::from(vec![
+            Span::styled("Freq: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                app.metrics
+                    .cpu_frequency
+                    .map_or_else(|| "N/A".to_string(), |freq| format!("{} MHz", freq)),
                 Style::default().fg(theme.text_primary),
             ),
         ]),
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::styled("Temp: ", Style::default().fg(theme.text_dim)),
-            ratatui::text::Span::styled(
-                format!("{:.1}°C", app.metrics.cpu_temperature),
-                Style::default()
-                    .fg(temp_color)
-                    .add_modifier(ratatui::style::Modifier::BOLD),
+        Line::from(vec![
+            Span::styled("Temp: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                app.metrics
+                    .cpu_temperature
+                    .map_or_else(|| "N/A".to_string(), |temp| format!("{:.1}°C", temp)),
+                Style::default().fg(temp_color).add_modifier(Modifier::BOLD),
             ),
         ]),
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::raw("  "),
-            ratatui::text::Span::styled(temp_bar, Style::default().fg(temp_color)),
+        Line::from(vec![
+            Span::raw("  "),
+            Span::styled(temp_bar, Style::default().fg(temp_color)),
         ]),
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::styled("Usage: ", Style::default().fg(theme.text_dim)),
-            ratatui::text::Span::styled(
+        Line::from(vec![
+            Span::styled("Usage: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
                 format!("{}%", app.metrics.cpu_total_usage),
                 Style::default()
                     .fg(cpu_usage_color)
-                    .add_modifier(ratatui::style::Modifier::BOLD),
+                    .add_modifier(Modifier::BOLD),
             ),
         ]),
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::raw("  "),
-            ratatui::text::Span::styled(usage_bar, Style::default().fg(cpu_usage_color)),
+        Line::from(vec![
+            Span::raw("  "),
+            Span::styled(usage_bar, Style::default().fg(cpu_usage_color)),
         ]),
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::styled("Load: ", Style::default().fg(theme.text_dim)),
-            ratatui::text::Span::styled(
+        Line::from(vec![
+            Span::styled("Load: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
                 format!("{:.2}", app.metrics.load_average.one),
                 Style::default().fg(
                     if app.metrics.load_average.one > (app.metrics.cpu_count as f32).into() {
@@ -184,7 +237,7 @@ fn render_cpu_info(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
             ),
         ]),
     ];
-    let info_para = Paragraph::new(info_text).block(ratatui::widgets::Block::default());
+    let info_para = Paragraph::new(info_text).block(Block::default());
     f.render_widget(info_block, area);
     f.render_widget(info_para, info_area);
 }
@@ -208,104 +261,91 @@ fn render_memory_disk_info(f: &mut Frame, area: Rect, app: &App, theme: &Theme)
 }
 
 fn render_memory_info(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
-    let mem_block = ratatui::widgets::Block::default()
+    let mem_block = Block::default()
         .title(Span::styled(
             " Memory Usage ",
             Style::default()
                 .fg(theme.text_bright)
-                .add_modifier(ratatui::style::Modifier::BOLD),
+                .add_modifier(Modifier::BOLD),
         ))
-        .borders(ratatui::widgets::Borders::ALL)
-        .border_style(Style::default().fg(theme.border));
+        .borders(Borders::ALL)
+        .border_style(components::alert_border(app, Area::Memory, theme));
     let mem_area = mem_block.inner(area);
     let mem_percent =
         (app.metrics.memory_used as f64 / app.metrics.memory_total as f64 * 100.0) as u64;
     let mem_color = theme.get_mem_color(mem_percent);
-    let mem_bar_width: usize = 20;
-    let mem_filled = (mem_percent as f64 * mem_bar_width as f64 / 100.0).round() as usize;
-    let mem_bar = format!(
-        "[{}{}]",
-        "█".repeat(mem_filled),
-        "░".repeat(mem_bar_width.saturating_sub(mem_filled))
-    );
     let swap_percent = if app.metrics.swap_total > 0 {
         (app.metrics.swap_used as f64 / app.metrics.swap_total as f64 * 100.0) as u64
     } else {
         0
     };
-    let mem_text = vec![
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::styled("Total: ", Style::default().fg(theme.text_dim)),
-            ratatui::text::Span::styled(
-                format!("{:.1} GB", app.metrics.memory_total as f64 / 1024.0),
+    let mut mem_text = vec![
+        Line::from(vec![
+            Span::styled("Total: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                crate::utils::format_size(app.metrics.memory_total, crate::utils::MB, app.units),
                 Style::default().fg(theme.text_primary),
             ),
         ]),
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::styled("Used: ", Style::default().fg(theme.text_dim)),
-            ratatui::text::Span::styled(
-                format!("{:.1} GB", app.metrics.memory_used as f64 / 1024.0),
-                Style::default()
-                    .fg(mem_color)
-                    .add_modifier(ratatui::style::Modifier::BOLD),
+        Line::from(vec![
+            Span::styled("Used: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                crate::utils::format_size(app.metrics.memory_used, crate::utils::MB, app.units),
+                Style::default().fg(mem_color).add_modifier(Modifier::BOLD),
             ),
-            ratatui::text::Span::raw(" "),
-            ratatui::text::Span::styled(
+            Span::raw(" "),
+            Span::styled(
                 format!("({}%)", mem_percent),
                 Style::default().fg(mem_color),
             ),
         ]),
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::raw("  "),
-            ratatui::text::Span::styled(mem_bar, Style::default().fg(mem_color)),
-        ]),
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::styled("Available: ", Style::default().fg(theme.text_dim)),
-            ratatui::text::Span::styled(
-                format!("{:.1} GB", app.metrics.memory_available as f64 / 1024.0),
+        Line::from(vec![
+            Span::styled("Available: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                crate::utils::format_size(
+                    app.metrics.memory_available,
+                    crate::utils::MB,
+                    app.units,
+                ),
                 Style::default().fg(theme.text_primary),
             ),
         ]),
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::styled("Swap: ", Style::default().fg(theme.text_dim)),
-            ratatui::text::Span::styled(
+        Line::from(vec![
+            Span::styled("Swap: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
                 format!(
-                    "{}/{} GB",
-                    app.metrics.swap_used / 1024,
-                    app.metrics.swap_total / 1024
+                    "{}/{}",
+                    crate::utils::format_size(app.metrics.swap_used, crate::utils::MB, app.units),
+                    crate::utils::format_size(app.metrics.swap_total, crate::utils::MB, app.units)
                 ),
-                Style::default().fg(if swap_percent > 50 {
-                    theme.danger
-                } else {
-                    theme.text_primary
-                }),
-            ),
-            ratatui::text::Span::raw(" "),
-            ratatui::text::Span::styled(
-                format!("({}%)", swap_percent),
-                Style::default().fg(if swap_percent > 50 {
-                    theme.danger
-                } else {
-                    theme.warning
-                }),
+                Style::default().fg(theme.text_primary),
             ),
+            Span::raw(" "),
+            theme.percentage(crate::utils::format_percentage(
+                swap_percent as f64,
+                theme.thresholds.swap,
+            )),
         ]),
     ];
-    let mem_para = Paragraph::new(mem_text).block(ratatui::widgets::Block::default());
+    mem_text.splice(
+        2..2,
+        components::memory_bar(&app.metrics, theme, mem_area.width, app.units),
+    );
+    let mem_para = Paragraph::new(mem_text).block(Block::default());
     f.render_widget(mem_block, area);
     f.render_widget(mem_para, mem_area);
 }
 
 fn render_disk_info(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
-    let disk_block = ratatui::widgets::Block::default()
+    let disk_block = Block::default()
         .title(Span::styled(
             " Disk Usage ",
             Style::default()
                 .fg(theme.text_bright)
-                .add_modifier(ratatui::style::Modifier::BOLD),
+                .add_modifier(Modifier::BOLD),
         ))
-        .borders(ratatui::widgets::Borders::ALL)
-        .border_style(Style::default().fg(theme.border));
+        .borders(Borders::ALL)
+        .border_style(components::alert_border(app, Area::Disks, theme));
     let disk_area = disk_block.inner(area);
     let binding_disk_info = DiskInfo::default();
     let disk = app.metrics.disks.first().unwrap_or(&binding_disk_info);
@@ -319,177 +359,308 @@ fn render_disk_info(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     );
 
     let disk_text = vec![
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::styled("Disk: ", Style::default().fg(theme.text_dim)),
-            ratatui::text::Span::styled(&disk.name, Style::default().fg(theme.text_primary)),
+        Line::from(vec![
+            Span::styled("Disk: ", Style::default().fg(theme.text_dim)),
+            Span::styled(&disk.name, Style::default().fg(theme.text_primary)),
         ]),
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::styled("Mount: ", Style::default().fg(theme.text_dim)),
-            ratatui::text::Span::styled(
-                &disk.mount_point,
-                Style::default().fg(theme.text_secondary),
-            ),
+        Line::from(vec![
+            Span::styled("Mount: ", Style::default().fg(theme.text_dim)),
+            Span::styled(&disk.mount_point, Style::default().fg(theme.text_secondary)),
         ]),
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::styled("Total: ", Style::default().fg(theme.text_dim)),
-            ratatui::text::Span::styled(
-                format!("{} GB", disk.total),
+        Line::from(vec![
+            Span::styled("Total: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                crate::utils::format_size(disk.total, crate::utils::GB, app.units),
                 Style::default().fg(theme.text_primary),
             ),
         ]),
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::styled("Used: ", Style::default().fg(theme.text_dim)),
-            ratatui::text::Span::styled(
-                format!("{} GB", disk.used),
-                Style::default()
-                    .fg(disk_color)
-                    .add_modifier(ratatui::style::Modifier::BOLD),
+        Line::from(vec![
+            Span::styled("Used: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                crate::utils::format_size(disk.used, crate::utils::GB, app.units),
+                Style::default().fg(disk_color).add_modifier(Modifier::BOLD),
             ),
-            ratatui::text::Span::raw(" "),
-            ratatui::text::Span::styled(
+            Span::raw(" "),
+            Span::styled(
                 format!("({}%)", disk.usage),
                 Style::default().fg(disk_color),
             ),
         ]),
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::raw("  "),
-            ratatui::text::Span::styled(disk_bar, Style::default().fg(disk_color)),
+        Line::from(vec![
+            Span::raw("  "),
+            Span::styled(disk_bar, Style::default().fg(disk_color)),
         ]),
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::styled("Free: ", Style::default().fg(theme.text_dim)),
-            ratatui::text::Span::styled(
-                format!("{} GB", disk.free),
+        Line::from(vec![
+            Span::styled("Free: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                crate::utils::format_size(disk.free, crate::utils::GB, app.units),
                 Style::default().fg(theme.text_primary),
             ),
         ]),
-        ratatui::text::Line::from(vec![
-            ratatui::text::Span::styled("I/O R/W: ", Style::default().fg(theme.text_dim)),
-            ratatui::text::Span::styled(
-                format!("{}/{} MB/s", disk.read_speed, disk.write_speed),
+        Line::from(vec![
+            Span::styled("I/O R/W: ", Style::default().fg(theme.text_dim)),
+            Span::styled(
+                match (disk.read_speed, disk.write_speed) {
+                    (Some(read), Some(write)) => format!(
+                        "{} / {}",
+                        crate::utils::format_rate(read * crate::utils::MB, app.units),
+                        crate::utils::format_rate(write * crate::utils::MB, app.units)
+                    ),
+                    _ => "N/A".to_string(),
+                },
                 Style::default()
                     .fg(theme.disk_colors[0])
-                    .add_modifier(ratatui::style::Modifier::BOLD),
+                    .add_modifier(Modifier::BOLD),
             ),
         ]),
     ];
-    let disk_para = Paragraph::new(disk_text).block(ratatui::widgets::Block::default());
+    let disk_para = Paragraph::new(disk_text).block(Block::default());
     f.render_widget(disk_block, area);
     f.render_widget(disk_para, disk_area);
 }
 
 fn render_process_table(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
-    let table_block = ratatui::widgets::Block::default()
+    let table_block = Block::default()
         .title(Span::styled(
             " Processes ",
             Style::default()
                 .fg(theme.text_bright)
-                .add_modifier(ratatui::style::Modifier::BOLD),
+                .add_modifier(Modifier::BOLD),
         ))
-        .borders(ratatui::widgets::Borders::ALL)
-        .border_style(Style::default().fg(theme.border));
+        .borders(Borders::ALL)
+        .border_style(components::alert_border(app, Area::Processes, theme));
     let table_area = table_block.inner(area);
     let visible_rows = (table_area.height as usize).saturating_sub(1);
-    let header = ratatui::widgets::Row::new(vec![
-        ratatui::widgets::Cell::from("PID").style(
-            Style::default()
-                .fg(theme.text_bright)
-                .add_modifier(ratatui::style::Modifier::BOLD),
-        ),
-        ratatui::widgets::Cell::from("Name").style(
-            Style::default()
-                .fg(theme.text_bright)
-                .add_modifier(ratatui::style::Modifier::BOLD),
-        ),
-        ratatui::widgets::Cell::from("CPU%").style(
-            Style::default()
-                .fg(theme.text_bright)
-                .add_modifier(ratatui::style::Modifier::BOLD),
-        ),
-        ratatui::widgets::Cell::from("MEM").style(
-            Style::default()
-                .fg(theme.text_bright)
-                .add_modifier(ratatui::style::Modifier::BOLD),
-        ),
-    ]);
+    let columns = crate::columns::fit(&OVERVIEW_COLUMNS, table_area.width, &app.pinned_columns);
+    let header = Row::new(
+        columns
+            .iter()
+            .map(|&column| components::process_header_cell(column, app, theme)),
+    );
+    let widths: Vec<Constraint> = columns.iter().map(|column| column.constraint()).collect();
+    let name_width = columns
+        .iter()
+        .position(|&column| column == Column::Name)
+        .map_or(0, |index| {
+            let areas = Layout::horizontal(widths.clone())
+                .spacing(1)
+                .split(table_area);
+            usize::from(areas[index].width)
+        });
     let start_idx = app.process_scroll_offset;
-    let end_idx = (start_idx + visible_rows).min(app.metrics.processes.len());
-    let rows: Vec<ratatui::widgets::Row> = app.metrics.processes[start_idx..end_idx]
+    let process_rows = app.process_rows();
+    let end_idx = (start_idx + visible_rows).min(process_rows.len());
+    let rows: Vec<Row> = process_rows[start_idx.min(end_idx)..end_idx]
         .iter()
         .enumerate()
-        .map(|(i, process)| {
+        .map(|(i, row)| {
+            let process = &*row.process;
             let global_idx = start_idx + i;
             let is_selected = global_idx == app.selected_process;
-            let cpu_color = if process.cpu_usage > 50.0 {
-                theme.danger
-            } else if process.cpu_usage > 25.0 {
-                theme.warning
-            } else {
-                theme.success
-            };
-            let mem_color = if process.memory_percent > 10.0 {
-                theme.danger
-            } else if process.memory_percent > 5.0 {
-                theme.warning
-            } else {
-                theme.info
+            let cpu_severity = theme.thresholds.process_cpu.classify(process.cpu_usage);
+            let cpu_color = theme.severity_color(cpu_severity);
+            let mem_severity = theme
+                .thresholds
+                .process_memory
+                .classify(process.memory_percent);
+            let mem_color = match mem_severity {
+                Severity::Normal => theme.info,
+                severity => theme.severity_color(severity),
             };
-            let bg_color = if is_selected {
-                theme.bg_lighter
+            let row_style = if is_selected {
+                theme.selected()
             } else if global_idx % 2 == 0 {
-                theme.bg_normal
+                Style::default().bg(theme.bg_normal)
             } else {
-                theme.bg_light
+                Style::default().bg(theme.bg_light)
             };
-            ratatui::widgets::Row::new(vec![
-                ratatui::widgets::Cell::from(process.pid.to_string())
-                    .style(Style::default().fg(theme.text_primary)),
-                ratatui::widgets::Cell::from(
-                    if app.show_full_command && !process.full_command.is_empty() {
-                        process.full_command.clone()
-                    } else {
-                        process.name.clone()
-                    },
-                )
-                .style(Style::default().fg(theme.text_primary)),
-                ratatui::widgets::Cell::from(format!("{:.1}", process.cpu_usage)).style(
-                    Style::default()
-                        .fg(cpu_color)
-                        .add_modifier(ratatui::style::Modifier::BOLD),
-                ),
-                ratatui::widgets::Cell::from(format!("{} MB", process.memory_usage)).style(
-                    Style::default()
-                        .fg(mem_color)
-                        .add_modifier(ratatui::style::Modifier::BOLD),
-                ),
-            ])
-            .style(Style::default().bg(bg_color))
+            Row::new(columns.iter().map(|column| {
+                match column {
+                    Column::Pid => components::text_cell(process.pid.to_string())
+                        .style(Style::default().fg(theme.text_primary)),
+                    Column::Name => {
+                        let group_label = row
+                            .group_size
+                            .map(|size| format!(" ×{}", size))
+                            .unwrap_or_default();
+                        let name = if app.show_full_command && !process.full_command.is_empty() {
+                            crate::utils::truncate_middle(
+                                &process.full_command,
+                                name_width.saturating_sub(row.prefix.width() + group_label.width()),
+                            )
+                        } else {
+                            Cow::Borrowed(process.name.as_str())
+                        };
+                        let line = if row.prefix.is_empty() && group_label.is_empty() {
+                            Line::from(Span::raw(name))
+                        } else {
+                            Line::from(vec![
+                                Span::raw(row.prefix.as_str()),
+                                Span::raw(name),
+                                Span::raw(group_label),
+                            ])
+                        };
+                        Cell::from(line).style(Style::default().fg(theme.text_primary))
+                    }
+                    Column::Cpu => {
+                        components::text_cell(components::process_figure(process, || {
+                            format!(
+                                "{:.1}{}",
+                                process.cpu_usage,
+                                theme.critical_mark(cpu_severity)
+                            )
+                        }))
+                        .style(Style::default().fg(cpu_color).add_modifier(Modifier::BOLD))
+                    }
+                    Column::MemPercent => {
+                        components::text_cell(components::process_figure(process, || {
+                            format!(
+                                "{:.1}{}",
+                                process.memory_percent,
+                                theme.critical_mark(mem_severity)
+                            )
+                        }))
+                        .style(Style::default().fg(mem_color).add_modifier(Modifier::BOLD))
+                    }
+                    Column::Mem => {
+                        components::text_cell(components::process_figure(process, || {
+                            crate::utils::format_size(
+                                process.memory_usage,
+                                crate::utils::MB,
+                                app.units,
+                            )
+                        }))
+                        .style(Style::default().fg(mem_color).add_modifier(Modifier::BOLD))
+                    }
+                    // The Overview only shows the compact set
+                    _ => Cell::default(),
+                }
+            }))
+            .style(row_style)
         })
         .collect();
-    let table = ratatui::widgets::Table::new(
-        rows,
-        vec![
-            Constraint::Length(8),
-            Constraint::Percentage(50),
-            Constraint::Length(8),
-            Constraint::Length(10),
-        ],
-    )
-    .header(header)
-    .block(ratatui::widgets::Block::default());
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::default());
     f.render_widget(table_block, area);
     f.render_widget(table, table_area);
 }
 
-fn render_help_overlay(f: &mut Frame, area: Rect, theme: &Theme) {
-    let overlay = Paragraph::new("").style(Style::default().bg(theme.bg_dark).fg(theme.text_dim));
-    f.render_widget(overlay, area);
-    let help_width = (area.width as f32 * 0.8) as u16;
-    let help_height = (area.height as f32 * 0.8) as u16;
-    let help_x = (area.width - help_width) / 2;
-    let help_y = (area.height - help_height) / 2;
-    let help_area = Rect::new(help_x, help_y, help_width, help_height);
-    let help_widget = components::render_help_view(help_area, theme);
-    help_widget(f);
+const VIEWS: [View; 10] = [
+    View::Overview,
+    View::System,
+    View::Process,
+    View::Resources,
+    View::Network,
+    View::Disks,
+    View::Options,
+    View::Gpu,
+    View::Sensors,
+    View::Services,
+];
+
+// One clickable label per view on the row under the header, centred
+fn render_view_tabs(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
+    let mut tabs = app.layout.view_tabs.borrow_mut();
+    tabs.clear();
+    if area.height == 0 {
+        return;
+    }
+    let labels: Vec<(String, View)> = VIEWS
+        .iter()
+        .map(|&view| {
+            let key = app.keys.labels(&[Action::View(view)]);
+            (format!(" {}:{} ", key, view_to_str(view)), view)
+        })
+        .collect();
+    let total: u16 = labels
+        .iter()
+        .map(|(label, _)| label.chars().count() as u16)
+        .sum();
+    let start = area.x + area.width.saturating_sub(total) / 2;
+    let right = area.x + area.width;
+    // Labels carry their own padding, so each tab is exactly its label wide
+    let mut x = start;
+    for (label, view) in &labels {
+        let width = (label.chars().count() as u16).min(right.saturating_sub(x));
+        if width == 0 {
+            break;
+        }
+        tabs.push((Rect::new(x, area.y, width, 1), *view));
+        x += width;
+    }
+    let selected = labels
+        .iter()
+        .position(|(_, view)| *view == app.current_view);
+    let bar = Tabs::new(labels.into_iter().map(|(label, _)| label))
+        .select(selected)
+        .padding("", "")
+        .divider("")
+        .style(Style::default().fg(theme.text_dim))
+        .highlight_style(
+            theme
+                .selected()
+                .fg(theme.text_bright)
+                .add_modifier(Modifier::BOLD),
+        );
+    f.render_widget(
+        Paragraph::new("").style(Style::default().bg(theme.bg_dark)),
+        area,
+    );
+    f.render_widget(bar, Rect::new(start, area.y, right - start, 1));
+}
+
+// Replaces the whole UI when the terminal cannot fit it. Nothing is
+// clickable, so the recorded layout is cleared too.
+fn render_too_small(f: &mut Frame, area: Rect, app: &App, theme: &Theme) {
+    app.layout.content.set(None);
+    app.layout.process_rows.set(None);
+    app.layout.process_headers.borrow_mut().clear();
+    app.layout.view_tabs.borrow_mut().clear();
+    app.layout.scroll_limit.set(0);
+    let message = vec![
+        Line::from(Span::styled(
+            "Terminal too small",
+            Style::default()
+                .fg(theme.warning)
+                .add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            format!(
+                "need at least {}×{}, have {}×{}",
+                MIN_WIDTH, MIN_HEIGHT, area.width, area.height
+            ),
+            Style::default().fg(theme.text_dim),
+        )),
+    ];
+    let height = (message.len() as u16).min(area.height);
+    let message_area = Rect::new(
+        area.x,
+        area.y + (area.height - height) / 2,
+        area.width,
+        height,
+    );
+    f.render_widget(
+        Paragraph::new(message)
+            .alignment(Alignment::Center)
+            .wrap(Wrap { trim: true }),
+        message_area,
+    );
+}
+
+/// The part of `area` `percent_x` wide and `percent_y` tall, by percent,
+/// centred in it. Percentages over 100 are taken as 100.
+fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
+    let scale =
+        |length: u16, percent: u16| (u32::from(length) * u32::from(percent.min(100)) / 100) as u16;
+    let width = scale(area.width, percent_x);
+    let height = scale(area.height, percent_y);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
 }
 
 fn create_thermal_bar(temp: f32, theme: &Theme) -> String {
@@ -521,11 +692,507 @@ fn create_usage_bar(usage: u64, theme: &Theme) -> String {
 
 fn view_to_str(view: View) -> &'static str {
     match view {
+        View::Overview => "Overview",
         View::System => "System",
         View::Process => "Process",
         View::Resources => "Resources",
         View::Network => "Network",
         View::Disks => "Disks",
         View::Options => "Options",
+        View::Gpu => "GPU",
+        View::Sensors => "Sensors",
+        View::Services => "Services",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{Terminal, backend::TestBackend, style::Color};
+
+    use super::*;
+    use crate::{
+        sys_info::ProcessInfo,
+        theme::{self, ColorDepth},
+        utils::UnitSystem,
+    };
+
+    // Draws the app on a terminal of the given size and returns what is on
+    // it, row after row
+    fn screen_text(app: &App, width: u16, height: u16) -> String {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|f| ui(f, app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        buffer.content().iter().map(|cell| cell.symbol()).collect()
+    }
+
+    #[test]
+    fn colors_core_bars_by_their_load() {
+        let mut app = App::default();
+        app.current_view = View::System;
+        app.metrics.cpu_usage_per_core = vec![5, 95];
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|f| ui(f, &app)).unwrap();
+        let theme = Theme::default();
+        let bar_colors: Vec<_> = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .filter(|cell| cell.symbol() == "█")
+            .map(|cell| cell.fg)
+            .collect();
+        assert!(bar_colors.contains(&theme.get_load_color(95)));
+        assert_ne!(theme.get_load_color(5), theme.get_load_color(95));
+    }
+
+    #[test]
+    fn dims_the_path_and_bolds_the_program() {
+        let mut app = App::default();
+        app.current_view = View::Process;
+        app.show_full_command = true;
+        app.metrics.processes = vec![ProcessInfo {
+            full_command: "/usr/bin/python3 -m http.server".to_string(),
+            ..ProcessInfo::fixture(42, 1, "python3")
+        }];
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|f| ui(f, &app)).unwrap();
+        let cells = terminal.backend().buffer().content();
+        let symbols: Vec<&str> = cells.iter().map(|cell| cell.symbol()).collect();
+        let start = (0..symbols.len())
+            .find(|&index| symbols[index..].starts_with(&["/", "u", "s", "r"]))
+            .unwrap();
+        let theme = Theme::default();
+        assert_eq!(cells[start].fg, theme.text_dim);
+        let program = &cells[start + "/usr/bin/".len()];
+        assert_eq!(program.symbol(), "p");
+        assert_eq!(program.fg, theme.text_bright);
+        assert!(program.modifier.contains(Modifier::BOLD));
+        assert_eq!(
+            cells[start + "/usr/bin/python3 ".len()].fg,
+            theme.text_secondary
+        );
+    }
+
+    #[test]
+    fn cuts_long_commands_and_mounts_in_the_middle() {
+        let mut app = App::default();
+        app.current_view = View::Process;
+        app.show_full_command = true;
+        let path = "/home/user/projects/".repeat(4);
+        app.metrics.processes = vec![ProcessInfo {
+            full_command: format!("{}my-server -v", path),
+            ..ProcessInfo::fixture(42, 1, "my-server")
+        }];
+        app.metrics.disks.truncate(1);
+        app.metrics.disks[0].mount_point = "/var/lib/containers/storage".to_string();
+        app.metrics.disks[0].fs_type = "ext4".to_string();
+        let processes = screen_text(&app, 120, 40);
+        assert!(processes.contains("/home/user/"));
+        assert!(processes.contains("…"));
+        assert!(processes.contains("/my-server -v"));
+        app.current_view = View::Disks;
+        assert!(screen_text(&app, 120, 40).contains("/var/l…orage"));
+    }
+
+    #[test]
+    fn drops_whole_header_fields_when_narrow() {
+        let mut app = App::default();
+        app.metrics.cpu_temperature = Some(85.0);
+        app.metrics.swap_total = 1000;
+        app.metrics.swap_used = 500;
+        let header = |width: u16| {
+            let mut terminal = Terminal::new(TestBackend::new(width, 40)).unwrap();
+            terminal.draw(|f| ui(f, &app)).unwrap();
+            let buffer = terminal.backend().buffer();
+            (0..width)
+                .map(|x| buffer[(x, 0)].symbol().to_string())
+                .collect::<String>()
+        };
+        let wide = header(200);
+        assert!(wide.contains("CPU 85°C"));
+        assert!(wide.contains("Swap: 50%"));
+        assert!(wide.contains("Threads: "));
+        let narrow = header(60);
+        assert!(narrow.contains("Load: "));
+        assert!(!narrow.contains("Threads"));
+        assert!(narrow.trim().split(" | ").all(|field| !field.is_empty()));
+        assert!(narrow.trim().len() < 60);
+    }
+
+    #[test]
+    fn highlights_the_current_view_in_the_tab_bar() {
+        let mut app = App::default();
+        app.current_view = View::Network;
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|f| ui(f, &app)).unwrap();
+        let tabs = app.layout.view_tabs.borrow().clone();
+        assert!(tabs.iter().all(|(rect, _)| rect.y == 1));
+        let (network, _) = tabs
+            .iter()
+            .find(|(_, view)| *view == View::Network)
+            .unwrap();
+        let buffer = terminal.backend().buffer();
+        let label: String = (network.x..network.right())
+            .map(|x| buffer[(x, 1)].symbol())
+            .collect();
+        assert!(label.contains("Network"));
+        assert_eq!(buffer[(network.x + 1, 1)].bg, Theme::default().bg_lighter);
+        app.show_tab_bar = false;
+        terminal.draw(|f| ui(f, &app)).unwrap();
+        assert!(app.layout.view_tabs.borrow().is_empty());
+        assert_eq!(app.layout.content.get().unwrap().y, 1);
+    }
+
+    #[test]
+    fn fits_the_view_footer_hints_to_the_width() {
+        let mut app = App::default();
+        let footer = |app: &App, width: u16| {
+            let mut terminal = Terminal::new(TestBackend::new(width, 40)).unwrap();
+            terminal.draw(|f| ui(f, app)).unwrap();
+            let buffer = terminal.backend().buffer();
+            (0..width)
+                .map(|x| buffer[(x, 39)].symbol().to_string())
+                .collect::<String>()
+        };
+        app.current_view = View::Network;
+        let wide = footer(&app, 200);
+        assert!(wide.contains("Interface"));
+        assert!(wide.contains("[+/-]Speed"));
+        app.current_view = View::Process;
+        assert!(footer(&app, 200).contains("[F9]Kill"));
+        let narrow = footer(&app, 60);
+        assert!(narrow.contains("[F1]Help"));
+        assert!(narrow.contains("[q]Quit"));
+        assert!(!narrow.contains("Speed"));
+        // Every hint shown is whole
+        assert!(narrow.split_whitespace().all(|hint| hint.starts_with('[')));
+    }
+
+    #[test]
+    fn heats_up_the_history_line_with_its_value() {
+        let theme = Theme::default();
+        let data = [(0.0, 5.0), (1.0, 5.0), (2.0, 95.0), (3.0, 95.0), (4.0, 5.0)];
+        // Flat and cool, then the rise, peak and fall all in the hottest band
+        assert_eq!(components::gradient_line(&data, 100.0, &theme).len(), 2);
+        assert_eq!(
+            components::gradient_line(&data[..1], 100.0, &theme).len(),
+            1
+        );
+        assert!(components::gradient_line(&[], 100.0, &theme).is_empty());
+        let plain = Theme {
+            chart_gradient: [theme.info; 5],
+            ..Theme::default()
+        };
+        assert_eq!(components::gradient_line(&data, 100.0, &plain).len(), 1);
+
+        let mut app = App::default();
+        app.current_view = View::Resources;
+        let start = std::time::Instant::now();
+        for (second, usage) in [10, 10, 10, 100, 100, 10].into_iter().enumerate() {
+            let at = start + std::time::Duration::from_secs(second as u64);
+            app.metrics.cpu_history.push(at, usage);
+        }
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|f| ui(f, &app)).unwrap();
+        let colors: Vec<_> = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .filter(|cell| {
+                ('\u{2801}'..='\u{28ff}').contains(&cell.symbol().chars().next().unwrap())
+            })
+            .map(|cell| cell.fg)
+            .collect();
+        assert!(colors.contains(&theme.get_load_color(10)));
+        assert!(colors.contains(&theme.get_load_color(100)));
+    }
+
+    #[test]
+    fn falls_back_to_meters_for_many_cores() {
+        use components::{CoreLayout, core_layout};
+        let pane = Rect::new(0, 0, 70, 8);
+        assert_eq!(core_layout(16, pane), CoreLayout::Bars { width: 3 });
+        assert_eq!(core_layout(20, pane), CoreLayout::Bars { width: 2 });
+        assert_eq!(core_layout(24, pane), CoreLayout::Meters { columns: 3 });
+        assert_eq!(core_layout(64, pane), CoreLayout::Meters { columns: 4 });
+
+        let mut app = App::default();
+        app.current_view = View::System;
+        app.metrics.cpu_usage_per_core = (0..64).map(|core| core % 101).collect();
+        let text = screen_text(&app, 120, 50);
+        assert!(text.contains("C0  ["));
+        assert!(text.contains("C63 ["));
+        assert!(text.contains(" 63%"));
+        assert!(!text.contains("more"));
+    }
+
+    #[test]
+    fn lists_open_files_by_kind() {
+        use crate::app::OpenFilesPopup;
+        use crate::sys_info::{FdKind, OpenFile, OpenFiles};
+
+        let file = |fd, kind, target: &str| OpenFile {
+            fd,
+            kind,
+            target: target.to_string(),
+        };
+        let mut app = App::default();
+        app.open_files = Some(OpenFilesPopup {
+            pid: 42,
+            name: "nginx".to_string(),
+            files: Ok(OpenFiles {
+                files: vec![
+                    file(0, FdKind::File, "/dev/null"),
+                    file(6, FdKind::Socket, "TCP 0.0.0.0:80 LISTEN"),
+                    file(7, FdKind::Socket, "UNIX /run/nginx.sock"),
+                ],
+                total: 5,
+            }),
+            scroll: 0,
+        });
+        assert_eq!(app.open_files.as_ref().unwrap().line_count(), 6);
+        let text = screen_text(&app, 100, 30);
+        assert!(text.contains("Files (1)"));
+        assert!(text.contains("Sockets (2)"));
+        assert!(text.contains("TCP 0.0.0.0:80 LISTEN"));
+        assert!(text.contains("+2 more"));
+
+        app.open_files.as_mut().unwrap().files = Err("permission denied — run as root".to_string());
+        assert!(screen_text(&app, 100, 30).contains("permission denied"));
+    }
+
+    #[test]
+    fn light_theme_text_stays_readable() {
+        let mut app = App::default();
+        app.cycle_theme(true);
+        assert_eq!(app.theme_name, "light");
+        let theme = Theme::light();
+        assert_ne!(theme.bg_normal, theme.bg_light);
+        assert_ne!(theme.bg_lighter, theme.bg_light);
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        for view in VIEWS {
+            app.current_view = view;
+            for help in [false, true] {
+                app.show_help = help;
+                terminal.draw(|f| ui(f, &app)).unwrap();
+                for cell in terminal.backend().buffer().content() {
+                    if cell.symbol() != " " && cell.bg != Color::Reset {
+                        assert_ne!(cell.fg, cell.bg, "{:?} {:?}", view, cell);
+                    }
+                }
+            }
+        }
+        app.cycle_theme(true);
+        assert_eq!(app.theme, Theme::default());
+    }
+
+    #[test]
+    fn switches_theme_on_the_next_frame() {
+        let mut app = App::default();
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        terminal.draw(|f| ui(f, &app)).unwrap();
+        assert_eq!(
+            terminal.backend().buffer()[(0, 0)].bg,
+            Theme::default().bg_dark
+        );
+        app.perform(Action::CycleTheme);
+        terminal.draw(|f| ui(f, &app)).unwrap();
+        assert_eq!(
+            terminal.backend().buffer()[(0, 0)].bg,
+            Theme::light().bg_dark
+        );
+    }
+
+    #[test]
+    fn sixteen_color_text_stays_readable() {
+        let mut app = App::default();
+        app.set_color_depth(ColorDepth::Ansi16);
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        for _ in theme::BUILT_IN {
+            app.cycle_theme(true);
+            for view in VIEWS {
+                app.current_view = view;
+                terminal.draw(|f| ui(f, &app)).unwrap();
+                for cell in terminal.backend().buffer().content() {
+                    if cell.symbol() != " " && cell.bg != Color::Reset {
+                        assert!(!matches!(cell.fg, Color::Rgb(..)));
+                        assert_ne!(cell.fg, cell.bg, "{} {:?} {:?}", app.theme_name, view, cell);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn marks_meaning_without_colors() {
+        let mut app = App::default();
+        app.set_color_depth(ColorDepth::Monochrome);
+        app.metrics.processes = vec![ProcessInfo {
+            cpu_usage: 75.0,
+            ..ProcessInfo::fixture(42, 1, "busy")
+        }];
+        app.current_view = View::Process;
+        let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+        terminal.draw(|f| ui(f, &app)).unwrap();
+        let buffer = terminal.backend().buffer();
+        assert!(
+            buffer
+                .content()
+                .iter()
+                .all(|cell| { cell.fg == Color::Reset && cell.bg == Color::Reset })
+        );
+        let text: String = buffer.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("75.0!"));
+        let row = (0..buffer.area.height)
+            .find(|&y| {
+                (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect::<String>()
+                    .contains("busy")
+            })
+            .unwrap();
+        assert!(buffer[(2, row)].modifier.contains(Modifier::REVERSED));
+    }
+
+    #[test]
+    fn centers_popups_within_the_screen() {
+        assert_eq!(
+            centered_rect(80, 80, Rect::new(0, 0, 10, 5)),
+            Rect::new(1, 0, 8, 4)
+        );
+        assert_eq!(
+            centered_rect(150, 50, Rect::new(5, 5, 20, 10)),
+            Rect::new(5, 7, 20, 5)
+        );
+    }
+
+    #[test]
+    fn scrolls_the_help_to_its_last_line() {
+        let mut app = App::default();
+        app.toggle_help();
+        let text = screen_text(&app, 80, 24);
+        assert!(text.contains("Navigation:"));
+        assert!(!text.contains("Scroll three lines"));
+        app.scroll_help(isize::MAX);
+        assert!(app.help_scroll > 0);
+        assert_eq!(app.help_scroll, app.layout.help_scroll_limit.get());
+        assert!(screen_text(&app, 80, 24).contains("Scroll three lines"));
+        app.toggle_help();
+        assert_eq!(app.help_scroll, 0);
+    }
+
+    #[test]
+    fn splits_the_memory_bar_by_use() {
+        let mut app = App::default();
+        app.metrics.memory_total = 16384;
+        app.metrics.memory_used = 4096;
+        app.metrics.memory_cached = 8192;
+        app.metrics.memory_buffers = 1024;
+        let theme = Theme::default();
+        let lines = components::memory_bar(&app.metrics, &theme, 32, app.units);
+        let cells: Vec<_> = lines[0]
+            .spans
+            .iter()
+            .map(|span| span.content.chars().count())
+            .collect();
+        assert_eq!(cells, vec![8, 16, 2, 6]);
+        assert!(lines[1].to_string().contains("cache 8.00 GiB"));
+        // Figures that overlap never overrun the width
+        app.metrics.memory_used = 16384;
+        let lines = components::memory_bar(&app.metrics, &theme, 32, app.units);
+        assert_eq!(lines[0].width(), 32);
+    }
+
+    #[test]
+    fn scales_the_network_chart_to_its_peak() {
+        let mut app = App::default();
+        app.current_view = View::Resources;
+        app.metrics.net_rx_history = Default::default();
+        app.metrics.net_tx_history = Default::default();
+        let now = std::time::Instant::now();
+        app.metrics.net_rx_history.push(now, 340);
+        app.metrics.net_tx_history.push(now, 20);
+        assert!(screen_text(&app, 120, 40).contains("500 KiB/s"));
+        // An idle link still gets a usable scale
+        app.metrics.net_rx_history.push(now, 0);
+        app.metrics.net_tx_history.push(now, 0);
+        app.metrics.net_rx_history.set_capacity(1);
+        app.metrics.net_tx_history.set_capacity(1);
+        assert!(screen_text(&app, 120, 40).contains("1.00 KiB/s"));
+        // Totals are scaled like the axis
+        app.current_view = View::Network;
+        app.metrics.total_rx = 1_536;
+        assert!(screen_text(&app, 120, 40).contains("Current RX: 1.50 MiB/s"));
+    }
+
+    #[test]
+    fn shows_unreadable_process_figures_as_unavailable() {
+        let mut app = App::default();
+        app.metrics.processes.truncate(1);
+        let process = &mut app.metrics.processes[0];
+        process.name = "locked".to_string();
+        process.shared_kb = Some(1_024);
+        // Each figure the row shows adds an N/A to the screen
+        for (view, figures) in [(View::Overview, 2), (View::Process, 5)] {
+            app.current_view = view;
+            app.metrics.processes[0].restricted = false;
+            let readable = screen_text(&app, 120, 40).matches("N/A").count();
+            app.metrics.processes[0].restricted = true;
+            let unreadable = screen_text(&app, 120, 40).matches("N/A").count();
+            assert_eq!(unreadable - readable, figures, "{:?} view", view);
+        }
+    }
+
+    #[test]
+    fn renders_sizes_in_fitting_units() {
+        let mut app = App::default();
+        app.metrics.memory_total = 16_384;
+        app.metrics.processes.truncate(1);
+        app.metrics.processes[0].memory_usage = 2_560;
+        app.metrics.disks.truncate(1);
+        app.metrics.disks[0].total = 1_500;
+        app.metrics.disks[0].fs_type = "ext4".to_string();
+        let overview = screen_text(&app, 120, 40);
+        assert!(overview.contains("Total: 16.0 GiB"));
+        assert!(overview.contains("2.50 GiB"));
+        assert!(overview.contains("Total: 1.46 TiB"));
+        app.current_view = View::Disks;
+        assert!(screen_text(&app, 120, 40).contains("1.46 TiB"));
+        // The same sizes counted in powers of 1000
+        app.units = UnitSystem::Decimal;
+        assert!(screen_text(&app, 120, 40).contains("1.61 TB"));
+        app.current_view = View::Overview;
+        let overview = screen_text(&app, 120, 40);
+        assert!(overview.contains("Total: 17.2 GB"));
+        assert!(overview.contains("2.68 GB"));
+    }
+
+    #[test]
+    fn draws_every_view_at_small_sizes() {
+        let mut app = App::default();
+        app.metrics.processes.truncate(3);
+        let sizes = [
+            (1, 1),
+            (20, 5),
+            (200, 3),
+            (3, 60),
+            (MIN_WIDTH - 1, MIN_HEIGHT),
+            (MIN_WIDTH, MIN_HEIGHT),
+            (80, 24),
+        ];
+        for (width, height) in sizes {
+            let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+            for view in VIEWS {
+                app.current_view = view;
+                for help in [false, true] {
+                    app.show_help = help;
+                    terminal.draw(|f| ui(f, &app)).unwrap();
+                }
+            }
+            let text = screen_text(&app, width, height);
+            let too_small = width < MIN_WIDTH || height < MIN_HEIGHT;
+            assert_eq!(text.contains("too small"), too_small && width >= 18);
+        }
     }
 }
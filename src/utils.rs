@@ -1,29 +1,205 @@
-use std::time::Duration;
+use std::{borrow::Cow, time::Duration};
 
-pub fn format_bytes(bytes: u64) -> String {
-    const UNITS: [&str; 6] = ["B", "KB", "MB", "GB", "TB", "PB"];
+use serde::{Deserialize, Serialize};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::theme::{Severity, Threshold};
+
+/// How sizes are scaled and labelled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    // Powers of 1024, labelled KiB, MiB, GiB
+    #[default]
+    Binary,
+    // Powers of 1000, labelled KB, MB, GB
+    Decimal,
+}
+
+impl UnitSystem {
+    fn base(self) -> f64 {
+        match self {
+            UnitSystem::Binary => 1024.0,
+            UnitSystem::Decimal => 1000.0,
+        }
+    }
+
+    fn labels(self) -> [&'static str; 6] {
+        match self {
+            UnitSystem::Binary => ["B", "KiB", "MiB", "GiB", "TiB", "PiB"],
+            UnitSystem::Decimal => ["B", "KB", "MB", "GB", "TB", "PB"],
+        }
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            UnitSystem::Binary => UnitSystem::Decimal,
+            UnitSystem::Decimal => UnitSystem::Binary,
+        }
+    }
+}
+
+pub fn format_bytes(bytes: u64, units: UnitSystem) -> String {
+    let labels = units.labels();
     let mut value = bytes as f64;
     let mut unit_index = 0;
-    while value >= 1024.0 && unit_index < UNITS.len() - 1 {
-        value /= 1024.0;
+    // Compared with what rounding will show, so 1023.6 KiB reads 1.00 MiB
+    // rather than 1024 KiB and 9.996 KiB reads 10.0 KiB rather than 10.00
+    while value >= units.base() - 0.5 && unit_index < labels.len() - 1 {
+        value /= units.base();
         unit_index += 1;
     }
-    if value < 10.0 {
-        format!("{:.2} {}", value, UNITS[unit_index])
-    } else if value < 100.0 {
-        format!("{:.1} {}", value, UNITS[unit_index])
+    if value < 9.995 {
+        format!("{:.2} {}", value, labels[unit_index])
+    } else if value < 99.95 {
+        format!("{:.1} {}", value, labels[unit_index])
     } else {
-        format!("{:.0} {}", value, UNITS[unit_index])
+        format!("{:.0} {}", value, labels[unit_index])
     }
 }
 
-pub fn format_percentage(percentage: f64, warn_threshold: f64, crit_threshold: f64) -> String {
-    if percentage >= crit_threshold {
-        format!("{:.1}%", percentage)
-    } else if percentage >= warn_threshold {
-        format!("{:.1}%", percentage)
+/// `format_bytes` per second, for network and disk throughput.
+pub fn format_rate(bytes_per_sec: u64, units: UnitSystem) -> String {
+    format!("{}/s", format_bytes(bytes_per_sec, units))
+}
+
+pub const KB: u64 = 1024;
+pub const MB: u64 = 1024 * KB;
+pub const GB: u64 = 1024 * MB;
+
+/// `format_bytes` for a size counted in `unit`s, such as the MB and GB
+/// fields of `SystemInfo`. The unit is always 1024-based, whichever
+/// system the result is shown in.
+pub fn format_size(size: u64, unit: u64, units: UnitSystem) -> String {
+    format_bytes(size.saturating_mul(unit), units)
+}
+
+pub fn format_count(count: u64) -> String {
+    const UNITS: [&str; 4] = ["", "k", "M", "G"];
+    let mut value = count as f64;
+    let mut unit_index = 0;
+    while value >= 1000.0 && unit_index < UNITS.len() - 1 {
+        value /= 1000.0;
+        unit_index += 1;
+    }
+    if unit_index == 0 {
+        format!("{}", count)
+    } else if value < 100.0 {
+        format!("{:.1}{}", value, UNITS[unit_index])
     } else {
-        format!("{:.1}%", percentage)
+        format!("{:.0}{}", value, UNITS[unit_index])
+    }
+}
+
+/// Byte range of the first case-insensitive occurrence of `needle`, always
+/// on char boundaries of `haystack`.
+pub fn find_ignore_case(haystack: &str, needle: &str) -> Option<(usize, usize)> {
+    if needle.is_empty() {
+        return None;
+    }
+    let needle: Vec<char> = needle.chars().flat_map(char::to_lowercase).collect();
+    haystack.char_indices().find_map(|(start, _)| {
+        let mut rest = haystack[start..].char_indices();
+        let mut wanted = needle.iter().peekable();
+        let mut end = start;
+        while wanted.peek().is_some() {
+            let (offset, c) = rest.next()?;
+            for lower in c.to_lowercase() {
+                if wanted.next() != Some(&lower) {
+                    return None;
+                }
+            }
+            end = start + offset + c.len_utf8();
+        }
+        Some((start, end))
+    })
+}
+
+/// Splits a command line into the program's directory, the program's name
+/// and its arguments, which together make up the whole line. A quoted
+/// program keeps its quotes out of the name; bracketed kernel thread names
+/// are all name.
+pub fn split_command(command: &str) -> (&str, &str, &str) {
+    if command.starts_with('[') {
+        return ("", command, "");
+    }
+    let mut quote = None;
+    let end = command
+        .char_indices()
+        .find(|&(_, c)| match quote {
+            Some(open) => {
+                if c == open {
+                    quote = None;
+                }
+                false
+            }
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                false
+            }
+            None => c.is_whitespace(),
+        })
+        .map_or(command.len(), |(index, _)| index);
+    let program = &command[..end];
+    let quoted = program.len() > 1
+        && program
+            .chars()
+            .next()
+            .is_some_and(|open| (open == '"' || open == '\'') && program.ends_with(open));
+    let name_end = if quoted { end - 1 } else { end };
+    let name_start = command[..name_end]
+        .rfind('/')
+        .map_or(usize::from(quoted), |slash| slash + 1);
+    (
+        &command[..name_start],
+        &command[name_start..name_end],
+        &command[name_end..],
+    )
+}
+
+/// Breaks a command line into lines of at most `width` columns, between
+/// arguments where it can, so `--flag=value` only splits when it is longer
+/// than a line on its own.
+pub fn wrap_command(command: &str, width: usize) -> Vec<&str> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut rest = command.trim();
+    while !rest.is_empty() {
+        // A character wider than the whole line still has to go somewhere
+        let limit = match prefix_within(rest, width).len() {
+            0 => rest.chars().next().map_or(0, char::len_utf8),
+            limit => limit,
+        };
+        if limit == rest.len() {
+            lines.push(rest);
+            break;
+        }
+        let end = if rest[limit..].starts_with(char::is_whitespace) {
+            limit
+        } else {
+            rest[..limit]
+                .rfind(char::is_whitespace)
+                .filter(|&space| space > 0)
+                .unwrap_or(limit)
+        };
+        lines.push(rest[..end].trim_end());
+        rest = rest[end..].trim_start();
+    }
+    lines
+}
+
+/// A percentage as shown, with how far past its thresholds it is so the
+/// caller can color it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Percentage {
+    pub text: String,
+    pub severity: Severity,
+}
+
+pub fn format_percentage(percentage: f64, threshold: Threshold) -> Percentage {
+    Percentage {
+        text: format!("{:.1}%", percentage),
+        severity: threshold.classify(percentage),
     }
 }
 
@@ -58,22 +234,67 @@ pub fn create_progress_bar(percentage: u64, width: usize) -> String {
     format!("[{}{}]", "█".repeat(filled), "░".repeat(empty))
 }
 
-pub fn truncate_with_ellipsis(s: &str, max_len: usize) -> String {
-    if s.len() <= max_len {
+/// The longest start of `s` that takes at most `width` terminal columns.
+/// Combining marks stay with the character before them.
+fn prefix_within(s: &str, width: usize) -> &str {
+    let mut used = 0;
+    let end = s
+        .char_indices()
+        .find(|&(_, c)| {
+            used += c.width().unwrap_or(0);
+            used > width
+        })
+        .map_or(s.len(), |(index, _)| index);
+    &s[..end]
+}
+
+/// Shortens `s` to at most `max_width` terminal columns, ending it with
+/// "..." when anything was cut. Wide characters count as two columns.
+pub fn truncate_with_ellipsis(s: &str, max_width: usize) -> String {
+    if s.width() <= max_width {
         s.to_string()
-    } else if max_len <= 3 {
-        ".".repeat(max_len)
+    } else if max_width <= 3 {
+        ".".repeat(max_width)
     } else {
-        format!("{}...", &s[..max_len - 3])
+        format!("{}...", prefix_within(s, max_width - 3))
+    }
+}
+
+/// The longest end of `s` that takes at most `width` terminal columns,
+/// never starting with a combining mark cut off from its character.
+fn suffix_within(s: &str, width: usize) -> &str {
+    let mut used = 0;
+    let start = s
+        .char_indices()
+        .rev()
+        .find(|&(_, c)| {
+            used += c.width().unwrap_or(0);
+            used > width
+        })
+        .map_or(0, |(index, c)| index + c.len_utf8());
+    s[start..].trim_start_matches(|c: char| c.width() == Some(0))
+}
+
+/// Shortens `s` to at most `max_width` terminal columns by cutting its
+/// middle, so that a path keeps both its root and its file name. Text that
+/// already fits is borrowed rather than copied.
+pub fn truncate_middle(s: &str, max_width: usize) -> Cow<'_, str> {
+    if s.width() <= max_width {
+        return Cow::Borrowed(s);
     }
+    let Some(kept) = max_width.checked_sub(1) else {
+        return Cow::Borrowed("");
+    };
+    let tail = suffix_within(s, kept / 2);
+    Cow::Owned(format!("{}…{}", prefix_within(s, kept - kept / 2), tail))
 }
 
 pub fn align_text(text: &str, width: usize, alignment: Alignment) -> String {
-    let text_len = text.chars().count();
-    if text_len >= width {
+    let text_width = text.width();
+    if text_width >= width {
         return truncate_with_ellipsis(text, width);
     }
-    let padding = width - text_len;
+    let padding = width - text_width;
     match alignment {
         Alignment::Left => format!("{}{}", text, " ".repeat(padding)),
         Alignment::Right => format!("{}{}", " ".repeat(padding), text),
@@ -121,6 +342,20 @@ fn lerp(a: f32, b: f32, t: f32) -> f32 {
     a + (b - a) * t
 }
 
+/// Rounds `value` up to the next 1, 2 or 5 times a power of ten, for chart
+/// bounds that give round axis labels.
+pub fn nice_ceiling(value: f64) -> f64 {
+    if !value.is_finite() || value <= 0.0 {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(value.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|step| step * magnitude)
+        .find(|&nice| nice >= value)
+        .unwrap_or(value)
+}
+
 pub mod simulator {
     use rand::Rng;
     use std::time::{Duration, Instant};
@@ -179,10 +414,117 @@ mod tests {
 
     #[test]
     fn test_format_bytes() {
-        assert_eq!(format_bytes(0), "0.00 B");
-        assert_eq!(format_bytes(1024), "1.00 KB");
-        assert_eq!(format_bytes(1048576), "1.00 MB");
-        assert_eq!(format_bytes(1073741824), "1.00 GB");
+        use UnitSystem::{Binary, Decimal};
+        assert_eq!(format_bytes(0, Binary), "0.00 B");
+        assert_eq!(format_bytes(1024, Binary), "1.00 KiB");
+        assert_eq!(format_bytes(1048576, Binary), "1.00 MiB");
+        assert_eq!(format_bytes(1073741824, Binary), "1.00 GiB");
+        assert_eq!(format_bytes(1000, Binary), "1000 B");
+        assert_eq!(format_bytes(1000, Decimal), "1.00 KB");
+        assert_eq!(format_bytes(1048576, Decimal), "1.05 MB");
+        assert_eq!(format_bytes(1_500_000_000, Decimal), "1.50 GB");
+    }
+
+    #[test]
+    fn test_format_size() {
+        use UnitSystem::{Binary, Decimal};
+        assert_eq!(format_size(16_384, MB, Binary), "16.0 GiB");
+        assert_eq!(format_size(512, MB, Binary), "512 MiB");
+        assert_eq!(format_size(1_500, GB, Binary), "1.46 TiB");
+        assert_eq!(format_size(4, KB, Binary), "4.00 KiB");
+        assert_eq!(format_size(16_384, MB, Decimal), "17.2 GB");
+        assert_eq!(format_size(4, KB, Decimal), "4.10 KB");
+    }
+
+    #[test]
+    fn test_format_rate() {
+        use UnitSystem::{Binary, Decimal};
+        assert_eq!(format_rate(0, Binary), "0.00 B/s");
+        assert_eq!(format_rate(1023, Binary), "1023 B/s");
+        assert_eq!(format_rate(1024, Binary), "1.00 KiB/s");
+        assert_eq!(format_rate(1048575, Binary), "1.00 MiB/s");
+        assert_eq!(format_rate(10236, Binary), "10.0 KiB/s");
+        assert_eq!(format_rate(999_499, Decimal), "999 KB/s");
+        assert_eq!(format_rate(999_500, Decimal), "1.00 MB/s");
+        assert_eq!(format_rate(1048576, Binary), "1.00 MiB/s");
+        assert_eq!(format_rate(3 * GB, Binary), "3.00 GiB/s");
+        assert_eq!(format_rate(1023, Decimal), "1.02 KB/s");
+    }
+
+    #[test]
+    fn test_format_count() {
+        assert_eq!(format_count(950), "950");
+        assert_eq!(format_count(12_345), "12.3k");
+        assert_eq!(format_count(250_000), "250k");
+        assert_eq!(format_count(1_200_000), "1.2M");
+    }
+
+    #[test]
+    fn test_nice_ceiling() {
+        assert_eq!(nice_ceiling(0.0), 1.0);
+        assert_eq!(nice_ceiling(f64::NAN), 1.0);
+        assert_eq!(nice_ceiling(1.0), 1.0);
+        assert_eq!(nice_ceiling(1.3), 2.0);
+        assert_eq!(nice_ceiling(340.0), 500.0);
+        assert_eq!(nice_ceiling(1000.0), 1000.0);
+        assert_eq!(nice_ceiling(5001.0), 10_000.0);
+    }
+
+    #[test]
+    fn test_find_ignore_case() {
+        assert_eq!(find_ignore_case("/usr/bin/Firefox", "fire"), Some((9, 13)));
+        assert_eq!(find_ignore_case("Größe", "SSE"), None);
+        assert_eq!(find_ignore_case("ÄBC", "bc"), Some((2, 4)));
+        assert_eq!(find_ignore_case("bash", ""), None);
+    }
+
+    #[test]
+    fn test_split_command() {
+        assert_eq!(
+            split_command("/usr/lib/chromium/chrome --type=renderer /tmp/x"),
+            ("/usr/lib/chromium/", "chrome", " --type=renderer /tmp/x")
+        );
+        assert_eq!(split_command("bash"), ("", "bash", ""));
+        assert_eq!(
+            split_command("\"/opt/My App/bin/app\" -v"),
+            ("\"/opt/My App/bin/", "app", "\" -v")
+        );
+        assert_eq!(split_command("'run' now"), ("'", "run", "' now"));
+        assert_eq!(split_command("[kworker/0:1]"), ("", "[kworker/0:1]", ""));
+    }
+
+    #[test]
+    fn test_wrap_command() {
+        assert_eq!(
+            wrap_command(
+                "java -Xmx4g --add-opens=java.base/java.lang=ALL-UNNAMED -jar app.jar",
+                20
+            ),
+            vec![
+                "java -Xmx4g",
+                "--add-opens=java.bas",
+                "e/java.lang=ALL-UNNA",
+                "MED -jar app.jar",
+            ]
+        );
+        assert_eq!(wrap_command("top -d 1", 8), vec!["top -d 1"]);
+        assert_eq!(wrap_command("top -d 1", 6), vec!["top -d", "1"]);
+        assert!(wrap_command("", 10).is_empty());
+        assert_eq!(wrap_command("./日本語 -v", 6), vec!["./日本", "語 -v"]);
+    }
+
+    #[test]
+    fn test_format_percentage() {
+        let threshold = Threshold {
+            warning: 70.0,
+            critical: 85.0,
+        };
+        let severity = |percentage| format_percentage(percentage, threshold).severity;
+        assert_eq!(format_percentage(42.25, threshold).text, "42.2%");
+        assert_eq!(severity(70.0), Severity::Normal);
+        assert_eq!(severity(70.1), Severity::Warning);
+        assert_eq!(severity(85.0), Severity::Warning);
+        assert_eq!(severity(85.1), Severity::Critical);
     }
 
     #[test]
@@ -197,6 +539,49 @@ mod tests {
         assert_eq!(truncate_with_ellipsis("Hello World", 5), "He...");
         assert_eq!(truncate_with_ellipsis("Hello", 10), "Hello");
         assert_eq!(truncate_with_ellipsis("Hello", 3), "...");
+        assert_eq!(truncate_with_ellipsis("Hi", 2), "Hi");
+        assert_eq!(truncate_with_ellipsis("Hi", 1), ".");
+        // Each of these takes two columns
+        assert_eq!(truncate_with_ellipsis("日本語サーバー", 10), "日本語...");
+        assert_eq!(truncate_with_ellipsis("日本語サーバー", 8), "日本...");
+        assert_eq!(truncate_with_ellipsis("/mnt/📦backup", 8), "/mnt/...");
+        assert_eq!(truncate_with_ellipsis("/mnt/📦backup", 9), "/mnt/...");
+        assert_eq!(truncate_with_ellipsis("/mnt/📦backup", 10), "/mnt/📦...");
+        // The accent is a separate, zero-width character
+        assert_eq!(
+            truncate_with_ellipsis("cafe\u{301} au lait", 7),
+            "cafe\u{301}..."
+        );
+        assert_eq!(truncate_with_ellipsis("ü", 2), "ü");
+        assert_eq!(align_text("日本", 6, Alignment::Right), "  日本");
+        assert_eq!(align_text("日本語サーバー", 7, Alignment::Left), "日本...");
+    }
+
+    #[test]
+    fn test_truncate_middle() {
+        assert_eq!(
+            truncate_middle("/home/user/projects/foo/target/release/my-server", 24),
+            "/home/user/p…e/my-server"
+        );
+        assert_eq!(truncate_middle("/mnt/data", 9), "/mnt/data");
+        assert_eq!(truncate_middle("/mnt/data", 4), "/m…a");
+        assert_eq!(truncate_middle("/mnt/data", 2), "/…");
+        assert_eq!(truncate_middle("/mnt/data", 1), "…");
+        assert_eq!(truncate_middle("/mnt/data", 0), "");
+        // Each of these takes two columns
+        assert_eq!(truncate_middle("日本語サーバー", 9), "日本…バー");
+        assert_eq!(truncate_middle("日本語サーバー", 8), "日本…ー");
+        assert_eq!(truncate_middle("/mnt/📦/backup", 8), "/mnt…kup");
+        // The accent is a separate, zero-width character
+        assert_eq!(
+            truncate_middle("cafe\u{301}-cafe\u{301}", 7),
+            "caf…afe\u{301}"
+        );
+        assert_eq!(truncate_middle("cafe\u{301}-cafe\u{301}", 2), "c…");
+        assert_eq!(
+            truncate_middle("cafe\u{301}-cafe\u{301}", 9),
+            "cafe\u{301}-cafe\u{301}"
+        );
     }
 
     #[test]
@@ -0,0 +1,114 @@
+// Allocation checks on drawing. They need a counting global allocator,
+// which would otherwise sit under every unit test, so they get a test
+// binary of their own.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use ratatui::{Terminal, backend::TestBackend};
+use xtop::{
+    app::{App, View},
+    sys_info::ProcessInfo,
+    ui::ui,
+};
+
+// Counts the bytes each thread holds and the allocations it makes, so a
+// test can tell what drawing costs without other tests interfering
+struct CountingAllocator;
+
+thread_local! {
+    static LIVE_BYTES: Cell<isize> = const { Cell::new(0) };
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        LIVE_BYTES.with(|live| live.set(live.get() + layout.size() as isize));
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        LIVE_BYTES.with(|live| live.set(live.get() - layout.size() as isize));
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[test]
+fn repeated_draws_do_not_leak() {
+    let mut terminal = Terminal::new(TestBackend::new(120, 40)).unwrap();
+    let mut app = App::default();
+    for view in [View::Overview, View::System, View::Resources, View::Gpu] {
+        app.current_view = view;
+        // Let the terminal settle its double buffers first
+        for _ in 0..3 {
+            terminal.draw(|f| ui(f, &app)).unwrap();
+        }
+        let before = LIVE_BYTES.with(Cell::get);
+        for _ in 0..20 {
+            terminal.draw(|f| ui(f, &app)).unwrap();
+        }
+        assert_eq!(LIVE_BYTES.with(Cell::get), before, "{:?} view", view);
+    }
+}
+
+// A busy machine's worth of processes with long command lines
+fn crowded_app() -> App {
+    let mut app = App::default();
+    let template = app.metrics.processes[0].clone();
+    app.metrics.processes = (1..=2000)
+        .map(|pid| ProcessInfo {
+            pid,
+            ppid: 1,
+            name: format!("worker{}", pid),
+            cpu_usage: f64::from(pid % 97),
+            memory_percent: f64::from(pid % 13),
+            full_command: format!("/usr/lib/worker/worker{} --queue jobs --id {}", pid, pid),
+            ..template.clone()
+        })
+        .collect();
+    app
+}
+
+// Allocations per draw, after the terminal's buffers and the layout cache
+// have settled
+fn allocations_per_draw(terminal: &mut Terminal<TestBackend>, app: &App) -> usize {
+    const DRAWS: usize = 20;
+    for _ in 0..3 {
+        terminal.draw(|f| ui(f, app)).unwrap();
+    }
+    let before = ALLOCATIONS.with(Cell::get);
+    for _ in 0..DRAWS {
+        terminal.draw(|f| ui(f, app)).unwrap();
+    }
+    (ALLOCATIONS.with(Cell::get) - before) / DRAWS
+}
+
+// The counts were 1301, 3036 and 3241 before the process tables built
+// their cells without copying
+#[test]
+fn drawing_stays_within_its_allocation_budget() {
+    let mut terminal = Terminal::new(TestBackend::new(160, 50)).unwrap();
+    let mut app = crowded_app();
+    for (view, full_command, budget) in [
+        (View::Overview, false, 1000),
+        (View::Process, false, 2000),
+        (View::Process, true, 2300),
+    ] {
+        app.current_view = view;
+        app.show_full_command = full_command;
+        let allocations = allocations_per_draw(&mut terminal, &app);
+        assert!(
+            allocations <= budget,
+            "{:?} view, full commands {}: {} allocations",
+            view,
+            full_command,
+            allocations
+        );
+    }
+}